@@ -20,12 +20,19 @@ use ink_prelude::string::String;
 pub mod aura {
     use super::String;
     use erc20::Result;
+    use ink_prelude::vec::Vec;
+    use ink_storage::collections::HashMap as StorageHashMap;
     use metis_erc20 as erc20;
     use metis_lang::{
         import,
         metis,
     };
     use metis_ownable as ownable;
+    pub use mintcraft_signature::Signature;
+    use scale::Encode;
+
+    /// Tag of the permit payloads, so they can not be replayed as other payloads.
+    const PERMIT_TAG: &[u8] = b"aura::permit";
 
     /// A simple ERC-20 contract.
     #[ink(storage)]
@@ -33,6 +40,20 @@ pub mod aura {
     pub struct AuraToken {
         ownable: ownable::Data<AuraToken>,
         erc20: erc20::Data<AuraToken>,
+        /// The number of permits used by each owner, part of the signed payloads.
+        nonces: StorageHashMap<AccountId, u64>,
+    }
+
+    /// The permit errors.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PermitError {
+        /// The deadline of the permit has passed.
+        Expired,
+        /// The signature is not the one of the owner for this payload.
+        InvalidSignature,
+        /// The approval failed.
+        Erc20(erc20::Error),
     }
 
     // TODO: gen by marco with erc20 component
@@ -90,6 +111,7 @@ pub mod aura {
             let mut instance = Self {
                 erc20: erc20::Data::new(),
                 ownable: ownable::Data::new(),
+                nonces: StorageHashMap::new(),
             };
 
             erc20::Impl::init(
@@ -199,6 +221,66 @@ pub mod aura {
             erc20::Impl::transfer_from(self, &from, &to, value)
         }
 
+        /// Returns the nonce of `owner`, to sign in its next permit.
+        #[ink(message)]
+        pub fn nonces(&self, owner: AccountId) -> u64 {
+            *self.nonces.get(&owner).unwrap_or(&0)
+        }
+
+        /// Returns the payload `owner` signs to permit `spender` to withdraw up to `value`
+        /// until `deadline`, with the current nonce of `owner`.
+        #[ink(message)]
+        pub fn permit_payload(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+        ) -> Vec<u8> {
+            (
+                PERMIT_TAG,
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                self.nonces(owner),
+                deadline,
+            )
+                .encode()
+        }
+
+        /// Allows `spender` to withdraw from the account of `owner` up to the `value`
+        /// amount, with the signature of `owner` instead of its call (EIP-2612).
+        ///
+        /// Anyone can submit the permit, so the gateway can relay it and pay the fees. The
+        /// signature is the one of the `permit_payload` and a permit can only be used once.
+        ///
+        /// An `Approval` event is emitted.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+            signature: Signature,
+        ) -> core::result::Result<(), PermitError> {
+            if self.env().block_timestamp() > deadline {
+                return Err(PermitError::Expired)
+            }
+
+            let payload = self.permit_payload(owner, spender, value, deadline);
+            if !signature.verify(&payload, &owner.encode()) {
+                return Err(PermitError::InvalidSignature)
+            }
+
+            erc20::Impl::_approve(self, &owner, &spender, value)
+                .map_err(PermitError::Erc20)?;
+            self.nonces.insert(owner, self.nonces(owner) + 1);
+
+            Ok(())
+        }
+
         #[ink(message)]
         pub fn get_ownership(&self) -> Option<AccountId> {
             *ownable::Impl::owner(self)
@@ -228,6 +310,10 @@ pub mod aura {
             },
             Clear,
         };
+        use mintcraft_signature::testing::{
+            owner_pair,
+            sign,
+        };
 
         use erc20::Error;
 
@@ -548,6 +634,81 @@ pub mod aura {
                 ink_env::test::recorded_events().collect::<Vec<_>>();
             assert_eq!(emitted_events_before.len(), emitted_events_after.len());
         }
+
+        #[ink::test]
+        fn permit_works() {
+            let mut erc20 = AuraToken::new(100);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let pair = owner_pair();
+            let owner = AccountId::from(pair.public.to_bytes());
+
+            // Alice relays the permit signed by the owner for Bob.
+            let payload = erc20.permit_payload(owner, accounts.bob, 10, u64::MAX);
+            let signature = sign(&pair, &payload);
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 10, u64::MAX, signature.clone()),
+                Ok(())
+            );
+            assert_eq!(erc20.allowance(owner, accounts.bob), 10);
+            assert_eq!(erc20.nonces(owner), 1);
+
+            // The permit can not be replayed.
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 10, u64::MAX, signature),
+                Err(PermitError::InvalidSignature)
+            );
+        }
+
+        #[ink::test]
+        fn permit_with_invalid_signature_fails() {
+            let mut erc20 = AuraToken::new(100);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let pair = owner_pair();
+            let owner = AccountId::from(pair.public.to_bytes());
+
+            // The payload is signed for another value.
+            let payload = erc20.permit_payload(owner, accounts.bob, 10, u64::MAX);
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 20, u64::MAX, sign(&pair, &payload)),
+                Err(PermitError::InvalidSignature)
+            );
+            // The payload is not signed by the owner.
+            assert_eq!(
+                erc20.permit(
+                    accounts.alice,
+                    accounts.bob,
+                    10,
+                    u64::MAX,
+                    sign(&pair, &payload)
+                ),
+                Err(PermitError::InvalidSignature)
+            );
+            assert_eq!(erc20.allowance(owner, accounts.bob), 0);
+            assert_eq!(erc20.nonces(owner), 0);
+        }
+
+        #[ink::test]
+        fn expired_permit_fails() {
+            let mut erc20 = AuraToken::new(100);
+            let accounts =
+                ink_env::test::default_accounts::<ink_env::DefaultEnvironment>()
+                    .expect("Cannot get accounts");
+            let pair = owner_pair();
+            let owner = AccountId::from(pair.public.to_bytes());
+
+            let payload = erc20.permit_payload(owner, accounts.bob, 10, 0);
+            let signature = sign(&pair, &payload);
+            ink_env::test::advance_block::<ink_env::DefaultEnvironment>()
+                .expect("Cannot advance block");
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 10, 0, signature),
+                Err(PermitError::Expired)
+            );
+        }
     }
 
     /// For calculating the event topic hash.
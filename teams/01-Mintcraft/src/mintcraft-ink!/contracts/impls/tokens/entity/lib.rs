@@ -53,6 +53,8 @@ mod entity {
     };
     #[allow(unused_imports)]
     use metis_ownable as ownable;
+    pub use mintcraft_signature::Signature;
+    use scale::Encode;
 
     #[allow(unused_imports)]
     use trait_erc1155::{
@@ -103,6 +105,66 @@ mod entity {
         value: Balance,
     }
 
+    /// @dev Either `TransferSingle` or `TransferBatch` MUST emit when tokens are transferred, including zero value transfers as well as minting or burning (see "Safe Transfer Rules" section of the standard).
+    ///
+    /// The `_ids` argument MUST be the list of tokens being transferred.
+    /// The `_values` argument MUST be the list of number of tokens (matching the list and order of tokens specified in _ids) the holder balance is decreased by and match what the recipient balance is increased by.
+    /// When burning/destroying tokens, the `_to` argument MUST be set to `0x0` (i.e. zero address).
+    #[ink(event)]
+    pub struct TransferBatch {
+        #[ink(topic)]
+        operator: Option<AccountId>,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        token_ids: Vec<TokenId>,
+        values: Vec<Balance>,
+    }
+
+    /// Event emitted when the contract owner grants a role to an account.
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when the contract owner revokes a role of an account.
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    /// Event emitted when a seller lists tokens for sale, a zero `value` removes the listing.
+    #[ink(event)]
+    pub struct Listed {
+        #[ink(topic)]
+        seller: AccountId,
+        #[ink(topic)]
+        token_id: TokenId,
+        value: Balance,
+        price: Balance,
+    }
+
+    /// Event emitted when listed tokens are sold, the `royalty` is paid to the creator.
+    #[ink(event)]
+    pub struct Sold {
+        #[ink(topic)]
+        seller: AccountId,
+        #[ink(topic)]
+        buyer: AccountId,
+        #[ink(topic)]
+        token_id: TokenId,
+        value: Balance,
+        payment: Balance,
+        royalty: Balance,
+    }
+
     /// @dev MUST emit when approval for a second party/operator address to manage all tokens for an owner address is enabled or disabled (absence of an event assumes disabled).
     #[ink(event)]
     pub struct ApprovalForAll {
@@ -113,6 +175,12 @@ mod entity {
         approved: bool,
     }
 
+    /// Royalties are in basis points of the sale price.
+    const ROYALTY_DENOMINATOR: Balance = 10_000;
+
+    /// Tag of the signed approval payloads, so they can not be replayed as other payloads.
+    const APPROVAL_TAG: &[u8] = b"entity::set_approval_for_all";
+
     /// Represents an (Owner, Operator) pair, in which the operator is allowed to spend funds on
     /// behalf of the operator.
     #[derive(
@@ -134,6 +202,28 @@ mod entity {
         operator: AccountId,
     }
 
+    /// The roles the contract owner grants to accounts.
+    #[derive(
+        Copy,
+        Clone,
+        Debug,
+        Ord,
+        PartialOrd,
+        Eq,
+        PartialEq,
+        PackedLayout,
+        SpreadLayout,
+        scale::Encode,
+        scale::Decode,
+    )]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Role {
+        /// Creates tokens and mints any token.
+        Minter,
+        /// Sets the base uri and the uris of the tokens.
+        UriSetter,
+    }
+
     /// An ERC-1155 contract.
     #[ink(storage)]
     #[import(ownable)]
@@ -152,12 +242,29 @@ mod entity {
         token_uris: StorageHashMap<TokenId, Option<String>>,
         /// token metadata baseuri
         base_uri: Option<String>,
+        /// The accounts granted a role by the contract owner
+        roles: StorageHashMap<(Role, AccountId), ()>,
+        /// The circulating supply of the tokens
+        supplies: StorageHashMap<TokenId, Balance>,
+        /// The max supply of the tokens, fixed at their creation
+        max_supplies: StorageHashMap<TokenId, Balance>,
+        /// The total amount ever minted of the tokens, burns do not lower it
+        minted: StorageHashMap<TokenId, Balance>,
+        /// The royalty of the creators in basis points, fixed at the creation of the tokens
+        royalties: StorageHashMap<TokenId, u16>,
+        /// The (value, price per token) listed for sale by the sellers
+        listings: StorageHashMap<(AccountId, TokenId), (Balance, Balance)>,
+        /// The number of signed payloads used by each account
+        nonces: StorageHashMap<AccountId, u64>,
     }
 
     impl Contract {
         /// Initialize a default instance of this ERC-1155 implementation.
+        ///
+        /// The caller becomes the contract owner and is granted all the roles.
         #[ink(constructor)]
         pub fn new(_base_uri: Option<String>) -> Self {
+            let caller = Self::env().caller();
             let mut instance = Self {
                 balances: Default::default(),
                 approvals: Default::default(),
@@ -166,9 +273,18 @@ mod entity {
                 creators: StorageHashMap::new(),
                 token_uris: StorageHashMap::new(),
                 base_uri: _base_uri,
+                roles: StorageHashMap::new(),
+                supplies: StorageHashMap::new(),
+                max_supplies: StorageHashMap::new(),
+                minted: StorageHashMap::new(),
+                royalties: StorageHashMap::new(),
+                listings: StorageHashMap::new(),
+                nonces: StorageHashMap::new(),
             };
             // init metis ownable module
             ownable::Impl::init(&mut instance);
+            instance.roles.insert((Role::Minter, caller), ());
+            instance.roles.insert((Role::UriSetter, caller), ());
             instance
         }
 
@@ -177,17 +293,28 @@ mod entity {
         /// The initial supply will be provided to the caller (a.k.a the minter), and the
         /// `token_id` will be assigned by the smart contract.
         ///
-        /// Note that as implemented anyone can create tokens. If you were to deploy this to a
-        /// production environment you'd probably want to lock down the addresses that are allowed
-        /// to create tokens.
+        /// Only minters can create tokens. The supply of the token can never go above
+        /// `_max_supply`, if any. The caller receives `_royalty` basis points of the sales made
+        /// with `buy`.
         #[ink(message)]
         pub fn create(
             &mut self,
             _initial_supply: Balance,
+            _max_supply: Option<Balance>,
+            _royalty: u16,
             _metadata_uri: Option<String>,
-        ) -> TokenId {
+        ) -> Result<TokenId> {
             let caller = self.env().caller();
 
+            ensure!(self.has_role(Role::Minter, caller), Error::NotMinter);
+            if let Some(max_supply) = _max_supply {
+                ensure!(_initial_supply <= max_supply, Error::MaxSupplyExceeded);
+            }
+            ensure!(
+                Balance::from(_royalty) <= ROYALTY_DENOMINATOR,
+                Error::InvalidRoyalty
+            );
+
             // Given that TokenId is a `u128` the likelihood of this overflowing is pretty slim.
             self.token_id_nonce += 1;
             // Add balance
@@ -195,8 +322,14 @@ mod entity {
                 self.balances
                     .insert((caller, self.token_id_nonce), _initial_supply);
             }
+            self.supplies.insert(self.token_id_nonce, _initial_supply);
+            self.minted.insert(self.token_id_nonce, _initial_supply);
+            if let Some(max_supply) = _max_supply {
+                self.max_supplies.insert(self.token_id_nonce, max_supply);
+            }
             // Set creator
             self.creators.insert(self.token_id_nonce, caller);
+            self.royalties.insert(self.token_id_nonce, _royalty);
 
             // Set metadata
             self.token_uris.insert(self.token_id_nonce, _metadata_uri);
@@ -214,7 +347,7 @@ mod entity {
                 value: _initial_supply,
             });
 
-            self.token_id_nonce
+            Ok(self.token_id_nonce)
         }
 
         /// Mint a `value` amount of `token_id` tokens.
@@ -222,21 +355,35 @@ mod entity {
         /// It is assumed that the token has already been `create`-ed. The newly minted supply will
         /// be assigned to the caller (a.k.a the minter).
         ///
-        /// Note that as implemented anyone can mint tokens. If you were to deploy this to a
-        /// production environment you'd probably want to lock down the addresses that are allowed
-        /// to mint tokens.
+        /// Only the creator of the token and the minters can mint it, up to its max supply. The
+        /// max supply caps the total amount ever minted, burnt tokens can not be minted again.
         #[ink(message)]
         pub fn mint(&mut self, token_id: TokenId, value: Balance) -> Result<()> {
-            ensure!(token_id <= self.token_id_nonce, Error::UnexistentToken);
-
             let caller = self.env().caller();
 
-            let creator = self.creators.get(&token_id);
-            ensure!(creator.clone().unwrap() == &caller, Error::NotTokenCreator);
+            let creator = *self.creators.get(&token_id).ok_or(Error::UnexistentToken)?;
+            ensure!(
+                creator == caller || self.has_role(Role::Minter, caller),
+                Error::NotMinter
+            );
 
             ensure!(value > 0, Error::ZeroAmountMint);
 
-            self.balances.insert((caller, token_id), value);
+            let minted = self
+                .total_minted(token_id)
+                .checked_add(value)
+                .ok_or(Error::MaxSupplyExceeded)?;
+            if let Some(max_supply) = self.max_supply(token_id) {
+                ensure!(minted <= max_supply, Error::MaxSupplyExceeded);
+            }
+            let supply = self.total_supply(token_id).saturating_add(value);
+
+            self.balances
+                .entry((caller, token_id))
+                .and_modify(|b| *b += value)
+                .or_insert(value);
+            self.supplies.insert(token_id, supply);
+            self.minted.insert(token_id, minted);
 
             // Emit transfer event but with mint semantics
             self.env().emit_event(TransferSingle {
@@ -250,6 +397,318 @@ mod entity {
             Ok(())
         }
 
+        /// Burn a `value` amount of `token_id` tokens of `from`.
+        ///
+        /// The caller must be `from` or an operator approved by `from`.
+        #[ink(message)]
+        pub fn burn(
+            &mut self,
+            from: AccountId,
+            token_id: TokenId,
+            value: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != from {
+                ensure!(self.is_approved_for_all(from, caller), Error::NotApproved);
+            }
+
+            let balance = self.balance_of(from, token_id);
+            ensure!(balance >= value, Error::InsufficientBalance);
+
+            self._burn(from, token_id, value);
+
+            // Emit transfer event but with burn semantics
+            self.env().emit_event(TransferSingle {
+                operator: Some(caller),
+                from: Some(from),
+                to: None,
+                token_id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Burn the `values` amounts of the `token_ids` tokens of `from`.
+        ///
+        /// The caller must be `from` or an operator approved by `from`.
+        #[ink(message)]
+        pub fn burn_batch(
+            &mut self,
+            from: AccountId,
+            token_ids: Vec<TokenId>,
+            values: Vec<Balance>,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+            if caller != from {
+                ensure!(self.is_approved_for_all(from, caller), Error::NotApproved);
+            }
+
+            ensure!(!token_ids.is_empty(), Error::BatchTransferMismatch);
+            ensure!(
+                token_ids.len() == values.len(),
+                Error::BatchTransferMismatch,
+            );
+
+            // A token may be listed more than once, so check the balances against the sums
+            let mut burns: BTreeMap<TokenId, Balance> = BTreeMap::new();
+            for (&id, &v) in token_ids.iter().zip(values.iter()) {
+                let total = burns.entry(id).or_insert(0);
+                *total = total.saturating_add(v);
+            }
+            for (&id, &v) in burns.iter() {
+                let balance = self.balance_of(from, id);
+                ensure!(balance >= v, Error::InsufficientBalance);
+            }
+
+            for (id, v) in burns {
+                self._burn(from, id, v);
+            }
+
+            // Emit transfer event but with burn semantics
+            self.env().emit_event(TransferBatch {
+                operator: Some(caller),
+                from: Some(from),
+                to: None,
+                token_ids,
+                values,
+            });
+
+            Ok(())
+        }
+
+        /// The circulating supply of `token_id`.
+        #[ink(message)]
+        pub fn total_supply(&self, token_id: TokenId) -> Balance {
+            *self.supplies.get(&token_id).unwrap_or(&0)
+        }
+
+        /// The total amount of `token_id` ever minted, including the burnt tokens.
+        #[ink(message)]
+        pub fn total_minted(&self, token_id: TokenId) -> Balance {
+            *self.minted.get(&token_id).unwrap_or(&0)
+        }
+
+        /// The max supply of `token_id`, `None` if it is not capped.
+        #[ink(message)]
+        pub fn max_supply(&self, token_id: TokenId) -> Option<Balance> {
+            self.max_supplies.get(&token_id).cloned()
+        }
+
+        /// The creator of `token_id` and the royalty owed to them for a sale at `sale_price`.
+        #[ink(message)]
+        pub fn royalty_info(
+            &self,
+            token_id: TokenId,
+            sale_price: Balance,
+        ) -> Result<(AccountId, Balance)> {
+            let creator = self.creators.get(&token_id).cloned().unwrap_or_default();
+            let royalty = Balance::from(*self.royalties.get(&token_id).unwrap_or(&0));
+            let royalty = sale_price
+                .checked_mul(royalty)
+                .ok_or(Error::RoyaltyOverflow)?
+                / ROYALTY_DENOMINATOR;
+
+            Ok((creator, royalty))
+        }
+
+        /// List `value` of the `token_id` tokens of the caller for sale at `price` each.
+        ///
+        /// Listing replaces the previous listing of the caller, a zero `value` removes it.
+        #[ink(message)]
+        pub fn list(
+            &mut self,
+            token_id: TokenId,
+            value: Balance,
+            price: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+
+            if value == 0 {
+                self.listings.take(&(caller, token_id));
+            } else {
+                let balance = self.balance_of(caller, token_id);
+                ensure!(balance >= value, Error::InsufficientBalance);
+
+                self.listings.insert((caller, token_id), (value, price));
+            }
+
+            self.env().emit_event(Listed {
+                seller: caller,
+                token_id,
+                value,
+                price,
+            });
+
+            Ok(())
+        }
+
+        /// Buy `value` of the `token_id` tokens listed by `seller`.
+        ///
+        /// The transferred balance must be the listed price of the tokens. It is split between
+        /// the creator of the token, who gets the royalty, and the seller.
+        #[ink(message, payable)]
+        pub fn buy(
+            &mut self,
+            seller: AccountId,
+            token_id: TokenId,
+            value: Balance,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+
+            let (listed, price) = *self
+                .listings
+                .get(&(seller, token_id))
+                .ok_or(Error::NotListed)?;
+            ensure!(value > 0 && value <= listed, Error::NotListed);
+
+            let balance = self.balance_of(seller, token_id);
+            ensure!(balance >= value, Error::InsufficientBalance);
+
+            let payment = price.checked_mul(value).ok_or(Error::WrongPayment)?;
+            ensure!(
+                self.env().transferred_balance() == payment,
+                Error::WrongPayment
+            );
+            let (creator, royalty) = self.royalty_info(token_id, payment)?;
+
+            if listed == value {
+                self.listings.take(&(seller, token_id));
+            } else {
+                self.listings
+                    .insert((seller, token_id), (listed - value, price));
+            }
+
+            self._perform_transfer(seller, caller, token_id, value);
+            self._transfer_acceptance_check(
+                caller,
+                seller,
+                caller,
+                token_id,
+                value,
+                Vec::new(),
+            );
+
+            // A failed payout must revert the whole sale, hence the panics
+            if royalty > 0 {
+                self.env()
+                    .transfer(creator, royalty)
+                    .expect("paying the royalty to the creator failed");
+            }
+            if payment > royalty {
+                self.env()
+                    .transfer(seller, payment - royalty)
+                    .expect("paying the seller failed");
+            }
+
+            self.env().emit_event(Sold {
+                seller,
+                buyer: caller,
+                token_id,
+                value,
+                payment,
+                royalty,
+            });
+
+            Ok(())
+        }
+
+        /// Grant `role` to `account`, only by the contract owner.
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+            ensure!(
+                self.get_ownership() == Some(caller),
+                Error::NotContractOwner
+            );
+
+            if self.roles.insert((role, account), ()).is_none() {
+                self.env().emit_event(RoleGranted { role, account });
+            }
+
+            Ok(())
+        }
+
+        /// Revoke `role` of `account`, only by the contract owner.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<()> {
+            let caller = self.env().caller();
+            ensure!(
+                self.get_ownership() == Some(caller),
+                Error::NotContractOwner
+            );
+
+            if self.roles.take(&(role, account)).is_some() {
+                self.env().emit_event(RoleRevoked { role, account });
+            }
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.roles.contains_key(&(role, account))
+        }
+
+        /// The nonce of `owner`, to sign in its next payload.
+        #[ink(message)]
+        pub fn nonces(&self, owner: AccountId) -> u64 {
+            *self.nonces.get(&owner).unwrap_or(&0)
+        }
+
+        /// The payload `owner` signs to approve or disapprove `operator` until `deadline`,
+        /// with the current nonce of `owner`.
+        #[ink(message)]
+        pub fn approval_payload(
+            &self,
+            owner: AccountId,
+            operator: AccountId,
+            approved: bool,
+            deadline: Timestamp,
+        ) -> Vec<u8> {
+            (
+                APPROVAL_TAG,
+                self.env().account_id(),
+                owner,
+                operator,
+                approved,
+                self.nonces(owner),
+                deadline,
+            )
+                .encode()
+        }
+
+        /// `set_approval_for_all` for `owner` with its signature of the `approval_payload`
+        /// instead of its call, so the gateway can relay it and pay the fees.
+        ///
+        /// A signed payload can only be used once.
+        #[ink(message)]
+        pub fn set_approval_for_all_by_sig(
+            &mut self,
+            owner: AccountId,
+            operator: AccountId,
+            approved: bool,
+            deadline: Timestamp,
+            signature: Signature,
+        ) -> Result<()> {
+            ensure!(
+                self.env().block_timestamp() <= deadline,
+                Error::SignatureExpired
+            );
+            ensure!(operator != owner, Error::SelfApproval);
+
+            let payload = self.approval_payload(owner, operator, approved, deadline);
+            ensure!(
+                signature.verify(&payload, &owner.encode()),
+                Error::InvalidSignature
+            );
+
+            self.nonces.insert(owner, self.nonces(owner) + 1);
+            self._set_approval_for_all(owner, operator, approved);
+
+            Ok(())
+        }
+
         // Ownable messages
         #[ink(message)]
         pub fn get_ownership(&self) -> Option<AccountId> {
@@ -270,12 +729,28 @@ mod entity {
         pub fn set_base_uri(&mut self, new_base_uri: Option<String>) -> Result<()> {
             let caller = self.env().caller();
 
+            ensure!(self.has_role(Role::UriSetter, caller), Error::NotUriSetter);
+
+            self.base_uri = new_base_uri;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_token_uri(
+            &mut self,
+            token_id: TokenId,
+            token_uri: Option<String>,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+
+            ensure!(self.has_role(Role::UriSetter, caller), Error::NotUriSetter);
             ensure!(
-                &self.get_ownership().clone().unwrap() == &caller,
-                Error::NotContractOwner
+                self.creators.contains_key(&token_id),
+                Error::UnexistentToken
             );
 
-            self.base_uri = new_base_uri;
+            self.token_uris.insert(token_id, token_uri);
 
             Ok(())
         }
@@ -287,6 +762,38 @@ mod entity {
             self.token_uris.get(&token_id).unwrap_or(&None).clone()
         }
 
+        fn _set_approval_for_all(
+            &mut self,
+            owner: AccountId,
+            operator: AccountId,
+            approved: bool,
+        ) {
+            let approval = Approval { owner, operator };
+
+            if approved {
+                self.approvals.insert(approval, ());
+            } else {
+                self.approvals.remove(&approval);
+            }
+
+            self.env().emit_event(ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            });
+        }
+
+        // Helper function for burning tokens, the balance is expected to be checked already.
+        fn _burn(&mut self, from: AccountId, token_id: TokenId, value: Balance) {
+            self.balances
+                .entry((from, token_id))
+                .and_modify(|b| *b -= value);
+
+            if let Some(supply) = self.supplies.get_mut(&token_id) {
+                *supply = supply.saturating_sub(value);
+            }
+        }
+
         // Helper function for performing single token transfers.
         //
         // Should not be used directly since it's missing certain checks which are important to the
@@ -311,7 +818,7 @@ mod entity {
             self.env().emit_event(TransferSingle {
                 operator: Some(caller),
                 from: Some(from),
-                to: Some(from),
+                to: Some(to),
                 token_id,
                 value,
             });
@@ -501,22 +1008,8 @@ mod entity {
             let caller = self.env().caller();
             ensure!(operator != caller, Error::SelfApproval);
 
-            let approval = Approval {
-                owner: caller,
-                operator,
-            };
+            self._set_approval_for_all(caller, operator, approved);
 
-            if approved {
-                self.approvals.insert(approval, ());
-            } else {
-                self.approvals.remove(&approval);
-            }
-
-            self.env().emit_event(ApprovalForAll {
-                owner: approval.owner,
-                operator,
-                approved,
-            });
             Ok(())
         }
 
@@ -595,6 +1088,10 @@ mod entity {
         /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
         use crate::entity::Contract;
+        use mintcraft_signature::testing::{
+            owner_pair,
+            sign,
+        };
 
         use ink_lang as ink;
 
@@ -615,6 +1112,29 @@ mod entity {
             );
         }
 
+        #[cfg(feature = "ink-experimental-engine")]
+        fn set_value_transferred(sender: AccountId, value: Balance) {
+            ink_env::test::set_caller::<Environment>(sender);
+            ink_env::test::set_value_transferred::<Environment>(value);
+        }
+
+        #[cfg(not(feature = "ink-experimental-engine"))]
+        fn set_value_transferred(sender: AccountId, value: Balance) {
+            const WALLET: [u8; 32] = [7; 32];
+            ink_env::test::push_execution_context::<Environment>(
+                sender,
+                WALLET.into(),
+                1000000,
+                value,
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])), /* dummy */
+            );
+        }
+
+        fn balance(account: AccountId) -> Balance {
+            ink_env::test::get_account_balance::<Environment>(account)
+                .expect("the account should exist")
+        }
+
         #[cfg(feature = "ink-experimental-engine")]
         fn default_accounts() -> ink_env::test::DefaultAccounts<Environment> {
             ink_env::test::default_accounts::<Environment>()
@@ -782,11 +1302,129 @@ mod entity {
             let mut erc = Contract::new(Option::default());
 
             set_sender(alice());
-            assert_eq!(erc.create(0, Option::default()), 1);
+            assert_eq!(erc.create(0, None, 0, Option::default()), Ok(1));
             assert_eq!(erc.balance_of(alice(), 1), 0);
 
             assert!(erc.mint(1, 123).is_ok());
             assert_eq!(erc.balance_of(alice(), 1), 123);
+
+            assert!(erc.mint(1, 7).is_ok());
+            assert_eq!(erc.balance_of(alice(), 1), 130);
+            assert_eq!(erc.total_supply(1), 130);
+        }
+
+        #[ink::test]
+        fn minting_restricted_to_creator_and_minters() {
+            let mut erc = Contract::new(Option::default());
+
+            set_sender(bob());
+            assert_eq!(
+                erc.create(10, None, 0, Option::default()).unwrap_err(),
+                Error::NotMinter
+            );
+
+            set_sender(alice());
+            assert!(erc.grant_role(Role::Minter, bob()).is_ok());
+            assert!(erc.has_role(Role::Minter, bob()));
+
+            set_sender(bob());
+            assert_eq!(erc.create(10, None, 0, Option::default()), Ok(1));
+
+            // the creator can still mint its token after the role is revoked
+            set_sender(alice());
+            assert!(erc.revoke_role(Role::Minter, bob()).is_ok());
+            assert!(!erc.has_role(Role::Minter, bob()));
+            set_sender(bob());
+            assert!(erc.mint(1, 5).is_ok());
+            assert_eq!(erc.balance_of(bob(), 1), 15);
+
+            // minters can mint any token, others can not
+            set_sender(alice());
+            assert!(erc.mint(1, 5).is_ok());
+            assert_eq!(erc.balance_of(alice(), 1), 5);
+            set_sender(charlie());
+            assert_eq!(erc.mint(1, 5).unwrap_err(), Error::NotMinter);
+            assert_eq!(erc.total_supply(1), 20);
+        }
+
+        #[ink::test]
+        fn roles_managed_by_owner() {
+            let mut erc = Contract::new(Option::default());
+
+            set_sender(bob());
+            assert_eq!(
+                erc.grant_role(Role::UriSetter, bob()).unwrap_err(),
+                Error::NotContractOwner
+            );
+            assert_eq!(
+                erc.revoke_role(Role::Minter, alice()).unwrap_err(),
+                Error::NotContractOwner
+            );
+            assert!(erc.has_role(Role::Minter, alice()));
+            assert!(!erc.has_role(Role::UriSetter, bob()));
+        }
+
+        #[ink::test]
+        fn minting_above_max_supply_fails() {
+            let mut erc = Contract::new(Option::default());
+
+            set_sender(alice());
+            assert_eq!(
+                erc.create(11, Some(10), 0, Option::default()).unwrap_err(),
+                Error::MaxSupplyExceeded
+            );
+            assert_eq!(erc.create(8, Some(10), 0, Option::default()), Ok(1));
+            assert_eq!(erc.max_supply(1), Some(10));
+
+            assert_eq!(erc.mint(1, 3).unwrap_err(), Error::MaxSupplyExceeded);
+            assert!(erc.mint(1, 2).is_ok());
+            assert_eq!(erc.total_supply(1), 10);
+
+            // burning does not make room in the supply
+            assert!(erc.burn(alice(), 1, 4).is_ok());
+            assert_eq!(erc.total_supply(1), 6);
+            assert_eq!(erc.total_minted(1), 10);
+            assert_eq!(erc.mint(1, 4).unwrap_err(), Error::MaxSupplyExceeded);
+            assert_eq!(erc.mint(1, 1).unwrap_err(), Error::MaxSupplyExceeded);
+            assert_eq!(erc.balance_of(alice(), 1), 6);
+        }
+
+        #[ink::test]
+        fn burning_tokens_works() {
+            let mut erc = Contract::new(Option::default());
+
+            set_sender(alice());
+            assert_eq!(erc.create(10, None, 0, Option::default()), Ok(1));
+            assert_eq!(erc.create(20, None, 0, Option::default()), Ok(2));
+
+            assert_eq!(
+                erc.burn(alice(), 1, 11).unwrap_err(),
+                Error::InsufficientBalance
+            );
+            assert!(erc.burn(alice(), 1, 4).is_ok());
+            assert_eq!(erc.balance_of(alice(), 1), 6);
+            assert_eq!(erc.total_supply(1), 6);
+
+            set_sender(bob());
+            assert_eq!(erc.burn(alice(), 1, 1).unwrap_err(), Error::NotApproved);
+
+            set_sender(alice());
+            assert!(erc.set_approval_for_all(bob(), true).is_ok());
+            set_sender(bob());
+            assert_eq!(
+                erc.burn_batch(alice(), vec![1, 2, 1], vec![3, 5, 4])
+                    .unwrap_err(),
+                Error::InsufficientBalance
+            );
+            assert_eq!(
+                erc.burn_batch(alice(), vec![1, 2], vec![3]).unwrap_err(),
+                Error::BatchTransferMismatch
+            );
+            assert!(erc
+                .burn_batch(alice(), vec![1, 2, 1], vec![3, 5, 3])
+                .is_ok());
+            assert_eq!(erc.balance_of_batch(vec![alice()], vec![1, 2]), vec![0, 15]);
+            assert_eq!(erc.total_supply(2), 15);
         }
 
         #[ink::test]
@@ -804,6 +1442,94 @@ mod entity {
             assert_eq!(baseUrl, String::from("test"));
         }
 
+        #[ink::test]
+        fn royalty_info_works() {
+            let mut erc = Contract::new(Option::default());
+
+            set_sender(alice());
+            assert_eq!(
+                erc.create(10, None, 10_001, Option::default()).unwrap_err(),
+                Error::InvalidRoyalty
+            );
+            assert_eq!(erc.create(10, None, 250, Option::default()), Ok(1));
+            assert_eq!(erc.create(10, None, 0, Option::default()), Ok(2));
+
+            assert_eq!(erc.royalty_info(1, 1_000), Ok((alice(), 25)));
+            assert_eq!(erc.royalty_info(1, 39), Ok((alice(), 0)));
+            assert_eq!(erc.royalty_info(2, 1_000), Ok((alice(), 0)));
+            assert_eq!(erc.royalty_info(3, 1_000), Ok((AccountId::default(), 0)));
+            assert_eq!(
+                erc.royalty_info(1, Balance::MAX).unwrap_err(),
+                Error::RoyaltyOverflow
+            );
+        }
+
+        #[ink::test]
+        fn listing_and_buying_checks_work() {
+            let mut erc = Contract::new(Option::default());
+
+            set_sender(alice());
+            assert_eq!(erc.create(10, None, 250, Option::default()), Ok(1));
+            assert_eq!(erc.list(1, 11, 5).unwrap_err(), Error::InsufficientBalance);
+            assert!(erc.list(1, 4, 5).is_ok());
+
+            set_sender(bob());
+            assert_eq!(erc.buy(charlie(), 1, 1).unwrap_err(), Error::NotListed);
+            assert_eq!(erc.buy(alice(), 1, 5).unwrap_err(), Error::NotListed);
+            // the transferred balance is not the price of 2 tokens
+            assert_eq!(erc.buy(alice(), 1, 2).unwrap_err(), Error::WrongPayment);
+            assert_eq!(erc.balance_of(bob(), 1), 0);
+
+            set_sender(alice());
+            assert!(erc.list(1, 0, 0).is_ok());
+            set_sender(bob());
+            assert_eq!(erc.buy(alice(), 1, 1).unwrap_err(), Error::NotListed);
+        }
+
+        #[ink::test]
+        fn buying_pays_the_seller_and_the_creator() {
+            let mut erc = Contract::new(Option::default());
+            let contract = ink_env::account_id::<Environment>()
+                .expect("the contract account should exist");
+
+            set_sender(alice());
+            assert_eq!(erc.create(10, None, 1_000, Option::default()), Ok(1));
+            assert!(erc.safe_transfer_from(alice(), charlie(), 1, 5, vec![]).is_ok());
+
+            set_sender(charlie());
+            assert!(erc.list(1, 4, 50).is_ok());
+
+            // the contract holds the payment transferred with the call
+            ink_env::test::set_account_balance::<Environment>(contract, 1_000)
+                .expect("the contract account should exist");
+            let (creator_balance, seller_balance) = (balance(alice()), balance(charlie()));
+
+            set_value_transferred(bob(), 100);
+            assert!(erc.buy(charlie(), 1, 2).is_ok());
+
+            assert_eq!(erc.balance_of(bob(), 1), 2);
+            assert_eq!(erc.balance_of(charlie(), 1), 3);
+            // the transfer event names the buyer
+            let transfer = ink_env::test::recorded_events()
+                .filter_map(|event| {
+                    match <Event as scale::Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::TransferSingle(transfer)) => Some(transfer),
+                        _ => None,
+                    }
+                })
+                .last()
+                .expect("the sale should emit a transfer");
+            assert_eq!((transfer.from, transfer.to), (Some(charlie()), Some(bob())));
+            // 10% of the payment goes to the creator, the rest to the seller
+            assert_eq!(balance(alice()), creator_balance + 10);
+            assert_eq!(balance(charlie()), seller_balance + 90);
+            assert_eq!(balance(contract), 900);
+
+            // only the rest of the listing is left for sale
+            set_value_transferred(bob(), 150);
+            assert_eq!(erc.buy(charlie(), 1, 3).unwrap_err(), Error::NotListed);
+        }
+
         #[ink::test]
         fn set_baseurl_works() {
             let mut erc = Contract::new(Option::default());
@@ -812,6 +1538,116 @@ mod entity {
             assert_eq!(baseUrl, String::from("test2"));
         }
 
+        #[ink::test]
+        fn set_approval_for_all_by_sig_works() {
+            let mut erc = Contract::new(Option::default());
+            let pair = owner_pair();
+            let owner = AccountId::from(pair.public.to_bytes());
+
+            // alice relays the approval signed by the owner
+            set_sender(alice());
+            let payload = erc.approval_payload(owner, bob(), true, u64::MAX);
+            assert_eq!(
+                erc.set_approval_for_all_by_sig(
+                    owner,
+                    charlie(),
+                    true,
+                    u64::MAX,
+                    sign(&pair, &payload)
+                )
+                .unwrap_err(),
+                Error::InvalidSignature
+            );
+            assert!(erc
+                .set_approval_for_all_by_sig(
+                    owner,
+                    bob(),
+                    true,
+                    u64::MAX,
+                    sign(&pair, &payload)
+                )
+                .is_ok());
+            assert!(erc.is_approved_for_all(owner, bob()));
+            assert_eq!(erc.nonces(owner), 1);
+
+            // the signed payload can not be replayed
+            assert_eq!(
+                erc.set_approval_for_all_by_sig(
+                    owner,
+                    bob(),
+                    true,
+                    u64::MAX,
+                    sign(&pair, &payload)
+                )
+                .unwrap_err(),
+                Error::InvalidSignature
+            );
+
+            let payload = erc.approval_payload(owner, bob(), false, u64::MAX);
+            assert!(erc
+                .set_approval_for_all_by_sig(
+                    owner,
+                    bob(),
+                    false,
+                    u64::MAX,
+                    sign(&pair, &payload)
+                )
+                .is_ok());
+            assert!(!erc.is_approved_for_all(owner, bob()));
+        }
+
+        #[ink::test]
+        fn expired_approval_signature_fails() {
+            let mut erc = Contract::new(Option::default());
+            let pair = owner_pair();
+            let owner = AccountId::from(pair.public.to_bytes());
+
+            let payload = erc.approval_payload(owner, bob(), true, 0);
+            ink_env::test::advance_block::<Environment>().expect("Cannot advance block");
+            assert_eq!(
+                erc.set_approval_for_all_by_sig(
+                    owner,
+                    bob(),
+                    true,
+                    0,
+                    sign(&pair, &payload)
+                )
+                .unwrap_err(),
+                Error::SignatureExpired
+            );
+            assert_eq!(erc.nonces(owner), 0);
+        }
+
+        #[ink::test]
+        fn setting_uris_requires_uri_setter() {
+            let mut erc = Contract::new(Some(String::from("ipfs://")));
+
+            set_sender(alice());
+            assert_eq!(erc.create(1, Some(1), 0, Option::default()), Ok(1));
+            assert_eq!(
+                erc.set_token_uri(2, Some(String::from("2.json")))
+                    .unwrap_err(),
+                Error::UnexistentToken
+            );
+
+            set_sender(bob());
+            assert_eq!(
+                erc.set_base_uri(Some(String::from("test"))).unwrap_err(),
+                Error::NotUriSetter
+            );
+            assert_eq!(
+                erc.set_token_uri(1, Some(String::from("1.json")))
+                    .unwrap_err(),
+                Error::NotUriSetter
+            );
+
+            set_sender(alice());
+            assert!(erc.grant_role(Role::UriSetter, bob()).is_ok());
+            set_sender(bob());
+            assert!(erc.set_token_uri(1, Some(String::from("1.json"))).is_ok());
+            assert_eq!(erc.uri(1), Some(String::from("ipfs://1.json")));
+        }
+
 
     }
 }
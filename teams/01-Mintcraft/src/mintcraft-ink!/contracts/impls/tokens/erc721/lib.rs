@@ -0,0 +1,997 @@
+// Copyright 2018-2021 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Evaluate `$x:expr` and if not true return `Err($y:expr)`.
+///
+/// Used as `ensure!(expression_to_ensure, expression_to_return_on_false)`.
+macro_rules! ensure {
+    ( $condition:expr, $error:expr $(,)? ) => {{
+        if !$condition {
+            return ::core::result::Result::Err(::core::convert::Into::into($error))
+        }
+    }};
+}
+
+pub use self::erc721::Contract;
+#[metis_lang::contract]
+mod erc721 {
+    #[allow(unused_imports)]
+    use ink_prelude::{
+        format,
+        string::String,
+        vec::Vec,
+    };
+    use ink_storage::collections::{
+        HashMap as StorageHashMap,
+        Vec as StorageVec,
+    };
+    #[allow(unused_imports)]
+    use metis_lang::{
+        import,
+        metis,
+    };
+    #[allow(unused_imports)]
+    use metis_ownable as ownable;
+
+    #[allow(unused_imports)]
+    use trait_erc721::{
+        consts::MAGIC_VALUE_RECEIVED,
+        Error,
+        IErc721,
+        IErc721Enumerable,
+        IErc721Metadata,
+        IErc721TokenReceiver,
+        Result,
+        TokenId,
+    };
+
+    /// Event emitted when Owner AccountId Transferred
+    #[ink(event)]
+    #[metis(ownable)]
+    pub struct OwnershipTransferred {
+        /// previous owner account id
+        #[ink(topic)]
+        previous_owner: Option<AccountId>,
+        /// new owner account id
+        #[ink(topic)]
+        new_owner: Option<AccountId>,
+    }
+
+    /// Event emitted when a token transfer occurs.
+    ///
+    /// `from` is `None` when the token is minted and `to` is `None` when it is burned.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    /// Event emitted when a token approve occurs.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    /// Event emitted when an operator is enabled or disabled for an owner.
+    /// The operator can manage all NFTs of the owner.
+    #[ink(event)]
+    pub struct ApprovalForAll {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+    }
+
+    /// Royalties are in basis points of the sale price.
+    const ROYALTY_DENOMINATOR: Balance = 10_000;
+
+    /// An ERC-721 contract.
+    #[ink(storage)]
+    #[import(ownable)]
+    pub struct Contract {
+        /// Mapping from token to owner.
+        token_owner: StorageHashMap<TokenId, AccountId>,
+        /// Mapping from token to the account approved to transfer it.
+        token_approvals: StorageHashMap<TokenId, AccountId>,
+        /// Mapping from owner to number of owned token.
+        owned_tokens_count: StorageHashMap<AccountId, u32>,
+        /// Mapping from (owner, operator) to operator approvals.
+        operator_approvals: StorageHashMap<(AccountId, AccountId), bool>,
+        /// All the tokens, for enumeration.
+        all_tokens: StorageVec<TokenId>,
+        /// Position of each token in `all_tokens`.
+        all_tokens_index: StorageHashMap<TokenId, u32>,
+        /// The tokens of each owner, by their position in the list of the owner.
+        owned_tokens: StorageHashMap<(AccountId, u32), TokenId>,
+        /// Position of each token in the list of its owner.
+        owned_tokens_index: StorageHashMap<TokenId, u32>,
+        /// Ownable data
+        ownable: ownable::Data<Contract>,
+        /// collection name
+        name: Option<String>,
+        /// collection symbol
+        symbol: Option<String>,
+        /// token metadata uri
+        token_uris: StorageHashMap<TokenId, Option<String>>,
+        /// token metadata baseuri
+        base_uri: Option<String>,
+        /// Mapping from token to the account which minted it with a royalty.
+        creators: StorageHashMap<TokenId, AccountId>,
+        /// The royalty of the creators in basis points, fixed when the token is minted.
+        royalties: StorageHashMap<TokenId, u16>,
+    }
+
+    impl Contract {
+        /// Initialize an ERC-721 collection with its metadata.
+        #[ink(constructor)]
+        pub fn with_metadata(
+            name: Option<String>,
+            symbol: Option<String>,
+            base_uri: Option<String>,
+        ) -> Self {
+            let mut instance = Self {
+                token_owner: StorageHashMap::new(),
+                token_approvals: StorageHashMap::new(),
+                owned_tokens_count: StorageHashMap::new(),
+                operator_approvals: StorageHashMap::new(),
+                all_tokens: StorageVec::new(),
+                all_tokens_index: StorageHashMap::new(),
+                owned_tokens: StorageHashMap::new(),
+                owned_tokens_index: StorageHashMap::new(),
+                ownable: ownable::Data::new(),
+                name,
+                symbol,
+                token_uris: StorageHashMap::new(),
+                base_uri,
+                creators: StorageHashMap::new(),
+                royalties: StorageHashMap::new(),
+            };
+            // init metis ownable module
+            ownable::Impl::init(&mut instance);
+            instance
+        }
+
+        /// Creates a new token with its metadata uri, relative to the base uri if any.
+        ///
+        /// Note that as implemented anyone can mint tokens, like `IErc721::mint`.
+        #[ink(message)]
+        pub fn mint_with_uri(
+            &mut self,
+            id: TokenId,
+            token_uri: Option<String>,
+        ) -> Result<()> {
+            self._mint(id)?;
+            self.token_uris.insert(id, token_uri);
+            Ok(())
+        }
+
+        /// Creates a new token whose sales owe `royalty` basis points of their price to the
+        /// caller, as reported by `royalty_info`.
+        #[ink(message)]
+        pub fn mint_with_royalty(&mut self, id: TokenId, royalty: u16) -> Result<()> {
+            let caller = self.env().caller();
+            ensure!(
+                Balance::from(royalty) <= ROYALTY_DENOMINATOR,
+                Error::InvalidRoyalty
+            );
+
+            self._mint(id)?;
+            self.creators.insert(id, caller);
+            self.royalties.insert(id, royalty);
+            Ok(())
+        }
+
+        /// The creator of the token and the royalty owed to them for a sale at `sale_price`,
+        /// nothing is owed for tokens minted without a royalty.
+        #[ink(message)]
+        pub fn royalty_info(
+            &self,
+            id: TokenId,
+            sale_price: Balance,
+        ) -> Result<(AccountId, Balance)> {
+            let creator = self.creators.get(&id).cloned().unwrap_or_default();
+            let royalty = Balance::from(*self.royalties.get(&id).unwrap_or(&0));
+            let royalty = sale_price
+                .checked_mul(royalty)
+                .ok_or(Error::RoyaltyOverflow)?
+                / ROYALTY_DENOMINATOR;
+
+            Ok((creator, royalty))
+        }
+
+        // Ownable messages
+        #[ink(message)]
+        pub fn get_ownership(&self) -> Option<AccountId> {
+            *ownable::Impl::owner(self)
+        }
+
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) {
+            ownable::Impl::renounce_ownership(self)
+        }
+
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) {
+            ownable::Impl::transfer_ownership(self, &new_owner)
+        }
+
+        #[ink(message)]
+        pub fn set_base_uri(&mut self, new_base_uri: Option<String>) -> Result<()> {
+            let caller = self.env().caller();
+
+            ensure!(
+                self.get_ownership() == Some(caller),
+                Error::NotContractOwner
+            );
+
+            self.base_uri = new_base_uri;
+
+            Ok(())
+        }
+
+        // ------------------------------ Private Methods ------------------------------
+
+        /// get token uri
+        fn _get_token_uri(&self, id: TokenId) -> Option<String> {
+            self.token_uris.get(&id).unwrap_or(&None).clone()
+        }
+
+        fn _exists(&self, id: TokenId) -> bool {
+            self.token_owner.contains_key(&id)
+        }
+
+        fn _balance_of(&self, owner: &AccountId) -> u32 {
+            *self.owned_tokens_count.get(owner).unwrap_or(&0)
+        }
+
+        // Returns true if the account is the owner of the token or approved to transfer it.
+        fn _approved_or_owner(&self, account: AccountId, id: TokenId) -> bool {
+            match self.owner_of(id) {
+                Some(owner) => {
+                    owner == account
+                        || self.token_approvals.get(&id) == Some(&account)
+                        || self.is_approved_for_all(owner, account)
+                }
+                None => false,
+            }
+        }
+
+        fn _clear_approval(&mut self, id: TokenId) {
+            self.token_approvals.take(&id);
+        }
+
+        fn _mint(&mut self, id: TokenId) -> Result<()> {
+            let caller = self.env().caller();
+
+            self._add_token_to(caller, id)?;
+            self._add_token_to_all_tokens_enumeration(id);
+
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(caller),
+                id,
+            });
+
+            Ok(())
+        }
+
+        // Helper function for performing token transfers, checks the caller may move the
+        // token.
+        fn _transfer_token_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+
+            ensure!(self._exists(id), Error::TokenNotFound);
+            ensure!(self._approved_or_owner(caller, id), Error::NotApproved);
+            ensure!(self.owner_of(id) == Some(from), Error::NotOwner);
+            ensure!(to != AccountId::default(), Error::NotAllowed);
+
+            self._clear_approval(id);
+            self._remove_token_from(from, id)?;
+            self._add_token_to(to, id)?;
+
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                id,
+            });
+
+            Ok(())
+        }
+
+        fn _add_token_to(&mut self, to: AccountId, id: TokenId) -> Result<()> {
+            ensure!(!self._exists(id), Error::TokenExists);
+            ensure!(to != AccountId::default(), Error::NotAllowed);
+
+            self._add_token_to_owner_enumeration(to, id);
+            self.token_owner.insert(id, to);
+
+            Ok(())
+        }
+
+        fn _remove_token_from(&mut self, from: AccountId, id: TokenId) -> Result<()> {
+            self._remove_token_from_owner_enumeration(from, id)?;
+            self.token_owner.take(&id).ok_or(Error::CannotRemove)?;
+
+            Ok(())
+        }
+
+        fn _add_token_to_owner_enumeration(&mut self, to: AccountId, id: TokenId) {
+            let index = self._balance_of(&to);
+
+            self.owned_tokens.insert((to, index), id);
+            self.owned_tokens_index.insert(id, index);
+            self.owned_tokens_count.insert(to, index + 1);
+        }
+
+        // The last token of the owner takes the place of the removed one, so the list of the
+        // owner has no gaps.
+        fn _remove_token_from_owner_enumeration(
+            &mut self,
+            from: AccountId,
+            id: TokenId,
+        ) -> Result<()> {
+            let last_index = self
+                ._balance_of(&from)
+                .checked_sub(1)
+                .ok_or(Error::CannotFetchValue)?;
+            let index = self
+                .owned_tokens_index
+                .take(&id)
+                .ok_or(Error::CannotFetchValue)?;
+            let last_id = self
+                .owned_tokens
+                .take(&(from, last_index))
+                .ok_or(Error::CannotRemove)?;
+
+            if index != last_index {
+                self.owned_tokens.insert((from, index), last_id);
+                self.owned_tokens_index.insert(last_id, index);
+            }
+            self.owned_tokens_count.insert(from, last_index);
+
+            Ok(())
+        }
+
+        fn _add_token_to_all_tokens_enumeration(&mut self, id: TokenId) {
+            self.all_tokens_index.insert(id, self.all_tokens.len());
+            self.all_tokens.push(id);
+        }
+
+        // The last token takes the place of the removed one, like for the owners.
+        fn _remove_token_from_all_tokens_enumeration(
+            &mut self,
+            id: TokenId,
+        ) -> Result<()> {
+            let index = self
+                .all_tokens_index
+                .take(&id)
+                .ok_or(Error::CannotFetchValue)?;
+            let last_id = self.all_tokens.pop().ok_or(Error::CannotRemove)?;
+
+            if last_id != id {
+                self.all_tokens
+                    .set(index, last_id)
+                    .map_err(|_| Error::CannotInsert)?;
+                self.all_tokens_index.insert(last_id, index);
+            }
+
+            Ok(())
+        }
+
+        // Check if the address at `to` is a smart contract which accepts ERC-721 token transfers.
+        //
+        // If they're a smart contract which **doesn't** accept tokens transfers this call will
+        // revert. Otherwise we risk locking the token in that contract with no chance of
+        // recovery.
+        #[cfg_attr(test, allow(unused_variables))]
+        fn _transfer_acceptance_check(
+            &mut self,
+            caller: AccountId,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) {
+            // This is disabled during tests due to the use of `eval_contract()` not being
+            // supported (tests end up panicking).
+            #[cfg(not(test))]
+            {
+                use ink_env::call::{
+                    build_call,
+                    utils::ReturnType,
+                    ExecutionInput,
+                    Selector,
+                };
+
+                // If our recipient is a smart contract we need to see if they accept or
+                // reject this transfer. If they reject it we need to revert the call.
+                let params = build_call::<ink_env::DefaultEnvironment>()
+                    .callee(to)
+                    .gas_limit(5000)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(MAGIC_VALUE_RECEIVED))
+                            .push_arg(caller)
+                            .push_arg(from)
+                            .push_arg(id)
+                            .push_arg(data),
+                    )
+                    .returns::<ReturnType<Vec<u8>>>()
+                    .params();
+
+                match ink_env::eval_contract(&params) {
+                    Ok(v) => {
+                        ink_env::debug_println!(
+                            "Received return value \"{:?}\" from contract {:?}",
+                            v,
+                            to
+                        );
+                        assert_eq!(
+                            v,
+                            &MAGIC_VALUE_RECEIVED[..],
+                            "The recipient contract at {:?} does not accept token transfers.\n
+                            Expected: {:?}, Got {:?}", to, MAGIC_VALUE_RECEIVED, v
+                        )
+                    }
+                    Err(e) => {
+                        match e {
+                            ink_env::Error::CodeNotFound
+                            | ink_env::Error::NotCallable => {
+                                // Our recipient wasn't a smart contract, so there's nothing more for
+                                // us to do
+                                ink_env::debug_println!(
+                                    "Recipient at {:?} is not a smart contract ({:?})",
+                                    to,
+                                    e
+                                );
+                            }
+                            _ => {
+                                // We got some sort of error from the call to our recipient smart
+                                // contract, and as such we must revert this call
+                                let msg = ink_prelude::format!(
+                                    "Got error \"{:?}\" while trying to call {:?}",
+                                    e,
+                                    to
+                                );
+                                ink_env::debug_println!("{}", &msg);
+                                panic!("{}", &msg)
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// ERC 721 basic implementation
+    impl IErc721 for Contract {
+        /// Creates a new ERC-721 token contract without metadata.
+        #[ink(constructor)]
+        fn new() -> Self {
+            Self::with_metadata(None, None, None)
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self._balance_of(&owner) as Balance
+        }
+
+        #[ink(message)]
+        fn owner_of(&self, id: TokenId) -> Option<AccountId> {
+            self.token_owner.get(&id).cloned()
+        }
+
+        #[ink(message)]
+        fn get_approved(&self, id: TokenId) -> Option<AccountId> {
+            self.token_approvals.get(&id).cloned()
+        }
+
+        #[ink(message)]
+        fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            *self
+                .operator_approvals
+                .get(&(owner, operator))
+                .unwrap_or(&false)
+        }
+
+        #[ink(message)]
+        fn set_approval_for_all(&mut self, to: AccountId, approved: bool) -> Result<()> {
+            let caller = self.env().caller();
+            ensure!(to != caller, Error::NotAllowed);
+
+            if approved {
+                self.operator_approvals.insert((caller, to), true);
+            } else {
+                self.operator_approvals.take(&(caller, to));
+            }
+
+            self.env().emit_event(ApprovalForAll {
+                owner: caller,
+                operator: to,
+                approved,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, to: AccountId, id: TokenId) -> Result<()> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            ensure!(
+                owner == caller || self.is_approved_for_all(owner, caller),
+                Error::NotAllowed
+            );
+            ensure!(to != AccountId::default(), Error::NotAllowed);
+            ensure!(to != owner, Error::NotAllowed);
+
+            self.token_approvals.insert(id, to);
+
+            self.env().emit_event(Approval {
+                from: caller,
+                to,
+                id,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, destination: AccountId, id: TokenId) -> Result<()> {
+            let caller = self.env().caller();
+            self._transfer_token_from(caller, destination, id)
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> Result<()> {
+            self._transfer_token_from(from, to, id)
+        }
+
+        #[ink(message)]
+        fn safe_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            let caller = self.env().caller();
+
+            self._transfer_token_from(from, to, id)?;
+            self._transfer_acceptance_check(caller, from, to, id, data);
+
+            Ok(())
+        }
+
+        /// Creates a new token owned by the caller.
+        ///
+        /// Note that as implemented anyone can mint tokens. If you were to deploy this to a
+        /// production environment you'd probably want to lock down the addresses that are allowed
+        /// to mint tokens.
+        #[ink(message)]
+        fn mint(&mut self, id: TokenId) -> Result<()> {
+            self._mint(id)
+        }
+
+        #[ink(message)]
+        fn burn(&mut self, id: TokenId) -> Result<()> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            ensure!(owner == caller, Error::NotOwner);
+
+            self._clear_approval(id);
+            self._remove_token_from(caller, id)?;
+            self._remove_token_from_all_tokens_enumeration(id)?;
+            self.token_uris.take(&id);
+            self.creators.take(&id);
+            self.royalties.take(&id);
+
+            self.env().emit_event(Transfer {
+                from: Some(caller),
+                to: None,
+                id,
+            });
+
+            Ok(())
+        }
+    }
+
+    impl IErc721Metadata for Contract {
+        #[ink(message)]
+        fn name(&self) -> Option<String> {
+            self.name.clone()
+        }
+
+        #[ink(message)]
+        fn symbol(&self) -> Option<String> {
+            self.symbol.clone()
+        }
+
+        /// The uri of the token is its own uri after the base uri, `None` for unknown tokens.
+        #[ink(message)]
+        fn token_uri(&self, id: TokenId) -> Option<String> {
+            if !self._exists(id) {
+                return None
+            }
+
+            let token_uri = self._get_token_uri(id);
+            // return uri
+            if self.base_uri.is_none() {
+                token_uri
+            } else if token_uri.is_some() {
+                Some(format!(
+                    "{0}{1}",
+                    &self.base_uri.clone().unwrap(),
+                    &token_uri.clone().unwrap()
+                ))
+            } else {
+                None
+            }
+        }
+    }
+
+    impl IErc721Enumerable for Contract {
+        #[ink(message)]
+        fn total_supply(&self) -> u32 {
+            self.all_tokens.len()
+        }
+
+        #[ink(message)]
+        fn token_by_index(&self, index: u32) -> Option<TokenId> {
+            self.all_tokens.get(index).cloned()
+        }
+
+        #[ink(message)]
+        fn token_of_owner_by_index(
+            &self,
+            owner: AccountId,
+            index: u32,
+        ) -> Option<TokenId> {
+            self.owned_tokens.get(&(owner, index)).cloned()
+        }
+
+        #[ink(message)]
+        fn tokens_of_owner(&self, owner: AccountId) -> Vec<TokenId> {
+            (0..self._balance_of(&owner))
+                .filter_map(|index| self.token_of_owner_by_index(owner, index))
+                .collect()
+        }
+    }
+
+    impl IErc721TokenReceiver for Contract {
+        #[ink(message, selector = "0x150B7A02")]
+        fn on_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _id: TokenId,
+            _data: Vec<u8>,
+        ) -> Vec<u8> {
+            // A contract which does not accept tokens must revert, so that tokens can not
+            // get stuck in it by a safe transfer.
+            //
+            // Note that the choice of whether or not to accept tokens is implementation specific,
+            // and we've decided to not accept them in this implementation.
+            unimplemented!("This smart contract does not accept token transfer.")
+        }
+    }
+
+    /// Unit tests.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use crate::erc721::Contract;
+
+        use ink_lang as ink;
+
+        #[cfg(feature = "ink-experimental-engine")]
+        fn set_sender(sender: AccountId) {
+            ink_env::test::set_caller::<Environment>(sender);
+        }
+
+        #[cfg(not(feature = "ink-experimental-engine"))]
+        fn set_sender(sender: AccountId) {
+            const WALLET: [u8; 32] = [7; 32];
+            ink_env::test::push_execution_context::<Environment>(
+                sender,
+                WALLET.into(),
+                1000000,
+                1000000,
+                ink_env::test::CallData::new(ink_env::call::Selector::new([0x00; 4])), /* dummy */
+            );
+        }
+
+        #[cfg(feature = "ink-experimental-engine")]
+        fn default_accounts() -> ink_env::test::DefaultAccounts<Environment> {
+            ink_env::test::default_accounts::<Environment>()
+        }
+
+        #[cfg(not(feature = "ink-experimental-engine"))]
+        fn default_accounts() -> ink_env::test::DefaultAccounts<Environment> {
+            ink_env::test::default_accounts::<Environment>()
+                .expect("off-chain environment should have been initialized already")
+        }
+
+        fn alice() -> AccountId {
+            default_accounts().alice
+        }
+
+        fn bob() -> AccountId {
+            default_accounts().bob
+        }
+
+        fn charlie() -> AccountId {
+            default_accounts().charlie
+        }
+
+        fn init_contract() -> Contract {
+            let mut erc = Contract::new();
+            set_sender(alice());
+            assert!(erc.mint(1).is_ok());
+            assert!(erc.mint(2).is_ok());
+            set_sender(bob());
+            assert!(erc.mint(3).is_ok());
+            set_sender(alice());
+
+            erc
+        }
+
+        #[ink::test]
+        fn minting_tokens_works() {
+            let erc = init_contract();
+
+            assert_eq!(erc.balance_of(alice()), 2);
+            assert_eq!(erc.balance_of(bob()), 1);
+            assert_eq!(erc.balance_of(charlie()), 0);
+            assert_eq!(erc.owner_of(1), Some(alice()));
+            assert_eq!(erc.owner_of(3), Some(bob()));
+            assert_eq!(erc.owner_of(4), None);
+        }
+
+        #[ink::test]
+        fn minting_existing_token_fails() {
+            let mut erc = init_contract();
+
+            assert_eq!(erc.mint(3).unwrap_err(), Error::TokenExists);
+            assert_eq!(erc.owner_of(3), Some(bob()));
+        }
+
+        #[ink::test]
+        fn can_transfer_tokens_between_accounts() {
+            let mut erc = init_contract();
+
+            assert!(erc.transfer(bob(), 1).is_ok());
+            assert_eq!(erc.owner_of(1), Some(bob()));
+            assert_eq!(erc.balance_of(alice()), 1);
+            assert_eq!(erc.balance_of(bob()), 2);
+        }
+
+        #[ink::test]
+        fn transferring_token_of_another_account_fails() {
+            let mut erc = init_contract();
+
+            assert_eq!(erc.transfer(charlie(), 3).unwrap_err(), Error::NotApproved);
+            assert_eq!(
+                erc.transfer_from(bob(), charlie(), 3).unwrap_err(),
+                Error::NotApproved
+            );
+            assert_eq!(
+                erc.transfer(charlie(), 9).unwrap_err(),
+                Error::TokenNotFound
+            );
+        }
+
+        #[ink::test]
+        fn transferring_tokens_to_zero_address_fails() {
+            let burn: AccountId = [0; 32].into();
+
+            let mut erc = init_contract();
+            assert_eq!(erc.transfer(burn, 1).unwrap_err(), Error::NotAllowed);
+        }
+
+        #[ink::test]
+        fn approved_account_can_transfer_token_once() {
+            let mut erc = init_contract();
+
+            assert!(erc.approve(bob(), 1).is_ok());
+            assert_eq!(erc.get_approved(1), Some(bob()));
+
+            set_sender(bob());
+            assert!(erc.transfer_from(alice(), charlie(), 1).is_ok());
+            assert_eq!(erc.owner_of(1), Some(charlie()));
+            // the approval is cleared by the transfer
+            assert_eq!(erc.get_approved(1), None);
+            assert_eq!(
+                erc.transfer_from(charlie(), bob(), 1).unwrap_err(),
+                Error::NotApproved
+            );
+        }
+
+        #[ink::test]
+        fn operator_can_transfer_tokens() {
+            let mut erc = init_contract();
+
+            assert!(erc.set_approval_for_all(bob(), true).is_ok());
+            assert!(erc.is_approved_for_all(alice(), bob()));
+
+            set_sender(bob());
+            assert!(erc
+                .safe_transfer_from(alice(), charlie(), 2, vec![])
+                .is_ok());
+            assert_eq!(erc.owner_of(2), Some(charlie()));
+
+            set_sender(alice());
+            assert!(erc.set_approval_for_all(bob(), false).is_ok());
+            assert!(!erc.is_approved_for_all(alice(), bob()));
+
+            set_sender(bob());
+            assert_eq!(
+                erc.safe_transfer_from(alice(), charlie(), 1, vec![])
+                    .unwrap_err(),
+                Error::NotApproved
+            );
+        }
+
+        #[ink::test]
+        fn safe_transfer_checks_the_owner() {
+            let mut erc = init_contract();
+
+            assert!(erc.set_approval_for_all(bob(), true).is_ok());
+            assert_eq!(
+                erc.safe_transfer_from(bob(), charlie(), 1, vec![])
+                    .unwrap_err(),
+                Error::NotOwner
+            );
+            assert!(erc
+                .safe_transfer_from(alice(), bob(), 1, vec![1, 2])
+                .is_ok());
+            assert_eq!(erc.owner_of(1), Some(bob()));
+        }
+
+        #[ink::test]
+        fn enumeration_follows_transfers() {
+            let mut erc = init_contract();
+
+            assert_eq!(erc.total_supply(), 3);
+            assert_eq!(erc.token_by_index(0), Some(1));
+            assert_eq!(erc.token_by_index(2), Some(3));
+            assert_eq!(erc.token_by_index(3), None);
+            assert_eq!(erc.tokens_of_owner(alice()), vec![1, 2]);
+
+            // the last token of alice takes the place of the transferred one
+            assert!(erc.transfer(bob(), 1).is_ok());
+            assert_eq!(erc.tokens_of_owner(alice()), vec![2]);
+            assert_eq!(erc.tokens_of_owner(bob()), vec![3, 1]);
+            assert_eq!(erc.token_of_owner_by_index(bob(), 1), Some(1));
+            assert_eq!(erc.token_of_owner_by_index(bob(), 2), None);
+            assert_eq!(erc.total_supply(), 3);
+        }
+
+        #[ink::test]
+        fn burning_tokens_works() {
+            let mut erc = init_contract();
+
+            assert_eq!(erc.burn(3).unwrap_err(), Error::NotOwner);
+            assert_eq!(erc.burn(9).unwrap_err(), Error::TokenNotFound);
+
+            assert!(erc.approve(charlie(), 1).is_ok());
+            assert!(erc.burn(1).is_ok());
+            assert_eq!(erc.owner_of(1), None);
+            assert_eq!(erc.get_approved(1), None);
+            assert_eq!(erc.balance_of(alice()), 1);
+            assert_eq!(erc.total_supply(), 2);
+            assert_eq!(erc.token_by_index(0), Some(3));
+            assert_eq!(erc.token_by_index(1), Some(2));
+            assert_eq!(erc.tokens_of_owner(alice()), vec![2]);
+
+            // a burned token can be minted again
+            assert!(erc.mint(1).is_ok());
+            assert_eq!(erc.tokens_of_owner(alice()), vec![2, 1]);
+        }
+
+        #[ink::test]
+        fn token_uri_works() {
+            let mut erc = Contract::with_metadata(
+                Some(String::from("Mintcraft")),
+                Some(String::from("MC")),
+                None,
+            );
+            assert_eq!(erc.name(), Some(String::from("Mintcraft")));
+            assert_eq!(erc.symbol(), Some(String::from("MC")));
+
+            assert!(erc
+                .mint_with_uri(1, Some(String::from("sword.json")))
+                .is_ok());
+            assert!(erc.mint(2).is_ok());
+            assert_eq!(erc.token_uri(1), Some(String::from("sword.json")));
+            assert_eq!(erc.token_uri(2), None);
+            assert_eq!(erc.token_uri(3), None);
+
+            assert!(erc
+                .set_base_uri(Some(String::from("ipfs://items/")))
+                .is_ok());
+            assert_eq!(
+                erc.token_uri(1),
+                Some(String::from("ipfs://items/sword.json"))
+            );
+
+            assert!(erc.burn(1).is_ok());
+            assert_eq!(erc.token_uri(1), None);
+        }
+
+        #[ink::test]
+        fn royalty_info_works() {
+            let mut erc = Contract::new();
+
+            set_sender(alice());
+            assert_eq!(
+                erc.mint_with_royalty(1, 10_001).unwrap_err(),
+                Error::InvalidRoyalty
+            );
+            assert_eq!(erc.owner_of(1), None);
+            assert!(erc.mint_with_royalty(1, 250).is_ok());
+            assert!(erc.mint(2).is_ok());
+
+            // the royalty stays with the creator when the token is sold on
+            assert!(erc.transfer(bob(), 1).is_ok());
+            assert_eq!(erc.royalty_info(1, 1_000), Ok((alice(), 25)));
+            assert_eq!(erc.royalty_info(1, 39), Ok((alice(), 0)));
+            assert_eq!(erc.royalty_info(2, 1_000), Ok((AccountId::default(), 0)));
+            assert_eq!(
+                erc.royalty_info(1, Balance::MAX).unwrap_err(),
+                Error::RoyaltyOverflow
+            );
+
+            // a burned token owes nothing
+            set_sender(bob());
+            assert!(erc.burn(1).is_ok());
+            assert_eq!(erc.royalty_info(1, 1_000), Ok((AccountId::default(), 0)));
+        }
+
+        #[ink::test]
+        fn set_baseurl_only_by_owner() {
+            let mut erc = Contract::new();
+
+            set_sender(bob());
+            assert_eq!(
+                erc.set_base_uri(Some(String::from("test"))).unwrap_err(),
+                Error::NotContractOwner
+            );
+
+            set_sender(alice());
+            assert!(erc.set_base_uri(Some(String::from("test"))).is_ok());
+            assert_eq!(erc.base_uri, Some(String::from("test")));
+        }
+    }
+}
@@ -61,6 +61,24 @@ mod erc1155 {
             NotContractOwner,
             // The caller is not the creator of the token.
             NotTokenCreator,
+            // The caller is neither the creator of the token nor a minter.
+            NotMinter,
+            // The caller is not allowed to set the metadata uris.
+            NotUriSetter,
+            // The supply of the token would go above its max supply.
+            MaxSupplyExceeded,
+            // The royalty is above 10000 basis points.
+            InvalidRoyalty,
+            // The royalty of the sale price does not fit in a balance.
+            RoyaltyOverflow,
+            // The seller does not list enough of the token.
+            NotListed,
+            // The transferred balance does not match the price.
+            WrongPayment,
+            // The deadline of the signed payload has passed.
+            SignatureExpired,
+            // The signature is not the one of the account for this payload.
+            InvalidSignature,
         }
     }
 
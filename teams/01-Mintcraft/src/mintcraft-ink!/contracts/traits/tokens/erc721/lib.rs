@@ -17,9 +17,12 @@
 
 // Export to pub
 pub use self::erc721::{
+    consts,
     Error,
     IErc721,
+    IErc721Enumerable,
     IErc721Metadata,
+    IErc721TokenReceiver,
     Result,
     TokenId,
 };
@@ -30,7 +33,10 @@ use ink_lang as ink;
 #[ink::contract]
 mod erc721 {
     use ink_lang as ink;
-    use ink_prelude::string::String;
+    use ink_prelude::{
+        string::String,
+        vec::Vec,
+    };
     use scale::{
         Decode,
         Encode,
@@ -53,6 +59,9 @@ mod erc721 {
         CannotRemove,
         CannotFetchValue,
         NotAllowed,
+        NotContractOwner,
+        InvalidRoyalty,
+        RoyaltyOverflow,
     }
 
     /// Event emitted when a token transfer occurs.
@@ -134,6 +143,18 @@ mod erc721 {
             id: TokenId,
         ) -> Result<()>;
 
+        /// Transfer approved or owned token, checking that a contract at `to`
+        /// accepts it by calling its `IErc721TokenReceiver::on_received` with
+        /// `data`.
+        #[ink(message)]
+        fn safe_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> Result<()>;
+
         /// Creates a new token.
         #[ink(message)]
         fn mint(&mut self, id: TokenId) -> Result<()>;
@@ -163,6 +184,70 @@ mod erc721 {
         fn token_uri(&self, id: TokenId) -> Option<String>;
     }
 
+    /// Trait implmented by all ERC-721 with enumeration
+    #[ink::trait_definition]
+    pub trait IErc721Enumerable {
+        /// Count of the tokens tracked by this contract.
+        #[ink(message)]
+        fn total_supply(&self) -> u32;
+
+        /// Enumerate the tokens, their order is not specified and may change
+        /// when a token is burned.
+        #[ink(message)]
+        fn token_by_index(&self, index: u32) -> Option<TokenId>;
+
+        /// Enumerate the tokens of the owner, their order is not specified
+        /// and may change when a token of the owner is transferred.
+        #[ink(message)]
+        fn token_of_owner_by_index(
+            &self,
+            owner: AccountId,
+            index: u32,
+        ) -> Option<TokenId>;
+
+        /// Returns all the tokens of the owner.
+        #[ink(message)]
+        fn tokens_of_owner(&self, owner: AccountId) -> Vec<TokenId>;
+    }
+
+    pub mod consts {
+        // This is the "magic" return value that we expect if a smart contract supports receiving ERC-721
+        // tokens.
+        //
+        // It is calculated with
+        // `bytes4(keccak256("onERC721Received(address,address,uint256,bytes)"))`, and corresponds
+        // to 0x150b7a02.
+        #[cfg_attr(test, allow(dead_code))]
+        pub const MAGIC_VALUE_RECEIVED: [u8; 4] = [0x15, 0x0B, 0x7A, 0x02];
+    }
+
+    /// The interface for an ERC-721 Token Receiver contract.
+    ///
+    /// Smart contracts which want to accept safe token transfers must implement this
+    /// interface. Any `safe_transfer_from` to a contract which does not implement it
+    /// must be reverted.
+    #[ink::trait_definition]
+    pub trait IErc721TokenReceiver {
+        /// Handle the receipt of an ERC-721 token.
+        ///
+        /// If the smart contract implementing this interface accepts the token then it must
+        /// return `MAGIC_VALUE_RECEIVED` from this function. To reject the transfer it must revert.
+        ///
+        /// @param _operator  The address which initiated the transfer (i.e. msg.sender)
+        /// @param _from      The address which previously owned the token
+        /// @param _id        The ID of the token being transferred
+        /// @param _data      Additional data with no specified format
+        /// @return           `bytes4(keccak256("onERC721Received(address,address,uint256,bytes)"))` (i.e. 0x150b7a02)
+        #[ink(message)]
+        fn on_received(
+            &mut self,
+            operator: AccountId,
+            from: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> Vec<u8>;
+    }
+
     // TODO tmp hack struct for passing compile
     #[ink(storage)]
     pub struct Phantom;
@@ -0,0 +1,157 @@
+//! Signatures of chain accounts, checked by the contracts.
+//!
+//! The game engine signs payloads for its players and the gateway relays them, so the
+//! contracts act for accounts which are not the caller. The signatures are the ones of
+//! substrate accounts: sr25519, where the account is the public key, and ecdsa, where the
+//! account is the blake2-256 hash of the compressed public key.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_env::hash::{
+    Blake2x256,
+    CryptoHash,
+    HashOutput,
+};
+use ink_prelude::vec::Vec;
+
+/// The signing context of the sr25519 signatures of substrate.
+const SIGNING_CONTEXT: &[u8] = b"substrate";
+
+/// A signature of a payload by an account.
+#[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Signature {
+    /// 64 bytes sr25519 signature of the payload.
+    Sr25519(Vec<u8>),
+    /// 65 bytes recoverable ecdsa signature of the blake2-256 hash of the payload.
+    Ecdsa(Vec<u8>),
+}
+
+impl Signature {
+    /// Returns true if this is a signature of `payload` by the 32 bytes `account`.
+    pub fn verify(&self, payload: &[u8], account: &[u8]) -> bool {
+        match self {
+            Signature::Sr25519(signature) => verify_sr25519(signature, payload, account),
+            Signature::Ecdsa(signature) => verify_ecdsa(signature, payload, account),
+        }
+    }
+}
+
+/// Hash `input` with blake2-256, like the ecdsa signatures and accounts.
+pub fn blake2_256(input: &[u8]) -> [u8; 32] {
+    let mut output = <<Blake2x256 as HashOutput>::Type as Default>::default();
+    <Blake2x256 as CryptoHash>::hash(input, &mut output);
+    output
+}
+
+fn verify_sr25519(signature: &[u8], payload: &[u8], account: &[u8]) -> bool {
+    let public = match schnorrkel::PublicKey::from_bytes(account) {
+        Ok(public) => public,
+        Err(_) => return false,
+    };
+    let signature = match schnorrkel::Signature::from_bytes(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+
+    public
+        .verify_simple(SIGNING_CONTEXT, payload, &signature)
+        .is_ok()
+}
+
+fn verify_ecdsa(signature: &[u8], payload: &[u8], account: &[u8]) -> bool {
+    if signature.len() != 65 {
+        return false
+    }
+
+    let message = secp256k1::Message::parse(&blake2_256(payload));
+    let rs = match secp256k1::Signature::parse_slice(&signature[..64]) {
+        Ok(rs) => rs,
+        Err(_) => return false,
+    };
+    // Accept the ethereum style recovery ids as well
+    let v = if signature[64] > 26 {
+        signature[64] - 27
+    } else {
+        signature[64]
+    };
+    let v = match secp256k1::RecoveryId::parse(v) {
+        Ok(v) => v,
+        Err(_) => return false,
+    };
+
+    match secp256k1::recover(&message, &rs, &v) {
+        Ok(public) => blake2_256(&public.serialize_compressed())[..] == *account,
+        Err(_) => false,
+    }
+}
+
+/// Signing for the off-chain tests of the contracts.
+///
+/// The contracts enable it with the `test-utils` feature in their dev-dependencies.
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing {
+    use super::{
+        Signature,
+        SIGNING_CONTEXT,
+    };
+
+    /// The sr25519 key pair of the owner of the signed payloads.
+    pub fn owner_pair() -> schnorrkel::Keypair {
+        schnorrkel::MiniSecretKey::from_bytes(&[7; 32])
+            .unwrap()
+            .expand_to_keypair(schnorrkel::ExpansionMode::Ed25519)
+    }
+
+    /// Sign `payload` like a substrate account.
+    pub fn sign(pair: &schnorrkel::Keypair, payload: &[u8]) -> Signature {
+        Signature::Sr25519(
+            pair.sign_simple(SIGNING_CONTEXT, payload)
+                .to_bytes()
+                .to_vec(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        testing::*,
+        *,
+    };
+
+    #[test]
+    fn sr25519_signatures_verify() {
+        let pair = owner_pair();
+        let account = pair.public.to_bytes();
+        let signature = sign(&pair, b"payload");
+
+        assert!(signature.verify(b"payload", &account));
+        assert!(!signature.verify(b"other payload", &account));
+        assert!(!signature.verify(b"payload", &[1; 32]));
+        assert!(!Signature::Sr25519(vec![0; 12]).verify(b"payload", &account));
+    }
+
+    #[test]
+    fn ecdsa_signatures_verify() {
+        let secret = secp256k1::SecretKey::parse(&[7; 32]).unwrap();
+        let public = secp256k1::PublicKey::from_secret_key(&secret);
+        let account = blake2_256(&public.serialize_compressed());
+
+        let message = secp256k1::Message::parse(&blake2_256(b"payload"));
+        let (rs, v) = secp256k1::sign(&message, &secret);
+        let signature = |v: u8| {
+            let mut bytes = rs.serialize().to_vec();
+            bytes.push(v);
+            Signature::Ecdsa(bytes)
+        };
+
+        // substrate and ethereum style recovery ids
+        assert!(signature(v.serialize()).verify(b"payload", &account));
+        assert!(signature(v.serialize() + 27).verify(b"payload", &account));
+        assert!(!signature(v.serialize() ^ 1).verify(b"payload", &account));
+        assert!(!signature(v.serialize()).verify(b"other payload", &account));
+        assert!(!signature(v.serialize()).verify(b"payload", &[1; 32]));
+        assert!(!Signature::Ecdsa(vec![0; 64]).verify(b"payload", &account));
+    }
+}
@@ -18,6 +18,7 @@ export function Main(props) {
   // Learn more at: https://reactjs.org/docs/hooks-intro.html
   const [status, setStatus] = useState('');
   const [digest, setDigest] = useState('');
+  const [title, setTitle] = useState('');
   const [owner, setOwner] = useState('');
   const [block, setBlock] = useState(0);
 
@@ -34,6 +35,7 @@ export function Main(props) {
     const jsonContent = JSON.stringify(allContent)
     const hash = blake2AsHex(jsonContent, 256);
     console.log(hash)
+    setTitle(stageTitle);
     setDigest(hash);
   }
 
@@ -46,9 +48,15 @@ export function Main(props) {
     // even if it changes.
     api.query.poe
       .proofs(digest, (result) => {
-        // Our storage item returns a tuple, which is represented as an array.
-        setOwner(result[0].toString());
-        setBlock(result[1].toNumber());
+        // Our storage item returns an optional proof, empty for unclaimed digests.
+        if (result.isSome) {
+          const proof = result.unwrap();
+          setOwner(proof.owner.toString());
+          setBlock(proof.created.toNumber());
+        } else {
+          setOwner('');
+          setBlock(0);
+        }
       })
       .then((unsub) => {
         unsubscribe = unsub;
@@ -84,8 +92,8 @@ export function Main(props) {
             attrs={{
               palletRpc: 'poe',
               callable: 'createProof',
-              inputParams: [digest],
-              paramFields: [true]
+              inputParams: [digest, 'Blake2_256', title, 'Story', null, []],
+              paramFields: [true, true, true, true, { optional: true }, true]
             }}
           />
           {/* Button to revoke a claim. Only active if a file is selected,
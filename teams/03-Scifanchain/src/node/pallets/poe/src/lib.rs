@@ -4,19 +4,105 @@
 
 pub use pallet::*;
 
+pub mod migrations;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::{
-        dispatch::DispatchResultWithPostInfo, 
+        dispatch::DispatchResultWithPostInfo,
         pallet_prelude::*,
+        storage::bounded_vec::BoundedVec,
+        traits::{Currency, ReservableCurrency},
+        RuntimeDebug,
     };
     use frame_system::pallet_prelude::*;
-    use sp_std::vec::Vec;
+    use sp_std::{convert::TryFrom, vec::Vec};
+
+    type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    /// The digest of a work, the key of its proof.
+    pub type ContentHash = [u8; 32];
+
+    /// How the digest of a work was computed.
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum HashAlgorithm {
+        /// The blake2-256 hash of the work.
+        Blake2_256,
+        /// The sha2-256 hash of the work.
+        Sha2_256,
+        /// The sha2-256 digest of the IPFS CIDv1 of the work.
+        Cidv1,
+    }
+
+    /// The kind of a work.
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum WorkType {
+        Story,
+        Chapter,
+        Setting,
+        Character,
+        Other,
+    }
+
+    /// The proof of existence of a work.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct Proof<AccountId, BlockNumber, Balance, Title> {
+        /// The owner of the proof, who may transfer or revoke it.
+        pub owner: AccountId,
+        /// The block at which the work was claimed.
+        pub created: BlockNumber,
+        pub algorithm: HashAlgorithm,
+        pub title: Title,
+        pub work_type: WorkType,
+        /// The proof of the work this one builds on, which its owner approves with
+        /// `approve_derivation`.
+        pub parent: Option<ContentHash>,
+        /// The share in percent of the owner, the co-authors holding the rest.
+        pub owner_share: u8,
+        /// The number of co-authors who have not signed the claim yet.
+        pub unsigned: u32,
+        /// The account which reserved the deposit, refunded when the proof is revoked.
+        pub depositor: AccountId,
+        pub deposit: Balance,
+    }
+
+    pub type ProofOf<T> = Proof<
+        <T as frame_system::Config>::AccountId,
+        <T as frame_system::Config>::BlockNumber,
+        BalanceOf<T>,
+        BoundedVec<u8, <T as Config>::MaxTitleLength>,
+    >;
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
         /// Because this pallet emits events, it depends on the runtime's definition of an event.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The currency of the proof deposits.
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// The deposit reserved for each proof.
+        #[pallet::constant]
+        type ProofDeposit: Get<BalanceOf<Self>>;
+
+        /// The maximum length of the title of a work.
+        #[pallet::constant]
+        type MaxTitleLength: Get<u32>;
+
+        /// The maximum number of co-authors of a work.
+        #[pallet::constant]
+        type MaxCoAuthors: Get<u32>;
+
+        /// The maximum number of works building on a work without the approval of its owner.
+        #[pallet::constant]
+        type MaxDerivations: Get<u32>;
     }
 
     // Pallets use events to inform users when important changes are made.
@@ -25,11 +111,22 @@ pub mod pallet {
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// Event emitted when a proof has been claimed. 
-        ProofCreated(T::AccountId, Vec<u8>),
+        ProofCreated(T::AccountId, ContentHash),
         /// Event emitted when a proof is revoked by the owner.
-        ProofRevoked(T::AccountId, Vec<u8>),
+        ProofRevoked(T::AccountId, ContentHash),
         /// Event transfered when a proof is tranfered to others.
-        ProofTransferred(T::AccountId, T::AccountId, Vec<u8>),
+        ProofTransferred(T::AccountId, T::AccountId, ContentHash),
+        /// Event emitted when a co-author signs the claim of a proof.
+        ProofSigned(T::AccountId, ContentHash),
+        /// Event emitted when all the co-authors of a proof have signed it.
+        ProofConfirmed(ContentHash),
+        /// Event emitted when a co-author withdraws its signature of a proof.
+        SignatureWithdrawn(T::AccountId, ContentHash),
+        /// Event emitted when the owner of a proof approves a work building on it.
+        DerivationApproved(ContentHash, ContentHash),
+        /// Event emitted when the storage migration drops a claim without a content hash, or
+        /// whose content hash was claimed again: its owner and its claimed bytes.
+        LegacyProofDropped(T::AccountId, Vec<u8>),
     }
 
     #[pallet::error]
@@ -40,33 +137,130 @@ pub mod pallet {
             NoSuchProof,
             /// It is claimed by another account, so caller can't revoke it.
             NotOwner,
+            /// The title is longer than `MaxTitleLength`.
+            TitleTooLong,
+            /// There are more than `MaxCoAuthors` co-authors.
+            TooManyCoAuthors,
+            /// A co-author has no share, or the shares exceed 100 percent.
+            InvalidShares,
+            /// A co-author is listed twice, or is the owner.
+            DuplicateCoAuthor,
+            /// The parent work has no proof.
+            NoSuchParent,
+            /// The parent work has `MaxDerivations` derivations waiting for approval.
+            TooManyDerivations,
+            /// Approved works build on the proof, so it cannot be revoked.
+            HasDerivatives,
+            /// The work does not claim to build on the proof.
+            NoSuchDerivation,
+            /// The caller is not a co-author of the proof.
+            NotCoAuthor,
+            /// The co-author has already signed the proof.
+            AlreadySigned,
+            /// The co-author has not signed the proof.
+            NotSigned,
+            /// A co-author has signed the proof, so the owner cannot revoke it alone.
+            CoAuthorsSigned,
+            /// The recipient is a co-author of the proof, whose shares would no longer add up.
+            RecipientIsCoAuthor,
         }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
     pub struct Pallet<T>(_);
     
-    #[pallet::storage] 
-    pub(super) type Proofs<T: Config> = StorageMap<
+    #[pallet::storage]
+    #[pallet::getter(fn proofs)]
+    pub type Proofs<T: Config> = StorageMap<
          _, 
          Blake2_128Concat, 
-         Vec<u8>, 
-         (T::AccountId, T::BlockNumber), 
+         ContentHash, 
+         ProofOf<T>, 
+         OptionQuery
+         >;
+
+    /// The share in percent of each co-author of a proof, and whether it signed the claim.
+    #[pallet::storage]
+    #[pallet::getter(fn co_authors)]
+    pub type CoAuthors<T: Config> = StorageDoubleMap<
+         _,
+         Blake2_128Concat,
+         ContentHash,
+         Blake2_128Concat,
+         T::AccountId,
+         (u8, bool),
+         OptionQuery
+         >;
+
+    /// The derivation graph: the proofs of the works building on each proof, and whether the
+    /// owner of the proof approved them.
+    #[pallet::storage]
+    #[pallet::getter(fn derivations)]
+    pub type Derivations<T: Config> = StorageDoubleMap<
+         _,
+         Blake2_128Concat,
+         ContentHash,
+         Blake2_128Concat,
+         ContentHash,
+         bool,
+         OptionQuery
+         >;
+
+    /// The number of derivations of each proof its owner has not approved yet.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_derivations)]
+    pub type PendingDerivations<T: Config> = StorageMap<
+         _,
+         Blake2_128Concat,
+         ContentHash,
+         u32,
          ValueQuery
          >;
 
+    /// The number of approved derivations of each proof.
+    #[pallet::storage]
+    #[pallet::getter(fn approved_derivations)]
+    pub type ApprovedDerivations<T: Config> = StorageMap<
+         _,
+         Blake2_128Concat,
+         ContentHash,
+         u32,
+         ValueQuery
+         >;
+
+    /// The last key of `Proofs` visited by the migration to version 4, while it runs.
+    #[pallet::storage]
+    pub(super) type MigrationCursor<T> = StorageValue<_, Vec<u8>, OptionQuery>;
+
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            crate::migrations::v4::migrate_step::<T>(crate::migrations::v4::PROOFS_PER_BLOCK)
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            crate::migrations::v4::migrate::<T>()
+        }
+    }
     
     // Dispatchable functions allows users to interact with the pallet and invoke state changes.
     // These functions materialize as "extrinsics", which are often compared to transactions.
     // Dispatchable functions must be annotated with a weight and must return a DispatchResult.
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        #[pallet::weight(1_000)]
+        /// Claim the work hashed to `proof`, with its metadata and co-authors.
+        ///
+        /// Each co-author holds a share in percent of the work, the owner holding the rest,
+        /// and must sign the claim with `sign_proof`.
+        #[pallet::weight(Pallet::<T>::create_proof_weight())]
         pub(super) fn create_proof(
             origin: OriginFor<T>,
-            proof: Vec<u8>,
+            proof: ContentHash,
+            algorithm: HashAlgorithm,
+            title: Vec<u8>,
+            work_type: WorkType,
+            parent: Option<ContentHash>,
+            co_authors: Vec<(T::AccountId, u8)>,
         ) -> DispatchResultWithPostInfo {
 
             // Check that the extrinsic was signed and get the signer.
@@ -75,12 +269,60 @@ pub mod pallet {
             // Verify that the specified proof has not already been claimed.         
             ensure!(!Proofs::<T>::contains_key(&proof), Error::<T>::AlreadyExist);
 
+            let title = BoundedVec::<u8, T::MaxTitleLength>::try_from(title)
+                .map_err(|_| Error::<T>::TitleTooLong)?;
+            if let Some(parent) = &parent {
+                ensure!(Proofs::<T>::contains_key(parent), Error::<T>::NoSuchParent);
+                ensure!(
+                    PendingDerivations::<T>::get(parent) < T::MaxDerivations::get(),
+                    Error::<T>::TooManyDerivations
+                );
+            }
+
+            // Verify the co-authors and their shares.
+            ensure!(
+                co_authors.len() <= T::MaxCoAuthors::get() as usize,
+                Error::<T>::TooManyCoAuthors
+            );
+            let mut shares: u32 = 0;
+            for (i, (co_author, share)) in co_authors.iter().enumerate() {
+                ensure!(*share > 0, Error::<T>::InvalidShares);
+                ensure!(
+                    *co_author != sender && !co_authors[..i].iter().any(|(a, _)| a == co_author),
+                    Error::<T>::DuplicateCoAuthor
+                );
+                shares += *share as u32;
+            }
+            ensure!(shares <= 100, Error::<T>::InvalidShares);
+
+            // Reserve the deposit of the proof.
+            let deposit = T::ProofDeposit::get();
+            T::Currency::reserve(&sender, deposit)?;
+
             // Get the block number from the FRAME System.
             //let current_block = <frame_system::Module<T>>::block_number();
             let current_block = <frame_system::pallet::Pallet<T>>::block_number();
 
             // Store the proof with the sender and block number.
-            Proofs::<T>::insert(&proof, (&sender, current_block));
+            Proofs::<T>::insert(&proof, Proof {
+                owner: sender.clone(),
+                created: current_block,
+                algorithm,
+                title,
+                work_type,
+                parent,
+                owner_share: (100 - shares) as u8,
+                unsigned: co_authors.len() as u32,
+                depositor: sender.clone(),
+                deposit,
+            });
+            for (co_author, share) in co_authors {
+                CoAuthors::<T>::insert(&proof, co_author, (share, false));
+            }
+            if let Some(parent) = parent {
+                Derivations::<T>::insert(parent, &proof, false);
+                PendingDerivations::<T>::mutate(parent, |count| *count += 1);
+            }
 
             // Emit an event that the claim was created.
             Self::deposit_event(Event::ProofCreated(sender, proof));
@@ -88,26 +330,56 @@ pub mod pallet {
             Ok(().into())
         }
 
-        #[pallet::weight(10_000)]
+        /// Revoke `proof` as its owner, as long as none of its co-authors signed it, or all
+        /// withdrew their signatures.
+        ///
+        /// The works building on it without approval lose their parent.
+        #[pallet::weight(Pallet::<T>::revoke_proof_weight())]
         fn revoke_proof(
             origin: OriginFor<T>,
-            proof: Vec<u8>,
+            proof: ContentHash,
         ) -> DispatchResultWithPostInfo {
            
             // Check that the extrinsic was signed and get the signer.
             let sender = ensure_signed(origin)?;
 
             // Verify that the specified proof has been claimed.
-            ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::NoSuchProof);
-
-            // Get owner of the claim.
-            let (owner, _) = Proofs::<T>::get(&proof);
+            let claim = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?;
 
             // Verify that sender of the current call is the claim owner.
-            ensure!(sender == owner, Error::<T>::NotOwner);
+            ensure!(sender == claim.owner, Error::<T>::NotOwner);
 
-            // Remove claim from storage.
+            // Verify that the co-authors did not agree to the claim, at most `MaxCoAuthors`.
+            ensure!(
+                !CoAuthors::<T>::iter_prefix_values(&proof).any(|(_, signed)| signed),
+                Error::<T>::CoAuthorsSigned
+            );
+
+            // Verify that no approved work builds on it, at most `MaxDerivations` others do.
+            ensure!(
+                ApprovedDerivations::<T>::get(&proof) == 0,
+                Error::<T>::HasDerivatives
+            );
+
+            // Remove claim from storage and refund its deposit.
             Proofs::<T>::remove(&proof);
+            CoAuthors::<T>::remove_prefix(&proof);
+            for (derivative, _) in Derivations::<T>::drain_prefix(&proof) {
+                Proofs::<T>::mutate(&derivative, |claim| {
+                    if let Some(claim) = claim {
+                        claim.parent = None;
+                    }
+                });
+            }
+            PendingDerivations::<T>::remove(&proof);
+            if let Some(parent) = claim.parent {
+                match Derivations::<T>::take(parent, &proof) {
+                    Some(true) => ApprovedDerivations::<T>::mutate(parent, |count| *count -= 1),
+                    Some(false) => PendingDerivations::<T>::mutate(parent, |count| *count -= 1),
+                    None => {}
+                }
+            }
+            let _ = T::Currency::unreserve(&claim.depositor, claim.deposit);
 
             // Emit an event that the claim was erased.
             Self::deposit_event(Event::ProofRevoked(sender, proof));
@@ -115,40 +387,150 @@ pub mod pallet {
             Ok(().into())
         }
 
+        /// Transfer `proof` and the share of its owner to `recipient`, who must not be one of
+        /// its co-authors.
         #[pallet::weight(1_000)]
         pub(super) fn transfer_proof(
             origin: OriginFor<T>,
             recipient: T::AccountId,
-            proof: Vec<u8>
+            proof: ContentHash,
         ) -> DispatchResultWithPostInfo {
 
             // Check that the extrinsic was signed and get the signer.
             let sender = ensure_signed(origin)?;
         
             // Verify that the specified proof has already been claimed.         
-            ensure!(Proofs::<T>::contains_key(&proof), Error::<T>::AlreadyExist);
-
-            // Get owner of the claim.
-            let (owner, _) = Proofs::<T>::get(&proof);
+            let mut claim = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?;
 
             // Verify that sender of the current call is the claim owner.
-            ensure!(sender == owner, Error::<T>::NotOwner);
+            ensure!(sender == claim.owner, Error::<T>::NotOwner);
+            ensure!(
+                !CoAuthors::<T>::contains_key(&proof, &recipient),
+                Error::<T>::RecipientIsCoAuthor
+            );
 
-            // Remove claim from storage.
-            Proofs::<T>::remove(&proof);
-
-            // Get the block number from the FRAME System.
-            //let current_block = <frame_system::Module<T>>::block_number();
-            let current_block = <frame_system::pallet::Pallet<T>>::block_number();
-
-            // Store the proof with the recipient and block number.
-            Proofs::<T>::insert(&proof, (&recipient, current_block));
+            // Store the proof with the recipient, keeping the block it was claimed at.
+            claim.owner = recipient.clone();
+            Proofs::<T>::insert(&proof, claim);
             
-            // Delete 
             // Emit an event that the proof was transferred.
             Self::deposit_event(Event::ProofTransferred(sender, recipient, proof));
 
             Ok(().into())
         }
+
+        /// Sign the claim of `proof` as one of its co-authors.
+        #[pallet::weight(1_000)]
+        pub(super) fn sign_proof(
+            origin: OriginFor<T>,
+            proof: ContentHash,
+        ) -> DispatchResultWithPostInfo {
+
+            // Check that the extrinsic was signed and get the signer.
+            let sender = ensure_signed(origin)?;
+
+            let mut claim = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?;
+            let (share, signed) = CoAuthors::<T>::get(&proof, &sender).ok_or(Error::<T>::NotCoAuthor)?;
+            ensure!(!signed, Error::<T>::AlreadySigned);
+
+            CoAuthors::<T>::insert(&proof, &sender, (share, true));
+            claim.unsigned -= 1;
+            let confirmed = claim.unsigned == 0;
+            Proofs::<T>::insert(&proof, claim);
+
+            Self::deposit_event(Event::ProofSigned(sender, proof));
+            if confirmed {
+                Self::deposit_event(Event::ProofConfirmed(proof));
+            }
+
+            Ok(().into())
+        }
+
+        /// Withdraw the signature of `proof` as one of its co-authors, who no longer agrees
+        /// to the claim.
+        ///
+        /// Once no co-author has signed it, the owner may revoke the proof and get its deposit
+        /// back.
+        #[pallet::weight(1_000)]
+        pub(super) fn withdraw_signature(
+            origin: OriginFor<T>,
+            proof: ContentHash,
+        ) -> DispatchResultWithPostInfo {
+
+            // Check that the extrinsic was signed and get the signer.
+            let sender = ensure_signed(origin)?;
+
+            let mut claim = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?;
+            let (share, signed) = CoAuthors::<T>::get(&proof, &sender).ok_or(Error::<T>::NotCoAuthor)?;
+            ensure!(signed, Error::<T>::NotSigned);
+
+            CoAuthors::<T>::insert(&proof, &sender, (share, false));
+            claim.unsigned += 1;
+            Proofs::<T>::insert(&proof, claim);
+
+            Self::deposit_event(Event::SignatureWithdrawn(sender, proof));
+
+            Ok(().into())
+        }
+
+        /// Approve, as the owner of `proof`, that the work of `derivative` builds on it.
+        ///
+        /// An approved derivation keeps `proof` from being revoked, until `derivative` is.
+        #[pallet::weight(1_000)]
+        pub(super) fn approve_derivation(
+            origin: OriginFor<T>,
+            proof: ContentHash,
+            derivative: ContentHash,
+        ) -> DispatchResultWithPostInfo {
+
+            // Check that the extrinsic was signed and get the signer.
+            let sender = ensure_signed(origin)?;
+
+            let claim = Proofs::<T>::get(&proof).ok_or(Error::<T>::NoSuchProof)?;
+            ensure!(sender == claim.owner, Error::<T>::NotOwner);
+            let approved = Derivations::<T>::get(&proof, &derivative)
+                .ok_or(Error::<T>::NoSuchDerivation)?;
+
+            if !approved {
+                Derivations::<T>::insert(&proof, &derivative, true);
+                PendingDerivations::<T>::mutate(&proof, |count| *count -= 1);
+                ApprovedDerivations::<T>::mutate(&proof, |count| *count += 1);
+                Self::deposit_event(Event::DerivationApproved(proof, derivative));
+            }
+
+            Ok(().into())
+        }
     } 
-}
\ No newline at end of file
+
+    impl<T: Config> Pallet<T> {
+        /// The weight of `create_proof` with `MaxCoAuthors` co-authors.
+        fn create_proof_weight() -> Weight {
+            let co_authors = T::MaxCoAuthors::get() as Weight;
+            T::DbWeight::get().reads_writes(4, 4 + co_authors).saturating_add(10_000)
+        }
+
+        /// The weight of `revoke_proof` with `MaxCoAuthors` co-authors and `MaxDerivations`
+        /// derivations, each losing its parent.
+        fn revoke_proof_weight() -> Weight {
+            let co_authors = T::MaxCoAuthors::get() as Weight;
+            let derivations = T::MaxDerivations::get() as Weight;
+            T::DbWeight::get()
+                .reads_writes(5 + co_authors + 2 * derivations, 5 + co_authors + 2 * derivations)
+                .saturating_add(10_000)
+        }
+
+        /// Whether all the co-authors of `proof` have signed it.
+        pub fn is_confirmed(proof: &ContentHash) -> bool {
+            Proofs::<T>::get(proof).map_or(false, |claim| claim.unsigned == 0)
+        }
+
+        /// The share in percent of `who` in the work of `proof`.
+        pub fn share_of(proof: &ContentHash, who: &T::AccountId) -> u8 {
+            match Proofs::<T>::get(proof) {
+                Some(claim) if claim.owner == *who => claim.owner_share,
+                Some(_) => CoAuthors::<T>::get(proof, who).map_or(0, |(share, _)| share),
+                None => 0,
+            }
+        }
+    }
+}
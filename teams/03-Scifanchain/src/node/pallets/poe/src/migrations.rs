@@ -0,0 +1,94 @@
+//! Storage migrations of the poe pallet.
+
+/// Version 4 keys the proofs by the content hash of the works, with their metadata.
+pub mod v4 {
+    use crate::{pallet::MigrationCursor, Config, Event, HashAlgorithm, Pallet, Proof, Proofs, WorkType};
+    use codec::{Decode, DecodeAll};
+    use frame_support::{
+        sp_runtime::traits::Zero,
+        storage::{unhashed, StoragePrefixedMap},
+        traits::{Get, GetPalletVersion, PalletVersion},
+        weights::Weight,
+    };
+    use sp_std::{convert::TryFrom, vec::Vec};
+
+    /// The most keys of `Proofs` visited by the migration in a block.
+    pub const PROOFS_PER_BLOCK: u32 = 64;
+
+    /// Start moving the proofs of the versions before 4, the owner and block of any claimed
+    /// bytes, to the proofs keyed by content hash, `PROOFS_PER_BLOCK` at a time from the next
+    /// block on.
+    pub fn migrate<T: Config>() -> Weight {
+        let storage_version = <Pallet<T> as GetPalletVersion>::storage_version();
+        if storage_version.map_or(false, |version| version >= PalletVersion::new(4, 0, 0)) {
+            return T::DbWeight::get().reads(1);
+        }
+
+        MigrationCursor::<T>::put(Proofs::<T>::final_prefix().to_vec());
+        T::DbWeight::get().reads_writes(1, 1)
+    }
+
+    /// Visit the next `limit` keys of `Proofs`, moving the claims of the versions before 4.
+    ///
+    /// The 32 bytes digests become Blake2-256 proofs of untitled works, without co-authors
+    /// nor deposit. The other claims, and the ones whose digest was claimed again since the
+    /// upgrade, are dropped with a `LegacyProofDropped` event.
+    pub fn migrate_step<T: Config>(limit: u32) -> Weight {
+        let mut cursor = match MigrationCursor::<T>::get() {
+            Some(cursor) => cursor,
+            None => return T::DbWeight::get().reads(1),
+        };
+        let prefix = Proofs::<T>::final_prefix();
+
+        let mut visited: Weight = 0;
+        while visited < limit as Weight {
+            let key = match sp_io::storage::next_key(&cursor).filter(|key| key.starts_with(&prefix)) {
+                Some(key) => key,
+                None => {
+                    MigrationCursor::<T>::kill();
+                    return T::DbWeight::get().reads_writes(1 + visited * 2, 1 + visited * 2);
+                }
+            };
+            visited += 1;
+            migrate_key::<T>(&prefix, &key);
+            cursor = key;
+        }
+
+        MigrationCursor::<T>::put(cursor);
+        T::DbWeight::get().reads_writes(1 + visited * 2, 1 + visited * 2)
+    }
+
+    /// Move the claim at `key` if it has the layout of the versions before 4, whose values
+    /// are only the owner and the block of the claim.
+    fn migrate_key<T: Config>(prefix: &[u8], key: &[u8]) {
+        let value = unhashed::get_raw(key).unwrap_or_default();
+        let (owner, created) = match <(T::AccountId, T::BlockNumber)>::decode_all(&value) {
+            Ok(claim) => claim,
+            Err(_) => return,
+        };
+        unhashed::kill(key);
+
+        // The Blake2_128Concat key ends with the claimed bytes.
+        let digest = key
+            .get(prefix.len() + 16..)
+            .and_then(|mut encoded| Vec::<u8>::decode(&mut encoded).ok())
+            .unwrap_or_default();
+        match <[u8; 32]>::try_from(&digest[..]) {
+            Ok(proof) if !Proofs::<T>::contains_key(&proof) => {
+                Proofs::<T>::insert(&proof, Proof {
+                    owner: owner.clone(),
+                    created,
+                    algorithm: HashAlgorithm::Blake2_256,
+                    title: Default::default(),
+                    work_type: WorkType::Other,
+                    parent: None,
+                    owner_share: 100,
+                    unsigned: 0,
+                    depositor: owner,
+                    deposit: Zero::zero(),
+                });
+            }
+            _ => Pallet::<T>::deposit_event(Event::LegacyProofDropped(owner, digest)),
+        }
+    }
+}
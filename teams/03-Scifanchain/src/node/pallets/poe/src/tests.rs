@@ -0,0 +1,302 @@
+use crate::{Error, Event as PoeEvent, HashAlgorithm, WorkType, mock::*, migrations::v4};
+use codec::Encode;
+use frame_support::{
+	assert_ok, assert_noop, migration, traits::PalletVersion, Blake2_128Concat, StorageHasher,
+};
+
+const STORY: [u8; 32] = [1; 32];
+const CHAPTER: [u8; 32] = [2; 32];
+
+fn create(who: u64, proof: [u8; 32], parent: Option<[u8; 32]>, co_authors: Vec<(u64, u8)>) {
+	assert_ok!(PoeModule::create_proof(
+		Origin::signed(who),
+		proof,
+		HashAlgorithm::Blake2_256,
+		b"Three Body".to_vec(),
+		WorkType::Story,
+		parent,
+		co_authors,
+	));
+}
+
+#[test]
+fn create_proof_works() {
+	new_test_ext().execute_with(|| {
+		create(1, STORY, None, vec![]);
+
+		let claim = PoeModule::proofs(STORY).unwrap();
+		assert_eq!(claim.owner, 1);
+		assert_eq!(claim.algorithm, HashAlgorithm::Blake2_256);
+		assert_eq!(claim.title.to_vec(), b"Three Body".to_vec());
+		assert_eq!(claim.owner_share, 100);
+		assert!(PoeModule::is_confirmed(&STORY));
+		assert_eq!(Balances::reserved_balance(1), 10);
+
+		assert_noop!(
+			PoeModule::create_proof(
+				Origin::signed(2), STORY, HashAlgorithm::Sha2_256, vec![], WorkType::Other, None, vec![],
+			),
+			Error::<Test>::AlreadyExist
+		);
+		assert_noop!(
+			PoeModule::create_proof(
+				Origin::signed(2), CHAPTER, HashAlgorithm::Cidv1, vec![0; 17], WorkType::Other, None, vec![],
+			),
+			Error::<Test>::TitleTooLong
+		);
+		// The deposit must be affordable.
+		assert!(PoeModule::create_proof(
+			Origin::signed(4), CHAPTER, HashAlgorithm::Cidv1, vec![], WorkType::Other, None, vec![],
+		).is_err());
+	});
+}
+
+#[test]
+fn co_authors_sign_and_share() {
+	new_test_ext().execute_with(|| {
+		let create_with = |co_authors| PoeModule::create_proof(
+			Origin::signed(1), STORY, HashAlgorithm::Blake2_256, vec![], WorkType::Story, None, co_authors,
+		);
+		assert_noop!(create_with(vec![(2, 60), (3, 41)]), Error::<Test>::InvalidShares);
+		assert_noop!(create_with(vec![(2, 0)]), Error::<Test>::InvalidShares);
+		assert_noop!(create_with(vec![(2, 10), (2, 10)]), Error::<Test>::DuplicateCoAuthor);
+		assert_noop!(create_with(vec![(1, 10)]), Error::<Test>::DuplicateCoAuthor);
+		assert_noop!(
+			create_with(vec![(2, 10), (3, 10), (4, 10)]),
+			Error::<Test>::TooManyCoAuthors
+		);
+
+		create(1, STORY, None, vec![(2, 30), (3, 20)]);
+		assert_eq!(PoeModule::share_of(&STORY, &1), 50);
+		assert_eq!(PoeModule::share_of(&STORY, &2), 30);
+		assert_eq!(PoeModule::share_of(&STORY, &4), 0);
+		assert!(!PoeModule::is_confirmed(&STORY));
+
+		assert_noop!(PoeModule::sign_proof(Origin::signed(4), STORY), Error::<Test>::NotCoAuthor);
+		assert_noop!(PoeModule::sign_proof(Origin::signed(2), CHAPTER), Error::<Test>::NoSuchProof);
+		assert_ok!(PoeModule::sign_proof(Origin::signed(2), STORY));
+		assert_noop!(PoeModule::sign_proof(Origin::signed(2), STORY), Error::<Test>::AlreadySigned);
+		assert!(!PoeModule::is_confirmed(&STORY));
+		assert_ok!(PoeModule::sign_proof(Origin::signed(3), STORY));
+		assert!(PoeModule::is_confirmed(&STORY));
+		assert_eq!(PoeModule::co_authors(STORY, 3), Some((20, true)));
+	});
+}
+
+#[test]
+fn co_signed_proofs_stay_with_their_co_authors() {
+	new_test_ext().execute_with(|| {
+		create(1, STORY, None, vec![(2, 30)]);
+
+		// The shares of the co-authors stay apart from the one of the owner.
+		assert_noop!(
+			PoeModule::transfer_proof(Origin::signed(1), 2, STORY),
+			Error::<Test>::RecipientIsCoAuthor
+		);
+
+		// Once a co-author agreed to the claim, the owner cannot revoke it alone.
+		assert_ok!(PoeModule::sign_proof(Origin::signed(2), STORY));
+		assert_noop!(PoeModule::revoke_proof(Origin::signed(1), STORY), Error::<Test>::CoAuthorsSigned);
+
+		create(1, CHAPTER, None, vec![(2, 30)]);
+		assert_ok!(PoeModule::revoke_proof(Origin::signed(1), CHAPTER));
+		assert_eq!(PoeModule::co_authors(CHAPTER, 2), None);
+	});
+}
+
+#[test]
+fn withdrawn_signatures_let_the_owner_revoke() {
+	new_test_ext().execute_with(|| {
+		create(1, STORY, None, vec![(2, 30), (3, 20)]);
+		assert_ok!(PoeModule::sign_proof(Origin::signed(2), STORY));
+		assert_ok!(PoeModule::sign_proof(Origin::signed(3), STORY));
+		assert!(PoeModule::is_confirmed(&STORY));
+
+		assert_noop!(PoeModule::withdraw_signature(Origin::signed(4), STORY), Error::<Test>::NotCoAuthor);
+		assert_ok!(PoeModule::withdraw_signature(Origin::signed(2), STORY));
+		assert_noop!(PoeModule::withdraw_signature(Origin::signed(2), STORY), Error::<Test>::NotSigned);
+		assert!(!PoeModule::is_confirmed(&STORY));
+		assert_eq!(PoeModule::co_authors(STORY, 2), Some((30, false)));
+		assert_noop!(PoeModule::revoke_proof(Origin::signed(1), STORY), Error::<Test>::CoAuthorsSigned);
+
+		// Once all the co-authors withdrew, the deposit comes back with the revoke.
+		assert_ok!(PoeModule::withdraw_signature(Origin::signed(3), STORY));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_ok!(PoeModule::revoke_proof(Origin::signed(1), STORY));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+	});
+}
+
+#[test]
+fn derivations_are_tracked() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::create_proof(
+				Origin::signed(2), CHAPTER, HashAlgorithm::Blake2_256, vec![], WorkType::Chapter,
+				Some(STORY), vec![],
+			),
+			Error::<Test>::NoSuchParent
+		);
+
+		create(1, STORY, None, vec![]);
+		create(2, CHAPTER, Some(STORY), vec![]);
+		assert_eq!(PoeModule::proofs(CHAPTER).unwrap().parent, Some(STORY));
+		assert_eq!(PoeModule::derivations(STORY, CHAPTER), Some(false));
+
+		// Only the owner of the parent approves the derivation.
+		assert_noop!(
+			PoeModule::approve_derivation(Origin::signed(2), STORY, CHAPTER),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			PoeModule::approve_derivation(Origin::signed(1), STORY, [3; 32]),
+			Error::<Test>::NoSuchDerivation
+		);
+		assert_ok!(PoeModule::approve_derivation(Origin::signed(1), STORY, CHAPTER));
+		assert_ok!(PoeModule::approve_derivation(Origin::signed(1), STORY, CHAPTER));
+		assert_eq!(PoeModule::derivations(STORY, CHAPTER), Some(true));
+		assert_eq!(PoeModule::approved_derivations(STORY), 1);
+
+		// A work others build on with its approval stays.
+		assert_noop!(PoeModule::revoke_proof(Origin::signed(1), STORY), Error::<Test>::HasDerivatives);
+		assert_ok!(PoeModule::revoke_proof(Origin::signed(2), CHAPTER));
+		assert_eq!(PoeModule::derivations(STORY, CHAPTER), None);
+		assert_eq!(PoeModule::approved_derivations(STORY), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_ok!(PoeModule::revoke_proof(Origin::signed(1), STORY));
+		assert_eq!(PoeModule::proofs(STORY), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn unapproved_derivations_do_not_block_revoke() {
+	new_test_ext().execute_with(|| {
+		create(1, STORY, None, vec![]);
+		create(2, CHAPTER, Some(STORY), vec![]);
+
+		assert_ok!(PoeModule::revoke_proof(Origin::signed(1), STORY));
+		assert_eq!(PoeModule::derivations(STORY, CHAPTER), None);
+		assert_eq!(PoeModule::pending_derivations(STORY), 0);
+		// The derivative loses its parent, and can still be revoked.
+		assert_eq!(PoeModule::proofs(CHAPTER).unwrap().parent, None);
+		assert_ok!(PoeModule::revoke_proof(Origin::signed(2), CHAPTER));
+	});
+}
+
+#[test]
+fn pending_derivations_are_bounded() {
+	new_test_ext().execute_with(|| {
+		create(1, STORY, None, vec![]);
+		create(2, CHAPTER, Some(STORY), vec![]);
+		create(3, [3; 32], Some(STORY), vec![]);
+		assert_eq!(PoeModule::pending_derivations(STORY), 2);
+		assert_noop!(
+			PoeModule::create_proof(
+				Origin::signed(2), [4; 32], HashAlgorithm::Blake2_256, vec![], WorkType::Chapter,
+				Some(STORY), vec![],
+			),
+			Error::<Test>::TooManyDerivations
+		);
+
+		// Approved or revoked derivations leave room for others.
+		assert_ok!(PoeModule::approve_derivation(Origin::signed(1), STORY, CHAPTER));
+		assert_eq!(PoeModule::pending_derivations(STORY), 1);
+		create(2, [4; 32], Some(STORY), vec![]);
+		assert_ok!(PoeModule::revoke_proof(Origin::signed(3), [3; 32]));
+		assert_eq!(PoeModule::pending_derivations(STORY), 1);
+		assert_eq!(PoeModule::approved_derivations(STORY), 1);
+	});
+}
+
+#[test]
+fn transfer_proof_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PoeModule::transfer_proof(Origin::signed(1), 2, STORY),
+			Error::<Test>::NoSuchProof
+		);
+
+		create(1, STORY, None, vec![]);
+		assert_noop!(PoeModule::transfer_proof(Origin::signed(2), 2, STORY), Error::<Test>::NotOwner);
+
+		System::set_block_number(5);
+		assert_ok!(PoeModule::transfer_proof(Origin::signed(1), 2, STORY));
+		let claim = PoeModule::proofs(STORY).unwrap();
+		assert_eq!(claim.owner, 2);
+		assert_eq!(claim.created, 0);
+		assert_noop!(PoeModule::revoke_proof(Origin::signed(1), STORY), Error::<Test>::NotOwner);
+
+		// The deposit returns to the account which reserved it.
+		assert_ok!(PoeModule::revoke_proof(Origin::signed(2), STORY));
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+fn put_legacy_claim(digest: &[u8], owner: u64) {
+	migration::put_storage_value(
+		b"PoeModule", b"Proofs", &Blake2_128Concat::hash(&digest.to_vec().encode()), (owner, 5u64),
+	);
+}
+
+fn legacy_claim(digest: &[u8]) -> Option<(u64, u64)> {
+	migration::get_storage_value(
+		b"PoeModule", b"Proofs", &Blake2_128Concat::hash(&digest.to_vec().encode()),
+	)
+}
+
+#[test]
+fn proofs_migrate_to_content_hashes() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		put_legacy_claim(&STORY, 1);
+		put_legacy_claim(b"not a digest", 2);
+		// Claimed again since the upgrade, before the migration reached it.
+		put_legacy_claim(&CHAPTER, 3);
+		create(2, CHAPTER, None, vec![]);
+
+		v4::migrate::<Test>();
+		assert_eq!(PoeModule::proofs(STORY), None);
+
+		// The keys of `Proofs` are visited a few at a time, the new proofs included.
+		v4::migrate_step::<Test>(1);
+		v4::migrate_step::<Test>(1);
+		v4::migrate_step::<Test>(10);
+		assert_eq!(crate::pallet::MigrationCursor::<Test>::get(), None);
+
+		let claim = PoeModule::proofs(STORY).unwrap();
+		assert_eq!((claim.owner, claim.created, claim.owner_share), (1, 5, 100));
+		assert_eq!((claim.depositor, claim.deposit), (1, 0));
+		assert_eq!(legacy_claim(&STORY), None);
+		assert_eq!(legacy_claim(b"not a digest"), None);
+		assert_eq!(legacy_claim(&CHAPTER), None);
+		assert_eq!(PoeModule::proofs(CHAPTER).unwrap().owner, 2);
+
+		let dropped: Vec<_> = System::events().into_iter().filter_map(|record| match record.event {
+			Event::pallet_poe(PoeEvent::LegacyProofDropped(owner, digest)) => Some((owner, digest)),
+			_ => None,
+		}).collect();
+		assert_eq!(dropped.len(), 2);
+		assert!(dropped.contains(&(2, b"not a digest".to_vec())));
+		assert!(dropped.contains(&(3, CHAPTER.to_vec())));
+
+		// The owner revokes the proof without a deposit to refund.
+		assert_ok!(PoeModule::revoke_proof(Origin::signed(1), STORY));
+	});
+}
+
+#[test]
+fn migration_is_skipped_at_version_4() {
+	new_test_ext().execute_with(|| {
+		PalletVersion::new(4, 0, 0).put_into_storage::<PalletInfo, PoeModule>();
+		put_legacy_claim(&STORY, 1);
+
+		v4::migrate::<Test>();
+		v4::migrate_step::<Test>(v4::PROOFS_PER_BLOCK);
+
+		assert_eq!(crate::pallet::MigrationCursor::<Test>::get(), None);
+		assert_eq!(legacy_claim(&STORY), Some((1, 5)));
+		assert_eq!(PoeModule::proofs(STORY), None);
+	});
+}
@@ -274,8 +274,20 @@ impl pallet_template::Config for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const ProofDeposit: Balance = 1_000_000_000_000;
+	pub const MaxTitleLength: u32 = 128;
+	pub const MaxCoAuthors: u32 = 16;
+	pub const MaxDerivations: u32 = 64;
+}
+
 impl pallet_poe::Config for Runtime {
 	type Event = Event;
+	type Currency = Balances;
+	type ProofDeposit = ProofDeposit;
+	type MaxTitleLength = MaxTitleLength;
+	type MaxCoAuthors = MaxCoAuthors;
+	type MaxDerivations = MaxDerivations;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
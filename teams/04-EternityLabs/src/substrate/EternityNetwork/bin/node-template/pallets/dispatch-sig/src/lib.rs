@@ -8,12 +8,13 @@ use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult,ensure
 };
 use frame_system::ensure_signed;
+use pallet_node_stake::{NodeStaking, Role};
 use sp_std::vec::Vec;
 
 
 
 
-pub trait Config: frame_system::Config {
+pub trait Config: frame_system::Config + pallet_node_stake::Config {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
 }
 
@@ -45,20 +46,22 @@ decl_error! {
 		/// Attempted to transfer more funds than were available
 		InsufficientFunds,
 		NotAuthority,
-		
+		/// A dispatch plan was already stored for this block.
+		AlreadyStored,
 	}
 }
 
 decl_module! {
 	pub struct Module<T: Config> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
-		/// Transfer tokens from one account to anothe
+		/// Store the dispatch plan of the current block. Only elected dispatch nodes may call
+		/// this, and the first plan stored for a block wins.
 		#[weight = 10_000]
 		fn ipfs_store(_origin, ipfshash: Vec<u16>) -> DispatchResult {
 			let sender = ensure_signed(_origin)?;
-			//ensure!(Self::is_manager(&sender) == sender,<Error<T>>::NotManager);
+			ensure!(<pallet_node_stake::Module<T> as NodeStaking<T::AccountId>>::is_node(Role::Dispatch, &sender),<Error<T>>::NotAuthority);
 			let now = <frame_system::Pallet<T>>::block_number();
-			//ensure!(Self::get_dispatch(now) == (None,None) ,<Error<T>>::NotAuthority);
+			ensure!(!<DispatchSig<T>>::contains_key(now),<Error<T>>::AlreadyStored);
 			<DispatchSig<T>>::insert(now,(ipfshash.clone(),sender.clone()));
 			Self::deposit_event(RawEvent::IPFSStored(sender,ipfshash));
 			Ok(())
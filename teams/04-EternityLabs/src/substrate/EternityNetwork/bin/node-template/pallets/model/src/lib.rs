@@ -1,28 +1,86 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-//! Simple Token Transfer
-//! 1. set total supply
-//! 2. establish ownership upon configuration of circulating tokens
-//! 3. coordinate token transfers with the runtime functions
+//! Model registry
+//! 1. anyone can register a model under a unique name and becomes its owner
+//! 2. the owner appends versions (content hash, result schema, result size bound); history is
+//!    never rewritten
+//! 3. a version moves Draft -> Active -> Deprecated
+//! 4. quantification nodes declare the active version they run in `pallet_quan_stake`
 use frame_support::{
-	decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+	decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure, traits::Get,
 };
 use frame_system::ensure_signed;
+use codec::{Encode,Decode};
+use sp_runtime::RuntimeDebug;
 use sp_std::vec::Vec;
 
+#[cfg(test)]
+mod mock;
 
-
+#[cfg(test)]
+mod tests;
 
 pub trait Config: frame_system::Config {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// Longest model name in bytes.
+	type MaxNameLength: Get<u32>;
+	/// Longest artifact hash in bytes.
+	type MaxHashLength: Get<u32>;
+	/// Longest result schema in bytes.
+	type MaxSchemaLength: Get<u32>;
+}
+
+pub type ModelId = u32;
+pub type VersionIndex = u32;
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum VersionStatus {
+	Draft,
+	Active,
+	Deprecated,
+}
+
+impl Default for VersionStatus {
+	fn default() -> Self {
+		VersionStatus::Draft
+	}
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ModelInfo<AccountId,BlockNumber>{
+	pub owner:AccountId,
+	pub name:Vec<u8>,
+	pub created:BlockNumber,
+	/// Number of versions appended so far; the next version gets this index.
+	pub versions:VersionIndex,
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ModelVersion<BlockNumber>{
+	/// Content hash of the model artifact, e.g. an IPFS CID.
+	pub hash:Vec<u8>,
+	/// Schema of the results this version produces: the comma separated top-level fields
+	/// the JSON result must carry, e.g. `b"model,epoch,weights"`.
+	pub schema:Vec<u8>,
+	/// Largest result payload in bytes this version produces.
+	pub max_size:u32,
+	pub created:BlockNumber,
+	pub status:VersionStatus,
 }
 
+type ModelInfoOf<T> = ModelInfo<<T as frame_system::Config>::AccountId,<T as frame_system::Config>::BlockNumber>;
+type ModelVersionOf<T> = ModelVersion<<T as frame_system::Config>::BlockNumber>;
+
 decl_storage! {
 	trait Store for Module<T: Config> as Model {
-		pub ModelList get(fn get_model): map hasher(blake2_128_concat) Vec<u8> => (Vec<u8>,T::AccountId);
 		pub SuperManager get(fn is_manager): map hasher(blake2_128_concat) T::AccountId => T::AccountId;
 		Init get(fn is_init): bool;
-
+		pub NextModelId get(fn next_model_id): ModelId;
+		pub Models get(fn model): map hasher(twox_64_concat) ModelId => Option<ModelInfoOf<T>>;
+		pub ModelIds get(fn model_id): map hasher(blake2_128_concat) Vec<u8> => Option<ModelId>;
+		pub Versions get(fn version): double_map hasher(twox_64_concat) ModelId, hasher(twox_64_concat) VersionIndex => Option<ModelVersionOf<T>>;
 	}
 }
 
@@ -33,9 +91,14 @@ decl_event!(
 	{
 		/// Token was initialized by user
 		Initialized(AccountId),
-		/// Tokens successfully transferred between users
-		Transfer(AccountId, AccountId, u64), // (from, to, value)
-		IPFSStored(),
+		/// A model was registered. [model, owner]
+		ModelCreated(ModelId,AccountId),
+		/// A model changed owner. [model, from, to]
+		ModelTransferred(ModelId,AccountId,AccountId),
+		/// A version was appended. [model, version]
+		VersionAdded(ModelId,VersionIndex),
+		/// A version changed status. [model, version, status]
+		VersionStatusChanged(ModelId,VersionIndex,VersionStatus),
 	}
 );
 
@@ -43,9 +106,18 @@ decl_error! {
 	pub enum Error for Module<T: Config> {
 		/// Attempted to initialize the token after it had already been initialized.
 		AlreadyInitialized,
-		/// Attempted to transfer more funds than were available
-		InsufficientFunds,
 		NotManager,
+		/// The model name is already registered.
+		NameTaken,
+		NoSuchModel,
+		NoSuchVersion,
+		/// Only the model owner may do this.
+		NotOwner,
+		/// The version can not move to the requested status.
+		InvalidTransition,
+		/// The name, hash or schema is longer than the configured bound.
+		TooLong,
+		Overflow,
 	}
 }
 
@@ -53,25 +125,117 @@ decl_module! {
 	pub struct Module<T: Config> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
-	
+
 		#[weight = 10_000]
 		fn init(origin) -> DispatchResult {
 			let creator = ensure_signed(origin)?;
 			ensure!(!Self::is_init(), <Error<T>>::AlreadyInitialized);
-            
+
             <SuperManager<T>>::insert(&creator,&creator.clone());
 			Init::put(true);
+			Self::deposit_event(RawEvent::Initialized(creator));
+			Ok(())
+		}
+
+		/// Register a model under `name`, owned by the caller.
+		#[weight = 10_000]
+		fn create_model(origin, name:Vec<u8>) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			ensure!(name.len() <= T::MaxNameLength::get() as usize,<Error<T>>::TooLong);
+			ensure!(!<ModelIds>::contains_key(&name),<Error<T>>::NameTaken);
+
+			let id = Self::next_model_id();
+			let next = id.checked_add(1).ok_or(Error::<T>::Overflow)?;
+			<Models<T>>::insert(id,ModelInfo{
+				owner:owner.clone(),
+				name:name.clone(),
+				created:<frame_system::Pallet<T>>::block_number(),
+				versions:0,
+			});
+			<ModelIds>::insert(name,id);
+			NextModelId::put(next);
+
+			Self::deposit_event(RawEvent::ModelCreated(id,owner));
 			Ok(())
 		}
 
-		/// Transfer tokens from one account to another
 		#[weight = 10_000]
-		fn add_model(_origin,model:Vec<u8>, ipfshash: Vec<u8>) -> DispatchResult {
-			let sender = ensure_signed(_origin)?;
-			ensure!(Self::is_manager(&sender) == sender,<Error<T>>::NotManager);
-			<ModelList<T>>::insert(model,(ipfshash,sender));
+		fn transfer_model(origin, model:ModelId, to:T::AccountId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			<Models<T>>::try_mutate(model, |info| -> DispatchResult {
+				let info = info.as_mut().ok_or(Error::<T>::NoSuchModel)?;
+				ensure!(info.owner == sender,<Error<T>>::NotOwner);
+				info.owner = to.clone();
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::ModelTransferred(model,sender,to));
 			Ok(())
 		}
-		
+
+		/// Append a Draft version with the artifact `hash`, result `schema` and result `max_size`.
+		#[weight = 10_000]
+		fn add_version(origin, model:ModelId, hash:Vec<u8>, schema:Vec<u8>, max_size:u32) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(hash.len() <= T::MaxHashLength::get() as usize,<Error<T>>::TooLong);
+			ensure!(schema.len() <= T::MaxSchemaLength::get() as usize,<Error<T>>::TooLong);
+			let mut info = Self::model(model).ok_or(Error::<T>::NoSuchModel)?;
+			ensure!(info.owner == sender,<Error<T>>::NotOwner);
+
+			let version = info.versions;
+			info.versions = version.checked_add(1).ok_or(Error::<T>::Overflow)?;
+			<Versions<T>>::insert(model,version,ModelVersion{
+				hash,
+				schema,
+				max_size,
+				created:<frame_system::Pallet<T>>::block_number(),
+				status:VersionStatus::Draft,
+			});
+			<Models<T>>::insert(model,info);
+
+			Self::deposit_event(RawEvent::VersionAdded(model,version));
+			Ok(())
+		}
+
+		/// Activate a Draft version. Only the owner may call this.
+		#[weight = 10_000]
+		fn activate_version(origin, model:ModelId, version:VersionIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let info = Self::model(model).ok_or(Error::<T>::NoSuchModel)?;
+			ensure!(info.owner == sender,<Error<T>>::NotOwner);
+			Self::set_status(model, version, VersionStatus::Active)
+		}
+
+		/// Deprecate a version. The owner or a super manager may call this.
+		#[weight = 10_000]
+		fn deprecate_version(origin, model:ModelId, version:VersionIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let info = Self::model(model).ok_or(Error::<T>::NoSuchModel)?;
+			ensure!(info.owner == sender || <SuperManager<T>>::contains_key(&sender),<Error<T>>::NotOwner);
+			Self::set_status(model, version, VersionStatus::Deprecated)
+		}
+
+	}
+}
+
+impl<T: Config> Module<T> {
+	pub fn is_active(model: ModelId, version: VersionIndex) -> bool {
+		Self::version(model, version).map(|v| v.status == VersionStatus::Active).unwrap_or(false)
 	}
-}
\ No newline at end of file
+
+	fn set_status(model: ModelId, version: VersionIndex, status: VersionStatus) -> DispatchResult {
+		<Versions<T>>::try_mutate(model, version, |v| -> DispatchResult {
+			let v = v.as_mut().ok_or(Error::<T>::NoSuchVersion)?;
+			let allowed = match (v.status, status) {
+				(VersionStatus::Draft, VersionStatus::Active) => true,
+				(VersionStatus::Draft, VersionStatus::Deprecated) => true,
+				(VersionStatus::Active, VersionStatus::Deprecated) => true,
+				_ => false,
+			};
+			ensure!(allowed,<Error<T>>::InvalidTransition);
+			v.status = status;
+			Ok(())
+		})?;
+		Self::deposit_event(RawEvent::VersionStatusChanged(model,version,status));
+		Ok(())
+	}
+}
@@ -0,0 +1,100 @@
+use crate::{Error, VersionStatus, mock::*};
+use frame_support::{assert_noop, assert_ok};
+
+const CID: &[u8] = b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+
+fn create_model_with_version() {
+	assert_ok!(Model::create_model(Origin::signed(1), b"mnist".to_vec()));
+	assert_ok!(Model::add_version(Origin::signed(1), 0, CID.to_vec(), b"model,weights".to_vec(), 1024));
+}
+
+#[test]
+fn create_model_reserves_name() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Model::create_model(Origin::signed(1), b"mnist".to_vec()));
+		assert_eq!(Model::model_id(b"mnist".to_vec()), Some(0));
+		assert_eq!(Model::model(0).unwrap().owner, 1);
+		assert_eq!(Model::next_model_id(), 1);
+
+		assert_noop!(
+			Model::create_model(Origin::signed(2), b"mnist".to_vec()),
+			Error::<Test>::NameTaken
+		);
+		assert_noop!(
+			Model::create_model(Origin::signed(2), vec![b'a'; 17]),
+			Error::<Test>::TooLong
+		);
+	});
+}
+
+#[test]
+fn only_owner_adds_versions() {
+	new_test_ext().execute_with(|| {
+		create_model_with_version();
+		assert_noop!(
+			Model::add_version(Origin::signed(2), 0, CID.to_vec(), Vec::new(), 1024),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			Model::add_version(Origin::signed(1), 1, CID.to_vec(), Vec::new(), 1024),
+			Error::<Test>::NoSuchModel
+		);
+		assert_noop!(
+			Model::add_version(Origin::signed(1), 0, vec![b'a'; 65], Vec::new(), 1024),
+			Error::<Test>::TooLong
+		);
+		assert_noop!(
+			Model::add_version(Origin::signed(1), 0, CID.to_vec(), vec![b'a'; 33], 1024),
+			Error::<Test>::TooLong
+		);
+
+		let version = Model::version(0, 0).unwrap();
+		assert_eq!(version.hash, CID.to_vec());
+		assert_eq!(version.max_size, 1024);
+		assert_eq!(version.status, VersionStatus::Draft);
+		assert_eq!(Model::model(0).unwrap().versions, 1);
+	});
+}
+
+#[test]
+fn version_status_moves_forward() {
+	new_test_ext().execute_with(|| {
+		create_model_with_version();
+		assert_noop!(Model::activate_version(Origin::signed(2), 0, 0), Error::<Test>::NotOwner);
+		assert_noop!(Model::activate_version(Origin::signed(1), 0, 1), Error::<Test>::NoSuchVersion);
+		assert_ok!(Model::activate_version(Origin::signed(1), 0, 0));
+		assert!(Model::is_active(0, 0));
+
+		assert_noop!(Model::activate_version(Origin::signed(1), 0, 0), Error::<Test>::InvalidTransition);
+		assert_ok!(Model::deprecate_version(Origin::signed(1), 0, 0));
+		assert!(!Model::is_active(0, 0));
+		assert_noop!(Model::activate_version(Origin::signed(1), 0, 0), Error::<Test>::InvalidTransition);
+	});
+}
+
+#[test]
+fn super_manager_deprecates_versions() {
+	new_test_ext().execute_with(|| {
+		create_model_with_version();
+		assert_noop!(Model::deprecate_version(Origin::signed(3), 0, 0), Error::<Test>::NotOwner);
+		assert_ok!(Model::init(Origin::signed(3)));
+		assert_noop!(Model::init(Origin::signed(3)), Error::<Test>::AlreadyInitialized);
+		assert_ok!(Model::deprecate_version(Origin::signed(3), 0, 0));
+		assert_eq!(Model::version(0, 0).unwrap().status, VersionStatus::Deprecated);
+	});
+}
+
+#[test]
+fn transfer_changes_owner() {
+	new_test_ext().execute_with(|| {
+		create_model_with_version();
+		assert_noop!(Model::transfer_model(Origin::signed(2), 0, 2), Error::<Test>::NotOwner);
+		assert_ok!(Model::transfer_model(Origin::signed(1), 0, 2));
+		assert_eq!(Model::model(0).unwrap().owner, 2);
+		assert_ok!(Model::add_version(Origin::signed(2), 0, CID.to_vec(), Vec::new(), 1024));
+		assert_noop!(
+			Model::add_version(Origin::signed(1), 0, CID.to_vec(), Vec::new(), 1024),
+			Error::<Test>::NotOwner
+		);
+	});
+}
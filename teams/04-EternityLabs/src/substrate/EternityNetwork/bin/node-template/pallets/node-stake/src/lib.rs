@@ -0,0 +1,462 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Node role staking
+//! 1. the manager opens a round per `Role` (end_block, minstake, max_authority, interval)
+//! 2. candidates bid by reserving `T::Currency`, raising a bid by at least `interval`
+//! 3. at `end_block` the top `max_authority` bidders are elected in `on_initialize`
+//! 4. unbonded and losing bids stay reserved for `UnbondingDelay` blocks and can be slashed
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+	traits::{Currency, Get, Imbalance, OnUnbalanced, ReservableCurrency},
+	weights::Weight,
+};
+use frame_system::ensure_signed;
+use codec::{Encode,Decode};
+use sp_runtime::{Perbill, RuntimeDebug, traits::{CheckedAdd, Saturating, Zero}};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub trait Config: frame_system::Config {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	type Currency: ReservableCurrency<Self::AccountId>;
+	/// Number of blocks an unbonded stake stays reserved before it can be withdrawn.
+	type UnbondingDelay: Get<Self::BlockNumber>;
+	/// Maximum number of unlocking chunks a node may have per role.
+	type MaxUnlockingChunks: Get<u32>;
+	/// Maximum number of candidates bidding for one role.
+	type MaxCandidates: Get<u32>;
+	/// Handler for the slashed part of a node's stake.
+	type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+}
+
+/// The kind of work a staked node does in the Eternity network.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Role {
+	Dispatch,
+	Analysis,
+	Verification,
+	Quantification,
+}
+
+impl Role {
+	pub fn all() -> [Role; 4] {
+		[Role::Dispatch, Role::Analysis, Role::Verification, Role::Quantification]
+	}
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct RoundInfo<AccountId,BlockNumber,Balance>{
+	pub benificiary:AccountId,//管理员发起的受益账户
+	pub end_block:BlockNumber,//当前轮结束区块
+	pub minstake:Balance,//最小质押量
+	pub max_authority:u32,//最大的质押节点个数
+	pub interval:Balance,//单次加价的最小幅度
+	pub state:bool,//是否在竞拍状态
+}
+
+/// A part of the stake that has been unbonded and is released at block `era`.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct UnlockChunk<Balance,BlockNumber>{
+	pub value:Balance,
+	pub era:BlockNumber,
+}
+
+/// Everything an account has reserved for one role.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct Ledger<Balance,BlockNumber>{
+	/// Active bid, counted in the election.
+	pub bid:Balance,
+	/// Unbonded stake that is still reserved until its chunk matures.
+	pub unlocking:Vec<UnlockChunk<Balance,BlockNumber>>,
+}
+
+impl<Balance,BlockNumber> Ledger<Balance,BlockNumber>
+where Balance: Copy + Saturating + Zero
+{
+	pub fn total(&self) -> Balance {
+		self.unlocking.iter().fold(self.bid, |acc, chunk| acc.saturating_add(chunk.value))
+	}
+}
+
+/// Node queries and slashing for the pallets that consume staked nodes.
+pub trait NodeStaking<AccountId> {
+	/// Whether `who` is an elected node for `role`.
+	fn is_node(role: Role, who: &AccountId) -> bool;
+	/// The elected nodes for `role`.
+	fn nodes(role: Role) -> Vec<AccountId>;
+	/// Slash `fraction` of everything `who` has reserved for `role`.
+	fn slash(role: Role, who: &AccountId, fraction: Perbill);
+}
+
+type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
+pub type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::NegativeImbalance;
+pub type RoundInfoOf<T> = RoundInfo<AccountIdOf<T>,<T as frame_system::Config>::BlockNumber,BalanceOf<T>>;
+pub type LedgerOf<T> = Ledger<BalanceOf<T>,<T as frame_system::Config>::BlockNumber>;
+pub type Manager<T> = <T as frame_system::Config>::AccountId;
+
+decl_storage! {
+	trait Store for Module<T: Config> as NodeStake {
+		Managers get(fn get_manger): Manager<T>;
+		pub Init get(fn is_init): bool;
+		/// The current round of each role.
+		pub Rounds get(fn round): map hasher(blake2_128_concat) Role => Option<RoundInfoOf<T>>;
+		/// Reserved stake of each account per role.
+		pub Ledgers get(fn ledger): double_map hasher(blake2_128_concat) Role, hasher(blake2_128_concat) T::AccountId => Option<LedgerOf<T>>;
+		/// Accounts with a non zero bid per role.
+		pub Candidates get(fn candidates): map hasher(blake2_128_concat) Role => Vec<T::AccountId>;
+		/// Nodes elected at the end of the last round per role.
+		pub Authorities get(fn authorities): map hasher(blake2_128_concat) Role => Vec<T::AccountId>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AccountId = <T as frame_system::Config>::AccountId,
+		BlockNumber = <T as frame_system::Config>::BlockNumber,
+		Balance = BalanceOf<T>,
+	{
+		/// The manager was set. [manager, block]
+		Initialized(AccountId,BlockNumber),
+		/// A round was opened. [role, end_block]
+		RoundCreated(Role,BlockNumber),
+		/// A round was closed without election. [role, manager]
+		RoundShutdown(Role,AccountId),
+		/// A candidate raised its bid. [role, who, amount]
+		Bid(Role,AccountId,Balance),
+		/// A node unbonded stake, withdrawable at the given block. [role, who, amount, era]
+		Unbonded(Role,AccountId,Balance,BlockNumber),
+		/// A node withdrew matured unlocking chunks. [role, who, amount]
+		Withdrawn(Role,AccountId,Balance),
+		/// Nodes were elected at the end of a round. [role, nodes]
+		Elected(Role,Vec<AccountId>),
+		/// A node was slashed. [role, who, amount]
+		Slashed(Role,AccountId,Balance),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// Attempted to initialize the pallet after it had already been initialized.
+		AlreadyInitialized,
+		/// Attempted to unbond more than was bid.
+		InsufficientFunds,
+		EndToEarly,
+		NotManager,
+		/// The role has no open round.
+		NoRound,
+		/// The role already has an open round.
+		RoundOpen,
+		/// The bid is below the round's `minstake`.
+		StakeTooLow,
+		/// The bid raise is below the round's `interval`.
+		RaiseTooLow,
+		/// The account has no stake for this role.
+		NotCandidate,
+		/// The role already has `MaxCandidates` candidates.
+		TooManyCandidates,
+		/// Too many unlocking chunks, call `withdraw_unbonded` first.
+		NoMoreChunks,
+		Overflow,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// set super manager
+		#[weight = 10_000]
+		fn init(origin) -> DispatchResult {
+			let creator = ensure_signed(origin)?;
+			ensure!(!Self::is_init(), <Error<T>>::AlreadyInitialized);
+			<Managers<T>>::put(&creator);
+			Init::put(true);
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			Self::deposit_event(RawEvent::Initialized(creator,now));
+			Ok(())
+		}
+
+		/// Open a bidding round for `role`, ending at `end_block`.
+		#[weight = 10_000]
+		fn create_round(origin,
+			role:Role,
+			benificiary:T::AccountId,
+			end_block:T::BlockNumber,
+			minstake:BalanceOf<T>,
+			max_authority:u32,
+			interval:BalanceOf<T>,) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::get_manger() == sender,<Error<T>>::NotManager);
+			Self::do_create_round(role, RoundInfo{
+				benificiary,
+				end_block,
+				minstake,
+				max_authority,
+				interval,
+				state:true,
+			})
+		}
+
+		/// Close the open round of `role` without electing anyone.
+		#[weight = 10_000]
+		fn shutdown(origin, role:Role) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::get_manger() == sender,<Error<T>>::NotManager);
+			Self::do_shutdown(role)?;
+			Self::deposit_event(RawEvent::RoundShutdown(role,sender));
+			Ok(())
+		}
+
+		/// Reserve `value` more as the caller's bid for `role`.
+		#[weight = 10_000]
+		fn bid(origin, role:Role, value:BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_bid(role, &who, value)
+		}
+
+		/// Unbond `value` of the caller's bid for `role`.
+		#[weight = 10_000]
+		fn unbond(origin, role:Role, value:BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_unbond(role, &who, value)
+		}
+
+		/// Release all matured unlocking chunks of the caller for `role`.
+		#[weight = 10_000]
+		fn withdraw_unbonded(origin, role:Role) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::do_withdraw_unbonded(role, &who)
+		}
+
+		/// Slash `fraction` of a node's reserved stake for `role`. Only the manager may call this.
+		#[weight = 10_000]
+		fn slash(origin, role:Role, node:T::AccountId, fraction:Perbill) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::get_manger() == sender,<Error<T>>::NotManager);
+			ensure!(<Ledgers<T>>::contains_key(role, &node),<Error<T>>::NotCandidate);
+			Self::do_slash(role, &node, fraction);
+			Ok(())
+		}
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut weight = T::DbWeight::get().reads(Role::all().len() as Weight);
+			for role in Role::all().iter() {
+				match Self::round(role) {
+					Some(round) if round.state && round.end_block <= now => {
+						weight = weight.saturating_add(Self::elect(*role, round, now));
+					}
+					_ => {}
+				}
+			}
+			weight
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	pub fn do_create_round(role: Role, round: RoundInfoOf<T>) -> DispatchResult {
+		let now = <frame_system::Pallet<T>>::block_number();
+		ensure!(round.end_block > now,<Error<T>>::EndToEarly);
+		ensure!(!Self::round(role).map(|r| r.state).unwrap_or(false),<Error<T>>::RoundOpen);
+
+		let end_block = round.end_block;
+		<Rounds<T>>::insert(role, round);
+		Self::deposit_event(RawEvent::RoundCreated(role,end_block));
+		Ok(())
+	}
+
+	pub fn do_shutdown(role: Role) -> DispatchResult {
+		<Rounds<T>>::try_mutate(role, |round| -> DispatchResult {
+			let round = round.as_mut().ok_or(Error::<T>::NoRound)?;
+			ensure!(round.state,<Error<T>>::NoRound);
+			round.state = false;
+			Ok(())
+		})
+	}
+
+	pub fn do_bid(role: Role, who: &T::AccountId, value: BalanceOf<T>) -> DispatchResult {
+		let round = Self::round(role).filter(|r| r.state).ok_or(Error::<T>::NoRound)?;
+		let mut ledger = Self::ledger(role, who).unwrap_or_default();
+
+		if ledger.bid.is_zero() {
+			ensure!(value >= round.minstake,<Error<T>>::StakeTooLow);
+			let mut candidates = Self::candidates(role);
+			ensure!((candidates.len() as u32) < T::MaxCandidates::get(),<Error<T>>::TooManyCandidates);
+			candidates.push(who.clone());
+			<Candidates<T>>::insert(role, candidates);
+		} else {
+			ensure!(value >= round.interval,<Error<T>>::RaiseTooLow);
+		}
+
+		ledger.bid = ledger.bid.checked_add(&value).ok_or(Error::<T>::Overflow)?;
+		T::Currency::reserve(who, value)?;
+		<Ledgers<T>>::insert(role, who, ledger);
+
+		Self::deposit_event(RawEvent::Bid(role,who.clone(),value));
+		Ok(())
+	}
+
+	/// The remaining bid must either be zero or still cover the round's `minstake`.
+	/// Unbonding the whole bid also drops the account from the candidates and authorities.
+	pub fn do_unbond(role: Role, who: &T::AccountId, value: BalanceOf<T>) -> DispatchResult {
+		let mut ledger = Self::ledger(role, who).ok_or(Error::<T>::NotCandidate)?;
+		ensure!((ledger.unlocking.len() as u32) < T::MaxUnlockingChunks::get(),<Error<T>>::NoMoreChunks);
+		ensure!(value <= ledger.bid,<Error<T>>::InsufficientFunds);
+
+		let remaining = ledger.bid.saturating_sub(value);
+		if remaining.is_zero() {
+			<Candidates<T>>::mutate(role, |c| c.retain(|a| a != who));
+			<Authorities<T>>::mutate(role, |a| a.retain(|a| a != who));
+		} else {
+			let minstake = Self::round(role).map(|r| r.minstake).unwrap_or_else(Zero::zero);
+			ensure!(remaining >= minstake,<Error<T>>::StakeTooLow);
+		}
+
+		let era = <frame_system::Pallet<T>>::block_number().saturating_add(T::UnbondingDelay::get());
+		ledger.bid = remaining;
+		ledger.unlocking.push(UnlockChunk{ value, era });
+		<Ledgers<T>>::insert(role, who, ledger);
+
+		Self::deposit_event(RawEvent::Unbonded(role,who.clone(),value,era));
+		Ok(())
+	}
+
+	/// A ledger with nothing left reserved is removed.
+	pub fn do_withdraw_unbonded(role: Role, who: &T::AccountId) -> DispatchResult {
+		let mut ledger = Self::ledger(role, who).ok_or(Error::<T>::NotCandidate)?;
+
+		let now = <frame_system::Pallet<T>>::block_number();
+		let mut withdrawn: BalanceOf<T> = Zero::zero();
+		ledger.unlocking.retain(|chunk| {
+			if chunk.era > now {
+				true
+			} else {
+				withdrawn = withdrawn.saturating_add(chunk.value);
+				false
+			}
+		});
+		T::Currency::unreserve(who, withdrawn);
+		Self::put_ledger(role, who, ledger);
+
+		Self::deposit_event(RawEvent::Withdrawn(role,who.clone(),withdrawn));
+		Ok(())
+	}
+
+	/// Slash bonded stake first and then unlocking chunks from the newest one.
+	pub fn do_slash(role: Role, who: &T::AccountId, fraction: Perbill) {
+		let mut ledger = match Self::ledger(role, who) {
+			Some(ledger) => ledger,
+			None => return,
+		};
+		let value = fraction * ledger.total();
+		if value.is_zero() {
+			return;
+		}
+
+		let (imbalance, _) = T::Currency::slash_reserved(who, value);
+		let slashed = imbalance.peek();
+		T::Slash::on_unbalanced(imbalance);
+
+		let mut left = slashed;
+		let from_bid = left.min(ledger.bid);
+		ledger.bid = ledger.bid.saturating_sub(from_bid);
+		left = left.saturating_sub(from_bid);
+		for chunk in ledger.unlocking.iter_mut().rev() {
+			if left.is_zero() {
+				break;
+			}
+			let from_chunk = left.min(chunk.value);
+			chunk.value = chunk.value.saturating_sub(from_chunk);
+			left = left.saturating_sub(from_chunk);
+		}
+		ledger.unlocking.retain(|chunk| !chunk.value.is_zero());
+
+		if ledger.bid.is_zero() {
+			<Candidates<T>>::mutate(role, |c| c.retain(|a| a != who));
+			<Authorities<T>>::mutate(role, |a| a.retain(|a| a != who));
+		}
+		Self::put_ledger(role, who, ledger);
+
+		Self::deposit_event(RawEvent::Slashed(role,who.clone(),slashed));
+	}
+
+	/// Elect the top `max_authority` bidders of `role`. Losing bids are unbonded.
+	fn elect(role: Role, mut round: RoundInfoOf<T>, now: T::BlockNumber) -> Weight {
+		let mut candidates: Vec<(T::AccountId, BalanceOf<T>)> = Self::candidates(role)
+			.into_iter()
+			.map(|who| {
+				let bid = Self::ledger(role, &who).map(|l| l.bid).unwrap_or_else(Zero::zero);
+				(who, bid)
+			})
+			.collect();
+		let count = candidates.len() as Weight;
+		candidates.sort_by(|a, b| b.1.cmp(&a.1));
+
+		let split = sp_std::cmp::min(round.max_authority as usize, candidates.len());
+		let losers = candidates.split_off(split);
+		let era = now.saturating_add(T::UnbondingDelay::get());
+		for (who, bid) in losers.into_iter() {
+			if let Some(mut ledger) = Self::ledger(role, &who) {
+				ledger.bid = Zero::zero();
+				Self::push_chunk(&mut ledger, bid, era);
+				<Ledgers<T>>::insert(role, &who, ledger);
+			}
+		}
+
+		let elected: Vec<T::AccountId> = candidates.into_iter().map(|(who, _)| who).collect();
+		<Candidates<T>>::insert(role, elected.clone());
+		<Authorities<T>>::insert(role, elected.clone());
+		round.state = false;
+		<Rounds<T>>::insert(role, round);
+
+		Self::deposit_event(RawEvent::Elected(role,elected));
+		T::DbWeight::get().reads_writes(count.saturating_add(1), count.saturating_add(3))
+	}
+
+	/// Once `MaxUnlockingChunks` is reached the value is merged into the newest chunk,
+	/// which then matures at the later of the two eras.
+	fn push_chunk(ledger: &mut LedgerOf<T>, value: BalanceOf<T>, era: T::BlockNumber) {
+		if (ledger.unlocking.len() as u32) < T::MaxUnlockingChunks::get() {
+			ledger.unlocking.push(UnlockChunk{ value, era });
+		} else if let Some(last) = ledger.unlocking.last_mut() {
+			last.value = last.value.saturating_add(value);
+			last.era = last.era.max(era);
+		} else {
+			ledger.unlocking.push(UnlockChunk{ value, era });
+		}
+	}
+
+	fn put_ledger(role: Role, who: &T::AccountId, ledger: LedgerOf<T>) {
+		if ledger.total().is_zero() {
+			<Ledgers<T>>::remove(role, who);
+		} else {
+			<Ledgers<T>>::insert(role, who, ledger);
+		}
+	}
+}
+
+impl<T: Config> NodeStaking<T::AccountId> for Module<T> {
+	fn is_node(role: Role, who: &T::AccountId) -> bool {
+		Self::authorities(role).contains(who)
+	}
+
+	fn nodes(role: Role) -> Vec<T::AccountId> {
+		Self::authorities(role)
+	}
+
+	fn slash(role: Role, who: &T::AccountId, fraction: Perbill) {
+		Self::do_slash(role, who, fraction)
+	}
+}
@@ -0,0 +1,90 @@
+use crate::{Error, Role, NodeStaking, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use sp_runtime::Perbill;
+
+fn open_round() {
+	assert_ok!(NodeStake::init(Origin::signed(1)));
+	assert_ok!(NodeStake::create_round(Origin::signed(1), Role::Analysis, 1, 5, 100, 2, 10));
+}
+
+#[test]
+fn bid_reserves_stake() {
+	new_test_ext().execute_with(|| {
+		open_round();
+		assert_noop!(
+			NodeStake::bid(Origin::signed(2), Role::Analysis, 99),
+			Error::<Test>::StakeTooLow
+		);
+		assert_ok!(NodeStake::bid(Origin::signed(2), Role::Analysis, 100));
+		assert_noop!(
+			NodeStake::bid(Origin::signed(2), Role::Analysis, 5),
+			Error::<Test>::RaiseTooLow
+		);
+		assert_ok!(NodeStake::bid(Origin::signed(2), Role::Analysis, 10));
+		assert_eq!(Balances::reserved_balance(&2), 110);
+	});
+}
+
+#[test]
+fn top_bidders_are_elected_at_round_end() {
+	new_test_ext().execute_with(|| {
+		open_round();
+		assert_ok!(NodeStake::bid(Origin::signed(2), Role::Analysis, 100));
+		assert_ok!(NodeStake::bid(Origin::signed(3), Role::Analysis, 300));
+		assert_ok!(NodeStake::bid(Origin::signed(4), Role::Analysis, 200));
+
+		run_to_block(5);
+
+		assert_eq!(NodeStake::nodes(Role::Analysis), vec![3, 4]);
+		assert!(!NodeStake::is_node(Role::Dispatch, &3));
+		assert!(!NodeStake::round(Role::Analysis).unwrap().state);
+
+		// The losing bid stays reserved until the unbonding delay has passed.
+		assert_eq!(NodeStake::ledger(Role::Analysis, 2).unwrap().bid, 0);
+		assert_ok!(NodeStake::withdraw_unbonded(Origin::signed(2), Role::Analysis));
+		assert_eq!(Balances::reserved_balance(&2), 100);
+		run_to_block(15);
+		assert_ok!(NodeStake::withdraw_unbonded(Origin::signed(2), Role::Analysis));
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(NodeStake::ledger(Role::Analysis, 2), None);
+	});
+}
+
+#[test]
+fn slash_takes_reserved_stake() {
+	new_test_ext().execute_with(|| {
+		open_round();
+		assert_ok!(NodeStake::bid(Origin::signed(2), Role::Analysis, 200));
+		assert_ok!(NodeStake::unbond(Origin::signed(2), Role::Analysis, 100));
+
+		<NodeStake as NodeStaking<u64>>::slash(Role::Analysis, &2, Perbill::from_percent(50));
+
+		assert_eq!(Balances::reserved_balance(&2), 100);
+		assert_eq!(Balances::free_balance(&2), 800);
+		let ledger = NodeStake::ledger(Role::Analysis, 2).unwrap();
+		assert_eq!(ledger.bid, 0);
+		assert_eq!(ledger.unlocking[0].value, 100);
+	});
+}
+
+#[test]
+fn losing_bid_merges_into_the_last_chunk_at_the_cap() {
+	new_test_ext().execute_with(|| {
+		open_round();
+		assert_ok!(NodeStake::bid(Origin::signed(2), Role::Analysis, 400));
+		for _ in 0..4 {
+			assert_ok!(NodeStake::unbond(Origin::signed(2), Role::Analysis, 50));
+		}
+		assert_ok!(NodeStake::bid(Origin::signed(3), Role::Analysis, 300));
+		assert_ok!(NodeStake::bid(Origin::signed(4), Role::Analysis, 300));
+
+		run_to_block(5);
+
+		let ledger = NodeStake::ledger(Role::Analysis, 2).unwrap();
+		assert_eq!(ledger.bid, 0);
+		assert_eq!(ledger.unlocking.len(), 4);
+		assert_eq!(ledger.unlocking[3].value, 250);
+		assert_eq!(ledger.unlocking[3].era, 15);
+		assert_eq!(Balances::reserved_balance(&2), 400);
+	});
+}
@@ -1,63 +1,75 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-//! Simple Token Transfer
-//! 1. set total supply
-//! 2. establish ownership upon configuration of circulating tokens
-//! 3. coordinate token transfers with the runtime functions
+//! Quantification result submission
+//! Elected quantification nodes commit a hash of their result and reveal it in the
+//! following window. Commitments, reveals and votes are kept by `pallet_verification_sig`.
+//! Every revealed result records the model version that produced it. The records of a
+//! task are removed together with its results in `pallet_verification_sig`.
+
 use frame_support::{
-	decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+	decl_error, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+	storage::IterableStorageDoubleMap, traits::Get, weights::Weight,
 };
 use frame_system::ensure_signed;
+use pallet_model::{ModelId, VersionIndex};
+use pallet_node_stake::Role;
+use pallet_verification_sig::TaskId;
 use sp_std::vec::Vec;
 
+pub const ROLE: Role = Role::Quantification;
 
+pub trait Config: frame_system::Config + pallet_verification_sig::Config + pallet_quan_stake::Config {}
 
-
-pub trait Config: frame_system::Config {
-	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
-}
+type VerificationSig<T> = pallet_verification_sig::Module<T>;
 
 decl_storage! {
-	trait Store for Module<T: Config> as Dispsig {
-		pub DispatchSig get(fn get_balance): map hasher(blake2_128_concat) <T as frame_system::Config>::BlockNumber => (Vec<u8>,T::AccountId);
-
-
+	trait Store for Module<T: Config> as QuanSig {
+		/// The model version each revealed result was produced with.
+		pub ResultModels get(fn result_model): double_map hasher(blake2_128_concat) TaskId<T>, hasher(blake2_128_concat) T::AccountId => Option<(ModelId,VersionIndex)>;
 	}
 }
 
-decl_event!(
-	pub enum Event<T>
-	where
-		AccountId = <T as frame_system::Config>::AccountId,
-	{
-		/// Token was initialized by user
-		Initialized(AccountId),
-		/// Tokens successfully transferred between users
-		Transfer(AccountId, AccountId, u64), // (from, to, value)
-	}
-);
-
 decl_error! {
 	pub enum Error for Module<T: Config> {
-		/// Attempted to initialize the token after it had already been initialized.
-		AlreadyInitialized,
-		/// Attempted to transfer more funds than were available
-		InsufficientFunds,
+		/// The model version is not the active one the node declared.
+		ModelMismatch,
 	}
 }
 
 decl_module! {
 	pub struct Module<T: Config> for enum Call where origin: T::Origin {
-		fn deposit_event() = default;
+		type Error = Error<T>;
 
-		/// Transfer tokens from one account to another
+		/// Commit `hash(sender, ipfshash, salt)` for the current task.
 		#[weight = 10_000]
-		fn ipfs_store(_origin, ipfshash: Vec<u8>) -> DispatchResult {
-			let sender = ensure_signed(_origin)?;
-			let now = <frame_system::Module<T>>::block_number();
-			<DispatchSig<T>>::insert(now,(ipfshash,sender));
+		fn commit(origin, commitment:T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			VerificationSig::<T>::do_commit(ROLE, &sender, commitment)
+		}
+
+		/// Reveal the committed result of the current task, produced by `model` at `version`.
+		#[weight = 10_000]
+		fn reveal(origin, model:ModelId, version:VersionIndex, ipfshash:Vec<u8>, salt:Vec<u8>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let declared = pallet_quan_stake::Module::<T>::get_node(&sender).map(|n| n.model);
+			ensure!(declared == Some((model, version)),<Error<T>>::ModelMismatch);
+			ensure!(pallet_model::Module::<T>::is_active(model, version),<Error<T>>::ModelMismatch);
+
+			VerificationSig::<T>::do_reveal(ROLE, &sender, ipfshash, salt)?;
+			let (task, _) = VerificationSig::<T>::phase(<frame_system::Pallet<T>>::block_number());
+			<ResultModels<T>>::insert(task, &sender, (model, version));
 			Ok(())
 		}
-		
+
+		/// Remove the model records of the task whose results expire at `now`.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			match VerificationSig::<T>::expired_task(now) {
+				Some(task) => {
+					let count = <ResultModels<T>>::drain_prefix(task).count() as Weight;
+					T::DbWeight::get().reads_writes(count, count)
+				}
+				None => 0,
+			}
+		}
 	}
-}
\ No newline at end of file
+}
@@ -1,80 +1,56 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-//! Simple Token Transfer
-//! 1. set total supply
-//! 2. establish ownership upon configuration of circulating tokens
-//! 3. coordinate token transfers with the runtime functions
-
-
-use std::u8;
-
-use frame_support::{PalletId,storage::child, decl_error, decl_event, decl_module, decl_storage, dispatch::{DispatchResult,DispatchError}, ensure, traits::{Currency, ExistenceRequirement::AllowDeath, Get, Imbalance, OnUnbalanced}};
+//! Quantification node staking
+//! A thin wrapper around `pallet_node_stake` fixed to `Role::Quantification`.
+//! Quantification nodes register the model version they run, their dex address and
+//! endpoint here while
+//! their stake is reserved, unbonded and slashed by `pallet_node_stake`.
+//! The pallet also keeps the donation pot used by `donate`/`allocate`.
+
+use frame_support::{PalletId, decl_error, decl_event, decl_module, decl_storage, dispatch::{DispatchResult,DispatchError}, ensure, traits::{Currency, ExistenceRequirement::AllowDeath, Get}};
 use sp_std::prelude::*;
 
 use frame_system::{ensure_signed};
 use codec::{Encode,Decode};
+use pallet_model::{ModelId, VersionIndex};
+use pallet_node_stake::{BalanceOf, NodeStaking, Role};
 use sp_runtime::{traits::{AccountIdConversion,CheckedAdd}};
-/// Hardcoded pallet ID; used to create the special Pot Account
-/// Must be exactly 8 characters long
-//const PALLET_ID: ModuleId = ModuleId(*b"Charity!");
 
+pub const ROLE: Role = Role::Quantification;
 
-pub trait Config: frame_system::Config {
+pub trait Config: frame_system::Config + pallet_node_stake::Config + pallet_model::Config {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
-	type Currency: Currency<Self::AccountId>;
+	/// Hardcoded pallet ID; used to create the special Pot Account
+	/// Must be exactly 8 characters long
 	type PalletId: Get<PalletId>;
 }
-/// Hardcoded pallet ID; used to create the special Pot Account
-/// Must be exactly 8 characters long
-
 
 type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
-type BalanceOf<T> = <<T as Config>::Currency as Currency<AccountIdOf<T>>>::Balance;
-type QuanInfoOf<T> = QuanInfo<AccountIdOf<T>,<T as frame_system::Config>::BlockNumber,BalanceOf<T>>;
-type QuanNodeOf<T> = QuanNode<AccountIdOf<T>,BalanceOf<T>>;
-pub type QuanIndex = u32;
-pub type Manager<T> = <T as frame_system::Config>::AccountId;
+type QuanNodeOf<T> = QuanNode<AccountIdOf<T>>;
+type NodeStake<T> = pallet_node_stake::Module<T>;
 
 #[derive(Encode, Decode, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(Debug))]
-pub struct QuanInfo<AccountId,BlockNumber,Balance>{
-	benificiary:AccountId,
-	end:BlockNumber,
-	minstake:Balance,
-	maxauthoritynum:u32,
-	status:bool,
-}
-
-#[derive(Encode, Decode, Default, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(Debug))]
-pub struct QuanNode<AccountId,Balance>{
-	model:Vec<u8>,
+pub struct QuanNode<AccountId>{
+	/// The model version this node runs, declared in `pallet_model`.
+	pub model:(ModelId,VersionIndex),
 	address:AccountId,
 	dexaddress:Vec<u8>,
-	stake:Balance,
 	ipport:Vec<u8>,
 }
 
-
-
 decl_storage! {
 	trait Store for Module<T: Config> as Quanstake {
-		
-		
 		pub QuanStakes get(fn get_quan): map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
-		pub Nodes get(fn get_node): map hasher(blake2_128_concat) T::AccountId => QuanNodeOf<T>;
-		Managers get(fn get_manger): Manager<T>;
-		pub Qunans get(fn quans):map hasher(blake2_128_concat) QuanIndex => Option<QuanInfoOf<T>>;
-		pub Init get(fn is_init): bool;
-		pub QuanCount get(fn is_index): QuanIndex;
+		pub Nodes get(fn get_node): map hasher(blake2_128_concat) T::AccountId => Option<QuanNodeOf<T>>;
 	}
 
 	add_extra_genesis {
 		build(|_config| {
 			// Create the charity's pot of funds, and ensure it has the minimum required deposit
-			let _ = T::Currency::make_free_balance_be(
+			let _ = <T as pallet_node_stake::Config>::Currency::make_free_balance_be(
 				&<Module<T>>::account_id(),
-				T::Currency::minimum_balance(),
+				<T as pallet_node_stake::Config>::Currency::minimum_balance(),
 			);
 		});
 	}
@@ -86,26 +62,23 @@ decl_event!(
 		<T as frame_system::Config>::AccountId,
 		BlockNumber = <T as frame_system::Config>::BlockNumber,
 	{
-		/// Token was initialized by user
-		Initialized(AccountId,BlockNumber),
-		/// Tokens successfully transferred between users
-		AddQuanNodeed(AccountId, AccountId,BlockNumber), // (from, to, value)
-		Created(QuanIndex,BlockNumber,AccountId),
-		Shutdowned(AccountId,BlockNumber),
+		/// A quantification node registered. [node, block]
+		AddQuanNodeed(AccountId,BlockNumber),
+		/// A quantification node left after withdrawing all of its stake. [node, block]
+		RemovedQuanNode(AccountId,BlockNumber),
+		/// A quantification node declared the model version it runs. [node, model, version]
+		ModelDeclared(AccountId,ModelId,VersionIndex),
 	}
 );
 
 decl_error! {
 	pub enum Error for Module<T: Config> {
-		/// Attempted to initialize the token after it had already been initialized.
-		AlreadyInitialized,
-		/// Attempted to transfer more funds than were available
-		InsufficientFunds,
-		EndToEarly,
-		NotManager,
 		AlreadyAdd,
-		AlreadyClose,
 		Overflow,
+		/// The account is not a quantification node.
+		NotNode,
+		/// The model version does not exist or is not active.
+		ModelNotActive,
 	}
 }
 
@@ -113,109 +86,87 @@ decl_module! {
 	pub struct Module<T: Config> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
-	
-		/// set super manager
+		/// Register as a quantification node and bid `stake` in the current round.
 		#[weight = 10_000]
-		fn init(_origin) -> DispatchResult {
-			let creator = ensure_signed(_origin)?;
-			ensure!(!Self::is_init(), <Error<T>>::AlreadyInitialized);
-            // <SuperManager<T>>::insert(&creator,&creator.clone());
-			<Managers<T>>::put(&creator.clone());
-			QuanCount::put(1u32);
-		
-			Init::put(true);
-			// Emit an event.
+		fn add_quan_node(origin,
+			model:ModelId,
+			version:VersionIndex,
+			dexaddress:Vec<u8>,
+			stake:BalanceOf<T>,
+			ipport:Vec<u8>,) -> DispatchResult {
+			let address = ensure_signed(origin)?;
+			ensure!(!<Nodes<T>>::contains_key(&address),<Error<T>>::AlreadyAdd);
+			ensure!(pallet_model::Module::<T>::is_active(model, version),<Error<T>>::ModelNotActive);
+
+			NodeStake::<T>::do_bid(ROLE, &address, stake)?;
+
+			<Nodes<T>>::insert(&address,QuanNode{
+				model:(model,version),
+				address:address.clone(),
+				dexaddress,
+				ipport,
+			});
+
 			let now = <frame_system::Pallet<T>>::block_number();
-			Self::deposit_event(RawEvent::Initialized(creator, now));
+			Self::deposit_event(RawEvent::AddQuanNodeed(address,now));
 			Ok(())
 		}
 
+		/// Switch the caller to another active model version.
 		#[weight = 10_000]
-		fn create(origin,
-			benificiary:AccountIdOf<T>,
-			end:T::BlockNumber,
-			minstake:BalanceOf<T>,
-			maxauthoritynum:u32,
-			status:bool,) -> DispatchResult {
-			let sender = ensure_signed(origin)?;
-			ensure!(Self::get_manger() == sender,<Error<T>>::NotManager);
-			let now = <frame_system::Pallet<T>>::block_number();
-			ensure!(end>now,<Error<T>>::EndToEarly);
-			let index = QuanCount::get() + 1;
-			<Qunans<T>>::insert(index,QuanInfo{
-				benificiary,
-				end,
-				minstake,
-				maxauthoritynum,
-				status
-			});
-			QuanCount::put(index);
-			Self::deposit_event(RawEvent::Created(index,now,sender));
+		fn declare_model(origin, model:ModelId, version:VersionIndex) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(pallet_model::Module::<T>::is_active(model, version),<Error<T>>::ModelNotActive);
+			<Nodes<T>>::try_mutate(&who, |node| -> DispatchResult {
+				let node = node.as_mut().ok_or(Error::<T>::NotNode)?;
+				node.model = (model, version);
+				Ok(())
+			})?;
+			Self::deposit_event(RawEvent::ModelDeclared(who,model,version));
 			Ok(())
 		}
+
+		/// Reserve `value` more of the caller's balance as bonded stake.
 		#[weight = 10_000]
-		fn shutdown(origin,index:u32)-> DispatchResult {
-			let shutdowner = ensure_signed(origin)?;
-			ensure!(Self::get_manger() == shutdowner,<Error<T>>::NotManager);
+		fn bond_extra(origin, value:BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(<Nodes<T>>::contains_key(&who),<Error<T>>::NotNode);
+			NodeStake::<T>::do_bid(ROLE, &who, value)
+		}
 
-			let now = <frame_system::Pallet<T>>::block_number();
-			let end =Qunans::<T>::get(index).ok_or(Error::<T>::AlreadyAdd)?.end;
-			let status = Qunans::<T>::get(index).ok_or(Error::<T>::AlreadyAdd)?.status;
-			ensure!(status == true,<Error<T>>::AlreadyClose);
-
-			let benificiary= Qunans::<T>::get(index).ok_or(Error::<T>::AlreadyAdd)?.benificiary;
-			let minstake= Qunans::<T>::get(index).ok_or(Error::<T>::AlreadyAdd)?.minstake;
-			let maxauthoritynum= Qunans::<T>::get(index).ok_or(Error::<T>::AlreadyAdd)?.maxauthoritynum;
-			let status = false;
-			<Qunans<T>>::insert(index,QuanInfo{
-				benificiary,
-				end,
-				minstake,
-				maxauthoritynum,
-				status
-			});
-			Self::deposit_event(RawEvent::Shutdowned(shutdowner,now));
-			Ok(())
+		/// Unbond `value` of the bonded stake. It stays reserved for `UnbondingDelay` blocks.
+		#[weight = 10_000]
+		fn unbond(origin, value:BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(<Nodes<T>>::contains_key(&who),<Error<T>>::NotNode);
+			NodeStake::<T>::do_unbond(ROLE, &who, value)
 		}
 
-		/// Transfer tokens from one account to another
+		/// Release all matured unlocking chunks. A node with nothing left reserved is removed.
 		#[weight = 10_000]
-		fn add_quan_node(_origin,
-			model:Vec<u8>,
-			address:T::AccountId,
-			dexaddress:Vec<u8>,
-			stake:BalanceOf<T>,
-			ipport:Vec<u8>,) -> DispatchResult {
-			let sender = ensure_signed(_origin)?;
-			ensure!(Self::get_manger() == sender,<Error<T>>::NotManager);
-			//ensure!(Self::get_quan(&quan_node) != quan_node ,<Error<T>>::AlreadyAdd);
-
-			let index = Self::is_index()+ 1 ;
-			
-			//<QuanStakes<T>>::insert(index,&quan_node);
-			QuanCount::put(index);
-			let node = address.clone();
-			<Nodes<T>>::insert(address.clone(),QuanNode{
-				model,
-				address,
-				dexaddress,
-				stake,
-				ipport
-			});
-			
-			let now = <frame_system::Pallet<T>>::block_number();
-			Self::deposit_event(RawEvent::AddQuanNodeed(sender, node,now));
+		fn withdraw_unbonded(origin) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(<Nodes<T>>::contains_key(&who),<Error<T>>::NotNode);
+			// A fully slashed node has no ledger left to withdraw from.
+			if NodeStake::<T>::ledger(ROLE, &who).is_some() {
+				NodeStake::<T>::do_withdraw_unbonded(ROLE, &who)?;
+			}
+
+			if NodeStake::<T>::ledger(ROLE, &who).is_none() {
+				<Nodes<T>>::remove(&who);
+				let now = <frame_system::Pallet<T>>::block_number();
+				Self::deposit_event(RawEvent::RemovedQuanNode(who,now));
+			}
 			Ok(())
 		}
 
 		#[weight = 10_000]
 		fn donate(origin,amount: BalanceOf<T>,dest:T::AccountId) -> DispatchResult{
 			let donor = ensure_signed(origin)?;
-			T::Currency::transfer(&donor, &Self::account_id(), amount, AllowDeath)
+			let new_amount = <QuanStakes<T>>::get(&donor).checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			<T as pallet_node_stake::Config>::Currency::transfer(&donor, &Self::account_id(), amount, AllowDeath)
 				.map_err(|_| DispatchError::Other("Can't make donation"))?;
-			let old_amount = <QuanStakes<T>>::get(&donor);
-			let update_amount_add = old_amount.checked_add(&amount).ok_or(Error::<T>::Overflow);
-			<QuanStakes<T>>::insert(donor,update_amount_add.unwrap());
+			<QuanStakes<T>>::insert(donor,new_amount);
 			Ok(())
 		}
 
@@ -228,7 +179,7 @@ decl_module! {
 			//ensure_root(origin)?;
 
 			// Make the transfer requested
-			T::Currency::transfer(
+			<T as pallet_node_stake::Config>::Currency::transfer(
 				&Self::account_id(),
 				&dest,
 				amount,
@@ -236,11 +187,11 @@ decl_module! {
 			).map_err(|_| DispatchError::Other("Can't make allocation"))?;
 
 			//TODO what about errors here??
-			
+
 			//Self::deposit_event(RawEvent::FundsAllocated(dest, amount, Self::pot()));
 			Ok(())
 		}
-		
+
 	}
 }
 
@@ -248,9 +199,17 @@ decl_module! {
 impl<T: Config> Module<T> {
 	/// The account ID that holds the Charity's funds
 	pub fn account_id() -> T::AccountId {
-		
+
 		T::PalletId::get().into_account()
-		
+
+	}
+
+	pub fn nodes() -> Vec<T::AccountId> {
+		<NodeStake<T> as NodeStaking<T::AccountId>>::nodes(ROLE)
+	}
+
+	pub fn is_node(who: &T::AccountId) -> bool {
+		<NodeStake<T> as NodeStaking<T::AccountId>>::is_node(ROLE, who)
 	}
 }
 
@@ -260,4 +219,3 @@ impl<T: Config> Module<T> {
 // 		T::Currency::free_balance(&Self::account_id())
 // 	}
 // }
-
@@ -0,0 +1,397 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Result payload checks
+//! `pallet_verification_sig` only sees the `ipfshash` a node reveals. In the reveal and vote
+//! phases the off-chain worker of every verification node, for each pending result:
+//! 1. validates the CID format
+//! 2. fetches the payload from the IPFS HTTP gateway
+//! 3. checks its size and schema against the model version the result claims in `pallet_quan_sig`
+//! 4. submits its verdict as an unsigned transaction, signed with its `ipfs` key
+//!
+//! A result is marked `Invalid` in `pallet_verification_sig`, so verification nodes do not vote
+//! on it, once `Quorum` of the verification nodes attested a failed check. Payloads that can not
+//! be fetched are retried in the next block. Attestations of a task are removed when its vote
+//! window closes.
+//!
+//! The gateway is kept on-chain (`set_gateway`) and a node can override it by setting the
+//! persistent offchain storage key `eternity::ipfs-gateway`.
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+	storage::IterableStorageDoubleMap, traits::Get, weights::Weight,
+};
+use frame_system::{
+	ensure_none, ensure_root,
+	offchain::{AppCrypto, SendTransactionTypes, SendUnsignedTransaction, SignedPayload, Signer, SigningTypes},
+};
+use codec::{Encode,Decode};
+use lite_json::json::JsonValue;
+use pallet_node_stake::{NodeStaking, Role};
+use pallet_verification_sig::{Phase, ResultStatus, TaskId};
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{
+	RuntimeDebug,
+	offchain::{http, Duration, storage::StorageValueRef},
+	traits::{IdentifyAccount, Saturating, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity, ValidTransaction,
+	},
+};
+use sp_std::vec::Vec;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// Key type of the keys verification nodes sign their attestations with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"ipfs");
+
+/// Offchain storage key a node may set to use its own gateway.
+pub const LOCAL_GATEWAY: &[u8] = b"eternity::ipfs-gateway";
+
+/// How long a single payload fetch may take.
+const FETCH_TIMEOUT_MS: u64 = 3_000;
+
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_core::sr25519::Signature as Sr25519Signature;
+	use sp_runtime::{
+		MultiSignature, MultiSigner,
+		app_crypto::{app_crypto, sr25519},
+		traits::Verify,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct AuthorityId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for AuthorityId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+
+	impl frame_system::offchain::AppCrypto<<Sr25519Signature as Verify>::Signer, Sr25519Signature> for AuthorityId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+pub trait Config: SigningTypes + SendTransactionTypes<Call<Self>> + pallet_quan_sig::Config {
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// The key verification nodes sign attestations with.
+	type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+	/// Largest payload in bytes of a result that claims no model version.
+	type MaxPayloadSize: Get<u32>;
+	/// Priority of the attestation transactions.
+	type UnsignedPriority: Get<TransactionPriority>;
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Verdict {
+	Valid,
+	/// `ipfshash` is not a CIDv0 or base32 CIDv1.
+	BadCid,
+	/// The result claims a model version that does not exist.
+	UnknownModel,
+	/// The payload is larger than the model version allows.
+	TooLarge,
+	/// The payload is not a JSON object carrying the fields of the model version schema.
+	BadSchema,
+}
+
+/// The verdict of a verification node on the result `node` revealed for `task`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Attestation<Public, AccountId, BlockNumber>{
+	pub role:Role,
+	pub task:BlockNumber,
+	pub node:AccountId,
+	pub verdict:Verdict,
+	/// Key of the verification node; its account must be an elected verification node.
+	pub public:Public,
+}
+
+impl<T: SigningTypes> SignedPayload<T> for Attestation<T::Public, T::AccountId, T::BlockNumber> {
+	fn public(&self) -> T::Public {
+		self.public.clone()
+	}
+}
+
+pub type AttestationOf<T> = Attestation<
+	<T as SigningTypes>::Public,
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+>;
+type NodeStake<T> = pallet_node_stake::Module<T>;
+type VerificationSig<T> = pallet_verification_sig::Module<T>;
+
+decl_storage! {
+	trait Store for Module<T: Config> as ResultCheck {
+		/// IPFS HTTP gateway payloads are fetched from; the CID is appended to it.
+		pub Gateway get(fn gateway): Vec<u8> = b"http://127.0.0.1:8080/ipfs/".to_vec();
+		/// Verdict of each verification node per role, task and result node.
+		pub Checks get(fn check): double_map hasher(blake2_128_concat) (Role, TaskId<T>, T::AccountId), hasher(blake2_128_concat) T::AccountId => Option<Verdict>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AccountId = <T as frame_system::Config>::AccountId,
+		BlockNumber = <T as frame_system::Config>::BlockNumber,
+	{
+		/// A verification node checked a result. [role, task, node, verdict, verifier]
+		Attested(Role,BlockNumber,AccountId,Verdict,AccountId),
+		/// The IPFS gateway was changed.
+		GatewaySet(Vec<u8>),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The attestation is not signed by a verification node.
+		NotAuthority,
+		/// The verification node has already checked the result.
+		AlreadyChecked,
+		NoResult,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		#[weight = 10_000]
+		fn set_gateway(origin, gateway:Vec<u8>) -> DispatchResult {
+			ensure_root(origin)?;
+			Gateway::put(&gateway);
+			Self::deposit_event(RawEvent::GatewaySet(gateway));
+			Ok(())
+		}
+
+		/// Record the verdict of a verification node. Sent by the off-chain worker; the
+		/// signature is checked in `validate_unsigned`. The result is marked invalid once
+		/// `Quorum` of the verification nodes attested a failed check.
+		#[weight = 10_000]
+		fn attest(origin, attestation:AttestationOf<T>, _signature:T::Signature) -> DispatchResult {
+			ensure_none(origin)?;
+			let verifier = attestation.public.clone().into_account();
+			ensure!(<NodeStake<T> as NodeStaking<T::AccountId>>::is_node(Role::Verification, &verifier),<Error<T>>::NotAuthority);
+			let result = VerificationSig::<T>::result((attestation.role, attestation.task), &attestation.node)
+				.ok_or(Error::<T>::NoResult)?;
+			let key = (attestation.role, attestation.task, attestation.node.clone());
+			ensure!(!<Checks<T>>::contains_key(&key, &verifier),<Error<T>>::AlreadyChecked);
+
+			<Checks<T>>::insert(&key, &verifier, attestation.verdict);
+			Self::deposit_event(RawEvent::Attested(attestation.role,attestation.task,attestation.node.clone(),attestation.verdict,verifier));
+
+			if attestation.verdict != Verdict::Valid && result.status == ResultStatus::Pending {
+				let failed = <Checks<T>>::iter_prefix_values(&key)
+					.filter(|verdict| *verdict != Verdict::Valid)
+					.count() as u32;
+				if failed >= VerificationSig::<T>::threshold() {
+					VerificationSig::<T>::do_mark_invalid(attestation.role, attestation.task, &attestation.node)?;
+				}
+			}
+			Ok(())
+		}
+
+		/// Remove the attestations of the task whose vote window just closed.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let period = VerificationSig::<T>::period();
+			if period.is_zero() || !(now % period).is_zero() || now < period {
+				return 0;
+			}
+			let task = now.saturating_sub(period);
+			let mut count: Weight = 0;
+			for role in [Role::Analysis, Role::Quantification].iter() {
+				for (node, _) in <pallet_verification_sig::Results<T>>::iter_prefix((*role, task)) {
+					let checks = <Checks<T>>::drain_prefix((*role, task, node)).count() as Weight;
+					count = count.saturating_add(checks).saturating_add(1);
+				}
+			}
+			T::DbWeight::get().reads_writes(count, count)
+		}
+
+		fn offchain_worker(now: T::BlockNumber) {
+			if let Err(e) = Self::check_results(now) {
+				frame_support::log::debug!(target: "result-check", "{}", e);
+			}
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// Check every pending result of the current task this node has not attested yet.
+	pub fn check_results(now: T::BlockNumber) -> Result<(), &'static str> {
+		let signer = Signer::<T, T::AuthorityId>::any_account();
+		if !signer.can_sign() {
+			return Err("No local ipfs key to sign attestations with");
+		}
+		let (task, phase) = VerificationSig::<T>::phase(now);
+		if phase == Phase::Commit {
+			return Ok(());
+		}
+		let gateway = Self::gateway_url();
+
+		for role in [Role::Analysis, Role::Quantification].iter() {
+			let role = *role;
+			for (node, result) in <pallet_verification_sig::Results<T>>::iter_prefix((role, task)) {
+				if result.status != ResultStatus::Pending {
+					continue;
+				}
+				// Attest each result only once from this node.
+				let key = (b"eternity::result-check", role, task, &node).encode();
+				let sent = StorageValueRef::persistent(&key);
+				if sent.get::<bool>().is_some() {
+					continue;
+				}
+
+				let verdict = match Self::verdict(role, task, &node, &result.ipfshash, &gateway) {
+					Ok(verdict) => verdict,
+					// The gateway is unreachable or the payload is not pinned yet.
+					Err(_) => continue,
+				};
+				let submitted = signer.send_unsigned_transaction(
+					|account| Attestation{
+						role,
+						task,
+						node:node.clone(),
+						verdict,
+						public:account.public.clone(),
+					},
+					|payload, signature| Call::attest(payload, signature),
+				);
+				if let Some((_, Ok(()))) = submitted {
+					sent.set(&true);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// The verdict on the payload behind `cid`, or `Err` if it could not be fetched.
+	fn verdict(
+		role: Role,
+		task: TaskId<T>,
+		node: &T::AccountId,
+		cid: &[u8],
+		gateway: &[u8],
+	) -> Result<Verdict, http::Error> {
+		if !is_valid_cid(cid) {
+			return Ok(Verdict::BadCid);
+		}
+		let (max_size, schema) = if role == Role::Quantification {
+			let version = pallet_quan_sig::Module::<T>::result_model(task, node)
+				.and_then(|(model, version)| pallet_model::Module::<T>::version(model, version));
+			match version {
+				Some(version) => (version.max_size, Some(version.schema)),
+				None => return Ok(Verdict::UnknownModel),
+			}
+		} else {
+			(T::MaxPayloadSize::get(), None)
+		};
+
+		let payload = Self::fetch(gateway, cid, max_size)?;
+		Ok(check_payload(&payload, max_size, schema.as_deref()))
+	}
+
+	fn gateway_url() -> Vec<u8> {
+		StorageValueRef::persistent(LOCAL_GATEWAY)
+			.get::<Vec<u8>>()
+			.flatten()
+			.unwrap_or_else(Self::gateway)
+	}
+
+	/// Fetch at most `max_size + 1` bytes of `cid`, enough to tell an oversized payload.
+	fn fetch(gateway: &[u8], cid: &[u8], max_size: u32) -> Result<Vec<u8>, http::Error> {
+		let mut url = gateway.to_vec();
+		url.extend_from_slice(cid);
+		let url = sp_std::str::from_utf8(&url).map_err(|_| http::Error::Unknown)?;
+
+		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MS));
+		let pending = http::Request::get(url)
+			.deadline(deadline)
+			.send()
+			.map_err(|_| http::Error::IoError)?;
+		let response = pending.try_wait(deadline)
+			.map_err(|_| http::Error::DeadlineReached)??;
+		if response.code != 200 {
+			return Err(http::Error::Unknown);
+		}
+		Ok(response.body().take(max_size as usize + 1).collect())
+	}
+}
+
+impl<T: Config> frame_support::unsigned::ValidateUnsigned for Module<T> {
+	type Call = Call<T>;
+
+	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+		if let Call::attest(ref attestation, ref signature) = call {
+			if !SignedPayload::<T>::verify::<T::AuthorityId>(attestation, signature.clone()) {
+				return InvalidTransaction::BadProof.into();
+			}
+			let verifier = attestation.public.clone().into_account();
+			if !<NodeStake<T> as NodeStaking<T::AccountId>>::is_node(Role::Verification, &verifier) {
+				return InvalidTransaction::BadProof.into();
+			}
+			match VerificationSig::<T>::result((attestation.role, attestation.task), &attestation.node) {
+				Some(result) if result.status == ResultStatus::Pending => {},
+				_ => return InvalidTransaction::Stale.into(),
+			}
+			let key = (attestation.role, attestation.task, attestation.node.clone());
+			if <Checks<T>>::contains_key(&key, &verifier) {
+				return InvalidTransaction::Stale.into();
+			}
+
+			ValidTransaction::with_tag_prefix("ResultCheck")
+				.priority(T::UnsignedPriority::get())
+				// One verdict per verification node and result.
+				.and_provides((attestation.role, attestation.task, attestation.node.clone(), verifier))
+				.longevity(64)
+				.propagate(true)
+				.build()
+		} else {
+			InvalidTransaction::Call.into()
+		}
+	}
+}
+
+/// Whether `cid` is a CIDv0 (`Qm...`, base58btc) or a base32 CIDv1 (`b...`).
+pub fn is_valid_cid(cid: &[u8]) -> bool {
+	const BASE58: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+	const BASE32: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+	match cid {
+		[b'Q', b'm', rest @ ..] => cid.len() == 46 && rest.iter().all(|c| BASE58.contains(c)),
+		[b'b', rest @ ..] => rest.len() >= 58 && rest.len() <= 128 && rest.iter().all(|c| BASE32.contains(c)),
+		_ => false,
+	}
+}
+
+/// Check a payload against the size bound and the comma separated schema fields.
+pub fn check_payload(payload: &[u8], max_size: u32, schema: Option<&[u8]>) -> Verdict {
+	if payload.len() > max_size as usize {
+		return Verdict::TooLarge;
+	}
+	let schema = match schema {
+		Some(schema) if !schema.is_empty() => schema,
+		_ => return Verdict::Valid,
+	};
+	let fields = match sp_std::str::from_utf8(payload).ok().and_then(|s| lite_json::parse_json(s).ok()) {
+		Some(JsonValue::Object(fields)) => fields,
+		_ => return Verdict::BadSchema,
+	};
+	let complete = schema
+		.split(|c| *c == b',')
+		.filter(|field| !field.is_empty())
+		.all(|field| fields.iter().any(|(key, _)| key.iter().map(|c| *c as u32).eq(field.iter().map(|b| *b as u32))));
+	if complete {
+		Verdict::Valid
+	} else {
+		Verdict::BadSchema
+	}
+}
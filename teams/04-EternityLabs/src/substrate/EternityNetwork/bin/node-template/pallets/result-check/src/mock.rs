@@ -0,0 +1,169 @@
+use crate as pallet_result_check;
+use sp_core::{H256, sr25519::Signature};
+use frame_support::parameter_types;
+use sp_runtime::{
+	Perbill,
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Verify},
+};
+use frame_system as system;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+pub type Extrinsic = TestXt<Call, ()>;
+pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		NodeStake: pallet_node_stake::{Pallet, Call, Storage, Event<T>},
+		Model: pallet_model::{Pallet, Call, Storage, Event<T>},
+		QuanStake: pallet_quan_stake::{Pallet, Call, Storage, Event<T>},
+		VerificationSig: pallet_verification_sig::{Pallet, Call, Storage, Event<T>},
+		QuanSig: pallet_quan_sig::{Pallet, Call, Storage},
+		ResultCheck: pallet_result_check::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const UnbondingDelay: u64 = 10;
+	pub const MaxUnlockingChunks: u32 = 4;
+	pub const MaxCandidates: u32 = 8;
+}
+
+impl pallet_node_stake::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type UnbondingDelay = UnbondingDelay;
+	type MaxUnlockingChunks = MaxUnlockingChunks;
+	type MaxCandidates = MaxCandidates;
+	type Slash = ();
+}
+
+parameter_types! {
+	pub const ModelMaxNameLength: u32 = 64;
+	pub const ModelMaxHashLength: u32 = 128;
+	pub const ModelMaxSchemaLength: u32 = 256;
+}
+
+impl pallet_model::Config for Test {
+	type Event = Event;
+	type MaxNameLength = ModelMaxNameLength;
+	type MaxHashLength = ModelMaxHashLength;
+	type MaxSchemaLength = ModelMaxSchemaLength;
+}
+
+parameter_types! {
+	pub const QuanPalletId: frame_support::PalletId = frame_support::PalletId(*b"quanstak");
+}
+
+impl pallet_quan_stake::Config for Test {
+	type Event = Event;
+	type PalletId = QuanPalletId;
+}
+
+parameter_types! {
+	pub const CommitPeriod: u64 = 2;
+	pub const RevealPeriod: u64 = 2;
+	pub const VotePeriod: u64 = 4;
+	pub const Quorum: Perbill = Perbill::from_percent(67);
+	pub const SlashFraction: Perbill = Perbill::from_percent(10);
+	pub const MaxHashLength: u32 = 128;
+}
+
+impl pallet_verification_sig::Config for Test {
+	type Event = Event;
+	type CommitPeriod = CommitPeriod;
+	type RevealPeriod = RevealPeriod;
+	type VotePeriod = VotePeriod;
+	type Quorum = Quorum;
+	type SlashFraction = SlashFraction;
+	type MaxHashLength = MaxHashLength;
+}
+
+impl pallet_quan_sig::Config for Test {}
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+parameter_types! {
+	pub const MaxPayloadSize: u32 = 64;
+	pub const UnsignedPriority: u64 = 1 << 20;
+}
+
+impl pallet_result_check::Config for Test {
+	type Event = Event;
+	type AuthorityId = pallet_result_check::crypto::AuthorityId;
+	type MaxPayloadSize = MaxPayloadSize;
+	type UnsignedPriority = UnsignedPriority;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
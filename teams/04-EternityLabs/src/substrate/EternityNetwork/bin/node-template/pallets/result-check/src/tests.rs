@@ -0,0 +1,202 @@
+use crate::{Attestation, Error, Verdict, check_payload, is_valid_cid, mock::*};
+use std::sync::Arc;
+use codec::Decode;
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize, unsigned::ValidateUnsigned};
+use pallet_model::{ModelVersion, VersionStatus};
+use pallet_node_stake::Role;
+use pallet_verification_sig::{ResultInfo, ResultStatus};
+use sp_core::{
+	offchain::{OffchainWorkerExt, TransactionPoolExt, testing},
+	sr25519::Signature,
+};
+use sp_keystore::{KeystoreExt, SyncCryptoStore, testing::KeyStore};
+use sp_runtime::{
+	RuntimeAppPublic,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+};
+
+const CID_V0: &[u8] = b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+const CID_V1: &[u8] = b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+fn account(seed: u8) -> AccountId {
+	sp_core::sr25519::Public::from_raw([seed; 32])
+}
+
+/// A quantification result for task 0 that claims model 0 version 0.
+fn reveal_result(node: &AccountId, cid: &[u8]) {
+	pallet_model::Versions::<Test>::insert(0, 0, ModelVersion{
+		hash:CID_V0.to_vec(),
+		schema:b"model,weights".to_vec(),
+		max_size:64,
+		created:0,
+		status:VersionStatus::Active,
+	});
+	pallet_verification_sig::Results::<Test>::insert((Role::Quantification, 0), node, ResultInfo{
+		ipfshash:cid.to_vec(),
+		..Default::default()
+	});
+	pallet_quan_sig::ResultModels::<Test>::insert(0, node, (0, 0));
+}
+
+#[test]
+fn cid_format() {
+	assert!(is_valid_cid(CID_V0));
+	assert!(is_valid_cid(CID_V1));
+	assert!(!is_valid_cid(b"Qm0000"));
+	assert!(!is_valid_cid(b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbd0"));
+	assert!(!is_valid_cid(b"BAFYBEIGDYRZT5SFP7UDM7HU76UH7Y26NF3EFUYLQABF3OCLGTQY55FBZDI"));
+	assert!(!is_valid_cid(b""));
+}
+
+#[test]
+fn payload_size_and_schema() {
+	let schema: &[u8] = b"model,weights";
+	assert_eq!(check_payload(br#"{"model":1,"weights":[1,2]}"#, 64, Some(schema)), Verdict::Valid);
+	assert_eq!(check_payload(br#"{"model":1,"weights":[1,2]}"#, 8, Some(schema)), Verdict::TooLarge);
+	assert_eq!(check_payload(br#"{"model":1}"#, 64, Some(schema)), Verdict::BadSchema);
+	assert_eq!(check_payload(b"not json", 64, Some(schema)), Verdict::BadSchema);
+	assert_eq!(check_payload(b"not json", 64, None), Verdict::Valid);
+}
+
+#[test]
+fn worker_attests_fetched_result() {
+	const PHRASE: &str = "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	SyncCryptoStore::sr25519_generate_new(
+		&keystore,
+		crate::crypto::Public::ID,
+		Some(&format!("{}/verifier", PHRASE))
+	).unwrap();
+	let verifier = SyncCryptoStore::sr25519_public_keys(&keystore, crate::crypto::Public::ID)[0];
+
+	// The local gateway stub.
+	offchain_state.write().expect_request(testing::PendingRequest {
+		method: "GET".into(),
+		uri: format!("http://127.0.0.1:8080/ipfs/{}", std::str::from_utf8(CID_V1).unwrap()),
+		response: Some(br#"{"model":1,"weights":[1,2]}"#.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainWorkerExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	t.register_extension(KeystoreExt(Arc::new(keystore)));
+
+	t.execute_with(|| {
+		let node = account(7);
+		reveal_result(&node, CID_V1);
+
+		// Task 0 is in its reveal phase at block 3.
+		assert_ok!(ResultCheck::check_results(3));
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+		match tx.call {
+			Call::ResultCheck(crate::Call::attest(attestation, _)) => {
+				assert_eq!(attestation.role, Role::Quantification);
+				assert_eq!(attestation.task, 0);
+				assert_eq!(attestation.node, node);
+				assert_eq!(attestation.verdict, Verdict::Valid);
+				assert_eq!(attestation.public, verifier);
+			}
+			call => panic!("unexpected call: {:?}", call),
+		}
+
+		// Each result is attested only once.
+		assert_ok!(ResultCheck::check_results(4));
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn invalid_result_is_marked() {
+	new_test_ext().execute_with(|| {
+		let (node, verifier) = (account(7), account(8));
+		reveal_result(&node, CID_V1);
+		let attestation = Attestation{
+			role:Role::Quantification,
+			task:0,
+			node:node.clone(),
+			verdict:Verdict::TooLarge,
+			public:verifier,
+		};
+
+		assert_noop!(
+			ResultCheck::attest(Origin::none(), attestation.clone(), Signature::default()),
+			Error::<Test>::NotAuthority
+		);
+
+		pallet_node_stake::Authorities::<Test>::insert(Role::Verification, vec![verifier]);
+		assert_ok!(ResultCheck::attest(Origin::none(), attestation.clone(), Signature::default()));
+		assert_eq!(
+			VerificationSig::result((Role::Quantification, 0), &node).unwrap().status,
+			ResultStatus::Invalid
+		);
+		assert_eq!(ResultCheck::check((Role::Quantification, 0, node), &verifier), Some(Verdict::TooLarge));
+
+		assert_noop!(
+			ResultCheck::attest(Origin::none(), attestation, Signature::default()),
+			Error::<Test>::AlreadyChecked
+		);
+	});
+}
+
+#[test]
+fn one_verifier_can_not_invalidate_alone() {
+	new_test_ext().execute_with(|| {
+		let node = account(7);
+		let verifiers = [account(8), account(9)];
+		reveal_result(&node, CID_V1);
+		pallet_node_stake::Authorities::<Test>::insert(Role::Verification, verifiers.to_vec());
+		let attestation = |public, verdict| Attestation{
+			role:Role::Quantification,
+			task:0,
+			node:node.clone(),
+			verdict,
+			public,
+		};
+
+		// 67% of 2 verification nodes is 2 attestations.
+		assert_ok!(ResultCheck::attest(Origin::none(), attestation(verifiers[0], Verdict::BadSchema), Signature::default()));
+		assert_eq!(
+			VerificationSig::result((Role::Quantification, 0), &node).unwrap().status,
+			ResultStatus::Pending
+		);
+		assert_ok!(ResultCheck::attest(Origin::none(), attestation(verifiers[1], Verdict::TooLarge), Signature::default()));
+		assert_eq!(
+			VerificationSig::result((Role::Quantification, 0), &node).unwrap().status,
+			ResultStatus::Invalid
+		);
+
+		// Task 0 ends at block 8.
+		ResultCheck::on_initialize(8);
+		assert_eq!(ResultCheck::check((Role::Quantification, 0, node), &verifiers[0]), None);
+	});
+}
+
+#[test]
+fn unsigned_attestation_needs_valid_signature() {
+	new_test_ext().execute_with(|| {
+		let (node, verifier) = (account(7), account(8));
+		reveal_result(&node, CID_V1);
+		pallet_node_stake::Authorities::<Test>::insert(Role::Verification, vec![verifier]);
+
+		let call = crate::Call::attest(Attestation{
+			role:Role::Quantification,
+			task:0,
+			node,
+			verdict:Verdict::BadSchema,
+			public:verifier,
+		}, Signature::default());
+		assert_eq!(
+			ResultCheck::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadProof.into()
+		);
+	});
+}
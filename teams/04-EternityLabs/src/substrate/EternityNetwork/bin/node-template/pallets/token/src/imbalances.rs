@@ -0,0 +1,158 @@
+//! Imbalances of the Eternity token. Dropping one squares up `TotalSupply`.
+
+use super::{Config, TotalSupply};
+use frame_support::traits::{Imbalance, SameOrOther, TryDrop};
+use sp_runtime::{RuntimeDebug, traits::{Saturating, Zero}};
+use sp_std::{mem, result};
+
+/// Funds that have been created without any equal and opposite accounting.
+#[must_use]
+#[derive(RuntimeDebug, PartialEq, Eq)]
+pub struct PositiveImbalance<T: Config>(T::Balance);
+
+impl<T: Config> PositiveImbalance<T> {
+	pub fn new(amount: T::Balance) -> Self {
+		PositiveImbalance(amount)
+	}
+}
+
+/// Funds that have been destroyed without any equal and opposite accounting.
+#[must_use]
+#[derive(RuntimeDebug, PartialEq, Eq)]
+pub struct NegativeImbalance<T: Config>(T::Balance);
+
+impl<T: Config> NegativeImbalance<T> {
+	pub fn new(amount: T::Balance) -> Self {
+		NegativeImbalance(amount)
+	}
+}
+
+impl<T: Config> TryDrop for PositiveImbalance<T> {
+	fn try_drop(self) -> result::Result<(), Self> {
+		self.drop_zero()
+	}
+}
+
+impl<T: Config> Default for PositiveImbalance<T> {
+	fn default() -> Self {
+		Self::zero()
+	}
+}
+
+impl<T: Config> Imbalance<T::Balance> for PositiveImbalance<T> {
+	type Opposite = NegativeImbalance<T>;
+
+	fn zero() -> Self {
+		Self(Zero::zero())
+	}
+	fn drop_zero(self) -> result::Result<(), Self> {
+		if self.0.is_zero() {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+	fn split(self, amount: T::Balance) -> (Self, Self) {
+		let first = self.0.min(amount);
+		let second = self.0 - first;
+
+		mem::forget(self);
+		(Self(first), Self(second))
+	}
+	fn merge(mut self, other: Self) -> Self {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+
+		self
+	}
+	fn subsume(&mut self, other: Self) {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+	}
+	fn offset(self, other: Self::Opposite) -> SameOrOther<Self, Self::Opposite> {
+		let (a, b) = (self.0, other.0);
+		mem::forget((self, other));
+
+		if a > b {
+			SameOrOther::Same(Self(a - b))
+		} else if b > a {
+			SameOrOther::Other(NegativeImbalance::new(b - a))
+		} else {
+			SameOrOther::None
+		}
+	}
+	fn peek(&self) -> T::Balance {
+		self.0
+	}
+}
+
+impl<T: Config> TryDrop for NegativeImbalance<T> {
+	fn try_drop(self) -> result::Result<(), Self> {
+		self.drop_zero()
+	}
+}
+
+impl<T: Config> Default for NegativeImbalance<T> {
+	fn default() -> Self {
+		Self::zero()
+	}
+}
+
+impl<T: Config> Imbalance<T::Balance> for NegativeImbalance<T> {
+	type Opposite = PositiveImbalance<T>;
+
+	fn zero() -> Self {
+		Self(Zero::zero())
+	}
+	fn drop_zero(self) -> result::Result<(), Self> {
+		if self.0.is_zero() {
+			Ok(())
+		} else {
+			Err(self)
+		}
+	}
+	fn split(self, amount: T::Balance) -> (Self, Self) {
+		let first = self.0.min(amount);
+		let second = self.0 - first;
+
+		mem::forget(self);
+		(Self(first), Self(second))
+	}
+	fn merge(mut self, other: Self) -> Self {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+
+		self
+	}
+	fn subsume(&mut self, other: Self) {
+		self.0 = self.0.saturating_add(other.0);
+		mem::forget(other);
+	}
+	fn offset(self, other: Self::Opposite) -> SameOrOther<Self, Self::Opposite> {
+		let (a, b) = (self.0, other.0);
+		mem::forget((self, other));
+
+		if a > b {
+			SameOrOther::Same(Self(a - b))
+		} else if b > a {
+			SameOrOther::Other(PositiveImbalance::new(b - a))
+		} else {
+			SameOrOther::None
+		}
+	}
+	fn peek(&self) -> T::Balance {
+		self.0
+	}
+}
+
+impl<T: Config> Drop for PositiveImbalance<T> {
+	fn drop(&mut self) {
+		<TotalSupply<T>>::mutate(|v| *v = v.saturating_add(self.0));
+	}
+}
+
+impl<T: Config> Drop for NegativeImbalance<T> {
+	fn drop(&mut self) {
+		<TotalSupply<T>>::mutate(|v| *v = v.saturating_sub(self.0));
+	}
+}
@@ -1,58 +1,114 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-//! Simple Token Transfer
-//! 1. set total supply
-//! 2. establish ownership upon configuration of circulating tokens
-//! 3. coordinate token transfers with the runtime functions
+//! Eternity Token
+//! 1. balances are endowed at genesis; `TotalSupply` tracks the circulating amount
+//! 2. holders transfer, or approve a spender who may `transfer_from` up to the allowance
+//! 3. the supply origin (governance) mints and burns
+//! 4. a price oracle schedules supply expansions/contractions through `SupplySchedule`;
+//!    they are applied to the token treasury every `SupplyPeriod` blocks, capped at
+//!    `MaxSupplyChange` of the supply
+//!
+//! The pallet implements `Currency`, `ReservableCurrency` and the `fungible` traits, so the
+//! stake pallets can use it as their `Currency`.
 use frame_support::{
-	decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+	PalletId, Parameter, decl_error, decl_event, decl_module, decl_storage,
+	dispatch::{DispatchError, DispatchResult}, ensure,
+	traits::{
+		BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement, Get, Imbalance, MaxEncodedLen,
+		ReservableCurrency, SignedImbalance, WithdrawReasons,
+		fungible,
+		tokens::{DepositConsequence, WithdrawConsequence},
+	},
+	weights::Weight,
 };
 use frame_system::ensure_signed;
+use codec::{Codec, Encode, Decode};
+use sp_runtime::{
+	Permill, RuntimeDebug,
+	traits::{
+		AccountIdConversion, AtLeast32BitUnsigned, Bounded, CheckedAdd, CheckedSub,
+		MaybeSerializeDeserialize, Member, Saturating, Zero,
+	},
+};
+use sp_std::{fmt::Debug, prelude::*};
+
+mod imbalances;
+pub use imbalances::{NegativeImbalance, PositiveImbalance};
 
+#[cfg(test)]
+mod tests;
 
 pub trait Config: frame_system::Config {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// The balance of an account.
+	type Balance: Parameter + Member + AtLeast32BitUnsigned + Codec + Default + Copy +
+		MaybeSerializeDeserialize + Debug + MaxEncodedLen;
+	/// The minimum amount required to keep an account open.
+	type ExistentialDeposit: Get<Self::Balance>;
+	/// Origin allowed to mint, burn and schedule supply changes.
+	type SupplyOrigin: EnsureOrigin<Self::Origin>;
+	/// The token treasury; supply changes are minted into and burnt from it.
+	type PalletId: Get<PalletId>;
+	/// Number of blocks between two supply changes.
+	type SupplyPeriod: Get<Self::BlockNumber>;
+	/// Largest share of the supply a single supply change may add or remove.
+	type MaxSupplyChange: Get<Permill>;
 }
 
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct AccountData<Balance>{
+	/// Balance that can be transferred, approved and reserved.
+	pub free:Balance,
+	/// Balance held by other pallets, e.g. node stakes. It still counts towards the account's total.
+	pub reserved:Balance,
+}
 
-// #[derive(Encode, Decode)]
-// #[cfg_attr(feature = "std", derive(Debug))]
-// pub struct DispatchsInfo<Benifical,EndBlock,Minstake,MaxNumOfDispatchs,Interval,State>{
-// 	benifical:Benifical,//管理员发起的受益账户. AccountID
-// 	end_block : EndBlock,//当前轮结束调度    u64
-// 	minstake: Minstake,//最小质押量    u64
-// 	max_authority:MaxNumOfDispatchs,//最大的质押节点个数   u64
-// 	interval:Interval,//单次起拍间隔价格     u64
-// 	state : State //是否在竞拍状态    bool
-// }
+impl<Balance: Saturating + Copy> AccountData<Balance> {
+	pub fn total(&self) -> Balance {
+		self.free.saturating_add(self.reserved)
+	}
+}
 
-// pub type DispIndex = u64;
+/// A change of the supply requested by the price oracle.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum SupplyChange<Balance> {
+	/// Mint into the treasury.
+	Expand(Balance),
+	/// Burn from the treasury.
+	Contract(Balance),
+}
 
-// type Benifical<T> = <T as frame_system::Config>::AccountId;
-// type Minstake = u64;
-// type EndBlock = u64;
-// type MaxNumOfDispatchs = u64;
-// type Interval = u64;
-// type State = bool;
+/// Driven by a price oracle to steer the token supply.
+pub trait SupplySchedule<Balance> {
+	/// Queue `change` for the next supply period. A later call replaces an earlier one.
+	fn schedule(change: SupplyChange<Balance>);
+}
 
-// type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
-// // type BalancesOf<T> = <pallet_token::Module<T>>::get_balance;
-// type DispatchsInfoOf<T> = DispatchsInfo<Benifical<T>,EndBlock,Minstake,MaxNumOfDispatchs,Interval,State>;
+type AccountDataOf<T> = AccountData<<T as Config>::Balance>;
 
 decl_storage! {
 	trait Store for Module<T: Config> as Token {
-		pub Balances get(fn get_balance): map hasher(blake2_128_concat) T::AccountId => u64;
+		pub Balances get(fn account): map hasher(blake2_128_concat) T::AccountId => AccountDataOf<T>;
 
-		pub TotalSupply get(fn total_supply): u64 = 100000000;
+		pub TotalSupply get(fn total_supply) build(|config: &GenesisConfig<T>| {
+			config.balances.iter().fold(Zero::zero(), |acc: T::Balance, &(_, n)| acc.saturating_add(n))
+		}): T::Balance;
 
-		Init get(fn is_init): bool;
+		/// Amount `spender` may still move out of `owner`'s account. [owner, spender]
+		pub Allowances get(fn allowance): double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) T::AccountId => T::Balance;
 
-		//another
-		// // pub TotalSupply get(fn total_supply): u64 = 21000000;
-		// pub Dispatchs get(fn get_dispatch): map hasher(blake2_128_concat) DispIndex => Option<DispatchsInfoOf<T>>;
-		// // Init get(fn is_init): bool;
-		// pub DispCount get(fn dispatch_count): DispIndex;
+		/// Supply change applied at the start of the next supply period.
+		pub PendingSupplyChange get(fn pending_supply_change): Option<SupplyChange<T::Balance>>;
+	}
 
+	add_extra_genesis {
+		config(balances): Vec<(T::AccountId, T::Balance)>;
+		build(|config: &GenesisConfig<T>| {
+			for (who, free) in config.balances.iter() {
+				assert!(*free >= T::ExistentialDeposit::get(), "the balance of any account should always be at least the existential deposit.");
+				<Balances<T>>::insert(who, AccountData{ free: *free, reserved: Zero::zero() });
+			}
+		});
 	}
 }
 
@@ -60,60 +116,554 @@ decl_event!(
 	pub enum Event<T>
 	where
 		AccountId = <T as frame_system::Config>::AccountId,
+		Balance = <T as Config>::Balance,
+		Change = SupplyChange<<T as Config>::Balance>,
 	{
-		/// Token was initialized by user
-		Initialized(AccountId),
 		/// Tokens successfully transferred between users
-		Transfer(AccountId, AccountId, u64), // (from, to, value)
+		Transfer(AccountId, AccountId, Balance), // (from, to, value)
+		/// An owner set the allowance of a spender. [owner, spender, value]
+		Approval(AccountId, AccountId, Balance),
+		/// Tokens were created by the supply origin. [to, value]
+		Minted(AccountId, Balance),
+		/// Tokens were destroyed by the supply origin. [from, value]
+		Burned(AccountId, Balance),
+		/// Balance was moved from free to reserved. [who, value]
+		Reserved(AccountId, Balance),
+		/// Balance was moved from reserved to free. [who, value]
+		Unreserved(AccountId, Balance),
+		/// An account fell below the existential deposit and its dust was burnt. [who, dust]
+		DustLost(AccountId, Balance),
+		/// A supply change was queued for the next supply period.
+		SupplyChangeScheduled(Change),
+		/// A supply change was applied to the treasury, after capping.
+		SupplyChanged(Change),
 	}
 );
 
 decl_error! {
 	pub enum Error for Module<T: Config> {
-		/// Attempted to initialize the token after it had already been initialized.
-		AlreadyInitialized,
 		/// Attempted to transfer more funds than were available
 		InsufficientFunds,
+		/// The spender's allowance is too low.
+		InsufficientAllowance,
+		/// The resulting balance would be below the existential deposit.
+		ExistentialDeposit,
+		/// The transfer would kill the sender's account.
+		KeepAlive,
+		/// The beneficiary account does not exist.
+		DeadAccount,
+		Overflow,
 	}
 }
 
 decl_module! {
 	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		const ExistentialDeposit: T::Balance = T::ExistentialDeposit::get();
+		const SupplyPeriod: T::BlockNumber = T::SupplyPeriod::get();
+
 		fn deposit_event() = default;
 
-		/// Initialize the token
-		/// transfers the total_supply amout to the caller
+		/// Transfer tokens from one account to another
 		#[weight = 10_000]
-		fn init(origin) -> DispatchResult {
+		fn transfer(origin, to: T::AccountId, value: T::Balance) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
-			ensure!(!Self::is_init(), <Error<T>>::AlreadyInitialized);
+			Self::do_transfer(&sender, &to, value, ExistenceRequirement::AllowDeath)
+		}
+
+		/// Allow `spender` to move up to `value` of the caller's tokens. Replaces the previous allowance.
+		#[weight = 10_000]
+		fn approve(origin, spender: T::AccountId, value: T::Balance) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			if value.is_zero() {
+				<Allowances<T>>::remove(&owner, &spender);
+			} else {
+				<Allowances<T>>::insert(&owner, &spender, value);
+			}
+			Self::deposit_event(RawEvent::Approval(owner, spender, value));
+			Ok(())
+		}
+
+		/// Move `value` from `owner` to `to`, spending the caller's allowance.
+		#[weight = 10_000]
+		fn transfer_from(origin, owner: T::AccountId, to: T::AccountId, value: T::Balance) -> DispatchResult {
+			let spender = ensure_signed(origin)?;
+			let allowance = Self::allowance(&owner, &spender);
+			let remaining = allowance.checked_sub(&value).ok_or(<Error<T>>::InsufficientAllowance)?;
 
-			<Balances<T>>::insert(sender, Self::total_supply());
+			Self::do_transfer(&owner, &to, value, ExistenceRequirement::AllowDeath)?;
 
-			Init::put(true);
-		
+			if remaining.is_zero() {
+				<Allowances<T>>::remove(&owner, &spender);
+			} else {
+				<Allowances<T>>::insert(&owner, &spender, remaining);
+			}
 			Ok(())
 		}
 
-		/// Transfer tokens from one account to another
+		/// Create `value` new tokens in `dest`.
+		#[weight = 10_000]
+		fn mint(origin, dest: T::AccountId, value: T::Balance) -> DispatchResult {
+			T::SupplyOrigin::ensure_origin(origin)?;
+			Self::do_mint(&dest, value)
+		}
+
+		/// Destroy `value` of the free tokens of `from`.
 		#[weight = 10_000]
-		fn transfer(_origin, to: T::AccountId, value: u64) -> DispatchResult {
-			let sender = ensure_signed(_origin)?;
-			let sender_balance = Self::get_balance(&sender);
-			let receiver_balance = Self::get_balance(&to);
+		fn burn(origin, from: T::AccountId, value: T::Balance) -> DispatchResult {
+			T::SupplyOrigin::ensure_origin(origin)?;
+			Self::do_burn(&from, value)
+		}
+
+		/// Queue a supply change for the next supply period.
+		#[weight = 10_000]
+		fn schedule_supply_change(origin, change: SupplyChange<T::Balance>) -> DispatchResult {
+			T::SupplyOrigin::ensure_origin(origin)?;
+			<Self as SupplySchedule<T::Balance>>::schedule(change);
+			Ok(())
+		}
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let period = T::SupplyPeriod::get();
+			if period.is_zero() || !(now % period).is_zero() {
+				return 0;
+			}
+			match <PendingSupplyChange<T>>::take() {
+				Some(change) => {
+					Self::apply_supply_change(change);
+					T::DbWeight::get().reads_writes(3, 3)
+				}
+				None => T::DbWeight::get().reads(1),
+			}
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The token treasury.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account()
+	}
+
+	pub fn get_balance(who: &T::AccountId) -> T::Balance {
+		Self::account(who).free
+	}
+
+	/// Mutate the account of `who`. An account left below the existential deposit is removed
+	/// and its dust taken out of the supply.
+	fn mutate_account<R>(
+		who: &T::AccountId,
+		f: impl FnOnce(&mut AccountDataOf<T>) -> Result<R, DispatchError>,
+	) -> Result<R, DispatchError> {
+		<Balances<T>>::try_mutate_exists(who, |maybe_account| {
+			let mut account = maybe_account.take().unwrap_or_default();
+			let result = f(&mut account)?;
+
+			let total = account.total();
+			if total < T::ExistentialDeposit::get() {
+				if !total.is_zero() {
+					<TotalSupply<T>>::mutate(|v| *v = v.saturating_sub(total));
+					Self::deposit_event(RawEvent::DustLost(who.clone(), total));
+				}
+			} else {
+				*maybe_account = Some(account);
+			}
+			Ok(result)
+		})
+	}
+
+	pub fn do_transfer(
+		from: &T::AccountId,
+		to: &T::AccountId,
+		value: T::Balance,
+		existence: ExistenceRequirement,
+	) -> DispatchResult {
+		if value.is_zero() || from == to {
+			return Ok(());
+		}
+		let ed = T::ExistentialDeposit::get();
+		Self::mutate_account(to, |to_account| -> DispatchResult {
+			Self::mutate_account(from, |from_account| -> DispatchResult {
+				from_account.free = from_account.free.checked_sub(&value).ok_or(<Error<T>>::InsufficientFunds)?;
+				to_account.free = to_account.free.checked_add(&value).ok_or(<Error<T>>::Overflow)?;
+				ensure!(to_account.total() >= ed,<Error<T>>::ExistentialDeposit);
+				ensure!(existence == ExistenceRequirement::AllowDeath || from_account.total() >= ed,<Error<T>>::KeepAlive);
+				Ok(())
+			})
+		})?;
+
+		Self::deposit_event(RawEvent::Transfer(from.clone(), to.clone(), value));
+		Ok(())
+	}
+
+	pub fn do_mint(who: &T::AccountId, value: T::Balance) -> DispatchResult {
+		if value.is_zero() {
+			return Ok(());
+		}
+		let supply = Self::total_supply().checked_add(&value).ok_or(<Error<T>>::Overflow)?;
+		Self::mutate_account(who, |account| -> DispatchResult {
+			account.free = account.free.checked_add(&value).ok_or(<Error<T>>::Overflow)?;
+			ensure!(account.total() >= T::ExistentialDeposit::get(),<Error<T>>::ExistentialDeposit);
+			Ok(())
+		})?;
+		<TotalSupply<T>>::put(supply);
+
+		Self::deposit_event(RawEvent::Minted(who.clone(), value));
+		Ok(())
+	}
+
+	pub fn do_burn(who: &T::AccountId, value: T::Balance) -> DispatchResult {
+		if value.is_zero() {
+			return Ok(());
+		}
+		Self::mutate_account(who, |account| -> DispatchResult {
+			account.free = account.free.checked_sub(&value).ok_or(<Error<T>>::InsufficientFunds)?;
+			Ok(())
+		})?;
+		<TotalSupply<T>>::mutate(|v| *v = v.saturating_sub(value));
+
+		Self::deposit_event(RawEvent::Burned(who.clone(), value));
+		Ok(())
+	}
+
+	/// Apply `change` to the treasury, capped at `MaxSupplyChange` of the supply.
+	fn apply_supply_change(change: SupplyChange<T::Balance>) {
+		let cap = T::MaxSupplyChange::get() * Self::total_supply();
+		let treasury = Self::account_id();
+		let applied = match change {
+			SupplyChange::Expand(value) => {
+				let value = value.min(cap);
+				match Self::do_mint(&treasury, value) {
+					Ok(()) => SupplyChange::Expand(value),
+					Err(_) => SupplyChange::Expand(Zero::zero()),
+				}
+			}
+			SupplyChange::Contract(value) => {
+				let value = value.min(cap).min(Self::get_balance(&treasury));
+				match Self::do_burn(&treasury, value) {
+					Ok(()) => SupplyChange::Contract(value),
+					Err(_) => SupplyChange::Contract(Zero::zero()),
+				}
+			}
+		};
+		Self::deposit_event(RawEvent::SupplyChanged(applied));
+	}
+}
+
+impl<T: Config> SupplySchedule<T::Balance> for Module<T> {
+	fn schedule(change: SupplyChange<T::Balance>) {
+		<PendingSupplyChange<T>>::put(change);
+		Self::deposit_event(RawEvent::SupplyChangeScheduled(change));
+	}
+}
+
+impl<T: Config> Currency<T::AccountId> for Module<T> {
+	type Balance = T::Balance;
+	type PositiveImbalance = PositiveImbalance<T>;
+	type NegativeImbalance = NegativeImbalance<T>;
+
+	fn total_balance(who: &T::AccountId) -> T::Balance {
+		Self::account(who).total()
+	}
+
+	fn can_slash(who: &T::AccountId, value: T::Balance) -> bool {
+		value.is_zero() || Self::free_balance(who) >= value
+	}
+
+	fn total_issuance() -> T::Balance {
+		Self::total_supply()
+	}
+
+	fn minimum_balance() -> T::Balance {
+		T::ExistentialDeposit::get()
+	}
 
-			// Calculate new balances
-			let updated_from_balance = sender_balance.checked_sub(value).ok_or(<Error<T>>::InsufficientFunds)?;
-			let updated_to_balance = receiver_balance.checked_add(value).expect("Entire supply fits in u64; qed");
+	fn burn(mut amount: T::Balance) -> Self::PositiveImbalance {
+		if amount.is_zero() {
+			return PositiveImbalance::zero();
+		}
+		<TotalSupply<T>>::mutate(|issued| {
+			*issued = issued.checked_sub(&amount).unwrap_or_else(|| {
+				amount = *issued;
+				Zero::zero()
+			});
+		});
+		PositiveImbalance::new(amount)
+	}
+
+	fn issue(mut amount: T::Balance) -> Self::NegativeImbalance {
+		if amount.is_zero() {
+			return NegativeImbalance::zero();
+		}
+		<TotalSupply<T>>::mutate(|issued| {
+			*issued = issued.checked_add(&amount).unwrap_or_else(|| {
+				amount = T::Balance::max_value() - *issued;
+				T::Balance::max_value()
+			});
+		});
+		NegativeImbalance::new(amount)
+	}
+
+	fn free_balance(who: &T::AccountId) -> T::Balance {
+		Self::account(who).free
+	}
+
+	// The token has no locks, so any free balance can be withdrawn.
+	fn ensure_can_withdraw(
+		_who: &T::AccountId,
+		_amount: T::Balance,
+		_reasons: WithdrawReasons,
+		_new_balance: T::Balance,
+	) -> DispatchResult {
+		Ok(())
+	}
+
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		value: T::Balance,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		Self::do_transfer(source, dest, value, existence_requirement)
+	}
+
+	fn slash(who: &T::AccountId, value: T::Balance) -> (Self::NegativeImbalance, T::Balance) {
+		if value.is_zero() || Self::total_balance(who).is_zero() {
+			return (NegativeImbalance::zero(), value);
+		}
+		Self::mutate_account(who, |account| -> Result<_, DispatchError> {
+			let free_slash = value.min(account.free);
+			account.free -= free_slash;
+			let remaining = value - free_slash;
+			let reserved_slash = remaining.min(account.reserved);
+			account.reserved -= reserved_slash;
+			Ok((NegativeImbalance::new(free_slash + reserved_slash), remaining - reserved_slash))
+		}).unwrap_or_else(|_| (NegativeImbalance::zero(), value))
+	}
+
+	fn deposit_into_existing(
+		who: &T::AccountId,
+		value: T::Balance,
+	) -> Result<Self::PositiveImbalance, DispatchError> {
+		if value.is_zero() {
+			return Ok(PositiveImbalance::zero());
+		}
+		Self::mutate_account(who, |account| -> Result<_, DispatchError> {
+			ensure!(!account.total().is_zero(),<Error<T>>::DeadAccount);
+			account.free = account.free.checked_add(&value).ok_or(<Error<T>>::Overflow)?;
+			Ok(PositiveImbalance::new(value))
+		})
+	}
 
-			// Write new balances to storage
-			<Balances<T>>::insert(&sender, updated_from_balance);
-			<Balances<T>>::insert(&to, updated_to_balance);
+	fn deposit_creating(who: &T::AccountId, value: T::Balance) -> Self::PositiveImbalance {
+		if value.is_zero() {
+			return PositiveImbalance::zero();
+		}
+		Self::mutate_account(who, |account| -> Result<_, DispatchError> {
+			if account.total().is_zero() && value < T::ExistentialDeposit::get() {
+				return Ok(PositiveImbalance::zero());
+			}
+			account.free = account.free.checked_add(&value).ok_or(<Error<T>>::Overflow)?;
+			Ok(PositiveImbalance::new(value))
+		}).unwrap_or_else(|_| PositiveImbalance::zero())
+	}
+
+	fn withdraw(
+		who: &T::AccountId,
+		value: T::Balance,
+		_reasons: WithdrawReasons,
+		liveness: ExistenceRequirement,
+	) -> Result<Self::NegativeImbalance, DispatchError> {
+		if value.is_zero() {
+			return Ok(NegativeImbalance::zero());
+		}
+		Self::mutate_account(who, |account| -> Result<_, DispatchError> {
+			account.free = account.free.checked_sub(&value).ok_or(<Error<T>>::InsufficientFunds)?;
+			ensure!(liveness == ExistenceRequirement::AllowDeath || account.total() >= T::ExistentialDeposit::get(),<Error<T>>::KeepAlive);
+			Ok(NegativeImbalance::new(value))
+		})
+	}
 
-			Self::deposit_event(RawEvent::Transfer(sender, to, value));
+	fn make_free_balance_be(
+		who: &T::AccountId,
+		balance: T::Balance,
+	) -> SignedImbalance<T::Balance, Self::PositiveImbalance> {
+		Self::mutate_account(who, |account| -> Result<_, DispatchError> {
+			ensure!(balance.saturating_add(account.reserved) >= T::ExistentialDeposit::get(),<Error<T>>::ExistentialDeposit);
+			let imbalance = if account.free <= balance {
+				SignedImbalance::Positive(PositiveImbalance::new(balance - account.free))
+			} else {
+				SignedImbalance::Negative(NegativeImbalance::new(account.free - balance))
+			};
+			account.free = balance;
+			Ok(imbalance)
+		}).unwrap_or_else(|_| SignedImbalance::Positive(PositiveImbalance::zero()))
+	}
+}
+
+impl<T: Config> ReservableCurrency<T::AccountId> for Module<T> {
+	fn can_reserve(who: &T::AccountId, value: T::Balance) -> bool {
+		value.is_zero() || Self::free_balance(who) >= value
+	}
+
+	fn slash_reserved(who: &T::AccountId, value: T::Balance) -> (Self::NegativeImbalance, T::Balance) {
+		if value.is_zero() || Self::total_balance(who).is_zero() {
+			return (NegativeImbalance::zero(), value);
+		}
+		Self::mutate_account(who, |account| -> Result<_, DispatchError> {
+			let actual = value.min(account.reserved);
+			account.reserved -= actual;
+			Ok((NegativeImbalance::new(actual), value - actual))
+		}).unwrap_or_else(|_| (NegativeImbalance::zero(), value))
+	}
+
+	fn reserved_balance(who: &T::AccountId) -> T::Balance {
+		Self::account(who).reserved
+	}
+
+	fn reserve(who: &T::AccountId, value: T::Balance) -> DispatchResult {
+		if value.is_zero() {
+			return Ok(());
+		}
+		Self::mutate_account(who, |account| -> DispatchResult {
+			account.free = account.free.checked_sub(&value).ok_or(<Error<T>>::InsufficientFunds)?;
+			account.reserved = account.reserved.checked_add(&value).ok_or(<Error<T>>::Overflow)?;
 			Ok(())
+		})?;
+		Self::deposit_event(RawEvent::Reserved(who.clone(), value));
+		Ok(())
+	}
+
+	fn unreserve(who: &T::AccountId, value: T::Balance) -> T::Balance {
+		if value.is_zero() || Self::total_balance(who).is_zero() {
+			return value;
+		}
+		let actual = Self::mutate_account(who, |account| -> Result<_, DispatchError> {
+			let actual = value.min(account.reserved);
+			account.reserved -= actual;
+			account.free = account.free.saturating_add(actual);
+			Ok(actual)
+		}).unwrap_or_else(|_| Zero::zero());
+		Self::deposit_event(RawEvent::Unreserved(who.clone(), actual));
+		value - actual
+	}
+
+	fn repatriate_reserved(
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: T::Balance,
+		status: BalanceStatus,
+	) -> Result<T::Balance, DispatchError> {
+		if value.is_zero() {
+			return Ok(Zero::zero());
+		}
+		if slashed == beneficiary {
+			return match status {
+				BalanceStatus::Free => Ok(Self::unreserve(slashed, value)),
+				BalanceStatus::Reserved => Ok(value.saturating_sub(Self::reserved_balance(slashed))),
+			};
 		}
+		let actual = Self::mutate_account(beneficiary, |to_account| -> Result<_, DispatchError> {
+			ensure!(!to_account.total().is_zero(),<Error<T>>::DeadAccount);
+			Self::mutate_account(slashed, |from_account| -> Result<_, DispatchError> {
+				let actual = value.min(from_account.reserved);
+				match status {
+					BalanceStatus::Free => to_account.free = to_account.free.checked_add(&actual).ok_or(<Error<T>>::Overflow)?,
+					BalanceStatus::Reserved => to_account.reserved = to_account.reserved.checked_add(&actual).ok_or(<Error<T>>::Overflow)?,
+				}
+				from_account.reserved -= actual;
+				Ok(actual)
+			})
+		})?;
+		Ok(value - actual)
+	}
+}
+
+impl<T: Config> fungible::Inspect<T::AccountId> for Module<T> {
+	type Balance = T::Balance;
+
+	fn total_issuance() -> T::Balance {
+		Self::total_supply()
+	}
+
+	fn minimum_balance() -> T::Balance {
+		T::ExistentialDeposit::get()
+	}
+
+	fn balance(who: &T::AccountId) -> T::Balance {
+		Self::account(who).total()
+	}
+
+	fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> T::Balance {
+		let account = Self::account(who);
+		if keep_alive {
+			// The reserved balance already counts towards keeping the account alive.
+			account.free.saturating_sub(T::ExistentialDeposit::get().saturating_sub(account.reserved))
+		} else {
+			account.free
+		}
+	}
+
+	fn can_deposit(who: &T::AccountId, amount: T::Balance) -> DepositConsequence {
+		if amount.is_zero() {
+			return DepositConsequence::Success;
+		}
+		if Self::total_supply().checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow;
+		}
+		match Self::account(who).total().checked_add(&amount) {
+			None => DepositConsequence::Overflow,
+			Some(total) if total < T::ExistentialDeposit::get() => DepositConsequence::BelowMinimum,
+			Some(_) => DepositConsequence::Success,
+		}
+	}
+
+	fn can_withdraw(who: &T::AccountId, amount: T::Balance) -> WithdrawConsequence<T::Balance> {
+		if amount.is_zero() {
+			return WithdrawConsequence::Success;
+		}
+		if Self::total_supply().checked_sub(&amount).is_none() {
+			return WithdrawConsequence::Underflow;
+		}
+		let account = Self::account(who);
+		let free = match account.free.checked_sub(&amount) {
+			Some(free) => free,
+			None => return WithdrawConsequence::NoFunds,
+		};
+		let total = free.saturating_add(account.reserved);
+		if total < T::ExistentialDeposit::get() {
+			WithdrawConsequence::ReducedToZero(total)
+		} else {
+			WithdrawConsequence::Success
+		}
+	}
+}
+
+impl<T: Config> fungible::Mutate<T::AccountId> for Module<T> {
+	fn mint_into(who: &T::AccountId, amount: T::Balance) -> DispatchResult {
+		Self::do_mint(who, amount)
+	}
+
+	fn burn_from(who: &T::AccountId, amount: T::Balance) -> Result<T::Balance, DispatchError> {
+		Self::do_burn(who, amount)?;
+		Ok(amount)
+	}
+}
 
-		
+impl<T: Config> fungible::Transfer<T::AccountId> for Module<T> {
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<T::Balance, DispatchError> {
+		let existence = if keep_alive {
+			ExistenceRequirement::KeepAlive
+		} else {
+			ExistenceRequirement::AllowDeath
+		};
+		Self::do_transfer(source, dest, amount, existence)?;
+		Ok(amount)
 	}
 }
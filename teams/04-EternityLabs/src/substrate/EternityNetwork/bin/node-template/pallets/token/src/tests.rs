@@ -1,11 +1,15 @@
-use crate::{self as basic_token, Config, Error};
-use frame_support::{assert_noop, assert_ok, construct_runtime, parameter_types};
+use crate::{self as basic_token, Config, Error, SupplyChange, SupplySchedule};
+use frame_support::{
+	assert_noop, assert_ok, construct_runtime, parameter_types, PalletId,
+	traits::{Currency, OnInitialize, ReservableCurrency},
+};
 use frame_system as system;
 use sp_core::H256;
 use sp_io::TestExternalities;
 use sp_runtime::{
+	Permill,
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BadOrigin, BlakeTwo256, IdentityLookup},
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
@@ -17,8 +21,8 @@ construct_runtime!(
 		NodeBlock = Block,
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
-		System: frame_system::{Module, Call, Config, Storage, Event<T>},
-		BasicToken: basic_token::{Module, Call, Storage, Event<T>},
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		BasicToken: basic_token::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -50,62 +54,139 @@ impl frame_system::Config for TestRuntime {
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 10;
+	pub const TokenPalletId: PalletId = PalletId(*b"et/token");
+	pub const SupplyPeriod: u64 = 10;
+	pub const MaxSupplyChange: Permill = Permill::from_percent(1);
 }
 
 impl Config for TestRuntime {
 	type Event = ();
+	type Balance = u64;
+	type ExistentialDeposit = ExistentialDeposit;
+	type SupplyOrigin = frame_system::EnsureRoot<u64>;
+	type PalletId = TokenPalletId;
+	type SupplyPeriod = SupplyPeriod;
+	type MaxSupplyChange = MaxSupplyChange;
 }
 
 struct ExternalityBuilder;
 
 impl ExternalityBuilder {
 	pub fn build() -> TestExternalities {
-		let storage = system::GenesisConfig::default()
+		let mut storage = system::GenesisConfig::default()
 			.build_storage::<TestRuntime>()
 			.unwrap();
+		basic_token::GenesisConfig::<TestRuntime> {
+			balances: vec![(1, 21000000), (BasicToken::account_id(), 1000)],
+		}.assimilate_storage(&mut storage).unwrap();
 		TestExternalities::from(storage)
 	}
 }
 
 #[test]
-fn init_works() {
-	ExternalityBuilder::build().execute_with(|| {
-		assert_ok!(BasicToken::init(Origin::signed(1)));
-		assert_eq!(BasicToken::get_balance(1), 21000000);
-	})
-}
-
-#[test]
-fn cant_double_init() {
+fn genesis_sets_supply() {
 	ExternalityBuilder::build().execute_with(|| {
-		assert_ok!(BasicToken::init(Origin::signed(1)));
-		assert_noop!(
-			BasicToken::init(Origin::signed(1)),
-			Error::<TestRuntime>::AlreadyInitialized
-		);
+		assert_eq!(BasicToken::get_balance(&1), 21000000);
+		assert_eq!(BasicToken::total_supply(), 21001000);
 	})
 }
 
 #[test]
 fn transfer_works() {
 	ExternalityBuilder::build().execute_with(|| {
-		assert_ok!(BasicToken::init(Origin::signed(1)));
-
 		// Transfer 100 tokens from user 1 to user 2
 		assert_ok!(BasicToken::transfer(Origin::signed(1), 2, 100));
 
-		assert_eq!(BasicToken::get_balance(1), 20999900);
-		assert_eq!(BasicToken::get_balance(2), 100);
+		assert_eq!(BasicToken::get_balance(&1), 20999900);
+		assert_eq!(BasicToken::get_balance(&2), 100);
 	})
 }
 
 #[test]
 fn cant_spend_more_than_you_have() {
 	ExternalityBuilder::build().execute_with(|| {
-		assert_ok!(BasicToken::init(Origin::signed(1)));
 		assert_noop!(
 			BasicToken::transfer(Origin::signed(1), 2, 21000001),
 			Error::<TestRuntime>::InsufficientFunds
 		);
 	})
-}
\ No newline at end of file
+}
+
+#[test]
+fn cant_create_account_below_existential_deposit() {
+	ExternalityBuilder::build().execute_with(|| {
+		assert_noop!(
+			BasicToken::transfer(Origin::signed(1), 2, 9),
+			Error::<TestRuntime>::ExistentialDeposit
+		);
+	})
+}
+
+#[test]
+fn transfer_from_spends_allowance() {
+	ExternalityBuilder::build().execute_with(|| {
+		assert_ok!(BasicToken::approve(Origin::signed(1), 2, 150));
+		assert_ok!(BasicToken::transfer_from(Origin::signed(2), 1, 3, 100));
+
+		assert_eq!(BasicToken::get_balance(&3), 100);
+		assert_eq!(BasicToken::allowance(1, 2), 50);
+		assert_noop!(
+			BasicToken::transfer_from(Origin::signed(2), 1, 3, 51),
+			Error::<TestRuntime>::InsufficientAllowance
+		);
+	})
+}
+
+#[test]
+fn only_supply_origin_mints_and_burns() {
+	ExternalityBuilder::build().execute_with(|| {
+		assert_noop!(BasicToken::mint(Origin::signed(1), 2, 100), BadOrigin);
+		assert_ok!(BasicToken::mint(Origin::root(), 2, 100));
+		assert_eq!(BasicToken::total_supply(), 21001100);
+
+		assert_ok!(BasicToken::burn(Origin::root(), 1, 1000000));
+		assert_eq!(BasicToken::get_balance(&1), 20000000);
+		assert_eq!(BasicToken::total_supply(), 20001100);
+	})
+}
+
+#[test]
+fn reserve_and_slash_keep_supply_in_sync() {
+	ExternalityBuilder::build().execute_with(|| {
+		assert_ok!(<BasicToken as ReservableCurrency<u64>>::reserve(&1, 1000));
+		assert_eq!(BasicToken::account(1).reserved, 1000);
+
+		let (imbalance, remaining) = <BasicToken as ReservableCurrency<u64>>::slash_reserved(&1, 400);
+		assert_eq!(remaining, 0);
+		drop(imbalance);
+		assert_eq!(BasicToken::account(1).reserved, 600);
+		assert_eq!(<BasicToken as Currency<u64>>::total_issuance(), 21000600);
+	})
+}
+
+#[test]
+fn supply_change_is_capped_and_applied_per_period() {
+	ExternalityBuilder::build().execute_with(|| {
+		<BasicToken as SupplySchedule<u64>>::schedule(SupplyChange::Expand(1000000));
+
+		// Not a period boundary yet.
+		BasicToken::on_initialize(5);
+		assert!(BasicToken::pending_supply_change().is_some());
+
+		// 1% of 21001000.
+		BasicToken::on_initialize(10);
+		assert_eq!(BasicToken::get_balance(&BasicToken::account_id()), 1000 + 210010);
+		assert_eq!(BasicToken::total_supply(), 21001000 + 210010);
+		assert!(BasicToken::pending_supply_change().is_none());
+
+		// Contraction can not take more than the treasury holds.
+		<BasicToken as SupplySchedule<u64>>::schedule(SupplyChange::Contract(1000000));
+		BasicToken::on_initialize(20);
+		assert_eq!(BasicToken::get_balance(&BasicToken::account_id()), 0);
+	})
+}
@@ -1,28 +1,97 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-//! Simple Token Transfer
-//! 1. set total supply
-//! 2. establish ownership upon configuration of circulating tokens
-//! 3. coordinate token transfers with the runtime functions
+//! Result verification
+//! Blocks are split into tasks of `CommitPeriod + RevealPeriod + VotePeriod` blocks,
+//! identified by the block the task starts at.
+//! 1. commit: analysis/quantification nodes commit `hash(node, ipfshash, salt)`
+//! 2. reveal: they reveal `ipfshash` and `salt`; unrevealed commits are slashed
+//! 3. vote: verification nodes approve or reject each revealed result
+//!
+//! A result is finalized once `Quorum` of the verification nodes agree. A rejected node
+//! and the verification nodes that voted against the outcome are slashed through
+//! `pallet_node_stake`.
+//!
+//! Results whose payload fails the off-chain checks of `pallet_result_check` are marked
+//! `Invalid` and take no more votes.
+//!
+//! Results and votes of a task are removed one period after its vote window closes.
+
 use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+	storage::IterableStorageDoubleMap, traits::Get, weights::Weight,
 };
 use frame_system::ensure_signed;
+use codec::{Encode,Decode};
+use pallet_node_stake::{NodeStaking, Role};
+use sp_runtime::{Perbill, RuntimeDebug, traits::{Hash, Saturating, Zero}};
 use sp_std::vec::Vec;
 
+#[cfg(test)]
+mod mock;
 
+#[cfg(test)]
+mod tests;
 
-
-pub trait Config: frame_system::Config {
+pub trait Config: frame_system::Config + pallet_node_stake::Config {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+	/// Number of blocks in which nodes may commit a result.
+	type CommitPeriod: Get<Self::BlockNumber>;
+	/// Number of blocks in which nodes may reveal their committed result.
+	type RevealPeriod: Get<Self::BlockNumber>;
+	/// Number of blocks in which verification nodes may vote on revealed results.
+	type VotePeriod: Get<Self::BlockNumber>;
+	/// Share of the verification nodes that must agree to finalize a result.
+	type Quorum: Get<Perbill>;
+	/// Share of the stake slashed from a node that disagrees with a finalized result.
+	type SlashFraction: Get<Perbill>;
+	/// Largest `ipfshash` and `salt` a node may reveal, in bytes.
+	type MaxHashLength: Get<u32>;
 }
 
-decl_storage! {
-	trait Store for Module<T: Config> as Model {
-		pub ModelList get(fn get_model): map hasher(blake2_128_concat) Vec<u8> => (Vec<u8>,T::AccountId);
-		pub SuperManager get(fn is_manager): map hasher(blake2_128_concat) T::AccountId => T::AccountId;
-		Init get(fn is_init): bool;
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Phase {
+	Commit,
+	Reveal,
+	Vote,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ResultStatus {
+	Pending,
+	Accepted,
+	Rejected,
+	/// The payload behind `ipfshash` failed the off-chain checks.
+	Invalid,
+}
+
+impl Default for ResultStatus {
+	fn default() -> Self {
+		ResultStatus::Pending
+	}
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ResultInfo{
+	/// At most `MaxHashLength` bytes.
+	pub ipfshash:Vec<u8>,
+	/// Number of approving votes; the votes themselves are kept in `Votes`.
+	pub ayes:u32,
+	pub nays:u32,
+	pub status:ResultStatus,
+}
+
+pub type TaskId<T> = <T as frame_system::Config>::BlockNumber;
+type NodeStake<T> = pallet_node_stake::Module<T>;
 
+decl_storage! {
+	trait Store for Module<T: Config> as VerificationSig {
+		/// Commitments of the current task per role.
+		pub Commits get(fn commit): double_map hasher(blake2_128_concat) (Role, TaskId<T>), hasher(blake2_128_concat) T::AccountId => Option<T::Hash>;
+		/// Revealed results and their votes per role and task.
+		pub Results get(fn result): double_map hasher(blake2_128_concat) (Role, TaskId<T>), hasher(blake2_128_concat) T::AccountId => Option<ResultInfo>;
+		/// Votes of verification nodes per role, task and result node.
+		pub Votes get(fn vote_of): double_map hasher(blake2_128_concat) (Role, TaskId<T>, T::AccountId), hasher(blake2_128_concat) T::AccountId => Option<bool>;
 	}
 }
 
@@ -30,21 +99,43 @@ decl_event!(
 	pub enum Event<T>
 	where
 		AccountId = <T as frame_system::Config>::AccountId,
+		BlockNumber = <T as frame_system::Config>::BlockNumber,
 	{
-		/// Token was initialized by user
-		Initialized(AccountId),
-		/// Tokens successfully transferred between users
-		Transfer(AccountId, AccountId, u64), // (from, to, value)
+		/// A node committed a result. [role, task, node]
+		Committed(Role,BlockNumber,AccountId),
+		/// A node revealed its result. [role, task, node, ipfshash]
+		Revealed(Role,BlockNumber,AccountId,Vec<u8>),
+		/// A verification node voted on a result. [role, task, node, verifier, approve]
+		Voted(Role,BlockNumber,AccountId,AccountId,bool),
+		/// A result reached quorum. [role, task, node, accepted]
+		Finalized(Role,BlockNumber,AccountId,bool),
+		/// A node was reported to the staking pallet. [role, node]
+		Reported(Role,AccountId),
+		/// A result failed the off-chain payload checks. [role, task, node]
+		MarkedInvalid(Role,BlockNumber,AccountId),
+		/// A result was removed without reaching quorum. [role, task, node]
+		Expired(Role,BlockNumber,AccountId),
 	}
 );
 
 decl_error! {
 	pub enum Error for Module<T: Config> {
-		/// Attempted to initialize the token after it had already been initialized.
-		AlreadyInitialized,
-		/// Attempted to transfer more funds than were available
-		InsufficientFunds,
-		NotManager,
+		/// The sender is not an elected node for the role.
+		NotAuthority,
+		/// Only analysis and quantification results go through verification.
+		InvalidRole,
+		/// The task is not in the required phase.
+		WrongPhase,
+		AlreadyCommitted,
+		NoCommit,
+		/// The revealed result does not match the commitment.
+		CommitMismatch,
+		AlreadyRevealed,
+		NoResult,
+		AlreadyVoted,
+		AlreadyFinalized,
+		/// The revealed `ipfshash` or `salt` is longer than `MaxHashLength`.
+		HashTooLong,
 	}
 }
 
@@ -52,26 +143,189 @@ decl_module! {
 	pub struct Module<T: Config> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
-	
+		/// Approve or reject the result `node` revealed for `task`.
 		#[weight = 10_000]
-		fn init(origin) -> DispatchResult {
-			let creator = ensure_signed(origin)?;
-			ensure!(!Self::is_init(), <Error<T>>::AlreadyInitialized);
-            
-            <SuperManager<T>>::insert(&creator,&creator.clone());
-			Init::put(true);
+		fn vote(origin, role:Role, task:TaskId<T>, node:T::AccountId, approve:bool) -> DispatchResult {
+			let verifier = ensure_signed(origin)?;
+			ensure!(<NodeStake<T> as NodeStaking<T::AccountId>>::is_node(Role::Verification, &verifier),<Error<T>>::NotAuthority);
+			Self::ensure_result_role(role)?;
+			let (current, phase) = Self::phase(<frame_system::Pallet<T>>::block_number());
+			ensure!(current == task && phase == Phase::Vote,<Error<T>>::WrongPhase);
+
+			let mut result = Self::result((role, task), &node).ok_or(Error::<T>::NoResult)?;
+			ensure!(result.status == ResultStatus::Pending,<Error<T>>::AlreadyFinalized);
+			let key = (role, task, node.clone());
+			ensure!(!<Votes<T>>::contains_key(&key, &verifier),<Error<T>>::AlreadyVoted);
+
+			if approve {
+				result.ayes = result.ayes.saturating_add(1);
+			} else {
+				result.nays = result.nays.saturating_add(1);
+			}
+			<Votes<T>>::insert(&key, &verifier, approve);
+			Self::deposit_event(RawEvent::Voted(role,task,node.clone(),verifier,approve));
+
+			let threshold = Self::threshold();
+			if result.ayes >= threshold {
+				result.status = ResultStatus::Accepted;
+			} else if result.nays >= threshold {
+				result.status = ResultStatus::Rejected;
+				Self::report(role, &node);
+			}
+			if result.status != ResultStatus::Pending {
+				let outcome = result.status == ResultStatus::Accepted;
+				for (voter, vote) in <Votes<T>>::iter_prefix(&key) {
+					if vote != outcome {
+						Self::report(Role::Verification, &voter);
+					}
+				}
+				Self::deposit_event(RawEvent::Finalized(role,task,node.clone(),outcome));
+			}
+
+			<Results<T>>::insert((role, task), &node, result);
 			Ok(())
 		}
 
-		/// Transfer tokens from one account to another
-		#[weight = 10_000]
-		fn ipfs_store(_origin,model:Vec<u8>, ipfshash: Vec<u8>) -> DispatchResult {
-			let sender = ensure_signed(_origin)?;
-			ensure!(Self::is_manager(&sender) == sender,<Error<T>>::NotManager);
+		/// Slash the nodes that committed but did not reveal once the reveal window closes,
+		/// and remove the results of the task that ended one period ago.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let period = Self::period();
+			if period.is_zero() {
+				return 0;
+			}
+			if let Some(task) = Self::expired_task(now) {
+				return Self::prune(task);
+			}
+			let offset = now % period;
+			if offset != T::CommitPeriod::get().saturating_add(T::RevealPeriod::get()) {
+				return 0;
+			}
+
+			let task = now.saturating_sub(offset);
+			let mut count: Weight = 0;
+			for role in [Role::Analysis, Role::Quantification].iter() {
+				for (node, _) in <Commits<T>>::drain_prefix((*role, task)) {
+					if !<Results<T>>::contains_key((*role, task), &node) {
+						Self::report(*role, &node);
+					}
+					count = count.saturating_add(1);
+				}
+			}
+			T::DbWeight::get().reads_writes(count.saturating_mul(2), count.saturating_mul(2))
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	pub fn period() -> T::BlockNumber {
+		T::CommitPeriod::get()
+			.saturating_add(T::RevealPeriod::get())
+			.saturating_add(T::VotePeriod::get())
+	}
 
-			<ModelList<T>>::insert(model,(ipfshash,sender));
+	/// The task `now` belongs to and its current phase.
+	pub fn phase(now: T::BlockNumber) -> (TaskId<T>, Phase) {
+		let period = Self::period();
+		let offset = if period.is_zero() { Zero::zero() } else { now % period };
+		let phase = if offset < T::CommitPeriod::get() {
+			Phase::Commit
+		} else if offset < T::CommitPeriod::get().saturating_add(T::RevealPeriod::get()) {
+			Phase::Reveal
+		} else {
+			Phase::Vote
+		};
+		(now.saturating_sub(offset), phase)
+	}
+
+	/// The task whose results are removed at `now`, if any.
+	pub fn expired_task(now: T::BlockNumber) -> Option<TaskId<T>> {
+		let period = Self::period();
+		if period.is_zero() {
+			return None;
+		}
+		let expired = period.saturating_mul(2u32.into());
+		if (now % period).is_zero() && now >= expired {
+			Some(now - expired)
+		} else {
+			None
+		}
+	}
+
+	/// The commitment a node has to submit for `ipfshash` and `salt`.
+	pub fn commitment(who: &T::AccountId, ipfshash: &[u8], salt: &[u8]) -> T::Hash {
+		T::Hashing::hash_of(&(who, ipfshash, salt))
+	}
+
+	pub fn do_commit(role: Role, who: &T::AccountId, commitment: T::Hash) -> DispatchResult {
+		Self::ensure_result_role(role)?;
+		ensure!(<NodeStake<T> as NodeStaking<T::AccountId>>::is_node(role, who),<Error<T>>::NotAuthority);
+		let (task, phase) = Self::phase(<frame_system::Pallet<T>>::block_number());
+		ensure!(phase == Phase::Commit,<Error<T>>::WrongPhase);
+		ensure!(!<Commits<T>>::contains_key((role, task), who),<Error<T>>::AlreadyCommitted);
+
+		<Commits<T>>::insert((role, task), who, commitment);
+		Self::deposit_event(RawEvent::Committed(role,task,who.clone()));
+		Ok(())
+	}
+
+	pub fn do_reveal(role: Role, who: &T::AccountId, ipfshash: Vec<u8>, salt: Vec<u8>) -> DispatchResult {
+		Self::ensure_result_role(role)?;
+		let (task, phase) = Self::phase(<frame_system::Pallet<T>>::block_number());
+		ensure!(phase == Phase::Reveal,<Error<T>>::WrongPhase);
+		let max = T::MaxHashLength::get() as usize;
+		ensure!(ipfshash.len() <= max && salt.len() <= max,<Error<T>>::HashTooLong);
+		let commitment = Self::commit((role, task), who).ok_or(Error::<T>::NoCommit)?;
+		ensure!(!<Results<T>>::contains_key((role, task), who),<Error<T>>::AlreadyRevealed);
+		ensure!(Self::commitment(who, &ipfshash, &salt) == commitment,<Error<T>>::CommitMismatch);
+
+		<Results<T>>::insert((role, task), who, ResultInfo{
+			ipfshash:ipfshash.clone(),
+			..Default::default()
+		});
+		Self::deposit_event(RawEvent::Revealed(role,task,who.clone(),ipfshash));
+		Ok(())
+	}
+
+	/// Take a pending result out of the vote because its payload is invalid.
+	pub fn do_mark_invalid(role: Role, task: TaskId<T>, who: &T::AccountId) -> DispatchResult {
+		<Results<T>>::try_mutate((role, task), who, |result| -> DispatchResult {
+			let result = result.as_mut().ok_or(Error::<T>::NoResult)?;
+			ensure!(result.status == ResultStatus::Pending,<Error<T>>::AlreadyFinalized);
+			result.status = ResultStatus::Invalid;
 			Ok(())
+		})?;
+		Self::deposit_event(RawEvent::MarkedInvalid(role,task,who.clone()));
+		Ok(())
+	}
+
+	/// Votes needed on either side to finalize a result.
+	pub fn threshold() -> u32 {
+		T::Quorum::get().mul_ceil(<NodeStake<T> as NodeStaking<T::AccountId>>::nodes(Role::Verification).len() as u32)
+	}
+
+	/// Remove the results of `task` and their votes. Results that never reached quorum
+	/// expire without slashing anyone.
+	fn prune(task: TaskId<T>) -> Weight {
+		let mut count: Weight = 0;
+		for role in [Role::Analysis, Role::Quantification].iter() {
+			for (node, result) in <Results<T>>::drain_prefix((*role, task)) {
+				let votes = <Votes<T>>::drain_prefix((*role, task, node.clone())).count() as Weight;
+				if result.status == ResultStatus::Pending {
+					Self::deposit_event(RawEvent::Expired(*role,task,node));
+				}
+				count = count.saturating_add(votes).saturating_add(1);
+			}
 		}
-		
+		T::DbWeight::get().reads_writes(count, count)
 	}
-}
\ No newline at end of file
+
+	fn ensure_result_role(role: Role) -> DispatchResult {
+		ensure!(role == Role::Analysis || role == Role::Quantification,<Error<T>>::InvalidRole);
+		Ok(())
+	}
+
+	fn report(role: Role, who: &T::AccountId) {
+		<NodeStake<T> as NodeStaking<T::AccountId>>::slash(role, who, T::SlashFraction::get());
+		Self::deposit_event(RawEvent::Reported(role,who.clone()));
+	}
+}
@@ -0,0 +1,126 @@
+use crate as pallet_verification_sig;
+use sp_core::H256;
+use frame_support::{parameter_types, traits::{OnInitialize, OnFinalize}};
+use sp_runtime::{
+	Perbill, traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+};
+use frame_system as system;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		NodeStake: pallet_node_stake::{Pallet, Call, Storage, Event<T>},
+		VerificationSig: pallet_verification_sig::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const UnbondingDelay: u64 = 10;
+	pub const MaxUnlockingChunks: u32 = 4;
+	pub const MaxCandidates: u32 = 8;
+}
+
+impl pallet_node_stake::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type UnbondingDelay = UnbondingDelay;
+	type MaxUnlockingChunks = MaxUnlockingChunks;
+	type MaxCandidates = MaxCandidates;
+	type Slash = ();
+}
+
+parameter_types! {
+	pub const CommitPeriod: u64 = 2;
+	pub const RevealPeriod: u64 = 2;
+	pub const VotePeriod: u64 = 4;
+	pub const Quorum: Perbill = Perbill::from_percent(60);
+	pub const SlashFraction: Perbill = Perbill::from_percent(10);
+	pub const MaxHashLength: u32 = 64;
+}
+
+impl pallet_verification_sig::Config for Test {
+	type Event = Event;
+	type CommitPeriod = CommitPeriod;
+	type RevealPeriod = RevealPeriod;
+	type VotePeriod = VotePeriod;
+	type Quorum = Quorum;
+	type SlashFraction = SlashFraction;
+	type MaxHashLength = MaxHashLength;
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: (1..=8).map(|who| (who, 1_000)).collect(),
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		VerificationSig::on_finalize(System::block_number());
+		NodeStake::on_finalize(System::block_number());
+		System::set_block_number(System::block_number() + 1);
+		NodeStake::on_initialize(System::block_number());
+		VerificationSig::on_initialize(System::block_number());
+	}
+}
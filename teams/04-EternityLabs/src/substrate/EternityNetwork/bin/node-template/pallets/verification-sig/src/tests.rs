@@ -0,0 +1,211 @@
+use crate::{Error, ResultStatus, mock::*};
+use frame_support::{assert_noop, assert_ok};
+use pallet_node_stake::Role;
+
+/// Task 8 runs from block 8: commit 8-9, reveal 10-11, vote 12-15.
+const TASK: u64 = 8;
+const CID: &[u8] = b"QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+
+/// Elect 2 and 3 as analysis nodes and 4, 5 and 6 as verification nodes, each with a
+/// stake of 100, at the start of the first task.
+fn elect_nodes() {
+	assert_ok!(NodeStake::init(Origin::signed(1)));
+	assert_ok!(NodeStake::create_round(Origin::signed(1), Role::Analysis, 1, TASK, 100, 2, 10));
+	assert_ok!(NodeStake::create_round(Origin::signed(1), Role::Verification, 1, TASK, 100, 3, 10));
+	for who in 2..=3 {
+		assert_ok!(NodeStake::bid(Origin::signed(who), Role::Analysis, 100));
+	}
+	for who in 4..=6 {
+		assert_ok!(NodeStake::bid(Origin::signed(who), Role::Verification, 100));
+	}
+	run_to_block(TASK);
+}
+
+fn commit(who: u64, salt: &[u8]) {
+	let commitment = VerificationSig::commitment(&who, CID, salt);
+	assert_ok!(VerificationSig::do_commit(Role::Analysis, &who, commitment));
+}
+
+fn reveal(who: u64, salt: &[u8]) {
+	assert_ok!(VerificationSig::do_reveal(Role::Analysis, &who, CID.to_vec(), salt.to_vec()));
+}
+
+#[test]
+fn commit_needs_elected_node_in_commit_phase() {
+	new_test_ext().execute_with(|| {
+		elect_nodes();
+		let commitment = VerificationSig::commitment(&7, CID, b"salt");
+		assert_noop!(
+			VerificationSig::do_commit(Role::Analysis, &7, commitment),
+			Error::<Test>::NotAuthority
+		);
+		assert_noop!(
+			VerificationSig::do_commit(Role::Verification, &4, commitment),
+			Error::<Test>::InvalidRole
+		);
+		commit(2, b"salt");
+		assert_noop!(
+			VerificationSig::do_commit(Role::Analysis, &2, commitment),
+			Error::<Test>::AlreadyCommitted
+		);
+
+		run_to_block(TASK + 2);
+		assert_noop!(
+			VerificationSig::do_commit(Role::Analysis, &3, commitment),
+			Error::<Test>::WrongPhase
+		);
+	});
+}
+
+#[test]
+fn reveal_must_match_commitment() {
+	new_test_ext().execute_with(|| {
+		elect_nodes();
+		commit(2, b"salt");
+		assert_noop!(
+			VerificationSig::do_reveal(Role::Analysis, &2, CID.to_vec(), b"salt".to_vec()),
+			Error::<Test>::WrongPhase
+		);
+
+		run_to_block(TASK + 2);
+		assert_noop!(
+			VerificationSig::do_reveal(Role::Analysis, &2, CID.to_vec(), b"pepper".to_vec()),
+			Error::<Test>::CommitMismatch
+		);
+		assert_noop!(
+			VerificationSig::do_reveal(Role::Analysis, &2, vec![b'a'; 65], b"salt".to_vec()),
+			Error::<Test>::HashTooLong
+		);
+		assert_noop!(
+			VerificationSig::do_reveal(Role::Analysis, &3, CID.to_vec(), b"salt".to_vec()),
+			Error::<Test>::NoCommit
+		);
+		reveal(2, b"salt");
+		assert_noop!(
+			VerificationSig::do_reveal(Role::Analysis, &2, CID.to_vec(), b"salt".to_vec()),
+			Error::<Test>::AlreadyRevealed
+		);
+
+		let result = VerificationSig::result((Role::Analysis, TASK), &2).unwrap();
+		assert_eq!(result.ipfshash, CID.to_vec());
+		assert_eq!(result.status, ResultStatus::Pending);
+	});
+}
+
+#[test]
+fn unrevealed_commit_is_slashed() {
+	new_test_ext().execute_with(|| {
+		elect_nodes();
+		commit(2, b"salt");
+		commit(3, b"salt");
+		run_to_block(TASK + 2);
+		reveal(2, b"salt");
+
+		run_to_block(TASK + 4);
+		assert_eq!(Balances::reserved_balance(&2), 100);
+		assert_eq!(Balances::reserved_balance(&3), 90);
+		assert_eq!(VerificationSig::commit((Role::Analysis, TASK), &2), None);
+	});
+}
+
+#[test]
+fn quorum_accepts_and_slashes_dissent() {
+	new_test_ext().execute_with(|| {
+		elect_nodes();
+		commit(2, b"salt");
+		run_to_block(TASK + 2);
+		reveal(2, b"salt");
+		assert_noop!(
+			VerificationSig::vote(Origin::signed(4), Role::Analysis, TASK, 2, true),
+			Error::<Test>::WrongPhase
+		);
+
+		run_to_block(TASK + 4);
+		assert_noop!(
+			VerificationSig::vote(Origin::signed(7), Role::Analysis, TASK, 2, true),
+			Error::<Test>::NotAuthority
+		);
+		assert_ok!(VerificationSig::vote(Origin::signed(4), Role::Analysis, TASK, 2, false));
+		assert_noop!(
+			VerificationSig::vote(Origin::signed(4), Role::Analysis, TASK, 2, true),
+			Error::<Test>::AlreadyVoted
+		);
+		assert_ok!(VerificationSig::vote(Origin::signed(5), Role::Analysis, TASK, 2, true));
+		assert_eq!(VerificationSig::result((Role::Analysis, TASK), &2).unwrap().status, ResultStatus::Pending);
+
+		// 60% of 3 verification nodes is 2 votes.
+		assert_ok!(VerificationSig::vote(Origin::signed(6), Role::Analysis, TASK, 2, true));
+		let result = VerificationSig::result((Role::Analysis, TASK), &2).unwrap();
+		assert_eq!((result.ayes, result.nays, result.status), (2, 1, ResultStatus::Accepted));
+		assert_eq!(Balances::reserved_balance(&4), 90);
+		assert_eq!(Balances::reserved_balance(&5), 100);
+		assert_eq!(Balances::reserved_balance(&2), 100);
+
+		assert_noop!(
+			VerificationSig::vote(Origin::signed(4), Role::Analysis, TASK, 2, true),
+			Error::<Test>::AlreadyFinalized
+		);
+	});
+}
+
+#[test]
+fn rejected_result_slashes_node() {
+	new_test_ext().execute_with(|| {
+		elect_nodes();
+		commit(2, b"salt");
+		run_to_block(TASK + 2);
+		reveal(2, b"salt");
+		run_to_block(TASK + 4);
+
+		assert_ok!(VerificationSig::vote(Origin::signed(4), Role::Analysis, TASK, 2, false));
+		assert_ok!(VerificationSig::vote(Origin::signed(5), Role::Analysis, TASK, 2, false));
+		assert_eq!(VerificationSig::result((Role::Analysis, TASK), &2).unwrap().status, ResultStatus::Rejected);
+		assert_eq!(Balances::reserved_balance(&2), 90);
+		assert_eq!(Balances::reserved_balance(&4), 100);
+	});
+}
+
+#[test]
+fn invalid_result_takes_no_votes() {
+	new_test_ext().execute_with(|| {
+		elect_nodes();
+		commit(2, b"salt");
+		run_to_block(TASK + 2);
+		reveal(2, b"salt");
+
+		assert_ok!(VerificationSig::do_mark_invalid(Role::Analysis, TASK, &2));
+		assert_noop!(
+			VerificationSig::do_mark_invalid(Role::Analysis, TASK, &2),
+			Error::<Test>::AlreadyFinalized
+		);
+		run_to_block(TASK + 4);
+		assert_noop!(
+			VerificationSig::vote(Origin::signed(4), Role::Analysis, TASK, 2, true),
+			Error::<Test>::AlreadyFinalized
+		);
+	});
+}
+
+#[test]
+fn results_are_pruned_one_period_after_the_vote() {
+	new_test_ext().execute_with(|| {
+		elect_nodes();
+		commit(2, b"salt");
+		commit(3, b"salt");
+		run_to_block(TASK + 2);
+		reveal(2, b"salt");
+		reveal(3, b"salt");
+		run_to_block(TASK + 4);
+		assert_ok!(VerificationSig::vote(Origin::signed(4), Role::Analysis, TASK, 2, true));
+		assert_ok!(VerificationSig::vote(Origin::signed(5), Role::Analysis, TASK, 3, true));
+
+		run_to_block(TASK + 15);
+		assert!(VerificationSig::result((Role::Analysis, TASK), &2).is_some());
+
+		run_to_block(TASK + 16);
+		assert_eq!(VerificationSig::result((Role::Analysis, TASK), &2), None);
+		assert_eq!(VerificationSig::result((Role::Analysis, TASK), &3), None);
+		assert_eq!(VerificationSig::vote_of((Role::Analysis, TASK, 2), &4), None);
+		System::assert_has_event(Event::pallet_verification_sig(crate::RawEvent::Expired(Role::Analysis, TASK, 3)));
+	});
+}
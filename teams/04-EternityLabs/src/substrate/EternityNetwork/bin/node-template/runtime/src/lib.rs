@@ -11,7 +11,7 @@ use sp_std::prelude::*;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
 	ApplyExtrinsicResult, generic, create_runtime_str, impl_opaque_keys, MultiSignature,
-	transaction_validity::{TransactionValidity, TransactionSource},
+	transaction_validity::{TransactionValidity, TransactionSource, TransactionPriority},
 };
 use sp_runtime::traits::{
 	BlakeTwo256, Block as BlockT, AccountIdLookup, Verify, IdentifyAccount, NumberFor,
@@ -279,14 +279,40 @@ impl pallet_template::Config for Runtime {
 	type Event = Event;
 }
 
+parameter_types! {
+	pub const TokenExistentialDeposit: Balance = 500;
+	pub const TokenPalletId: PalletId = PalletId(*b"et/token");
+	pub const TokenSupplyPeriod: BlockNumber = DAYS;
+	pub const TokenMaxSupplyChange: Permill = Permill::from_percent(1);
+}
+
 impl pallet_token::Config for Runtime {
 	type Event = Event;
+	type Balance = Balance;
+	type ExistentialDeposit = TokenExistentialDeposit;
+	type SupplyOrigin = frame_system::EnsureRoot<AccountId>;
+	type PalletId = TokenPalletId;
+	type SupplyPeriod = TokenSupplyPeriod;
+	type MaxSupplyChange = TokenMaxSupplyChange;
+}
+
+parameter_types! {
+	pub const NodeUnbondingDelay: BlockNumber = 7 * DAYS;
+	pub const NodeMaxUnlockingChunks: u32 = 32;
+	pub const NodeMaxCandidates: u32 = 256;
 }
 
-impl pallet_dispatch_stake::Config for Runtime {
+impl pallet_node_stake::Config for Runtime {
 	type Event = Event;
+	type Currency = TokenModule;
+	type UnbondingDelay = NodeUnbondingDelay;
+	type MaxUnlockingChunks = NodeMaxUnlockingChunks;
+	type MaxCandidates = NodeMaxCandidates;
+	type Slash = ();
 }
 
+impl pallet_dispatch_stake::Config for Runtime {}
+
 impl pallet_dispatch_sig::Config for Runtime {
 	type Event = Event;
 	
@@ -296,37 +322,73 @@ parameter_types! {
 }
 impl pallet_quan_stake::Config for Runtime {
 	type Event = Event;
-	type Currency = Balances;
 	type PalletId = QuanPalletId;
 }
 
-impl pallet_quan_sig::Config for Runtime {
-	type Event = Event;
-}
+impl pallet_quan_sig::Config for Runtime {}
 
-impl pallet_analysis_stake::Config for Runtime {
-	type Event = Event;
-	
-	
+impl pallet_analysis_stake::Config for Runtime {}
+
+impl pallet_analysis_sig::Config for Runtime {}
+
+
+impl pallet_verification_stake::Config for Runtime {}
+
+parameter_types! {
+	pub const ResultCommitPeriod: BlockNumber = 10 * MINUTES;
+	pub const ResultRevealPeriod: BlockNumber = 10 * MINUTES;
+	pub const ResultVotePeriod: BlockNumber = 20 * MINUTES;
+	pub const ResultQuorum: Perbill = Perbill::from_percent(67);
+	pub const ResultSlashFraction: Perbill = Perbill::from_percent(10);
+	pub const ResultMaxHashLength: u32 = 128;
 }
 
-impl pallet_analysis_sig::Config for Runtime {
+impl pallet_verification_sig::Config for Runtime {
 	type Event = Event;
+	type CommitPeriod = ResultCommitPeriod;
+	type RevealPeriod = ResultRevealPeriod;
+	type VotePeriod = ResultVotePeriod;
+	type Quorum = ResultQuorum;
+	type SlashFraction = ResultSlashFraction;
+	type MaxHashLength = ResultMaxHashLength;
+}
 
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
 }
 
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime where
+	Call: From<C>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = UncheckedExtrinsic;
+}
 
-impl pallet_verification_stake::Config for Runtime {
-	type Event = Event;
+parameter_types! {
+	pub const ResultMaxPayloadSize: u32 = 1024 * 1024;
+	pub const ResultCheckPriority: TransactionPriority = TransactionPriority::max_value() / 2;
 }
 
-impl pallet_verification_sig::Config for Runtime {
+impl pallet_result_check::Config for Runtime {
 	type Event = Event;
+	type AuthorityId = pallet_result_check::crypto::AuthorityId;
+	type MaxPayloadSize = ResultMaxPayloadSize;
+	type UnsignedPriority = ResultCheckPriority;
 }
 
 
+parameter_types! {
+	pub const ModelMaxNameLength: u32 = 64;
+	pub const ModelMaxHashLength: u32 = 128;
+	pub const ModelMaxSchemaLength: u32 = 256;
+}
+
 impl pallet_model::Config for Runtime {
 	type Event = Event;
+	type MaxNameLength = ModelMaxNameLength;
+	type MaxHashLength = ModelMaxHashLength;
+	type MaxSchemaLength = ModelMaxSchemaLength;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -346,16 +408,18 @@ construct_runtime!(
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template::{Pallet, Call, Storage, Event<T>},
-		TokenModule:pallet_token::{Pallet, Call, Storage, Event<T>},
-		DispStakeMoudle:pallet_dispatch_stake::{Pallet, Call, Storage, Event<T>},
+		TokenModule:pallet_token::{Pallet, Call, Storage, Config<T>, Event<T>},
+		NodeStakeMoudle:pallet_node_stake::{Pallet, Call, Storage, Event<T>},
+		DispStakeMoudle:pallet_dispatch_stake::{Pallet, Call},
 		DispSigMoudle:pallet_dispatch_sig::{Pallet, Call, Storage, Event<T>},
-		QuanSigMoudle:pallet_quan_sig::{Pallet, Call, Storage, Event<T>},
+		QuanSigMoudle:pallet_quan_sig::{Pallet, Call, Storage},
 		QuanStakeMoudle:pallet_quan_stake::{Pallet, Call, Storage, Event<T>},
-		AnalySigMoudle:pallet_analysis_sig::{Pallet, Call, Storage, Event<T>},
-		AnalyStakeMoudle:pallet_analysis_stake::{Pallet, Call, Storage, Event<T>},
-		VerifStakeMoudle:pallet_verification_stake::{Pallet, Call, Storage, Event<T>},
+		AnalySigMoudle:pallet_analysis_sig::{Pallet, Call},
+		AnalyStakeMoudle:pallet_analysis_stake::{Pallet, Call},
+		VerifStakeMoudle:pallet_verification_stake::{Pallet, Call},
 		VerifSigMoudle:pallet_verification_sig::{Pallet, Call, Storage, Event<T>},
 		ModelMoudle:pallet_model::{Pallet, Call, Storage, Event<T>},
+		ResultCheckMoudle:pallet_result_check::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
 
 
 	}
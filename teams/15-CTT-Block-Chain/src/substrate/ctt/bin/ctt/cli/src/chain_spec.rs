@@ -409,6 +409,7 @@ pub fn testnet_genesis(
                 ),
                 (b"misc".to_vec(), (400010000, 1_000_000 * DOLLARS, 0, 0, 10)),
             ],
+            power_params: Default::default(),
         }),
         members: Some(MembersConfig {
             finance_root: hex![
@@ -0,0 +1,388 @@
+//! SQLite store of the indexed kp events.
+
+use kp::{ModerationTarget, PowerTarget, RawEvent};
+use node_primitives::{BlockNumber, Hash, PowerSize};
+use rusqlite::{params, Connection, Result, Transaction};
+use std::path::Path;
+
+pub type KpEvent = kp::Event<ctt_runtime::Runtime>;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS blocks (
+    number INTEGER PRIMARY KEY,
+    hash TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS events (
+    block INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (block, event_index)
+);
+CREATE TABLE IF NOT EXISTS models (
+    app_id INTEGER NOT NULL,
+    model_id BLOB NOT NULL,
+    owner TEXT NOT NULL,
+    block INTEGER NOT NULL,
+    PRIMARY KEY (app_id, model_id)
+);
+CREATE TABLE IF NOT EXISTS documents (
+    app_id INTEGER NOT NULL,
+    document_id BLOB NOT NULL,
+    document_type TEXT NOT NULL,
+    model_id BLOB NOT NULL,
+    product_id BLOB NOT NULL,
+    cart_id BLOB NOT NULL,
+    sender TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'Active',
+    revisions INTEGER NOT NULL DEFAULT 0,
+    block INTEGER NOT NULL,
+    PRIMARY KEY (app_id, document_id)
+);
+CREATE TABLE IF NOT EXISTS comments (
+    app_id INTEGER NOT NULL,
+    comment_id BLOB NOT NULL,
+    document_id BLOB NOT NULL,
+    sender TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'Active',
+    block INTEGER NOT NULL,
+    PRIMARY KEY (app_id, comment_id)
+);
+CREATE TABLE IF NOT EXISTS power_changes (
+    block INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    account TEXT NOT NULL,
+    app_id INTEGER NOT NULL,
+    target TEXT NOT NULL,
+    target_id BLOB NOT NULL,
+    power_before INTEGER NOT NULL,
+    power_after INTEGER NOT NULL,
+    delta INTEGER NOT NULL,
+    PRIMARY KEY (block, event_index)
+);
+CREATE TABLE IF NOT EXISTS power_slashes (
+    block INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    account TEXT NOT NULL,
+    app_id INTEGER NOT NULL,
+    target TEXT NOT NULL,
+    target_id BLOB NOT NULL,
+    power INTEGER NOT NULL,
+    PRIMARY KEY (block, event_index)
+);
+CREATE TABLE IF NOT EXISTS tech_fund_withdrawals (
+    block INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    receiver TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (block, event_index)
+);
+";
+
+pub struct Database {
+    conn: Connection,
+}
+
+impl Database {
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Database { conn })
+    }
+
+    /// The last indexed block.
+    pub fn last_block(&self) -> Result<Option<BlockNumber>> {
+        self.conn
+            .query_row("SELECT MAX(number) FROM blocks", params![], |row| {
+                row.get(0)
+            })
+    }
+
+    /// Write the kp events of a block in one transaction, so an interrupted run
+    /// indexes the block again.
+    pub fn index_block(
+        &mut self,
+        number: BlockNumber,
+        hash: Hash,
+        events: &[(u32, KpEvent)],
+    ) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for (index, event) in events {
+            index_event(&tx, number, *index, event)?;
+        }
+        tx.execute(
+            "INSERT INTO blocks (number, hash) VALUES (?1, ?2)",
+            params![number, format!("{:?}", hash)],
+        )?;
+        tx.commit()
+    }
+}
+
+fn index_event(tx: &Transaction, block: BlockNumber, index: u32, event: &KpEvent) -> Result<()> {
+    let data = format!("{:?}", event);
+    let name = data.split('(').next().unwrap_or_default().to_string();
+    tx.execute(
+        "INSERT INTO events (block, event_index, name, data) VALUES (?1, ?2, ?3, ?4)",
+        params![block, index, name, data],
+    )?;
+
+    match event {
+        RawEvent::ModelCreated(owner, app_id, model_id) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO models (app_id, model_id, owner, block)
+                VALUES (?1, ?2, ?3, ?4)",
+                params![app_id, model_id, owner.to_string(), block],
+            )?;
+        }
+        RawEvent::ModelOwnerTransfered(_, app_id, model_id, new_owner) => {
+            tx.execute(
+                "UPDATE models SET owner = ?3 WHERE app_id = ?1 AND model_id = ?2",
+                params![app_id, model_id, new_owner.to_string()],
+            )?;
+        }
+        RawEvent::KnowledgeCreated(
+            sender,
+            app_id,
+            document_id,
+            document_type,
+            model_id,
+            product_id,
+            cart_id,
+        ) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO documents
+                (app_id, document_id, document_type, model_id, product_id, cart_id, sender, block)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    app_id,
+                    document_id,
+                    format!("{:?}", document_type),
+                    model_id,
+                    product_id,
+                    cart_id,
+                    sender.to_string(),
+                    block
+                ],
+            )?;
+        }
+        RawEvent::DocumentRevised(_, app_id, document_id, revisions) => {
+            tx.execute(
+                "UPDATE documents SET revisions = ?3 WHERE app_id = ?1 AND document_id = ?2",
+                params![app_id, document_id, revisions],
+            )?;
+        }
+        RawEvent::DocumentRetracted(_, app_id, document_id) => {
+            set_document_status(tx, *app_id, document_id, "Retracted")?;
+        }
+        RawEvent::ContentModerated(_, app_id, target, true) => match target {
+            ModerationTarget::Document(document_id) => {
+                set_document_status(tx, *app_id, document_id, "Hidden")?;
+            }
+            ModerationTarget::Comment(comment_id) => {
+                tx.execute(
+                    "UPDATE comments SET status = 'Hidden' WHERE app_id = ?1 AND comment_id = ?2",
+                    params![app_id, comment_id],
+                )?;
+            }
+        },
+        RawEvent::CommentCreated(sender, app_id, comment_id, document_id) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO comments (app_id, comment_id, document_id, sender, block)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![app_id, comment_id, document_id, sender.to_string(), block],
+            )?;
+        }
+        RawEvent::PowerChanged(account, app_id, target, before, after) => {
+            let (target, target_id) = power_target(target);
+            tx.execute(
+                "INSERT INTO power_changes
+                (block, event_index, account, app_id, target, target_id, power_before, power_after, delta)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    block,
+                    index,
+                    account.to_string(),
+                    app_id,
+                    target,
+                    target_id,
+                    power(*before),
+                    power(*after),
+                    power(*after) - power(*before)
+                ],
+            )?;
+        }
+        RawEvent::PowerSlashed(account, app_id, target, slashed) => {
+            let (target, target_id) = power_target(target);
+            tx.execute(
+                "INSERT INTO power_slashes
+                (block, event_index, account, app_id, target, target_id, power)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    block,
+                    index,
+                    account.to_string(),
+                    app_id,
+                    target,
+                    target_id,
+                    power(*slashed)
+                ],
+            )?;
+        }
+        RawEvent::TechFundWithdrawed(receiver, reason, amount) => {
+            // balances overflow SQLite integers, kept as decimal text
+            tx.execute(
+                "INSERT INTO tech_fund_withdrawals (block, event_index, receiver, reason, amount)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    block,
+                    index,
+                    receiver.to_string(),
+                    format!("{:?}", reason),
+                    amount.to_string()
+                ],
+            )?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn set_document_status(
+    tx: &Transaction,
+    app_id: u32,
+    document_id: &[u8],
+    status: &str,
+) -> Result<()> {
+    tx.execute(
+        "UPDATE documents SET status = ?3 WHERE app_id = ?1 AND document_id = ?2",
+        params![app_id, document_id, status],
+    )?;
+    Ok(())
+}
+
+fn power_target(target: &PowerTarget) -> (&'static str, &[u8]) {
+    match target {
+        PowerTarget::Document(id) => ("Document", id),
+        PowerTarget::MiscDocument(id) => ("MiscDocument", id),
+        PowerTarget::Commodity(id) => ("Commodity", id),
+        PowerTarget::Attend => ("Attend", &[]),
+    }
+}
+
+// SQLite integers are signed, powers stay far below `i64::MAX`.
+fn power(value: PowerSize) -> i64 {
+    value as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kp::DocumentType;
+    use node_primitives::AccountId;
+
+    #[test]
+    fn indexes_documents_and_power_changes() {
+        let mut db = Database::open_in_memory().unwrap();
+        let owner = AccountId::from([1u8; 32]);
+        assert_eq!(db.last_block().unwrap(), None);
+
+        db.index_block(
+            1,
+            Hash::repeat_byte(1),
+            &[
+                (
+                    2,
+                    RawEvent::KnowledgeCreated(
+                        owner.clone(),
+                        1,
+                        b"doc".to_vec(),
+                        DocumentType::ProductIdentify,
+                        b"model".to_vec(),
+                        b"product".to_vec(),
+                        b"cart".to_vec(),
+                    ),
+                ),
+                (
+                    3,
+                    RawEvent::PowerChanged(
+                        owner.clone(),
+                        1,
+                        PowerTarget::Commodity(b"cart".to_vec()),
+                        70,
+                        50,
+                    ),
+                ),
+            ],
+        )
+        .unwrap();
+        db.index_block(
+            2,
+            Hash::repeat_byte(2),
+            &[
+                (
+                    0,
+                    RawEvent::DocumentRetracted(owner.clone(), 1, b"doc".to_vec()),
+                ),
+                (
+                    1,
+                    RawEvent::TechFundWithdrawed(
+                        owner,
+                        Hash::repeat_byte(3),
+                        25_000_000_000_000_000_000_000,
+                    ),
+                ),
+            ],
+        )
+        .unwrap();
+        assert_eq!(db.last_block().unwrap(), Some(2));
+
+        let (document_type, cart_id, status): (String, Vec<u8>, String) = db
+            .conn
+            .query_row(
+                "SELECT document_type, cart_id, status FROM documents WHERE document_id = ?1",
+                params![b"doc".to_vec()],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(document_type, "ProductIdentify");
+        assert_eq!(cart_id, b"cart".to_vec());
+        assert_eq!(status, "Retracted");
+
+        let (target, delta): (String, i64) = db
+            .conn
+            .query_row(
+                "SELECT target, delta FROM power_changes WHERE block = 1 AND event_index = 3",
+                params![],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(target, "Commodity");
+        assert_eq!(delta, -20);
+
+        let names: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM events", params![], |row| row.get(0))
+            .unwrap();
+        assert_eq!(names, 4);
+
+        let amount: String = db
+            .conn
+            .query_row(
+                "SELECT amount FROM tech_fund_withdrawals WHERE block = 2 AND event_index = 1",
+                params![],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(amount, "25000000000000000000000000");
+    }
+}
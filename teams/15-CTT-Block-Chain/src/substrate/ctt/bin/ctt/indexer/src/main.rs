@@ -0,0 +1,171 @@
+//! Indexer of the kp pallet events.
+//!
+//! Follows the finalized blocks of a ctt node over its HTTP RPC and writes the documents,
+//! comments, models and power changes carried by the kp events into a local SQLite
+//! database for the dashboard. Indexing resumes from the last block in the database.
+//!
+//! Events are decoded with the types of the runtime the indexer is built against, so it
+//! stops at the first block whose runtime has another `spec_version`. Rebuild it against
+//! that runtime to go on, on a chain upgraded before indexing started pass `--from` with
+//! the first block of that runtime to skip the blocks of older ones.
+
+mod db;
+
+use codec::Decode;
+use ctt_runtime::Event;
+use frame_system::EventRecord;
+use jsonrpc_core_client::{transports::http, RpcError};
+use node_primitives::{Block, BlockNumber, Hash, Header};
+use sc_rpc::{chain::ChainClient, state::StateClient};
+use sp_core::{storage::StorageKey, twox_128};
+use sp_rpc::{list::ListOrValue, number::NumberOrHex};
+use sp_runtime::generic::SignedBlock;
+use std::{path::PathBuf, thread, time::Duration};
+use structopt::StructOpt;
+use tokio::runtime::Runtime;
+
+type Chain = ChainClient<BlockNumber, Hash, Header, SignedBlock<Block>>;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "ctt-indexer",
+    about = "Index kp events of a ctt node into SQLite"
+)]
+struct Opt {
+    /// HTTP RPC endpoint of the node.
+    #[structopt(long, default_value = "http://localhost:9933")]
+    url: String,
+
+    /// SQLite database file, created when missing.
+    #[structopt(long, default_value = "kp-index.db", parse(from_os_str))]
+    db: PathBuf,
+
+    /// Seconds to wait before looking for newly finalized blocks.
+    #[structopt(long, default_value = "6")]
+    interval: u64,
+
+    /// First block to index, blocks before it are skipped while the database is behind.
+    #[structopt(long, default_value = "0")]
+    from: BlockNumber,
+}
+
+fn main() {
+    sp_tracing::try_init_simple();
+
+    if let Err(e) = run(Opt::from_args()) {
+        log::error!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(opt: Opt) -> Result<(), String> {
+    let mut runtime = Runtime::new().map_err(|e| e.to_string())?;
+    let chain: Chain = runtime
+        .block_on(http::connect(&opt.url))
+        .map_err(rpc_error)?;
+    let state: StateClient<Hash> = runtime
+        .block_on(http::connect(&opt.url))
+        .map_err(rpc_error)?;
+    let mut db = db::Database::open(&opt.db).map_err(|e| e.to_string())?;
+
+    loop {
+        let finalized = runtime
+            .block_on(chain.finalized_head())
+            .map_err(rpc_error)?;
+        let head = runtime
+            .block_on(chain.header(Some(finalized)))
+            .map_err(rpc_error)?
+            .ok_or("finalized header not found")?
+            .number;
+
+        let from = db
+            .last_block()
+            .map_err(|e| e.to_string())?
+            .map_or(0, |number| number + 1)
+            .max(opt.from);
+        for number in from..=head {
+            let hash = block_hash(&mut runtime, &chain, number)?;
+            check_spec_version(&mut runtime, &state, number, hash)?;
+            let events = block_events(&mut runtime, &state, hash)?;
+            db.index_block(number, hash, &events)
+                .map_err(|e| e.to_string())?;
+            log::info!("indexed block #{} with {} kp events", number, events.len());
+        }
+
+        thread::sleep(Duration::from_secs(opt.interval));
+    }
+}
+
+fn block_hash(runtime: &mut Runtime, chain: &Chain, number: BlockNumber) -> Result<Hash, String> {
+    let at = ListOrValue::Value(NumberOrHex::Number(number.into()));
+    match runtime
+        .block_on(chain.block_hash(Some(at)))
+        .map_err(rpc_error)?
+    {
+        ListOrValue::Value(Some(hash)) => Ok(hash),
+        _ => Err(format!("hash of block #{} not found", number)),
+    }
+}
+
+/// Fail unless the block was built by the runtime version the events are decoded with,
+/// another version may encode them differently.
+fn check_spec_version(
+    runtime: &mut Runtime,
+    state: &StateClient<Hash>,
+    number: BlockNumber,
+    hash: Hash,
+) -> Result<(), String> {
+    let version = runtime
+        .block_on(state.runtime_version(Some(hash)))
+        .map_err(rpc_error)?;
+    if version.spec_name != ctt_runtime::VERSION.spec_name
+        || version.spec_version != ctt_runtime::VERSION.spec_version
+    {
+        return Err(format!(
+            "block #{} was built by runtime {} v{}, this indexer decodes events of {} v{}, \
+             rebuild it against the runtime of the chain or pass --from past that block",
+            number,
+            version.spec_name,
+            version.spec_version,
+            ctt_runtime::VERSION.spec_name,
+            ctt_runtime::VERSION.spec_version,
+        ));
+    }
+    Ok(())
+}
+
+/// The kp events of the block, with their index among all events of the block.
+fn block_events(
+    runtime: &mut Runtime,
+    state: &StateClient<Hash>,
+    hash: Hash,
+) -> Result<Vec<(u32, db::KpEvent)>, String> {
+    let data = runtime
+        .block_on(state.storage(events_key(), Some(hash)))
+        .map_err(rpc_error)?;
+    let records = match data {
+        Some(data) => Vec::<EventRecord<Event, Hash>>::decode(&mut &data.0[..])
+            .map_err(|e| format!("events of block {:?} undecodable: {}", hash, e))?,
+        None => return Ok(vec![]),
+    };
+
+    Ok(records
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, record)| match record.event {
+            Event::kp(event) => Some((index as u32, event)),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Key of `System::Events`.
+fn events_key() -> StorageKey {
+    let mut key = twox_128(b"System").to_vec();
+    key.extend_from_slice(&twox_128(b"Events"));
+    StorageKey(key)
+}
+
+fn rpc_error(e: RpcError) -> String {
+    format!("rpc: {:?}", e)
+}
@@ -3,10 +3,10 @@
 pub use self::gen_client::Client as KpClient;
 use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
 use jsonrpc_derive::rpc;
-use kp::{CommoditySlashRecord, ModelDisputeRecord};
+use kp::{CommoditySlashRecord, ModelDisputeRecord, PowerParams};
 use kp_runtime_api::KpApi as KpRuntimeApi;
 pub use kp_runtime_api::KpApi as KpRuntimeRpcApi;
-use primitives::{AuthAccountId, Balance, BlockNumber, PowerSize};
+use primitives::{AuthAccountId, Balance, BlockNumber, Hash, PowerSize};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::Bytes;
@@ -58,6 +58,15 @@ pub struct QueryDocumentPowerParams {
     doc_id: Bytes,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct QueryDocumentPowerWithParams {
+    app_id: u32,
+    doc_id: Bytes,
+    power_params: PowerParams,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -67,6 +76,16 @@ pub struct QueryLeaderBoardParams {
     block: u32,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct QueryLeaderBoardHistoryParams {
+    app_id: u32,
+    model_id: Bytes,
+    from: u32,
+    limit: u32,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -84,6 +103,38 @@ pub struct LeaderBoardResultRPC<AccountId> {
     board: Vec<LeaderBoardItemRPC<AccountId>>,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct LeaderBoardHistoryItemRPC<AccountId> {
+    epoch: u32,
+    block: BlockNumber,
+    accounts: Vec<AccountId>,
+    board: Vec<LeaderBoardItemRPC<AccountId>>,
+    seed_epoch: u64,
+    seed: Hash,
+    pools: Vec<LeaderBoardDrawPoolRPC<AccountId>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct LeaderBoardDrawPoolRPC<AccountId> {
+    accounts: Vec<AccountId>,
+    positions: Vec<u32>,
+    hits: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct LeaderBoardHistoryRPC<AccountId> {
+    records: Vec<LeaderBoardHistoryItemRPC<AccountId>>,
+    next: Option<u32>,
+    first: u32,
+    pruned_root: Hash,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -175,6 +226,16 @@ pub struct TechMemberSignParams {
     sign: Bytes,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct SignedPayloadParams {
+    call: Bytes,
+    signer: AuthAccountId,
+    params: Bytes,
+    expiry: BlockNumber,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
@@ -183,6 +244,17 @@ pub struct AppCommentKeyParams {
     comment_id: Bytes,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct ElectionRankRPC {
+    vote_weight: u64,
+    backing: u64,
+    rank: u32,
+    candidates: u32,
+    seats: u32,
+}
+
 #[rpc]
 pub trait KpApi<BlockHash, AccountId, Balance, BlockNumber> {
     #[rpc(name = "kp_totalPower")]
@@ -205,6 +277,14 @@ pub trait KpApi<BlockHash, AccountId, Balance, BlockNumber> {
         at: Option<BlockHash>,
     ) -> Result<DocumentPowerRPC>;
 
+    /// Power of the document recomputed with the given power params, nothing is stored.
+    #[rpc(name = "kp_documentPowerWithParams")]
+    fn document_power_with_params(
+        &self,
+        query: QueryDocumentPowerWithParams,
+        at: Option<BlockHash>,
+    ) -> Result<DocumentPowerRPC>;
+
     #[rpc(name = "kp_isCommodityPowerExist")]
     fn is_commodity_power_exist(
         &self,
@@ -219,6 +299,13 @@ pub trait KpApi<BlockHash, AccountId, Balance, BlockNumber> {
         at: Option<BlockHash>,
     ) -> Result<LeaderBoardResultRPC<AccountId>>;
 
+    #[rpc(name = "kp_leaderBoardHistory")]
+    fn leader_board_history(
+        &self,
+        query: QueryLeaderBoardHistoryParams,
+        at: Option<BlockHash>,
+    ) -> Result<LeaderBoardHistoryRPC<AccountId>>;
+
     #[rpc(name = "kp_stakeToVote")]
     fn stake_to_vote(
         &self,
@@ -314,6 +401,16 @@ pub trait KpApi<BlockHash, AccountId, Balance, BlockNumber> {
 
     #[rpc(name = "kp_powerRatio")]
     fn power_ratio(&self, account: AccountId, at: Option<BlockHash>) -> Result<u64>;
+
+    #[rpc(name = "kp_electionRank")]
+    fn election_rank(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<ElectionRankRPC>;
+
+    #[rpc(name = "kp_signedPayload")]
+    fn signed_payload(&self, params: SignedPayloadParams, at: Option<BlockHash>) -> Result<Bytes>;
 }
 
 /// A struct that implements the `KpApi`.
@@ -469,6 +566,38 @@ where
         }
     }
 
+    fn document_power_with_params(
+        &self,
+        query: QueryDocumentPowerWithParams,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<DocumentPowerRPC> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let QueryDocumentPowerWithParams {
+            app_id,
+            doc_id,
+            power_params,
+        } = query;
+
+        let runtime_api_result =
+            api.document_power_with_params(&at, app_id, doc_id.to_vec(), power_params);
+        runtime_api_result
+            .map(|v| DocumentPowerRPC {
+                doc_type: v.doc_type.into(),
+                power: v.power,
+                is_slashed: v.is_slashed,
+                is_exist: v.is_exist,
+            })
+            .map_err(|e| RpcError {
+                code: ErrorCode::ServerError(9876), // No real reason for this value
+                message: "Something wrong".into(),
+                data: Some(format!("{:?}", e).into()),
+            })
+    }
+
     fn misc_document_power(
         &self,
         query: QueryDocumentPowerParams,
@@ -588,6 +717,73 @@ where
         }
     }
 
+    fn leader_board_history(
+        &self,
+        query: QueryLeaderBoardHistoryParams,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<LeaderBoardHistoryRPC<AuthAccountId>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let QueryLeaderBoardHistoryParams {
+            app_id,
+            model_id,
+            from,
+            limit,
+        } = query;
+
+        let runtime_api_result =
+            api.leader_board_history(&at, app_id, model_id.to_vec(), from, limit);
+
+        // convert result
+        match runtime_api_result {
+            Ok(v) => Ok(LeaderBoardHistoryRPC {
+                records: v
+                    .records
+                    .into_iter()
+                    .map(|item| LeaderBoardHistoryItemRPC {
+                        epoch: item.epoch,
+                        block: item.block,
+                        accounts: item.result.accounts,
+                        board: item
+                            .result
+                            .board
+                            .into_iter()
+                            .map(|item| LeaderBoardItemRPC {
+                                cart_id: item.cart_id.into(),
+                                power: item.power,
+                                owner: item.owner,
+                            })
+                            .collect(),
+                        seed_epoch: item.seed_epoch,
+                        seed: item.seed,
+                        pools: item
+                            .pools
+                            .into_iter()
+                            .map(|pool| LeaderBoardDrawPoolRPC {
+                                accounts: pool.accounts,
+                                positions: pool.positions,
+                                hits: pool.hits,
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+                next: v.next,
+                first: v.first,
+                pruned_root: v.pruned_root,
+            }),
+            Err(e) => {
+                Err(RpcError {
+                    code: ErrorCode::ServerError(9876), // No real reason for this value
+                    message: "Something wrong".into(),
+                    data: Some(format!("{:?}", e).into()),
+                })
+            }
+        }
+    }
+
     fn stake_to_vote(
         &self,
         params: StakeToVoteParams<AuthAccountId, u64>,
@@ -909,4 +1105,60 @@ where
             data: Some(format!("{:?}", e).into()),
         })
     }
+
+    fn election_rank(
+        &self,
+        account: AuthAccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<ElectionRankRPC> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let runtime_api_result = api.election_rank(&at, account);
+        // convert result
+        match runtime_api_result {
+            Ok(v) => Ok(ElectionRankRPC {
+                vote_weight: convert_balance(v.vote_weight),
+                backing: convert_balance(v.backing),
+                rank: v.rank,
+                candidates: v.candidates,
+                seats: v.seats,
+            }),
+            Err(e) => {
+                Err(RpcError {
+                    code: ErrorCode::ServerError(9876), // No real reason for this value
+                    message: "Something wrong".into(),
+                    data: Some(format!("{:?}", e).into()),
+                })
+            }
+        }
+    }
+
+    fn signed_payload(
+        &self,
+        query: SignedPayloadParams,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Bytes> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(||
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash));
+
+        let SignedPayloadParams {
+            call,
+            signer,
+            params,
+            expiry,
+        } = query;
+
+        let runtime_api_result =
+            api.signed_payload(&at, call.to_vec(), signer, params.to_vec(), expiry);
+        runtime_api_result.map(Bytes).map_err(|e| RpcError {
+            code: ErrorCode::ServerError(9876), // No real reason for this value
+            message: "Something wrong".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
 }
@@ -4,9 +4,10 @@
 use codec::Codec;
 use kp::{
     AppFinancedData, AppFinancedUserExchangeData, AppIncomeCycleRecord, CommoditySlashRecord,
-    DocumentPowerInfo, LeaderBoardResult, ModelDisputeRecord, ModelIncomeCurrentStage,
+    DocumentPowerInfo, ElectionRank, LeaderBoardHistoryPage, LeaderBoardResult, ModelDisputeRecord,
+    ModelIncomeCurrentStage, PowerParams,
 };
-use primitives::PowerSize;
+use primitives::{Hash, PowerSize};
 use sp_std::prelude::*;
 
 // Here we declare the runtime API. It is implemented it the `impl` block in
@@ -17,8 +18,10 @@ sp_api::decl_runtime_apis! {
         fn account_power(account: AccountId) -> PowerSize;
         fn commodity_power(app_id: u32, cart_id: Vec<u8>) -> PowerSize;
         fn document_power(app_id: u32, doc_id: Vec<u8>) -> DocumentPowerInfo;
+        fn document_power_with_params(app_id: u32, doc_id: Vec<u8>, params: PowerParams) -> DocumentPowerInfo;
         fn is_commodity_power_exist(app_id: u32, cart_id: Vec<u8>) -> bool;
         fn leader_board_result(block: u32, app_id: u32, model_id: Vec<u8>) -> LeaderBoardResult<AccountId>;
+        fn leader_board_history(app_id: u32, model_id: Vec<u8>, from: u32, limit: u32) -> LeaderBoardHistoryPage<AccountId, BlockNumber, Hash>;
         fn stake_to_vote(account: AccountId, stake: Balance) -> Balance;
         fn power_ratio(account: AccountId) -> u64;
         fn app_finance_record(app_id: u32, proposal_id: Vec<u8>) -> AppFinancedData<Balance, BlockNumber>;
@@ -34,5 +37,7 @@ sp_api::decl_runtime_apis! {
         fn model_dispute_record(app_id: u32, comment_id: Vec<u8>) -> ModelDisputeRecord<BlockNumber>;
         fn commodity_power_slash_record(app_id: u32, comment_id: Vec<u8>) -> CommoditySlashRecord<BlockNumber>;
         fn is_commodity_in_black_list(app_id: u32, cart_id: Vec<u8>) -> bool;
+        fn election_rank(account: AccountId) -> ElectionRank<Balance>;
+        fn signed_payload(call: Vec<u8>, signer: AccountId, params: Vec<u8>, expiry: BlockNumber) -> Vec<u8>;
     }
 }
@@ -0,0 +1,1029 @@
+//! Kp pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::OnInitialize;
+use frame_system::RawOrigin;
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::traits::Bounded;
+
+use crate::Module as Kp;
+
+const SEED: u32 = 0;
+const APP_ID: u32 = 1;
+const COMMODITY_TYPE: u32 = 1;
+const KEY_TYPE: KeyTypeId = KeyTypeId(*b"kpbh");
+
+const MODEL_ID: &[u8] = b"model";
+const PRODUCT_ID: &[u8] = b"product";
+const PUBLISH_DOC_ID: &[u8] = b"publish";
+// The finance members of the runtime.
+const MAX_FINANCE_MEMBERS: u32 = 64;
+
+fn fund<T: Trait>(who: &T::AccountId) {
+    T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 1000u32.into());
+}
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+    let who: T::AccountId = account(name, index, SEED);
+    fund::<T>(&who);
+    who
+}
+
+fn fund_treasury<T: Trait>(id: ModuleId) {
+    fund::<T>(&id.into_account());
+}
+
+// Generate a funded key in the benchmark keystore, returns it with its chain account.
+fn auth_key<T: Trait>() -> (sr25519::Public, T::AccountId) {
+    let key = sp_io::crypto::sr25519_generate(KEY_TYPE, None);
+    let who = Kp::<T>::convert_account(&AuthAccountId::from(key.clone()));
+    fund::<T>(&who);
+    (key, who)
+}
+
+// Sign `params` of `call`, valid until the last block.
+fn sign<T: Trait, P: Encode>(call: &[u8], key: &sr25519::Public, params: &P) -> sr25519::Signature {
+    let payload = T::PayloadVerify::payload_bytes(
+        call,
+        &AuthAccountId::from(key.clone()),
+        &params.encode(),
+        T::PayloadVerify::max_expiry(),
+    );
+    sp_io::crypto::sr25519_sign(KEY_TYPE, key, &payload)
+        .expect("key was generated in the keystore; qed")
+}
+
+fn units<T: Trait>(n: u32) -> BalanceOf<T> {
+    (n as u128 * 1_000_000_000_000u128).saturated_into()
+}
+
+// Register the benchmark app, returns the app user key and the app key, which is
+// also the app admin.
+fn setup_app<T: Trait>() -> (sr25519::Public, sr25519::Public) {
+    let (user, _) = auth_key::<T>();
+    let (auth, auth_account) = auth_key::<T>();
+
+    T::Membership::config_app_setting(APP_ID, 100, b"app".to_vec(), 0u32.into());
+    T::Membership::config_app_admin(&auth_account, APP_ID);
+    T::Membership::config_app_key(&auth_account, APP_ID);
+    CommodityTypeMap::insert(COMMODITY_TYPE, b"type".to_vec());
+
+    (user, auth)
+}
+
+// An enabled model owned by `owner` with its create deposit reserved.
+fn create_model<T: Trait>(model_id: &[u8], owner: &sr25519::Public) -> Result<(), &'static str> {
+    let model_id = model_id.to_vec();
+    let key = T::Hashing::hash_of(&(APP_ID, &model_id));
+    let owner_id = AuthAccountId::from(owner.clone());
+    let owner_account = Kp::<T>::convert_account(&owner_id);
+
+    let deposit = T::ModelCreateDeposit::get();
+    T::Currency::reserve(&owner_account, deposit)?;
+    <KPModelDepositMap<T>>::insert(&key, deposit);
+    T::Membership::set_model_creator(&key, &owner_account, false);
+
+    <KPModelDataByIdHash<T>>::insert(
+        &key,
+        KPModelData {
+            app_id: APP_ID,
+            model_id,
+            status: ModelStatus::ENABLED,
+            commodity_type: COMMODITY_TYPE,
+            owner: owner_id,
+            ..Default::default()
+        },
+    );
+    Ok(())
+}
+
+fn publish_document<T: Trait>(
+    caller: &T::AccountId,
+    user: &sr25519::Public,
+    auth: &sr25519::Public,
+) -> Result<(), &'static str> {
+    let params = ClientParamsCreatePublishDoc {
+        app_id: APP_ID,
+        document_id: PUBLISH_DOC_ID.to_vec(),
+        model_id: MODEL_ID.to_vec(),
+        product_id: PRODUCT_ID.to_vec(),
+        content_hash: Default::default(),
+        para_issue_rate: 5000,
+        self_issue_rate: 3000,
+    };
+    Kp::<T>::create_product_publish_document(
+        RawOrigin::Signed(caller.clone()).into(),
+        params.clone(),
+        AuthAccountId::from(user.clone()),
+        sign::<T, _>(b"kp.create_product_publish_document", user, &params),
+        AuthAccountId::from(auth.clone()),
+        sign::<T, _>(b"kp.create_product_publish_document", auth, &params),
+        T::PayloadVerify::max_expiry(),
+    )?;
+    Ok(())
+}
+
+fn identify_params<T: Trait>(
+    document_id: &[u8],
+    cart_id: &[u8],
+) -> ClientParamsCreateIdentifyDoc<T::Hash> {
+    ClientParamsCreateIdentifyDoc {
+        app_id: APP_ID,
+        document_id: document_id.to_vec(),
+        product_id: PRODUCT_ID.to_vec(),
+        content_hash: Default::default(),
+        goods_price: 100,
+        ident_rate: 50,
+        ident_consistence: 50,
+        seller_consistence: 50,
+        cart_id: cart_id.to_vec(),
+    }
+}
+
+fn identify_document<T: Trait>(
+    caller: &T::AccountId,
+    user: &sr25519::Public,
+    auth: &sr25519::Public,
+    document_id: &[u8],
+    cart_id: &[u8],
+) -> Result<(), &'static str> {
+    let params = identify_params::<T>(document_id, cart_id);
+    Kp::<T>::create_product_identify_document(
+        RawOrigin::Signed(caller.clone()).into(),
+        params.clone(),
+        AuthAccountId::from(user.clone()),
+        sign::<T, _>(b"kp.create_product_identify_document", user, &params),
+        AuthAccountId::from(auth.clone()),
+        sign::<T, _>(b"kp.create_product_identify_document", auth, &params),
+        T::PayloadVerify::max_expiry(),
+    )?;
+    Ok(())
+}
+
+fn comment_params<T: Trait>(document_id: &[u8], comment_id: &[u8]) -> CommentData<T::Hash> {
+    CommentData {
+        app_id: APP_ID,
+        document_id: document_id.to_vec(),
+        comment_id: comment_id.to_vec(),
+        comment_hash: Default::default(),
+        comment_fee: 10,
+        comment_trend: 0,
+    }
+}
+
+fn cart_id(i: u32) -> Vec<u8> {
+    (&b"cart"[..], i).encode()
+}
+
+// Fill the model board and the app board with `n` commodities which all hold more
+// power than any commodity the benchmark creates.
+fn fill_leader_boards<T: Trait>(n: u32) {
+    for model_id in &[MODEL_ID.to_vec(), vec![]] {
+        let key = T::Hashing::hash_of(&(APP_ID, model_id));
+        let board: Vec<CommodityLeaderBoardData<T>> = (0..n)
+            .map(|i| {
+                let cart_id = cart_id(i);
+                <LeaderBoardCommoditySet<T>>::insert(&key, &cart_id, ());
+                CommodityLeaderBoardData {
+                    cart_id_hash: T::Hashing::hash_of(&cart_id),
+                    cart_id,
+                    power: PowerSize::max_value() - i as PowerSize,
+                    owner: account("leader", i, SEED),
+                }
+            })
+            .collect();
+        <AppModelCommodityLeaderBoards<T>>::insert(&key, board);
+    }
+}
+
+// Fill the moderation queue with `n` reports of other comments.
+fn fill_moderation_queue<T: Trait>(queue_key: &T::Hash, n: u32) {
+    let queue: Vec<ModerationReportOf<T>> = (0..n)
+        .map(|i| ModerationReport {
+            target: ModerationTarget::Comment(i.encode()),
+            reporter: account("reporter", i, SEED),
+            reason_hash: Default::default(),
+            block: Default::default(),
+            deposit: Default::default(),
+        })
+        .collect();
+    <ModerationQueue<T>>::insert(queue_key, queue);
+}
+
+fn fill_comment_pool<T: Trait>(document_id: &[u8], n: u32) {
+    let key = T::Hashing::hash_of(&(APP_ID, &document_id.to_vec()));
+    let pool: Vec<CommentWeightData<T>> = (0..n)
+        .map(|i| CommentWeightData {
+            account: account("commenter", i, SEED),
+            position: i as u64,
+            cash_cost: 10 + i as PowerSize,
+        })
+        .collect();
+    <DocumentCommentsAccountPool<T>>::insert(&key, pool);
+}
+
+// First block of the income stage which starts `offset` blocks into the second cycle.
+fn income_cycle_block<T: Trait>(offset: T::BlockNumber) -> T::BlockNumber {
+    T::ModelIncomeCyclePeriod::get() + offset
+}
+
+fn rewarding_block<T: Trait>() -> T::BlockNumber {
+    income_cycle_block::<T>(T::ModelIncomeCollectingPeriod::get())
+}
+
+fn confirming_block<T: Trait>() -> T::BlockNumber {
+    rewarding_block::<T>() + T::ModelIncomeRewardingPeriod::get()
+}
+
+fn compensating_block<T: Trait>() -> T::BlockNumber {
+    confirming_block::<T>() + T::ModelIncomeRewardingPeriod::get() / 2u32.into()
+}
+
+// Queue `n` enabled models without any deposit for a check at `when`.
+fn fill_pre_black_list<T: Trait>(when: T::BlockNumber, n: u32) -> Result<(), &'static str> {
+    for i in 0..n {
+        let model_id = i.encode();
+        let key = T::Hashing::hash_of(&(APP_ID, &model_id));
+        <KPModelDataByIdHash<T>>::insert(
+            &key,
+            KPModelData {
+                app_id: APP_ID,
+                model_id: model_id.clone(),
+                ..Default::default()
+            },
+        );
+        Kp::<T>::schedule_model_pre_black_list(APP_ID, &model_id, when);
+    }
+    ensure!(
+        <ModelPreBlackList<T>>::get(when).len() == n as usize,
+        "didn't fill pre black list"
+    );
+    Ok(())
+}
+
+// An income redeem of `user` waiting for the confirmation of `finance`.
+fn app_income_redeem<T: Trait>(
+    user: &sr25519::Public,
+    auth: &sr25519::Public,
+    finance: &T::AccountId,
+) -> Result<AppIncomeRedeemParams<T::AccountId, BalanceOf<T>, T::BlockNumber>, &'static str> {
+    T::Membership::add_finance_member(finance);
+    let cycle: T::BlockNumber = 1u32.into();
+    <AppCycleIncome<T>>::mutate(cycle, APP_ID, |record| {
+        record.income = 1_000_000;
+        record.cycle = cycle;
+        record.app_id = APP_ID;
+    });
+
+    let params = AppIncomeRedeemParams {
+        account: Kp::<T>::convert_account(&AuthAccountId::from(user.clone())),
+        app_id: APP_ID,
+        cycle,
+        exchange_amount: units::<T>(1),
+    };
+    let caller = funded_account::<T>("caller", 0);
+    Kp::<T>::app_income_redeem_request(
+        RawOrigin::Signed(caller).into(),
+        params.clone(),
+        AuthAccountId::from(user.clone()),
+        sign::<T, _>(b"kp.app_income_redeem_request", user, &params),
+        AuthAccountId::from(auth.clone()),
+        sign::<T, _>(b"kp.app_income_redeem_request", auth, &params),
+        T::PayloadVerify::max_expiry(),
+    )?;
+    // as if drawn in a later epoch
+    <AppCycleIncomeFinanceMember<T>>::insert(&T::Hashing::hash_of(&(APP_ID, cycle)), finance);
+    Ok(params)
+}
+
+// An app financed exchange of `user` waiting for the confirmation of `finance`.
+fn app_financed_exchange<T: Trait>(
+    user: &sr25519::Public,
+    auth: &sr25519::Public,
+    finance: &T::AccountId,
+) -> Result<AppFinancedUserExchangeParams<T::AccountId, BalanceOf<T>>, &'static str> {
+    T::Membership::add_finance_member(finance);
+    let proposal_id = b"proposal".to_vec();
+    let key = T::Hashing::hash_of(&(APP_ID, &proposal_id));
+    <AppFinancedRecord<T>>::insert(
+        &key,
+        AppFinancedData {
+            app_id: APP_ID,
+            proposal_id: proposal_id.clone(),
+            amount: units::<T>(100),
+            exchange: units::<T>(100),
+            exchange_end_block: <system::Module<T>>::block_number()
+                + T::AppFinanceExchangePeriod::get(),
+            ..Default::default()
+        },
+    );
+
+    let params = AppFinancedUserExchangeParams {
+        account: Kp::<T>::convert_account(&AuthAccountId::from(user.clone())),
+        app_id: APP_ID,
+        proposal_id,
+        exchange_amount: units::<T>(1),
+    };
+    let caller = funded_account::<T>("caller", 0);
+    Kp::<T>::app_financed_user_exchange_request(
+        RawOrigin::Signed(caller).into(),
+        params.clone(),
+        AuthAccountId::from(user.clone()),
+        sign::<T, _>(b"kp.app_financed_user_exchange_request", user, &params),
+        AuthAccountId::from(auth.clone()),
+        sign::<T, _>(b"kp.app_financed_user_exchange_request", auth, &params),
+        T::PayloadVerify::max_expiry(),
+    )?;
+    // as if drawn in a later epoch
+    <AppFinanceFinanceMember<T>>::insert(&key, finance);
+    Ok(params)
+}
+
+benchmarks! {
+    _ { }
+
+    on_initialize_pre_black_list {
+        let n in 0 .. T::MaxPreBlackListPerBlock::get();
+        let when: T::BlockNumber = 2u32.into();
+
+        fill_pre_black_list::<T>(when, n)?;
+    }: { Kp::<T>::on_initialize(when); }
+    verify {
+        ensure!(<ModelPreBlackList<T>>::get(when).is_empty(), "didn't clear pre black list");
+        for i in 0..n {
+            let key = T::Hashing::hash_of(&(APP_ID, &i.encode()));
+            ensure!(
+                <KPModelDataByIdHash<T>>::get(&key).status == ModelStatus::DISABLED,
+                "didn't disable model"
+            );
+        }
+    }
+
+    on_initialize_leader_board_draw {
+        let p in 1 .. T::AppLeaderBoardMaxPos::get() * 3;
+        let c in 1 .. T::MaxDocumentComments::get();
+        let when: T::BlockNumber = 2u32.into();
+        let user: T::AccountId = account("user", 0, SEED);
+        let board_key = T::Hashing::hash_of(&(APP_ID, &MODEL_ID.to_vec()));
+
+        let hits = min(Percent::from_percent(30) * c, 100);
+        let pool = LeaderBoardDrawPool {
+            accounts: (0..c).map(|i| account("commenter", i, SEED)).collect(),
+            positions: (1..=c).map(|i| i * 100 / c).collect(),
+            hits,
+        };
+        <AppLeaderBoardPendingDraw<T>>::insert(&board_key, LeaderBoardPendingDraw {
+            app_id: APP_ID,
+            model_id: MODEL_ID.to_vec(),
+            block: T::AppLeaderBoardRetention::get(),
+            seed_epoch: 0,
+            board: (0..T::AppLeaderBoardMaxPos::get()).map(|i| LeaderBoardItem {
+                cart_id: cart_id(i),
+                power: i as PowerSize,
+                owner: user.clone(),
+            }).collect(),
+            pools: vec![pool; p as usize],
+        });
+        <AppLeaderBoardDrawQueue<T>>::put(vec![board_key]);
+
+        // the oldest record leaves the retention window and is pruned into the deepest archive path
+        <AppLeaderBoardEpochs<T>>::insert(&board_key, (0, 1));
+        <AppLeaderBoardHistory<T>>::insert(&board_key, 0, LeaderBoardHistoryItem {
+            epoch: 0,
+            block: 0u32.into(),
+            result: LeaderBoardResult {
+                accounts: vec![user.clone(); (p * hits) as usize],
+                board: (0..T::AppLeaderBoardMaxPos::get()).map(|i| LeaderBoardItem {
+                    cart_id: cart_id(i),
+                    power: i as PowerSize,
+                    owner: user.clone(),
+                }).collect(),
+            },
+            seed_epoch: 0,
+            seed: Default::default(),
+            pools: vec![],
+        });
+        <AppLeaderBoardArchive<T>>::insert(&board_key, LeaderBoardArchive {
+            count: (1 << 31) - 1,
+            branch: vec![T::Hash::default(); 31],
+            root: T::Hash::default(),
+        });
+    }: { Kp::<T>::on_initialize(when); }
+    verify {
+        ensure!(!<AppLeaderBoardPendingDraw<T>>::contains_key(&board_key), "lottery not drawn");
+        ensure!(
+            <AppLeaderBoardEpochs<T>>::get(&board_key) == (1, 2),
+            "lottery not recorded"
+        );
+    }
+
+    on_initialize_finance_member_draw {
+        let m in 1 .. MAX_FINANCE_MEMBERS;
+        let when: T::BlockNumber = 2u32.into();
+        for i in 0 .. m {
+            T::Membership::add_finance_member(&funded_account::<T>("finance", i));
+        }
+        let key = T::Hashing::hash_of(&(APP_ID, &b"proposal".to_vec()));
+        <FinanceMemberPendingDraws<T>>::insert(&key, FinanceMemberPendingDraw {
+            kind: FinanceMemberDrawKind::AppFinance,
+            seed_epoch: 0,
+        });
+        <FinanceMemberDrawQueue<T>>::put(vec![key]);
+    }: { Kp::<T>::on_initialize(when); }
+    verify {
+        ensure!(<AppFinanceFinanceMember<T>>::contains_key(&key), "finance member not drawn");
+    }
+
+    create_model {
+        let (user, auth) = setup_app::<T>();
+        fund_treasury::<T>(T::ModTreasuryModuleId::get());
+        let caller = funded_account::<T>("caller", 0);
+
+        let client_params = ClientParamsCreateModel {
+            app_id: APP_ID,
+            expert_id: b"expert".to_vec(),
+            commodity_name: b"commodity".to_vec(),
+            commodity_type: COMMODITY_TYPE,
+            content_hash: Default::default(),
+        };
+        let auth_params = AuthParamsCreateModel { model_id: MODEL_ID.to_vec() };
+        let user_sign = sign::<T, _>(b"kp.create_model", &user, &client_params);
+        let auth_sign = sign::<T, _>(b"kp.create_model", &auth, &auth_params);
+    }: _(RawOrigin::Signed(caller), client_params, auth_params,
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        ensure!(Kp::<T>::is_valid_model(APP_ID, &MODEL_ID.to_vec()), "model not created");
+    }
+
+    model_owner_release {
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+
+        let params = ModelKeyParams { app_id: APP_ID, model_id: MODEL_ID.to_vec() };
+        let user_sign = sign::<T, _>(b"kp.model_owner_release", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.model_owner_release", &auth, &params);
+    }: _(RawOrigin::Signed(caller), params,
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth.clone()), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &MODEL_ID.to_vec()));
+        ensure!(<KPModelDataByIdHash<T>>::get(&key).owner == AuthAccountId::from(auth), "owner not released");
+    }
+
+    add_model_deposit {
+        let (user, _) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let owner = Kp::<T>::convert_account(&AuthAccountId::from(user));
+        let amount = T::ModelCreateDeposit::get();
+    }: _(RawOrigin::Signed(owner), APP_ID, MODEL_ID.to_vec(), amount)
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &MODEL_ID.to_vec()));
+        ensure!(<KPModelDepositMap<T>>::get(&key) == amount + amount, "deposit not added");
+    }
+
+    create_product_publish_document {
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+
+        let params = ClientParamsCreatePublishDoc {
+            app_id: APP_ID,
+            document_id: PUBLISH_DOC_ID.to_vec(),
+            model_id: MODEL_ID.to_vec(),
+            product_id: PRODUCT_ID.to_vec(),
+            content_hash: Default::default(),
+            para_issue_rate: 5000,
+            self_issue_rate: 3000,
+        };
+        let user_sign = sign::<T, _>(b"kp.create_product_publish_document", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.create_product_publish_document", &auth, &params);
+    }: _(RawOrigin::Signed(caller), params,
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &PUBLISH_DOC_ID.to_vec()));
+        ensure!(<KPDocumentDataByIdHash<T>>::contains_key(&key), "document not created");
+    }
+
+    create_product_identify_document {
+        let b in 1 .. T::AppLeaderBoardMaxPos::get();
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+        publish_document::<T>(&caller, &user, &auth)?;
+        fill_leader_boards::<T>(b);
+
+        let params = identify_params::<T>(b"identify", b"cart");
+        let user_sign = sign::<T, _>(b"kp.create_product_identify_document", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.create_product_identify_document", &auth, &params);
+    }: _(RawOrigin::Signed(caller), params,
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &b"cart".to_vec()));
+        ensure!(<KPCartProductIdentifyIndexByIdHash<T>>::contains_key(&key), "document not created");
+    }
+
+    create_product_try_document {
+        let b in 1 .. T::AppLeaderBoardMaxPos::get();
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+        publish_document::<T>(&caller, &user, &auth)?;
+        fill_leader_boards::<T>(b);
+
+        let params = ClientParamsCreateTryDoc {
+            app_id: APP_ID,
+            document_id: b"try".to_vec(),
+            product_id: PRODUCT_ID.to_vec(),
+            content_hash: Default::default(),
+            goods_price: 100,
+            offset_rate: 50,
+            true_rate: 50,
+            seller_consistence: 50,
+            cart_id: b"cart".to_vec(),
+        };
+        let user_sign = sign::<T, _>(b"kp.create_product_try_document", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.create_product_try_document", &auth, &params);
+    }: _(RawOrigin::Signed(caller), params,
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &b"cart".to_vec()));
+        ensure!(<KPCartProductTryIndexByIdHash<T>>::contains_key(&key), "document not created");
+    }
+
+    create_product_choose_document {
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+
+        let params = ClientParamsCreateChooseDoc {
+            app_id: APP_ID,
+            document_id: b"choose".to_vec(),
+            model_id: MODEL_ID.to_vec(),
+            product_id: PRODUCT_ID.to_vec(),
+            content_hash: Default::default(),
+            sell_count: 100,
+            try_count: 100,
+        };
+        let user_sign = sign::<T, _>(b"kp.create_product_choose_document", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.create_product_choose_document", &auth, &params);
+    }: _(RawOrigin::Signed(caller), params,
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &b"choose".to_vec()));
+        ensure!(<KPDocumentDataByIdHash<T>>::contains_key(&key), "document not created");
+    }
+
+    create_model_create_document {
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+
+        let params = ClientParamsCreateModelDoc {
+            app_id: APP_ID,
+            document_id: b"model_doc".to_vec(),
+            model_id: MODEL_ID.to_vec(),
+            product_id: PRODUCT_ID.to_vec(),
+            content_hash: Default::default(),
+            producer_count: 100,
+            product_count: 100,
+        };
+        let user_sign = sign::<T, _>(b"kp.create_model_create_document", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.create_model_create_document", &auth, &params);
+    }: _(RawOrigin::Signed(caller), params,
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &b"model_doc".to_vec()));
+        ensure!(<KPDocumentDataByIdHash<T>>::contains_key(&key), "document not created");
+    }
+
+    create_comment {
+        let b in 1 .. T::AppLeaderBoardMaxPos::get();
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+        publish_document::<T>(&caller, &user, &auth)?;
+        // the commented commodity is the last one of full boards
+        fill_leader_boards::<T>(b - 1);
+        identify_document::<T>(&caller, &user, &auth, b"identify", b"cart")?;
+        fill_comment_pool::<T>(b"identify", T::MaxDocumentComments::get() - 1);
+
+        let params = comment_params::<T>(b"identify", b"comment");
+        let user_sign = sign::<T, _>(b"kp.create_comment", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.create_comment", &auth, &params);
+    }: _(RawOrigin::Signed(caller), params,
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &b"comment".to_vec()));
+        ensure!(<KPCommentDataByIdHash<T>>::contains_key(&key), "comment not created");
+    }
+
+    revise_document {
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+        publish_document::<T>(&caller, &user, &auth)?;
+        identify_document::<T>(&caller, &user, &auth, b"identify", b"cart")?;
+        let doc_key = T::Hashing::hash_of(&(APP_ID, &b"identify".to_vec()));
+        let revisions: Vec<DocumentRevision<T::Hash, T::BlockNumber>> =
+            (1..T::MaxDocumentRevisions::get()).map(|_| Default::default()).collect();
+        <KPDocumentRevisions<T>>::insert(&doc_key, revisions);
+        let owner = Kp::<T>::convert_account(&AuthAccountId::from(user));
+    }: _(RawOrigin::Signed(owner), APP_ID, b"identify".to_vec(), T::Hashing::hash_of(&1u32))
+    verify {
+        ensure!(
+            <KPDocumentRevisions<T>>::decode_len(&doc_key) == Some(T::MaxDocumentRevisions::get() as usize),
+            "document not revised"
+        );
+    }
+
+    retract_document {
+        let q in 1 .. T::MaxModerationQueue::get();
+        let b in 1 .. T::AppLeaderBoardMaxPos::get();
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+        publish_document::<T>(&caller, &user, &auth)?;
+        // the retracted commodity is the last one of full boards
+        fill_leader_boards::<T>(b - 1);
+        identify_document::<T>(&caller, &user, &auth, b"identify", b"cart")?;
+        // a report on it is dropped from a full queue
+        let queue_key = T::Hashing::hash_of(&(APP_ID, &MODEL_ID.to_vec()));
+        fill_moderation_queue::<T>(&queue_key, q - 1);
+        let target = ModerationTarget::Document(b"identify".to_vec());
+        Kp::<T>::report_content(RawOrigin::Signed(caller).into(), APP_ID, target, Default::default())?;
+        let owner = Kp::<T>::convert_account(&AuthAccountId::from(user));
+    }: _(RawOrigin::Signed(owner), APP_ID, b"identify".to_vec())
+    verify {
+        let doc_key = T::Hashing::hash_of(&(APP_ID, &b"identify".to_vec()));
+        ensure!(<KPDocumentStatus<T>>::get(&doc_key) == ContentStatus::Retracted, "document not retracted");
+        ensure!(<ModerationQueue<T>>::decode_len(&queue_key) == Some(q as usize - 1), "report not dropped");
+    }
+
+    report_content {
+        let q in 1 .. T::MaxModerationQueue::get();
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+        publish_document::<T>(&caller, &user, &auth)?;
+        identify_document::<T>(&caller, &user, &auth, b"identify", b"cart")?;
+        let queue_key = T::Hashing::hash_of(&(APP_ID, &MODEL_ID.to_vec()));
+        fill_moderation_queue::<T>(&queue_key, q - 1);
+    }: _(RawOrigin::Signed(caller), APP_ID, ModerationTarget::Document(b"identify".to_vec()), Default::default())
+    verify {
+        ensure!(<ModerationQueue<T>>::decode_len(&queue_key) == Some(q as usize), "content not reported");
+    }
+
+    moderate_content {
+        let q in 1 .. T::MaxModerationQueue::get();
+        let b in 1 .. T::AppLeaderBoardMaxPos::get();
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+        publish_document::<T>(&caller, &user, &auth)?;
+        fill_leader_boards::<T>(b - 1);
+        identify_document::<T>(&caller, &user, &auth, b"identify", b"cart")?;
+        // the handled report is the last one of a full queue
+        let queue_key = T::Hashing::hash_of(&(APP_ID, &MODEL_ID.to_vec()));
+        fill_moderation_queue::<T>(&queue_key, q - 1);
+        let target = ModerationTarget::Document(b"identify".to_vec());
+        Kp::<T>::report_content(RawOrigin::Signed(caller).into(), APP_ID, target.clone(), Default::default())?;
+        let expert: T::AccountId = account("expert", 0, SEED);
+        T::Membership::add_model_expert(&expert, APP_ID, &MODEL_ID.to_vec());
+    }: _(RawOrigin::Signed(expert), APP_ID, target, true)
+    verify {
+        let doc_key = T::Hashing::hash_of(&(APP_ID, &b"identify".to_vec()));
+        ensure!(<KPDocumentStatus<T>>::get(&doc_key) == ContentStatus::Hidden, "document not hidden");
+    }
+
+    create_commodity_type {
+        let n in 1 .. T::MaxCommodityTypes::get();
+        let types: Vec<CommodityTypeData> = (0..n - 1)
+            .map(|i| CommodityTypeData { type_id: i, type_desc: i.encode() })
+            .collect();
+        CommodityTypeSets::put(types);
+    }: _(RawOrigin::Root, n, b"type".to_vec())
+    verify {
+        ensure!(CommodityTypeMap::contains_key(n), "commodity type not created");
+    }
+
+    set_app_model_total {
+    }: _(RawOrigin::Root, APP_ID, 100)
+    verify {
+        ensure!(AppModelTotalConfig::get(APP_ID) == 100, "app model total not set");
+    }
+
+    set_model_income {
+        let n in 1 .. T::MaxModelIncomeItems::get();
+        let (_, admin) = setup_app::<T>();
+        let (finance, finance_account) = auth_key::<T>();
+        T::Membership::add_finance_member(&finance_account);
+
+        let model_ids: Vec<Vec<u8>> = (0..n).map(|i| (MODEL_ID, i).encode()).collect();
+        for model_id in &model_ids {
+            create_model::<T>(model_id, &admin)?;
+        }
+        let params = ModelIncomeCollectingParam {
+            app_id: APP_ID,
+            model_ids,
+            incomes: (0..n).map(|i| 1000 + i as u64).collect(),
+        };
+        let user_sign = sign::<T, _>(b"kp.set_model_income", &admin, &params);
+        let auth_sign = sign::<T, _>(b"kp.set_model_income", &finance, &params);
+
+        let caller = funded_account::<T>("caller", 0);
+        <system::Module<T>>::set_block_number(income_cycle_block::<T>(0u32.into()));
+    }: _(RawOrigin::Signed(caller), params,
+        AuthAccountId::from(admin), user_sign, AuthAccountId::from(finance), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        ensure!(<ModelCycleIncomeTotal<T>>::get(T::BlockNumber::from(1u32)) > 0, "income not collected");
+    }
+
+    request_model_reward {
+        let (user, _) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        fund_treasury::<T>(T::ModTreasuryModuleId::get());
+
+        let cycle: T::BlockNumber = 1u32.into();
+        let key = T::Hashing::hash_of(&(APP_ID, &MODEL_ID.to_vec()));
+        <ModelCycleIncomeTotal<T>>::insert(cycle, 2000);
+        <ModelCycleIncome<T>>::insert(cycle, &key, 1000);
+
+        let owner = Kp::<T>::convert_account(&AuthAccountId::from(user));
+        <system::Module<T>>::set_block_number(rewarding_block::<T>());
+    }: _(RawOrigin::Signed(owner), APP_ID, MODEL_ID.to_vec())
+    verify {
+        ensure!(<ModelCycleIncomeRewardRecords<T>>::contains_key(cycle, &key), "model not rewarded");
+    }
+
+    app_income_redeem_request {
+        let (user, auth) = setup_app::<T>();
+        T::Membership::add_finance_member(&funded_account::<T>("finance", 0));
+        let cycle: T::BlockNumber = 1u32.into();
+        <AppCycleIncome<T>>::mutate(cycle, APP_ID, |record| {
+            record.income = 1_000_000;
+            record.cycle = cycle;
+            record.app_id = APP_ID;
+        });
+
+        let params = AppIncomeRedeemParams {
+            account: Kp::<T>::convert_account(&AuthAccountId::from(user.clone())),
+            app_id: APP_ID,
+            cycle,
+            exchange_amount: units::<T>(1),
+        };
+        let user_sign = sign::<T, _>(b"kp.app_income_redeem_request", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.app_income_redeem_request", &auth, &params);
+
+        let caller = funded_account::<T>("caller", 0);
+        <system::Module<T>>::set_block_number(rewarding_block::<T>());
+    }: _(RawOrigin::Signed(caller), params.clone(),
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        let key = Kp::<T>::app_income_exchange_record_key(APP_ID, cycle, &params.account);
+        ensure!(<AppCycleIncomeExchangeRecords<T>>::get(&key).status == 1, "redeem not requested");
+    }
+
+    app_income_redeem_confirm {
+        let (user, auth) = setup_app::<T>();
+        let finance = funded_account::<T>("finance", 0);
+        <system::Module<T>>::set_block_number(rewarding_block::<T>());
+        let request = app_income_redeem::<T>(&user, &auth, &finance)?;
+
+        let params = AppIncomeRedeemConfirmParams {
+            account: request.account.clone(),
+            app_id: APP_ID,
+            pay_id: b"pay".to_vec(),
+            cycle: request.cycle,
+        };
+        <system::Module<T>>::set_block_number(confirming_block::<T>());
+    }: _(RawOrigin::Signed(finance), params)
+    verify {
+        let key = Kp::<T>::app_income_exchange_record_key(APP_ID, request.cycle, &request.account);
+        ensure!(<AppCycleIncomeExchangeRecords<T>>::get(&key).status == 2, "redeem not confirmed");
+    }
+
+    app_income_redeem_compensate {
+        let (user, auth) = setup_app::<T>();
+        let finance = funded_account::<T>("finance", 0);
+        <system::Module<T>>::set_block_number(rewarding_block::<T>());
+        let request = app_income_redeem::<T>(&user, &auth, &finance)?;
+        <system::Module<T>>::set_block_number(compensating_block::<T>());
+    }: _(RawOrigin::Signed(request.account.clone()), APP_ID, request.cycle)
+    verify {
+        let key = Kp::<T>::app_income_exchange_record_key(APP_ID, request.cycle, &request.account);
+        ensure!(<AppCycleIncomeExchangeRecords<T>>::get(&key).status == 3, "redeem not compensated");
+    }
+
+    democracy_slash_commodity_power {
+        let b in 1 .. T::AppLeaderBoardMaxPos::get();
+        let (user, auth) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        let caller = funded_account::<T>("caller", 0);
+        publish_document::<T>(&caller, &user, &auth)?;
+        // the slashed commodity is the last one of full boards
+        fill_leader_boards::<T>(b - 1);
+        identify_document::<T>(&caller, &user, &auth, b"identify", b"cart")?;
+
+        let params = comment_params::<T>(b"identify", b"comment");
+        Kp::<T>::create_comment(
+            RawOrigin::Signed(caller.clone()).into(),
+            params.clone(),
+            AuthAccountId::from(user.clone()),
+            sign::<T, _>(b"kp.create_comment", &user, &params),
+            AuthAccountId::from(auth.clone()),
+            sign::<T, _>(b"kp.create_comment", &auth, &params),
+            T::PayloadVerify::max_expiry(),
+        )?;
+    }: _(RawOrigin::Root, APP_ID, b"cart".to_vec(), b"comment".to_vec(), caller)
+    verify {
+        ensure!(Kp::<T>::is_commodity_in_black_list(APP_ID, b"cart".to_vec()), "power not slashed");
+    }
+
+    democracy_model_dispute {
+        let (user, _) = setup_app::<T>();
+        create_model::<T>(MODEL_ID, &user)?;
+        fund_treasury::<T>(T::TreasuryModuleId::get());
+
+        // a serious dispute past the cycle limit disables the model
+        let key = T::Hashing::hash_of(&(APP_ID, &MODEL_ID.to_vec()));
+        let cycle = Kp::<T>::model_income_cycle_index(<system::Module<T>>::block_number());
+        <ModelCycleDisputeCount<T>>::insert(cycle, &key, T::ModelDisputeCycleCount::get());
+        let reporter: T::AccountId = account("reporter", 0, SEED);
+    }: _(RawOrigin::Root, APP_ID, MODEL_ID.to_vec(), ModelDisputeType::Serious, b"comment".to_vec(), reporter)
+    verify {
+        ensure!(!Kp::<T>::is_valid_model(APP_ID, &MODEL_ID.to_vec()), "model not disabled");
+    }
+
+    democracy_add_app {
+        let app_type = b"app".to_vec();
+        <AppIdRange<T>>::insert(&app_type, (100, units::<T>(1), 0, 0, 0));
+        let (admin, admin_account) = auth_key::<T>();
+        let params = AddAppParams {
+            app_type,
+            app_name: b"app".to_vec(),
+            app_key: account("app_key", 0, SEED),
+            app_admin_key: admin_account,
+            return_rate: 100,
+        };
+        let admin_sign = sign::<T, _>(b"kp.democracy_add_app", &admin, &params);
+    }: _(RawOrigin::Root, params, AuthAccountId::from(admin), admin_sign, T::PayloadVerify::max_expiry())
+    verify {
+        ensure!(T::Membership::is_valid_app(101), "app not added");
+    }
+
+    democracy_app_financed {
+        let (user, _) = setup_app::<T>();
+        let (finance, finance_account) = auth_key::<T>();
+        T::Membership::add_finance_member(&finance_account);
+        fund_treasury::<T>(T::FinTreasuryModuleId::get());
+
+        let investor = funded_account::<T>("investor", 0);
+        T::Membership::add_investor(&investor);
+        let params = AppFinancedProposalParams {
+            account: investor,
+            app_id: APP_ID,
+            proposal_id: b"proposal".to_vec(),
+            exchange: units::<T>(100),
+            amount: units::<T>(100),
+        };
+        let user_sign = sign::<T, _>(b"kp.democracy_app_financed", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.democracy_app_financed", &finance, &params);
+    }: _(RawOrigin::Root, params, AuthAccountId::from(user), user_sign, AuthAccountId::from(finance), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        ensure!(AppFinancedCount::get() == 1, "app not financed");
+    }
+
+    app_financed_user_exchange_request {
+        let (user, auth) = setup_app::<T>();
+        T::Membership::add_finance_member(&funded_account::<T>("finance", 0));
+        let proposal_id = b"proposal".to_vec();
+        <AppFinancedRecord<T>>::insert(
+            &T::Hashing::hash_of(&(APP_ID, &proposal_id)),
+            AppFinancedData {
+                app_id: APP_ID,
+                proposal_id: proposal_id.clone(),
+                amount: units::<T>(100),
+                exchange: units::<T>(100),
+                exchange_end_block: <system::Module<T>>::block_number()
+                    + T::AppFinanceExchangePeriod::get(),
+                ..Default::default()
+            },
+        );
+
+        let params = AppFinancedUserExchangeParams {
+            account: Kp::<T>::convert_account(&AuthAccountId::from(user.clone())),
+            app_id: APP_ID,
+            proposal_id,
+            exchange_amount: units::<T>(1),
+        };
+        let user_sign = sign::<T, _>(b"kp.app_financed_user_exchange_request", &user, &params);
+        let auth_sign = sign::<T, _>(b"kp.app_financed_user_exchange_request", &auth, &params);
+        let caller = funded_account::<T>("caller", 0);
+    }: _(RawOrigin::Signed(caller), params.clone(),
+        AuthAccountId::from(user), user_sign, AuthAccountId::from(auth), auth_sign, T::PayloadVerify::max_expiry())
+    verify {
+        let key = Kp::<T>::app_financed_exchange_record_key(APP_ID, &params.proposal_id, &params.account);
+        ensure!(<AppFinancedUserExchangeRecord<T>>::get(&key).status == 1, "exchange not requested");
+    }
+
+    app_financed_user_exchange_confirm {
+        let (user, auth) = setup_app::<T>();
+        let finance = funded_account::<T>("finance", 0);
+        let request = app_financed_exchange::<T>(&user, &auth, &finance)?;
+
+        let params = AppFinancedUserExchangeConfirmParams {
+            account: request.account.clone(),
+            app_id: APP_ID,
+            pay_id: b"pay".to_vec(),
+            proposal_id: request.proposal_id.clone(),
+        };
+    }: _(RawOrigin::Signed(finance), params)
+    verify {
+        let key = Kp::<T>::app_financed_exchange_record_key(APP_ID, &request.proposal_id, &request.account);
+        ensure!(<AppFinancedUserExchangeRecord<T>>::get(&key).status == 2, "exchange not confirmed");
+    }
+
+    app_finance_redeem_compensate {
+        let (user, auth) = setup_app::<T>();
+        let finance = funded_account::<T>("finance", 0);
+        let request = app_financed_exchange::<T>(&user, &auth, &finance)?;
+
+        let period = T::AppFinanceExchangePeriod::get();
+        let now = <system::Module<T>>::block_number();
+        <system::Module<T>>::set_block_number(now + period + period / 2u32.into() + 1u32.into());
+    }: _(RawOrigin::Signed(request.account.clone()), APP_ID, request.proposal_id.clone())
+    verify {
+        let key = Kp::<T>::app_financed_exchange_record_key(APP_ID, &request.proposal_id, &request.account);
+        ensure!(<AppFinancedUserExchangeRecord<T>>::get(&key).status == 3, "exchange not compensated");
+    }
+
+    create_power_leader_board {
+        let b in 1 .. T::AppLeaderBoardMaxPos::get();
+        let c in 1 .. T::MaxDocumentComments::get();
+        let (user, _) = setup_app::<T>();
+        fill_leader_boards::<T>(b);
+
+        // every leader is identified against the same publish document
+        let model_key = T::Hashing::hash_of(&(APP_ID, &MODEL_ID.to_vec()));
+        <AppModelCommodityCount<T>>::insert(&model_key, if b > 5 { b * 5 } else { b });
+        <KPDocumentProductIndexByIdHash<T>>::insert(
+            &T::Hashing::hash_of(&(APP_ID, &PRODUCT_ID.to_vec())),
+            PUBLISH_DOC_ID.to_vec(),
+        );
+        fill_comment_pool::<T>(PUBLISH_DOC_ID, c);
+        for i in 0..b {
+            let document_id = (&b"identify"[..], i).encode();
+            <KPCartProductIdentifyIndexByIdHash<T>>::insert(
+                &T::Hashing::hash_of(&(APP_ID, &cart_id(i))),
+                &document_id,
+            );
+            <KPDocumentDataByIdHash<T>>::insert(
+                &T::Hashing::hash_of(&(APP_ID, &document_id)),
+                KPDocumentData {
+                    app_id: APP_ID,
+                    document_id: document_id.clone(),
+                    product_id: PRODUCT_ID.to_vec(),
+                    owner: AuthAccountId::from(user.clone()),
+                    ..Default::default()
+                },
+            );
+            fill_comment_pool::<T>(&document_id, c);
+        }
+        CommentMaxInfoPerDocMap::insert(APP_ID, CommentMaxRecord {
+            max_count: c as PowerSize,
+            max_fee: 10 + c as PowerSize,
+            ..Default::default()
+        });
+
+    }: _(RawOrigin::Root, APP_ID, MODEL_ID.to_vec())
+    verify {
+        ensure!(
+            <AppLeaderBoardPendingDraw<T>>::contains_key(&model_key),
+            "leader board not closed"
+        );
+    }
+
+    democracy_tech_fund_withdraw {
+        fund_treasury::<T>(T::TechTreasuryModuleId::get());
+        let receiver: T::AccountId = account("receiver", 0, SEED);
+        let origin = T::TechMemberOrigin::successful_origin();
+    }: _(origin, receiver, Default::default(), TechFundWithdrawType::ChainDev, TechFundWithdrawLevel::LV1)
+    verify {
+        ensure!(<TechFundWithdrawRecords<T>>::decode_len() == Some(1), "fund not withdrawn");
+    }
+
+    set_power_params {
+        let params = PowerParams {
+            top_weight_account_attend: 5,
+            top_weight_account_stake: 10,
+            ..Default::default()
+        };
+        let origin = T::TechMemberOrigin::successful_origin();
+    }: _(origin, params.clone())
+    verify {
+        ensure!(Kp::<T>::power_params() == params, "power params not set");
+    }
+}
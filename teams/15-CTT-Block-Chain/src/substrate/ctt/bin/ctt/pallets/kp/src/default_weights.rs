@@ -0,0 +1,205 @@
+//! Weights of the kp pallet for tests and runtimes that do not benchmark it, the ctt
+//! runtime uses `runtime/src/weights/kp.rs`. Like those, these are hand estimated from
+//! the storage each call touches and have not been measured with the benchmarks.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+impl crate::WeightInfo for () {
+    fn on_initialize_pre_black_list(n: u32) -> Weight {
+        (5_000_000 as Weight)
+            .saturating_add((60_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(DbWeight::get().reads(1 as Weight))
+            .saturating_add(DbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+            .saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
+    fn on_initialize_leader_board_draw(p: u32, c: u32) -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add((120_000 as Weight).saturating_mul(p as Weight))
+            .saturating_add(
+                (90_000 as Weight)
+                    .saturating_mul(p as Weight)
+                    .saturating_mul(c as Weight),
+            )
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+    }
+    fn on_initialize_finance_member_draw(m: u32) -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add((150_000 as Weight).saturating_mul(m as Weight))
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(m as Weight)))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
+    fn create_model() -> Weight {
+        (215_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(11 as Weight))
+            .saturating_add(DbWeight::get().writes(9 as Weight))
+    }
+    fn model_owner_release() -> Weight {
+        (190_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+    }
+    fn add_model_deposit() -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn create_product_publish_document() -> Weight {
+        (235_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(14 as Weight))
+            .saturating_add(DbWeight::get().writes(10 as Weight))
+    }
+    fn create_product_identify_document(b: u32) -> Weight {
+        (290_000_000 as Weight)
+            .saturating_add((1_150_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(DbWeight::get().reads(24 as Weight))
+            .saturating_add(DbWeight::get().writes(19 as Weight))
+    }
+    fn create_product_try_document(b: u32) -> Weight {
+        (290_000_000 as Weight)
+            .saturating_add((1_150_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(DbWeight::get().reads(24 as Weight))
+            .saturating_add(DbWeight::get().writes(19 as Weight))
+    }
+    fn create_product_choose_document() -> Weight {
+        (205_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(12 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+    }
+    fn create_model_create_document() -> Weight {
+        (210_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(13 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+    }
+    fn create_comment(b: u32) -> Weight {
+        (330_000_000 as Weight)
+            .saturating_add((1_200_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(DbWeight::get().reads(26 as Weight))
+            .saturating_add(DbWeight::get().writes(18 as Weight))
+    }
+    fn revise_document() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn retract_document(q: u32, b: u32) -> Weight {
+        (125_000_000 as Weight)
+            .saturating_add((900_000 as Weight).saturating_mul(q as Weight))
+            .saturating_add((1_050_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(DbWeight::get().reads(13 as Weight))
+            .saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(q as Weight)))
+            .saturating_add(DbWeight::get().writes(10 as Weight))
+            .saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(q as Weight)))
+    }
+    fn report_content(q: u32) -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add((350_000 as Weight).saturating_mul(q as Weight))
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn moderate_content(q: u32, b: u32) -> Weight {
+        (150_000_000 as Weight)
+            .saturating_add((1_250_000 as Weight).saturating_mul(q as Weight))
+            .saturating_add((1_050_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(DbWeight::get().reads(18 as Weight))
+            .saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(q as Weight)))
+            .saturating_add(DbWeight::get().writes(16 as Weight))
+            .saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(q as Weight)))
+    }
+    fn create_commodity_type(n: u32) -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add((95_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(DbWeight::get().reads(2 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
+    }
+    fn set_app_model_total() -> Weight {
+        (12_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(0 as Weight))
+            .saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+    fn set_model_income(n: u32) -> Weight {
+        (180_000_000 as Weight)
+            .saturating_add((24_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+            .saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
+    fn request_model_reward() -> Weight {
+        (150_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().writes(5 as Weight))
+    }
+    fn app_income_redeem_request() -> Weight {
+        (185_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(14 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+    }
+    fn app_income_redeem_confirm() -> Weight {
+        (160_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(5 as Weight))
+    }
+    fn app_income_redeem_compensate() -> Weight {
+        (140_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(5 as Weight))
+    }
+    fn democracy_slash_commodity_power(b: u32) -> Weight {
+        (140_000_000 as Weight)
+            .saturating_add((1_050_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(DbWeight::get().reads(12 as Weight))
+            .saturating_add(DbWeight::get().writes(11 as Weight))
+    }
+    fn democracy_model_dispute() -> Weight {
+        (150_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(8 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+    }
+    fn democracy_add_app() -> Weight {
+        (125_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(7 as Weight))
+    }
+    fn democracy_app_financed() -> Weight {
+        (170_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(12 as Weight))
+            .saturating_add(DbWeight::get().writes(7 as Weight))
+    }
+    fn app_financed_user_exchange_request() -> Weight {
+        (180_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(13 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
+    }
+    fn app_financed_user_exchange_confirm() -> Weight {
+        (160_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(7 as Weight))
+            .saturating_add(DbWeight::get().writes(5 as Weight))
+    }
+    fn app_finance_redeem_compensate() -> Weight {
+        (140_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(6 as Weight))
+            .saturating_add(DbWeight::get().writes(5 as Weight))
+    }
+    fn create_power_leader_board(b: u32, c: u32) -> Weight {
+        (95_000_000 as Weight)
+            .saturating_add((9_500_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add((1_600_000 as Weight).saturating_mul(c as Weight))
+            .saturating_add(DbWeight::get().reads(7 as Weight))
+            .saturating_add(DbWeight::get().reads((7 as Weight).saturating_mul(b as Weight)))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
+    fn democracy_tech_fund_withdraw() -> Weight {
+        (85_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
+    fn set_power_params() -> Weight {
+        (25_000_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+    }
+}
@@ -4,9 +4,10 @@
 use frame_support::{
     codec::{Decode, Encode},
     decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
+    storage::IterableStorageMap,
     traits::{
         Contains, Currency, EnsureOrigin, ExistenceRequirement::KeepAlive, Get, LockableCurrency,
-        OnUnbalanced, Randomness, ReservableCurrency, WithdrawReason,
+        OnUnbalanced, ReservableCurrency, WithdrawReason,
     },
     weights::Weight,
 };
@@ -36,9 +37,10 @@ use sp_std::prelude::*;
 /// For more guidance on Substrate FRAME, see the example pallet
 /// https://github.com/paritytech/substrate/blob/master/frame/example/src/lib.rs
 use frame_system::{self as system, ensure_root, ensure_signed};
-use primitives::{AuthAccountId, Membership, PowerSize};
+use primitives::{AuthAccountId, EpochRandomness, Membership, PowerSize, SignedPayloadVerify};
 use sp_core::sr25519;
 use sp_runtime::{
+    curve::PiecewiseLinear,
     print,
     traits::{AccountIdConversion, Hash, SaturatedConversion, TrailingZeroInput, Verify},
     ModuleId, MultiSignature, Perbill, Percent, Permill, RuntimeDebug,
@@ -46,6 +48,21 @@ use sp_runtime::{
 
 const FLOAT_COMPUTE_PRECISION: PowerSize = 10000;
 
+/// Levels of the Merkle tree in `LeaderBoardArchive`, enough for `u32::MAX` records.
+const LEADER_BOARD_ARCHIVE_DEPTH: usize = 32;
+
+/// The most leader board records returned by one `leader_board_history` page.
+const LEADER_BOARD_HISTORY_PAGE: u32 = 100;
+
+/// The most leader board records pruned into the archive by one new record.
+const LEADER_BOARD_PRUNE_LIMIT: u32 = 4;
+
+/// Legacy leader board records moved into the paged history per block.
+const LEADER_BOARD_MIGRATION_BATCH: u32 = 64;
+
+/// Blocks from the due one searched for room in a per-block queue before giving up.
+const MAX_SCHEDULE_PROBE: u32 = 64;
+
 type BalanceOf<T> =
     <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 type NegativeImbalanceOf<T> =
@@ -59,6 +76,7 @@ type CommodityPowerSet = (
     PowerSize,
 );
 
+/// Vote weight factor `(num, frac, frac_cond)`: a stake `b` votes with `b * num + (frac * b) * frac_cond`.
 pub type PowerRatioType = (u32, Perbill, u32);
 
 pub trait PowerVote<AccountId> {
@@ -73,6 +91,20 @@ pub trait PowerVote<AccountId> {
     }
 }
 
+/// Simulated standing of an account in the next validator election.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug)]
+pub struct ElectionRank<Balance> {
+    /// Own bonded stake with the kp power factor applied.
+    pub vote_weight: Balance,
+    /// `vote_weight` plus the weighted stake nominators put behind the account.
+    pub backing: Balance,
+    /// 1-based position by backing among all candidates, 0 if nothing is bonded.
+    pub rank: u32,
+    pub candidates: u32,
+    pub seats: u32,
+}
+
 #[derive(Encode, Decode, PartialEq, Clone, RuntimeDebug)]
 pub struct LeaderBoardIndex(u32);
 
@@ -104,6 +136,45 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+mod benchmarking;
+mod default_weights;
+
+pub trait WeightInfo {
+    fn on_initialize_pre_black_list(n: u32) -> Weight;
+    fn on_initialize_leader_board_draw(p: u32, c: u32) -> Weight;
+    fn on_initialize_finance_member_draw(m: u32) -> Weight;
+    fn create_model() -> Weight;
+    fn model_owner_release() -> Weight;
+    fn add_model_deposit() -> Weight;
+    fn create_product_publish_document() -> Weight;
+    fn create_product_identify_document(b: u32) -> Weight;
+    fn create_product_try_document(b: u32) -> Weight;
+    fn create_product_choose_document() -> Weight;
+    fn create_model_create_document() -> Weight;
+    fn create_comment(b: u32) -> Weight;
+    fn revise_document() -> Weight;
+    fn retract_document(q: u32, b: u32) -> Weight;
+    fn report_content(q: u32) -> Weight;
+    fn moderate_content(q: u32, b: u32) -> Weight;
+    fn create_commodity_type(n: u32) -> Weight;
+    fn set_app_model_total() -> Weight;
+    fn set_model_income(n: u32) -> Weight;
+    fn request_model_reward() -> Weight;
+    fn app_income_redeem_request() -> Weight;
+    fn app_income_redeem_confirm() -> Weight;
+    fn app_income_redeem_compensate() -> Weight;
+    fn democracy_slash_commodity_power(b: u32) -> Weight;
+    fn democracy_model_dispute() -> Weight;
+    fn democracy_add_app() -> Weight;
+    fn democracy_app_financed() -> Weight;
+    fn app_financed_user_exchange_request() -> Weight;
+    fn app_financed_user_exchange_confirm() -> Weight;
+    fn app_finance_redeem_compensate() -> Weight;
+    fn create_power_leader_board(b: u32, c: u32) -> Weight;
+    fn democracy_tech_fund_withdraw() -> Weight;
+    fn set_power_params() -> Weight;
+}
+
 #[derive(Encode, Decode, PartialEq, Clone, RuntimeDebug)]
 pub enum ModelStatus {
     ENABLED = 0,
@@ -126,7 +197,7 @@ impl From<u8> for ModelStatus {
     }
 }
 
-#[derive(Encode, Decode, PartialEq, Clone, RuntimeDebug)]
+#[derive(Encode, Decode, PartialEq, Eq, Clone, RuntimeDebug)]
 pub enum DocumentType {
     ProductPublish = 0,
     ProductIdentify,
@@ -352,6 +423,185 @@ pub struct DocumentPowerInfo {
     pub is_slashed: bool,
 }
 
+/// Weights of the power formula, in percent.
+///
+/// Each group of weights sums to 100, the default is the formula the chain launched with.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PowerParams {
+    /// 5 dimensions weight config
+    pub top_weight_product_publish: u8,
+    pub top_weight_document_identify: u8,
+    pub top_weight_document_try: u8,
+    pub top_weight_account_attend: u8,
+    pub top_weight_account_stake: u8,
+
+    /// Document power weights
+    pub document_power_weight_attend: u8,
+    pub document_power_weight_content: u8,
+    pub document_power_weight_judge: u8,
+
+    /// Comment power weights: count, cost, cost per unit and positive
+    pub comment_power_weight_count: u8,
+    pub comment_power_weight_cost: u8,
+    pub comment_power_weight_per_cost: u8,
+    pub comment_power_weight_positive: u8,
+    pub comment_power_weight: u8,
+
+    /// Document publish content weights
+    pub document_publish_weight_params_rate: u8,
+    pub document_publish_weight_params_self_rate: u8,
+    pub document_publish_weight_params_attend_rate: u8,
+
+    /// Document identify content weights
+    pub document_identify_weight_params_rate: u8,
+    pub document_identify_weight_check_rate: u8,
+    pub document_identify_weight_consistent_rate: u8,
+
+    /// Document try content weights
+    pub document_try_weight_bias_rate: u8,
+    pub document_try_weight_true_rate: u8,
+    pub document_try_weight_consistent_rate: u8,
+
+    /// Document choose content weights
+    pub document_choose_weight_sell_count: u8,
+    pub document_choose_weight_try_count: u8,
+
+    /// Document model content weights
+    pub document_model_weight_producer_count: u8,
+    pub document_model_weight_product_count: u8,
+
+    /// Document choose & model power weights
+    pub document_cm_power_weight_attend: u8,
+    pub document_cm_power_weight_content: u8,
+    pub document_cm_power_weight_judge: u8,
+
+    /// Comment power weights of choose & model documents
+    pub comment_cm_power_weight_count: u8,
+    pub comment_cm_power_weight_cost: u8,
+    pub comment_cm_power_weight_per_cost: u8,
+    pub comment_cm_power_weight_positive: u8,
+    pub cm_power_account_attend: u8,
+}
+
+impl Default for PowerParams {
+    fn default() -> Self {
+        PowerParams {
+            top_weight_product_publish: 15,
+            top_weight_document_identify: 30,
+            top_weight_document_try: 40,
+            top_weight_account_attend: 10,
+            top_weight_account_stake: 5,
+
+            document_power_weight_attend: 40,
+            document_power_weight_content: 30,
+            document_power_weight_judge: 30,
+
+            comment_power_weight_count: 65,
+            comment_power_weight_cost: 20,
+            comment_power_weight_per_cost: 10,
+            comment_power_weight_positive: 5,
+            comment_power_weight: 40,
+
+            document_publish_weight_params_rate: 20,
+            document_publish_weight_params_self_rate: 35,
+            document_publish_weight_params_attend_rate: 45,
+
+            document_identify_weight_params_rate: 20,
+            document_identify_weight_check_rate: 35,
+            document_identify_weight_consistent_rate: 45,
+
+            document_try_weight_bias_rate: 20,
+            document_try_weight_true_rate: 35,
+            document_try_weight_consistent_rate: 45,
+
+            document_choose_weight_sell_count: 60,
+            document_choose_weight_try_count: 40,
+
+            document_model_weight_producer_count: 40,
+            document_model_weight_product_count: 60,
+
+            document_cm_power_weight_attend: 40,
+            document_cm_power_weight_content: 20,
+            document_cm_power_weight_judge: 30,
+
+            comment_cm_power_weight_count: 65,
+            comment_cm_power_weight_cost: 20,
+            comment_cm_power_weight_per_cost: 10,
+            comment_cm_power_weight_positive: 5,
+            cm_power_account_attend: 10,
+        }
+    }
+}
+
+impl PowerParams {
+    /// Whether each group of weights sums to 100.
+    pub fn is_valid(&self) -> bool {
+        let groups: [&[u8]; 10] = [
+            &[
+                self.top_weight_product_publish,
+                self.top_weight_document_identify,
+                self.top_weight_document_try,
+                self.top_weight_account_attend,
+                self.top_weight_account_stake,
+            ],
+            &[
+                self.document_power_weight_attend,
+                self.document_power_weight_content,
+                self.document_power_weight_judge,
+            ],
+            &[
+                self.comment_power_weight_count,
+                self.comment_power_weight_cost,
+                self.comment_power_weight_per_cost,
+                self.comment_power_weight_positive,
+            ],
+            &[
+                self.document_publish_weight_params_rate,
+                self.document_publish_weight_params_self_rate,
+                self.document_publish_weight_params_attend_rate,
+            ],
+            &[
+                self.document_identify_weight_params_rate,
+                self.document_identify_weight_check_rate,
+                self.document_identify_weight_consistent_rate,
+            ],
+            &[
+                self.document_try_weight_bias_rate,
+                self.document_try_weight_true_rate,
+                self.document_try_weight_consistent_rate,
+            ],
+            &[
+                self.document_choose_weight_sell_count,
+                self.document_choose_weight_try_count,
+            ],
+            &[
+                self.document_model_weight_producer_count,
+                self.document_model_weight_product_count,
+            ],
+            // choose & model documents have no top weight, the account attend one is theirs
+            &[
+                self.document_cm_power_weight_attend,
+                self.document_cm_power_weight_content,
+                self.document_cm_power_weight_judge,
+                self.cm_power_account_attend,
+            ],
+            &[
+                self.comment_cm_power_weight_count,
+                self.comment_cm_power_weight_cost,
+                self.comment_cm_power_weight_per_cost,
+                self.comment_cm_power_weight_positive,
+            ],
+        ];
+
+        self.comment_power_weight <= 100
+            && groups
+                .iter()
+                .all(|group| group.iter().map(|w| *w as u32).sum::<u32>() == 100)
+    }
+}
+
 // power store
 #[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug)]
 pub struct DocumentPower {
@@ -409,6 +659,67 @@ pub struct KPCommentData<AccountId, Hash> {
     owner: AuthAccountId,
 }
 
+/// Lifecycle state of a document or comment, power only counts for active ones.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ContentStatus {
+    Active,
+    /// Withdrawn by its author, its power is rolled back.
+    Retracted,
+    /// Hidden by a model expert, its author's power is slashed.
+    Hidden,
+}
+
+impl Default for ContentStatus {
+    fn default() -> Self {
+        ContentStatus::Active
+    }
+}
+
+/// A replaced version of a document.
+#[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug)]
+pub struct DocumentRevision<Hash, BlockNumber> {
+    pub content_hash: Hash,
+    /// Block at which this version was replaced.
+    pub block: BlockNumber,
+}
+
+/// Document or comment reported to the model experts, by id.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ModerationTarget {
+    Document(Vec<u8>),
+    Comment(Vec<u8>),
+}
+
+/// Store of a power value in kp events, by its id in the app.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum PowerTarget {
+    /// `KPDocumentPowerByIdHash` of a document.
+    Document(Vec<u8>),
+    /// `KPMiscDocumentPowerByIdHash` of a product choose or model create document.
+    MiscDocument(Vec<u8>),
+    /// `KPPurchasePowerByIdHash` of a commodity, by cart id.
+    Commodity(Vec<u8>),
+    /// `AccountAttendPowerMap` of the account in the app, earned by comments.
+    Attend,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct ModerationReport<AccountId, Hash, BlockNumber, Balance> {
+    pub target: ModerationTarget,
+    pub reporter: AccountId,
+    pub reason_hash: Hash,
+    pub block: BlockNumber,
+    /// Reserved from the reporter, returned if the content is hidden and slashed otherwise.
+    pub deposit: Balance,
+}
+
+type ModerationReportOf<T> = ModerationReport<
+    <T as system::Trait>::AccountId,
+    <T as system::Trait>::Hash,
+    <T as system::Trait>::BlockNumber,
+    BalanceOf<T>,
+>;
+
 type KPModelDataOf<T> =
     KPModelData<<T as system::Trait>::AccountId, <T as system::Trait>::Hash, BalanceOf<T>>;
 #[derive(Encode, Decode, Clone, Default, RuntimeDebug)]
@@ -543,6 +854,92 @@ pub struct LeaderBoardItem<AccountId> {
     pub owner: AccountId,
 }
 
+/// A leader board result of an (app, model), the epoch counts the boards of it.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LeaderBoardHistoryItem<AccountId, BlockNumber, Hash> {
+    pub epoch: u32,
+    pub block: BlockNumber,
+    pub result: LeaderBoardResult<AccountId>,
+    /// Randomness epoch of the lottery seed.
+    pub seed_epoch: u64,
+    /// The lottery seed, re-run `Module::leader_board_draw_winners` with it and `pools` to
+    /// check the accounts.
+    pub seed: Hash,
+    /// The lottery entries the accounts were drawn from.
+    pub pools: Vec<LeaderBoardDrawPool<AccountId>>,
+}
+
+/// A page of the leader board history of an (app, model).
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LeaderBoardHistoryPage<AccountId, BlockNumber, Hash> {
+    pub records: Vec<LeaderBoardHistoryItem<AccountId, BlockNumber, Hash>>,
+    /// Epoch to continue from, `None` once the latest record is in this page.
+    pub next: Option<u32>,
+    /// Records before this epoch are pruned.
+    pub first: u32,
+    /// Merkle root of the pruned records.
+    pub pruned_root: Hash,
+}
+
+/// Lottery entries of a document, account `i` wins a hit at position `p` when
+/// `positions[i - 1] <= p < positions[i]`.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LeaderBoardDrawPool<AccountId> {
+    pub accounts: Vec<AccountId>,
+    pub positions: Vec<u32>,
+    pub hits: u32,
+}
+
+/// A closed leader board waiting for the randomness of `seed_epoch` to draw its lottery.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LeaderBoardPendingDraw<AccountId, BlockNumber> {
+    pub app_id: u32,
+    pub model_id: Vec<u8>,
+    /// Block at which the entries closed.
+    pub block: BlockNumber,
+    pub seed_epoch: u64,
+    pub board: Vec<LeaderBoardItem<AccountId>>,
+    pub pools: Vec<LeaderBoardDrawPool<AccountId>>,
+}
+
+/// What a finance member is drawn for.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum FinanceMemberDrawKind {
+    /// Redeems of an app income cycle, keyed by (AppId, cycle)
+    AppCycleIncome,
+    /// Exchanges of an app finance proposal, keyed by (AppId, proposal id)
+    AppFinance,
+}
+
+/// A finance member draw waiting for the randomness of `seed_epoch`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct FinanceMemberPendingDraw {
+    pub kind: FinanceMemberDrawKind,
+    pub seed_epoch: u64,
+}
+
+/// A finance member drawn from `candidates` with `seed`.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct FinanceMemberDrawRecord<AccountId, Hash> {
+    pub seed_epoch: u64,
+    pub seed: Hash,
+    pub candidates: Vec<AccountId>,
+    pub member: AccountId,
+}
+
+/// Incremental Merkle tree of fixed depth over the pruned leader board records of an
+/// (app, model), leaf `i` is the hash of the record of epoch `i`.
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct LeaderBoardArchive<Hash> {
+    pub count: u32,
+    /// Left siblings of the path to the next leaf, one per level.
+    pub branch: Vec<Hash>,
+    pub root: Hash,
+}
+
 #[derive(Encode, Decode, Default, Clone, Eq, RuntimeDebug)]
 pub struct CommodityLeaderBoardData<T: Trait> {
     cart_id: Vec<u8>,
@@ -698,6 +1095,21 @@ pub struct ModelIncomeCollectingParam {
     incomes: Vec<u64>,
 }
 
+/// Storage layout versions of the pallet.
+#[derive(Encode, Decode, PartialEq, Clone, Copy, RuntimeDebug)]
+enum Releases {
+    /// Leader board records in `AppLeaderBoardRcord`.
+    V1_0_0,
+    /// Leader board records in the paged `AppLeaderBoardHistory`.
+    V2_0_0,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1_0_0
+    }
+}
+
 #[derive(Encode, Decode, PartialEq, Clone, Copy, RuntimeDebug)]
 enum ModelIncomeStage {
     NORMAL,
@@ -786,6 +1198,9 @@ pub trait Trait: system::Trait {
     /// Membership control
     type Membership: Membership<Self::AccountId, Self::Hash, BalanceOf<Self>>;
 
+    /// Checks the signed payloads of app users and auth servers, each is accepted once.
+    type PayloadVerify: SignedPayloadVerify<Self::BlockNumber>;
+
     /// TechnicalCommittee member ship check
     type TechMembers: Contains<Self::AccountId>;
 
@@ -802,8 +1217,8 @@ pub trait Trait: system::Trait {
     /// Handler for the unbalanced decrease when redeem amount are burned.
     type BurnDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
 
-    /// Something that provides randomness in the runtime.
-    type Randomness: Randomness<Self::Hash>;
+    /// VRF randomness of the current epoch, for lottery and finance member draws.
+    type EpochRandomness: EpochRandomness<Self::Hash>;
 
     /// Finance treasury model id
     type FinTreasuryModuleId: Get<ModuleId>;
@@ -820,92 +1235,45 @@ pub trait Trait: system::Trait {
     /// The overarching event type.
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
-    /// 5 dimensions weight config
-    type TopWeightProductPublish: Get<u8>;
-    type TopWeightDocumentIdentify: Get<u8>;
-    type TopWeightDocumentTry: Get<u8>;
-    type TopWeightAccountAttend: Get<u8>;
-    type TopWeightAccountStake: Get<u8>;
-
-    /// Document Power attend weight
-    type DocumentPowerWeightAttend: Get<u8>;
-
-    /// Document Power content weight
-    type DocumentPowerWeightContent: Get<u8>;
-
-    /// Document Power judge weight
-    type DocumentPowerWeightJudge: Get<u8>;
-
-    /// Comment Power count weight
-    type CommentPowerWeightCount: Get<u8>;
-
-    /// Comment Power cost weight
-    type CommentPowerWeightCost: Get<u8>;
-
-    /// Comment Power cost per uint weight
-    type CommentPowerWeightPerCost: Get<u8>;
-
-    /// Comment Power positive weight
-    type CommentPowerWeightPositive: Get<u8>;
-
-    type CommentPowerWeight: Get<u8>;
-
-    /// Document Publish content weight
-    type DocumentPublishWeightParamsRate: Get<u8>;
-    type DocumentPublishWeightParamsSelfRate: Get<u8>;
-    type DocumentPublishWeightParamsAttendRate: Get<u8>;
-
-    /// Document Identify content weight
-    type DocumentIdentifyWeightParamsRate: Get<u8>;
-    type DocumentIdentifyWeightCheckRate: Get<u8>;
-    type DocumentIdentifyWeightConsistentRate: Get<u8>;
-
-    /// Document Try content weight
-    type DocumentTryWeightBiasRate: Get<u8>;
-    type DocumentTryWeightTrueRate: Get<u8>;
-    type DocumentTryWeightConsistentRate: Get<u8>;
-
-    /// Below for Choose & Model special documents
-    /// Document Choose content weight
-    type DocumentChooseWeightSellCount: Get<u8>;
-    type DocumentChooseWeightTryCount: Get<u8>;
-
-    /// Document Model content weight
-    type DocumentModelWeightProducerCount: Get<u8>;
-    type DocumentModelWeightProductCount: Get<u8>;
+    type ModelCreateDeposit: Get<BalanceOf<Self>>;
+    type ModelCycleIncomeRewardTotal: Get<BalanceOf<Self>>;
 
-    /// Document Choose & Model Power attend weight
-    type DocumentCMPowerWeightAttend: Get<u8>;
+    /// App financed purpose minimal exchange rate
+    type KptExchangeMinRate: Get<Permill>;
 
-    /// Document Choose & Model Power content weight
-    type DocumentCMPowerWeightContent: Get<u8>;
+    type AppLeaderBoardInterval: Get<Self::BlockNumber>;
 
-    /// Document Choose & Model Power judge weight
-    type DocumentCMPowerWeightJudge: Get<u8>;
+    type AppLeaderBoardMaxPos: Get<u32>;
 
-    /// Comment Power count weight
-    type CommentCMPowerWeightCount: Get<u8>;
+    /// The number of blocks a leader board record is kept for, older ones are pruned
+    /// into `AppLeaderBoardArchive`.
+    type AppLeaderBoardRetention: Get<Self::BlockNumber>;
 
-    /// Comment Power cost weight
-    type CommentCMPowerWeightCost: Get<u8>;
+    /// The maximum number of comments of a document taking part in the leader board lottery.
+    type MaxDocumentComments: Get<u32>;
 
-    /// Comment Power cost per uint weight
-    type CommentCMPowerWeightPerCost: Get<u8>;
+    /// The maximum number of commodity types.
+    type MaxCommodityTypes: Get<u32>;
 
-    /// Comment Power positive weight
-    type CommentCMPowerWeightPositive: Get<u8>;
+    /// The maximum number of replaced versions kept for a document.
+    type MaxDocumentRevisions: Get<u32>;
 
-    type CMPowerAccountAttend: Get<u8>;
+    /// The maximum number of open reports in the moderation queue of a model.
+    type MaxModerationQueue: Get<u32>;
 
-    type ModelCreateDeposit: Get<BalanceOf<Self>>;
-    type ModelCycleIncomeRewardTotal: Get<BalanceOf<Self>>;
+    /// Reserved from the reporter of a document or comment until the report is handled.
+    type ModerationReportDeposit: Get<BalanceOf<Self>>;
 
-    /// App financed purpose minimal exchange rate
-    type KptExchangeMinRate: Get<Permill>;
+    /// The maximum number of models in a single `set_model_income` call.
+    type MaxModelIncomeItems: Get<u32>;
 
-    type AppLeaderBoardInterval: Get<Self::BlockNumber>;
+    /// The maximum number of leader board lotteries, and of finance member draws, waiting
+    /// for their seed.
+    type MaxPendingDraws: Get<u32>;
 
-    type AppLeaderBoardMaxPos: Get<u32>;
+    /// The maximum number of leader board lotteries, and of finance member draws, run in
+    /// one block.
+    type MaxDrawsPerBlock: Get<u32>;
 
     type AppFinanceExchangePeriod: Get<Self::BlockNumber>;
 
@@ -932,6 +1300,20 @@ pub trait Trait: system::Trait {
 
     type CommentRewardNormalRate: Get<u32>;
     type CommentRewardExpertRate: Get<u32>;
+
+    /// Power to vote curve, maps `power / PowerVoteMaxPower` to the share of
+    /// `PowerVoteMaxBoost` added on top of an account's stake.
+    type PowerVoteCurve: Get<&'static PiecewiseLinear<'static>>;
+    /// Power at which the curve reaches its last point.
+    type PowerVoteMaxPower: Get<PowerSize>;
+    /// Extra stake multiples granted at the top of the curve.
+    type PowerVoteMaxBoost: Get<u32>;
+
+    /// The maximum number of models checked against their deposit in a single block.
+    type MaxPreBlackListPerBlock: Get<u32>;
+
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
 }
 
 // This pallet's storage items.
@@ -996,6 +1378,22 @@ decl_storage! {
         KPCommentDataByIdHash get(fn kp_comment_data_by_idhash):
             map hasher(twox_64_concat) T::Hash => KPCommentDataOf<T>;
 
+        // (AppId, DocumentId) -> replaced versions of the document, oldest first
+        KPDocumentRevisions get(fn kp_document_revisions):
+            map hasher(twox_64_concat) T::Hash => Vec<DocumentRevision<T::Hash, T::BlockNumber>>;
+
+        // (AppId, DocumentId) -> document lifecycle state
+        KPDocumentStatus get(fn kp_document_status):
+            map hasher(twox_64_concat) T::Hash => ContentStatus;
+
+        // (AppId, CommentId) -> comment lifecycle state
+        KPCommentStatus get(fn kp_comment_status):
+            map hasher(twox_64_concat) T::Hash => ContentStatus;
+
+        // (AppId, ModelId) -> open reports waiting for the model experts
+        ModerationQueue get(fn moderation_queue):
+            map hasher(twox_64_concat) T::Hash => Vec<ModerationReportOf<T>>;
+
         // global total knowledge power (only for commodity power)
         TotalPower get(fn total_power): PowerSize;
 
@@ -1144,17 +1542,57 @@ decl_storage! {
         LeaderBoardCommoditySet get(fn leader_board_commodity_set):
             double_map hasher(twox_64_concat) T::Hash, hasher(twox_64_concat) Vec<u8> => ();
 
-        // Leader board history records (AppId, ModelId, BlockNumber) => (Vec<CommodityLeaderBoardData>, Vec<T::AccountId>)
+        // Legacy leader board records (AppId, ModelId, BlockNumber) => LeaderBoardResult,
+        // only kept to move them into AppLeaderBoardHistory while StorageVersion is V1_0_0
         AppLeaderBoardRcord get(fn app_leader_board_record):
             map hasher(twox_64_concat) T::Hash => LeaderBoardResult<T::AccountId>;
 
-        // Store AppLeaderBoardRcord keys, for load
+        // Legacy AppLeaderBoardRcord keys, in creating order
         AppLeaderBoardSequenceKeys get(fn app_leader_board_sequence_keys): Vec<(u32, T::BlockNumber, Vec<u8>)>;
 
+        // Leader board history, group key: (AppId, ModelId) hash, sub key: epoch
+        AppLeaderBoardHistory get(fn app_leader_board_history):
+            double_map hasher(twox_64_concat) T::Hash, hasher(twox_64_concat) u32
+            => Option<LeaderBoardHistoryItem<T::AccountId, T::BlockNumber, T::Hash>>;
+
+        // Kept epochs of leader board history (AppId, ModelId) -> (first epoch, next epoch)
+        AppLeaderBoardEpochs get(fn app_leader_board_epochs):
+            map hasher(twox_64_concat) T::Hash => (u32, u32);
+
+        // Epoch of a kept leader board (AppId, ModelId, BlockNumber) -> epoch
+        AppLeaderBoardBlockEpoch get(fn app_leader_board_block_epoch):
+            map hasher(twox_64_concat) T::Hash => Option<u32>;
+
+        // Merkle tree of the pruned leader board history (AppId, ModelId) -> LeaderBoardArchive
+        AppLeaderBoardArchive get(fn app_leader_board_archive):
+            map hasher(twox_64_concat) T::Hash => LeaderBoardArchive<T::Hash>;
+
         // Leader board last record (AppId, ModelId) -> BlockNumber
         AppLeaderBoardLastTime get(fn app_leader_board_last_time):
             map hasher(twox_64_concat) T::Hash => T::BlockNumber;
 
+        // (AppId, ModelId) -> closed leader board waiting for its lottery seed
+        AppLeaderBoardPendingDraw get(fn app_leader_board_pending_draw):
+            map hasher(twox_64_concat) T::Hash
+            => Option<LeaderBoardPendingDraw<T::AccountId, T::BlockNumber>>;
+
+        // pending draws in closing order, so their seed epochs never decrease
+        AppLeaderBoardDrawQueue get(fn app_leader_board_draw_queue): Vec<T::Hash>;
+
+        // storage layout version, legacy leader board records are migrated while it is V1_0_0
+        StorageVersion: Releases;
+
+        // finance member draws, keyed by what the member is drawn for
+        FinanceMemberDraws get(fn finance_member_draws):
+            map hasher(twox_64_concat) T::Hash => Option<FinanceMemberDrawRecord<T::AccountId, T::Hash>>;
+
+        // key of `FinanceMemberDraws` -> finance member draw waiting for its seed
+        FinanceMemberPendingDraws get(fn finance_member_pending_draw):
+            map hasher(twox_64_concat) T::Hash => Option<FinanceMemberPendingDraw>;
+
+        // pending finance member draws in request order, so their seed epochs never decrease
+        FinanceMemberDrawQueue get(fn finance_member_draw_queue): Vec<T::Hash>;
+
         // Document comment order pool (AppId, DocumentId) -> Vec<CommentWeightData>
         DocumentCommentsAccountPool get(fn document_comments_account_pool):
             map hasher(twox_64_concat) T::Hash => Vec<CommentWeightData<T>>;
@@ -1173,8 +1611,14 @@ decl_storage! {
 
         TechFundWithdrawRecords get(fn tech_fund_withdraw_records): Vec<TechFundWithdrawData<T::AccountId, BalanceOf<T>, T::Hash>>;
 
-        // pre-black list of model dispute, this is a collection which reserved balance lower than required 50%
-        ModelPreBlackList get(fn model_black_list_pre): Vec<(u32, Vec<u8>, T::AccountId, T::BlockNumber)>;
+        // pre-black list of model dispute, models whose deposit dropped lower than required 50%,
+        // keyed by the block at which the deposit is checked again
+        ModelPreBlackList get(fn model_black_list_pre):
+            map hasher(twox_64_concat) T::BlockNumber => Vec<(u32, Vec<u8>)>;
+
+        // (app_id, model_id) -> block at which the model is due in ModelPreBlackList
+        ModelPreBlackListDue get(fn model_black_list_pre_due):
+            map hasher(twox_64_concat) T::Hash => Option<T::BlockNumber>;
 
         ModelSlashCycleRewardIndex get(fn model_slash_cycle_reward_index):
             map hasher(twox_64_concat) T::Hash => T::BlockNumber;
@@ -1186,6 +1630,9 @@ decl_storage! {
         // (app_id, comment_id)
         CommoditySlashRecords get(fn commodity_slash_record):
             map hasher(twox_64_concat) T::Hash => CommoditySlashRecord<T::BlockNumber>;
+
+        // Weights of the power formula, set by the tech members or democracy
+        KPPowerParams get(fn power_params) config(): PowerParams;
     }
 }
 
@@ -1195,33 +1642,68 @@ decl_event!(
     where
         AccountId = <T as system::Trait>::AccountId,
         BlockNumber = <T as system::Trait>::BlockNumber,
+        Hash = <T as system::Trait>::Hash,
+        Balance = BalanceOf<T>,
     {
-        /// Just a dummy event.
-        /// Event `Something` is declared with a parameter of the type `u32` and `AccountId`
-        /// To emit this event, we call the deposit function, from our runtime functions
-        // SomethingStored(u32, AccountId),
-        KnowledgeCreated(AccountId),
-        CommentCreated(AccountId),
-        ModelCreated(AccountId),
-        ModelOwnerTransfered(AccountId),
+        /// Document created: sender, app, document, type, model, product and cart, the cart
+        /// is empty for documents of no commodity
+        KnowledgeCreated(
+            AccountId,
+            u32,
+            Vec<u8>,
+            DocumentType,
+            Vec<u8>,
+            Vec<u8>,
+            Vec<u8>,
+        ),
+        /// Comment created: sender, app, comment and the document commented
+        CommentCreated(AccountId, u32, Vec<u8>, Vec<u8>),
+        ModelCreated(AccountId, u32, Vec<u8>),
+        /// Model of the app released by its owner to the app admin
+        ModelOwnerTransfered(AccountId, u32, Vec<u8>, AccountId),
         CommodityTypeCreated(u32),
-        AppModelTotal(u32),
-        ModelCycleIncome(AccountId),
-        PowerSlashed(AccountId),
+        /// Max models of the app
+        AppModelTotal(u32, u32),
+        /// Income of a model of the app in the cycle
+        ModelCycleIncome(AccountId, u32, Vec<u8>, BlockNumber, u64),
+        /// Power of the target owned by the account slashed, with the slashed power
+        PowerSlashed(AccountId, u32, PowerTarget, PowerSize),
+        /// Power of the target owned by the account changed, from the former to the latter
+        PowerChanged(AccountId, u32, PowerTarget, PowerSize, PowerSize),
         AppAdded(u32),
-        AppFinanced(u32),
+        AppFinanced(u32, Vec<u8>),
         LeaderBoardsCreated(BlockNumber, u32, Vec<u8>),
-        ModelDisputed(AccountId),
+        /// Lottery of the board closed at the block is drawn, with seed epoch and seed
+        LeaderBoardDrawn(BlockNumber, u32, Vec<u8>, u64, Hash),
+        /// Finance member drawn for the key of `FinanceMemberDraws`
+        FinanceMemberDrawn(AccountId, Hash),
+        /// Model of the app disputed by the comment, with the model owner
+        ModelDisputed(AccountId, u32, Vec<u8>, Vec<u8>),
         AppRedeemed(AccountId),
-        AppFinanceUserExchangeStart(AccountId, AccountId),
-        AppFinanceUserExchangeConfirmed(AccountId),
-        AppFinanceUserExchangeCompensated(AccountId),
-        AppCycleIncomeUserExchangeConfirmed(AccountId),
-        ModelIncomeRewarded(AccountId),
-        AppCycleIncomeRedeem(AccountId, AccountId),
-        AppIncomeUserExchangeCompensated(AccountId),
-        TechFundWithdrawed(AccountId),
-        ModelDepositAdded(AccountId),
+        /// Exchange of the financed app proposal requested by the account
+        AppFinanceUserExchangeStart(AccountId, u32, Vec<u8>),
+        AppFinanceUserExchangeConfirmed(AccountId, u32, Vec<u8>),
+        AppFinanceUserExchangeCompensated(AccountId, u32, Vec<u8>),
+        AppCycleIncomeUserExchangeConfirmed(AccountId, u32, BlockNumber),
+        /// Model of the app rewarded for its income in the cycle
+        ModelIncomeRewarded(AccountId, u32, Vec<u8>, BlockNumber, Balance),
+        /// Redeem of the app income in the cycle requested by the account
+        AppCycleIncomeRedeem(AccountId, u32, BlockNumber),
+        AppIncomeUserExchangeCompensated(AccountId, u32, BlockNumber),
+        /// Tech fund paid by democracy: receiver, reason and amount
+        TechFundWithdrawed(AccountId, Hash, Balance),
+        ModelDepositAdded(AccountId, u32, Vec<u8>, Balance),
+        ModelDisabled(AccountId, u32, Vec<u8>),
+        /// Document revised by its author, with the number of replaced versions
+        DocumentRevised(AccountId, u32, Vec<u8>, u32),
+        DocumentRetracted(AccountId, u32, Vec<u8>),
+        ContentReported(AccountId, u32, ModerationTarget),
+        /// Report handled by a model expert, true if the content was hidden
+        ContentModerated(AccountId, u32, ModerationTarget, bool),
+        /// Report dropped with its deposit returned, its document was retracted or hidden
+        ReportDropped(AccountId, u32, ModerationTarget),
+        /// Weights of the power formula replaced, powers computed from now on use them
+        PowerParamsUpdated(PowerParams),
     }
 );
 
@@ -1265,6 +1747,9 @@ decl_error! {
         DocumentIdentifyAlreadyExisted,
         DocumentTryAlreadyExisted,
         LeaderBoardCreateNotPermit,
+        LeaderBoardDrawPending,
+        DrawQueueFull,
+        FinanceMemberNotDrawn,
         AppRedeemTransactionIdRepeat,
         SignVerifyErrorUser,
         SignVerifyErrorAuth,
@@ -1290,6 +1775,22 @@ decl_error! {
         TechFundAmountComputeError,
         CartIdInBalckList,
         NotFoundValidFinanceMember,
+        ModelIncomeParamsInvalid,
+        CommodityTypeReachMax,
+        DocumentCommentsReachMax,
+        DocumentNotActive,
+        CommentNotActive,
+        NotDocumentOwner,
+        DocumentRevisionsReachMax,
+        DocumentRetractNotAllowed,
+        ModerationQueueFull,
+        ModerationAlreadyReported,
+        ModerationReportNotFound,
+        NotModelExpert,
+        /// A group of power weights does not sum to 100.
+        PowerParamsInvalid,
+        /// No block within `MAX_SCHEDULE_PROBE` of the due one has room in the pre black list.
+        PreBlackListFull,
     }
 }
 
@@ -1306,54 +1807,26 @@ decl_module! {
         // this is needed only if you are using events in your pallet
         fn deposit_event() = default;
 
-        const TopWeightProductPublish: u8 = T::TopWeightProductPublish::get();
-        const TopWeightDocumentIdentify: u8 = T::TopWeightDocumentIdentify::get();
-        const TopWeightDocumentTry: u8 = T::TopWeightDocumentTry::get();
-        const TopWeightAccountAttend: u8 = T::TopWeightAccountAttend::get();
-        const TopWeightAccountStake: u8 = T::TopWeightAccountStake::get();
-
-        const DocumentPowerWeightAttend: u8 = T::DocumentPowerWeightAttend::get();
-        const DocumentPowerWeightContent: u8 = T::DocumentPowerWeightContent::get();
-        const DocumentPowerWeightJudge: u8 = T::DocumentPowerWeightJudge::get();
-
-        const CommentPowerWeightCount: u8 = T::CommentPowerWeightCount::get();
-        const CommentPowerWeightCost: u8 = T::CommentPowerWeightCost::get();
-        const CommentPowerWeightPerCost: u8 = T::CommentPowerWeightPerCost::get();
-        const CommentPowerWeightPositive: u8 = T::CommentPowerWeightPositive::get();
-        const CommentPowerWeight: u8 = T::CommentPowerWeight::get();
-
-        const DocumentPublishWeightParamsRate: u8 = T::DocumentPublishWeightParamsRate::get();
-        const DocumentPublishWeightParamsSelfRate: u8 = T::DocumentPublishWeightParamsSelfRate::get();
-
-        const DocumentIdentifyWeightParamsRate: u8 = T::DocumentIdentifyWeightParamsRate::get();
-        const DocumentIdentifyWeightCheckRate: u8 = T::DocumentIdentifyWeightCheckRate::get();
-
-        const DocumentTryWeightBiasRate: u8 = T::DocumentTryWeightBiasRate::get();
-        const DocumentTryWeightTrueRate: u8 = T::DocumentTryWeightTrueRate::get();
-
-        // CM parameters
-        const DocumentChooseWeightSellCount: u8 = T::DocumentChooseWeightSellCount::get();
-        const DocumentChooseWeightTryCount: u8 = T::DocumentChooseWeightTryCount::get();
-        const DocumentModelWeightProducerCount: u8 = T::DocumentModelWeightProducerCount::get();
-        const DocumentModelWeightProductCount: u8 = T::DocumentModelWeightProductCount::get();
-        const DocumentCMPowerWeightAttend: u8 = T::DocumentCMPowerWeightAttend::get();
-        const DocumentCMPowerWeightContent: u8 = T::DocumentCMPowerWeightContent::get();
-        const DocumentCMPowerWeightJudge: u8 = T::DocumentCMPowerWeightJudge::get();
-        const CommentCMPowerWeightCount: u8 = T::CommentCMPowerWeightCount::get();
-        const CommentCMPowerWeightCost: u8 = T::CommentCMPowerWeightCost::get();
-        const CommentCMPowerWeightPerCost: u8 = T::CommentCMPowerWeightPerCost::get();
-        const CommentCMPowerWeightPositive: u8 = T::CommentCMPowerWeightPositive::get();
-        const CMPowerAccountAttend: u8 = T::CMPowerAccountAttend::get();
-
         const ModelCreateDeposit: BalanceOf<T> = T::ModelCreateDeposit::get();
         const KptExchangeMinRate: Permill = T::KptExchangeMinRate::get();
         const AppLeaderBoardInterval: T::BlockNumber = T::AppLeaderBoardInterval::get();
+        const AppLeaderBoardMaxPos: u32 = T::AppLeaderBoardMaxPos::get();
+        const AppLeaderBoardRetention: T::BlockNumber = T::AppLeaderBoardRetention::get();
+        const MaxDocumentComments: u32 = T::MaxDocumentComments::get();
+        const MaxCommodityTypes: u32 = T::MaxCommodityTypes::get();
+        const MaxDocumentRevisions: u32 = T::MaxDocumentRevisions::get();
+        const MaxModerationQueue: u32 = T::MaxModerationQueue::get();
+        const ModerationReportDeposit: BalanceOf<T> = T::ModerationReportDeposit::get();
+        const MaxModelIncomeItems: u32 = T::MaxModelIncomeItems::get();
         const ModelIncomeCyclePeriod: T::BlockNumber = T::ModelIncomeCyclePeriod::get();
         const ModelIncomeCollectingPeriod: T::BlockNumber = T::ModelIncomeCollectingPeriod::get();
         const ModelIncomeRewardingPeriod: T::BlockNumber = T::ModelIncomeRewardingPeriod::get();
         const AppFinanceExchangePeriod: T::BlockNumber = T::AppFinanceExchangePeriod::get();
+        const PowerVoteMaxPower: PowerSize = T::PowerVoteMaxPower::get();
+        const PowerVoteMaxBoost: u32 = T::PowerVoteMaxBoost::get();
+        const MaxPreBlackListPerBlock: u32 = T::MaxPreBlackListPerBlock::get();
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::create_model()]
         pub fn create_model(origin,
             client_params: ClientParamsCreateModel<T::Hash>,
             auth_params: AuthParamsCreateModel,
@@ -1362,13 +1835,15 @@ decl_module! {
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature
-
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber
             )-> dispatch::DispatchResult {
             let who = ensure_signed(origin)?;
 
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &client_params.encode()), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &auth_params.encode()), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_model", &app_user_account, app_user_sign, &client_params.encode(), expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_model", &auth_server, auth_sign, &auth_params.encode(), expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.create_model", &app_user_account, &client_params.encode(), expiry);
+            T::PayloadVerify::use_payload(b"kp.create_model", &auth_server, &auth_params.encode(), expiry);
 
             let ClientParamsCreateModel {
                 app_id,
@@ -1432,22 +1907,25 @@ decl_module! {
             <KPModelDataByIdHash<T>>::insert(&key, &model);
             <AppModelCount>::insert(app_id, count + 1);
 
-            Self::deposit_event(RawEvent::ModelCreated(who));
+            Self::deposit_event(RawEvent::ModelCreated(who, app_id, model.model_id));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::model_owner_release()]
         pub fn model_owner_release(origin, params: ModelKeyParams,
             app_user_account: AuthAccountId,
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
             let _who = ensure_signed(origin)?;
 
             let encode = params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &encode), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &encode), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.model_owner_release", &app_user_account, app_user_sign, &encode, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.model_owner_release", &auth_server, auth_sign, &encode, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.model_owner_release", &app_user_account, &encode, expiry);
+            T::PayloadVerify::use_payload(b"kp.model_owner_release", &auth_server, &encode, expiry);
 
             let ModelKeyParams {
                 app_id,
@@ -1479,11 +1957,11 @@ decl_module! {
                 model.owner = auth_server;
             });
 
-            Self::deposit_event(RawEvent::ModelOwnerTransfered(owner));
+            Self::deposit_event(RawEvent::ModelOwnerTransfered(owner, app_id, model_id, admin));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::add_model_deposit()]
         pub fn add_model_deposit(origin, app_id: u32, model_id: Vec<u8>, amount: BalanceOf<T>) -> dispatch::DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -1498,11 +1976,11 @@ decl_module! {
                 *value += amount;
             });
 
-            Self::deposit_event(RawEvent::ModelDepositAdded(who));
+            Self::deposit_event(RawEvent::ModelDepositAdded(who, app_id, model_id, amount));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::create_product_publish_document()]
         pub fn create_product_publish_document(origin,
             client_params: ClientParamsCreatePublishDoc<T::Hash>,
 
@@ -1510,14 +1988,17 @@ decl_module! {
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
 
             // Check it was signed and get the signer. See also: ensure_root and ensure_none
             let who = ensure_signed(origin)?;
 
             let encode = client_params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &encode), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &encode), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_product_publish_document", &app_user_account, app_user_sign, &encode, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_product_publish_document", &auth_server, auth_sign, &encode, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.create_product_publish_document", &app_user_account, &encode, expiry);
+            T::PayloadVerify::use_payload(b"kp.create_product_publish_document", &auth_server, &encode, expiry);
 
             let ClientParamsCreatePublishDoc {
                 app_id,
@@ -1569,11 +2050,11 @@ decl_module! {
             // create product id -> document id record
             <KPDocumentProductIndexByIdHash<T>>::insert(&product_key_hash, &document_id);
 
-            Self::deposit_event(RawEvent::KnowledgeCreated(who));
+            Self::deposit_knowledge_created(who, &doc);
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::create_product_identify_document(T::AppLeaderBoardMaxPos::get())]
         pub fn create_product_identify_document(origin,
             client_params: ClientParamsCreateIdentifyDoc<T::Hash>,
 
@@ -1581,13 +2062,16 @@ decl_module! {
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
 
             let who = ensure_signed(origin)?;
 
             let encode = client_params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &encode), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &encode), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_product_identify_document", &app_user_account, app_user_sign, &encode, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_product_identify_document", &auth_server, auth_sign, &encode, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.create_product_identify_document", &app_user_account, &encode, expiry);
+            T::PayloadVerify::use_payload(b"kp.create_product_identify_document", &auth_server, &encode, expiry);
 
             let ClientParamsCreateIdentifyDoc {
                 app_id,
@@ -1649,11 +2133,11 @@ decl_module! {
                 &Self::convert_account(&doc.owner),
             );
 
-            Self::deposit_event(RawEvent::KnowledgeCreated(who));
+            Self::deposit_knowledge_created(who, &doc);
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::create_product_try_document(T::AppLeaderBoardMaxPos::get())]
         pub fn create_product_try_document(origin,
             client_params: ClientParamsCreateTryDoc<T::Hash>,
 
@@ -1661,13 +2145,16 @@ decl_module! {
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
 
             let who = ensure_signed(origin)?;
 
             let encode = client_params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &encode), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &encode), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_product_try_document", &app_user_account, app_user_sign, &encode, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_product_try_document", &auth_server, auth_sign, &encode, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.create_product_try_document", &app_user_account, &encode, expiry);
+            T::PayloadVerify::use_payload(b"kp.create_product_try_document", &auth_server, &encode, expiry);
 
             let ClientParamsCreateTryDoc {
                 app_id,
@@ -1730,11 +2217,11 @@ decl_module! {
                 &Self::convert_account(&doc.owner),
             );
 
-            Self::deposit_event(RawEvent::KnowledgeCreated(who));
+            Self::deposit_knowledge_created(who, &doc);
             Ok(())
         }
 
-         #[weight = 0]
+        #[weight = T::WeightInfo::create_product_choose_document()]
         pub fn create_product_choose_document(origin,
             client_params: ClientParamsCreateChooseDoc<T::Hash>,
 
@@ -1742,13 +2229,16 @@ decl_module! {
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
 
             let who = ensure_signed(origin)?;
 
             let encode = client_params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &encode), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &encode), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_product_choose_document", &app_user_account, app_user_sign, &encode, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_product_choose_document", &auth_server, auth_sign, &encode, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.create_product_choose_document", &app_user_account, &encode, expiry);
+            T::PayloadVerify::use_payload(b"kp.create_product_choose_document", &auth_server, &encode, expiry);
 
             let ClientParamsCreateChooseDoc {
                 app_id,
@@ -1790,11 +2280,11 @@ decl_module! {
             // create document record
             <KPDocumentDataByIdHash<T>>::insert(&doc_key_hash, &doc);
 
-            Self::deposit_event(RawEvent::KnowledgeCreated(who));
+            Self::deposit_knowledge_created(who, &doc);
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::create_model_create_document()]
         pub fn create_model_create_document(origin,
             client_params: ClientParamsCreateModelDoc<T::Hash>,
 
@@ -1802,13 +2292,16 @@ decl_module! {
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
 
             let who = ensure_signed(origin)?;
 
             let encode = client_params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &encode), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &encode), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_model_create_document", &app_user_account, app_user_sign, &encode, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_model_create_document", &auth_server, auth_sign, &encode, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.create_model_create_document", &app_user_account, &encode, expiry);
+            T::PayloadVerify::use_payload(b"kp.create_model_create_document", &auth_server, &encode, expiry);
 
             let ClientParamsCreateModelDoc {
                 app_id,
@@ -1852,11 +2345,11 @@ decl_module! {
             // create document record
             <KPDocumentDataByIdHash<T>>::insert(&doc_key_hash, &doc);
 
-            Self::deposit_event(RawEvent::KnowledgeCreated(who));
+            Self::deposit_knowledge_created(who, &doc);
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::create_comment(T::AppLeaderBoardMaxPos::get())]
         pub fn create_comment(origin,
             comment_data: CommentData<T::Hash>,
 
@@ -1864,13 +2357,16 @@ decl_module! {
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
 
             let who = ensure_signed(origin)?;
 
             let buf = comment_data.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &buf), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &buf), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_comment", &app_user_account, app_user_sign, &buf, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.create_comment", &auth_server, auth_sign, &buf, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.create_comment", &app_user_account, &buf, expiry);
+            T::PayloadVerify::use_payload(b"kp.create_comment", &auth_server, &buf, expiry);
 
             let CommentData {
               app_id,
@@ -1893,6 +2389,9 @@ decl_module! {
             ensure!(!<KPCommentDataByIdHash<T>>::contains_key(&key), Error::<T>::CommentAlreadyExisted);
 
             let doc_key_hash = T::Hashing::hash_of(&(app_id, &document_id));
+            ensure!(<KPDocumentStatus<T>>::get(&doc_key_hash) == ContentStatus::Active, Error::<T>::DocumentNotActive);
+            let comments = <DocumentCommentsAccountPool<T>>::decode_len(&doc_key_hash).unwrap_or(0);
+            ensure!((comments as u32) < T::MaxDocumentComments::get(), Error::<T>::DocumentCommentsReachMax);
 
             let comment = KPCommentData {
                 sender: who.clone(),
@@ -1914,16 +2413,137 @@ decl_module! {
             // create comment record
             <KPCommentDataByIdHash<T>>::insert(&key, &comment);
 
-            Self::deposit_event(RawEvent::CommentCreated(who));
+            Self::deposit_event(RawEvent::CommentCreated(who, app_id, comment_id, document_id));
+            Ok(())
+        }
+
+        /// Replace the content of a document by its author, the old version is kept.
+        #[weight = T::WeightInfo::revise_document()]
+        pub fn revise_document(origin, app_id: u32, document_id: Vec<u8>, content_hash: T::Hash) -> dispatch::DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let doc_key = T::Hashing::hash_of(&(app_id, &document_id));
+            ensure!(<KPDocumentDataByIdHash<T>>::contains_key(&doc_key), Error::<T>::DocumentNotFound);
+            ensure!(<KPDocumentStatus<T>>::get(&doc_key) == ContentStatus::Active, Error::<T>::DocumentNotActive);
+
+            let mut doc = <KPDocumentDataByIdHash<T>>::get(&doc_key);
+            ensure!(Self::convert_account(&doc.owner) == who, Error::<T>::NotDocumentOwner);
+
+            let mut revisions = <KPDocumentRevisions<T>>::get(&doc_key);
+            ensure!((revisions.len() as u32) < T::MaxDocumentRevisions::get(), Error::<T>::DocumentRevisionsReachMax);
+
+            revisions.push(DocumentRevision {
+                content_hash: doc.content_hash,
+                block: <system::Module<T>>::block_number(),
+            });
+            doc.content_hash = content_hash;
+
+            let count = revisions.len() as u32;
+            <KPDocumentRevisions<T>>::insert(&doc_key, revisions);
+            <KPDocumentDataByIdHash<T>>::insert(&doc_key, &doc);
+
+            Self::deposit_event(RawEvent::DocumentRevised(who, app_id, document_id, count));
             Ok(())
         }
 
-        #[weight = 0]
+        /// Withdraw a document by its author, rolling back its power and dropping the open
+        /// reports on it and its comments. Publish documents carry the products others
+        /// build on and can not be retracted.
+        #[weight = T::WeightInfo::retract_document(T::MaxModerationQueue::get(), T::AppLeaderBoardMaxPos::get())]
+        pub fn retract_document(origin, app_id: u32, document_id: Vec<u8>) -> dispatch::DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let doc_key = T::Hashing::hash_of(&(app_id, &document_id));
+            ensure!(<KPDocumentDataByIdHash<T>>::contains_key(&doc_key), Error::<T>::DocumentNotFound);
+            ensure!(<KPDocumentStatus<T>>::get(&doc_key) == ContentStatus::Active, Error::<T>::DocumentNotActive);
+
+            let doc = <KPDocumentDataByIdHash<T>>::get(&doc_key);
+            ensure!(Self::convert_account(&doc.owner) == who, Error::<T>::NotDocumentOwner);
+            ensure!(doc.document_type != DocumentType::ProductPublish, Error::<T>::DocumentRetractNotAllowed);
+
+            Self::rollback_document_power(&doc);
+            <KPDocumentStatus<T>>::insert(&doc_key, ContentStatus::Retracted);
+            Self::drop_document_reports(&doc);
+
+            Self::deposit_event(RawEvent::DocumentRetracted(who, app_id, document_id));
+            Ok(())
+        }
+
+        /// Report a document or comment to the experts of its model, reserving the report
+        /// deposit.
+        #[weight = T::WeightInfo::report_content(T::MaxModerationQueue::get())]
+        pub fn report_content(origin, app_id: u32, target: ModerationTarget, reason_hash: T::Hash) -> dispatch::DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let doc = Self::moderation_document(app_id, &target)?;
+            let queue_key = T::Hashing::hash_of(&(app_id, &doc.model_id));
+            let mut queue = <ModerationQueue<T>>::get(&queue_key);
+            ensure!((queue.len() as u32) < T::MaxModerationQueue::get(), Error::<T>::ModerationQueueFull);
+            ensure!(!queue.iter().any(|report| report.target == target), Error::<T>::ModerationAlreadyReported);
+
+            let deposit = T::ModerationReportDeposit::get();
+            T::Currency::reserve(&who, deposit)?;
+
+            queue.push(ModerationReport {
+                target: target.clone(),
+                reporter: who.clone(),
+                reason_hash,
+                block: <system::Module<T>>::block_number(),
+                deposit,
+            });
+            <ModerationQueue<T>>::insert(&queue_key, queue);
+
+            Self::deposit_event(RawEvent::ContentReported(who, app_id, target));
+            Ok(())
+        }
+
+        /// Handle a report by an expert of the model, hiding the content slashes its
+        /// author's power and returns the report deposit, keeping it slashes the deposit.
+        #[weight = T::WeightInfo::moderate_content(T::MaxModerationQueue::get(), T::AppLeaderBoardMaxPos::get())]
+        pub fn moderate_content(origin, app_id: u32, target: ModerationTarget, hide: bool) -> dispatch::DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let doc = Self::moderation_document(app_id, &target)?;
+            ensure!(T::Membership::is_expert(&who, app_id, &doc.model_id), Error::<T>::NotModelExpert);
+
+            let queue_key = T::Hashing::hash_of(&(app_id, &doc.model_id));
+            let mut queue = <ModerationQueue<T>>::get(&queue_key);
+            let index = queue.iter().position(|report| report.target == target)
+                .ok_or(Error::<T>::ModerationReportNotFound)?;
+            let report = queue.remove(index);
+            <ModerationQueue<T>>::insert(&queue_key, queue);
+
+            if hide {
+                T::Currency::unreserve(&report.reporter, report.deposit);
+                match &target {
+                    ModerationTarget::Document(document_id) => {
+                        Self::slash_document_power(&doc);
+                        let doc_key = T::Hashing::hash_of(&(app_id, document_id));
+                        <KPDocumentStatus<T>>::insert(&doc_key, ContentStatus::Hidden);
+                        Self::drop_document_reports(&doc);
+                    }
+                    ModerationTarget::Comment(comment_id) => {
+                        let comment_key = T::Hashing::hash_of(&(app_id, comment_id));
+                        let comment = <KPCommentDataByIdHash<T>>::get(&comment_key);
+                        Self::slash_comment_power(&comment);
+                        <KPCommentStatus<T>>::insert(&comment_key, ContentStatus::Hidden);
+                    }
+                }
+            } else {
+                T::Slash::on_unbalanced(T::Currency::slash_reserved(&report.reporter, report.deposit).0);
+            }
+
+            Self::deposit_event(RawEvent::ContentModerated(who, app_id, target, hide));
+            Ok(())
+        }
+
+        #[weight = T::WeightInfo::create_commodity_type(T::MaxCommodityTypes::get())]
         pub fn create_commodity_type(origin, type_id: u32, type_desc: Vec<u8>) -> dispatch::DispatchResult {
             ensure_root(origin)?;
             ensure!(!<CommodityTypeMap>::contains_key(type_id), Error::<T>::CommodityTypeExisted);
 
             let mut types = CommodityTypeSets::get();
+            ensure!((types.len() as u32) < T::MaxCommodityTypes::get(), Error::<T>::CommodityTypeReachMax);
 
             let type_data = CommodityTypeData {
                 type_id,
@@ -1945,28 +2565,31 @@ decl_module! {
             }
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::set_app_model_total()]
         pub fn set_app_model_total(origin, app_id: u32, total: u32) -> dispatch::DispatchResult {
             ensure_root(origin)?;
 
             <AppModelTotalConfig>::insert(app_id, total);
 
-            Self::deposit_event(RawEvent::AppModelTotal(total));
+            Self::deposit_event(RawEvent::AppModelTotal(app_id, total));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::set_model_income(params.incomes.len() as u32)]
         pub fn set_model_income(origin, params: ModelIncomeCollectingParam,
             user_key: AuthAccountId,
             user_sign: sr25519::Signature,
             auth_key: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
             let who = ensure_signed(origin)?;
             ensure!(T::Membership::is_finance_member(&Self::convert_account(&auth_key)), Error::<T>::AuthIdentityNotFinanceMember);
 
             let buf = params.encode();
-            ensure!(Self::verify_sign(&user_key, user_sign, &buf), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_key, auth_sign, &buf), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.set_model_income", &user_key, user_sign, &buf, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.set_model_income", &auth_key, auth_sign, &buf, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.set_model_income", &user_key, &buf, expiry);
+            T::PayloadVerify::use_payload(b"kp.set_model_income", &auth_key, &buf, expiry);
 
             let ModelIncomeCollectingParam {
                 app_id,
@@ -1975,7 +2598,8 @@ decl_module! {
             } = params;
 
             ensure!(T::Membership::is_app_admin(&Self::convert_account(&user_key), app_id), Error::<T>::NotAppAdmin);
-            ensure!(incomes.len() <= 100, Error::<T>::ModelIncomeParamsTooLarge);
+            ensure!(incomes.len() as u32 <= T::MaxModelIncomeItems::get(), Error::<T>::ModelIncomeParamsTooLarge);
+            ensure!(incomes.len() == model_ids.len(), Error::<T>::ModelIncomeParamsInvalid);
 
             let block = <system::Module<T>>::block_number();
             ensure!(Self::model_income_stage(block).0 == ModelIncomeStage::COLLECTING, Error::<T>::ModelIncomeNotInCollectingStage);
@@ -2018,13 +2642,14 @@ decl_module! {
                     record.cycle = cycle_index;
                     record.app_id = app_id;
                 });
+
+                Self::deposit_event(RawEvent::ModelCycleIncome(who.clone(), app_id, model_id.clone(), cycle_index, income));
             }
 
-            Self::deposit_event(RawEvent::ModelCycleIncome(who));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::request_model_reward()]
         pub fn request_model_reward(origin, app_id: u32, model_id: Vec<u8>) -> dispatch::DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -2072,32 +2697,33 @@ decl_module! {
             // update records
             <ModelCycleIncomeRewardRecords<T>>::insert(cycle_index, &sub_key, reward);
 
-            <ModelCycleIncomeRewardStore<T>>::mutate(cycle_index, |store| {
-                store.push(ModelCycleIncomeReward {
-                    account: who.clone(),
-                    app_id,
-                    model_id: model_id.clone(),
-                    reward
-                })
+            <ModelCycleIncomeRewardStore<T>>::append(cycle_index, ModelCycleIncomeReward {
+                account: who.clone(),
+                app_id,
+                model_id: model_id.clone(),
+                reward
             });
 
-            Self::deposit_event(RawEvent::ModelIncomeRewarded(who));
+            Self::deposit_event(RawEvent::ModelIncomeRewarded(who, app_id, model_id, cycle_index, reward));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::app_income_redeem_request()]
         pub fn app_income_redeem_request(origin, params: AppIncomeRedeemParams<T::AccountId, BalanceOf<T>, T::BlockNumber>,
             app_user_account: AuthAccountId,
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
 
             let who = ensure_signed(origin)?;
 
             let buf = params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &buf), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &buf), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.app_income_redeem_request", &app_user_account, app_user_sign, &buf, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.app_income_redeem_request", &auth_server, auth_sign, &buf, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.app_income_redeem_request", &app_user_account, &buf, expiry);
+            T::PayloadVerify::use_payload(b"kp.app_income_redeem_request", &auth_server, &buf, expiry);
 
             let AppIncomeRedeemParams {
                 account,
@@ -2119,14 +2745,10 @@ decl_module! {
                 Error::<T>::AppFinancedUserExchangeAlreadyPerformed);
 
             let fkey = T::Hashing::hash_of(&(app_id, cycle));
-            let finance_member: T::AccountId;
             // check if we have specified a finance member to do confirm
             if !<AppCycleIncomeFinanceMember<T>>::contains_key(&fkey) {
-                // random choose one
-                finance_member = Self::choose_finance_member()?;
-                <AppCycleIncomeFinanceMember<T>>::insert(&fkey, finance_member.clone());
-            } else {
-                finance_member = <AppCycleIncomeFinanceMember<T>>::get(&fkey);
+                // random choose one with the randomness of a later epoch
+                Self::schedule_finance_member_draw(&fkey, FinanceMemberDrawKind::AppCycleIncome)?;
             }
 
             // read app cycle income record
@@ -2172,15 +2794,13 @@ decl_module! {
                 ..Default::default()
             });
 
-            let mut accounts = <AppCycleIncomeExchangeSet<T>>::get(&fkey);
-            accounts.push(account.clone());
-            <AppCycleIncomeExchangeSet<T>>::insert(&fkey, accounts);
+            <AppCycleIncomeExchangeSet<T>>::append(&fkey, &account);
 
-            Self::deposit_event(RawEvent::AppCycleIncomeRedeem(who, finance_member));
+            Self::deposit_event(RawEvent::AppCycleIncomeRedeem(who, app_id, cycle));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::app_income_redeem_confirm()]
         pub fn app_income_redeem_confirm(origin, params: AppIncomeRedeemConfirmParams<T::AccountId, T::BlockNumber>) -> dispatch::DispatchResult {
 
             let who = ensure_signed(origin)?;
@@ -2196,6 +2816,7 @@ decl_module! {
 
             // get this cycle's finance member
             let member_key = T::Hashing::hash_of(&(app_id, cycle));
+            ensure!(<AppCycleIncomeFinanceMember<T>>::contains_key(&member_key), Error::<T>::FinanceMemberNotDrawn);
             let finance_member = <AppCycleIncomeFinanceMember<T>>::get(&member_key);
             ensure!(finance_member == who, Error::<T>::AuthIdentityNotExpectedFinanceMember);
 
@@ -2248,11 +2869,11 @@ decl_module! {
 
             <AppCycleIncomeBurnTotal<T>>::put(<AppCycleIncomeBurnTotal<T>>::get() + record.exchange_amount);
 
-            Self::deposit_event(RawEvent::AppCycleIncomeUserExchangeConfirmed(account));
+            Self::deposit_event(RawEvent::AppCycleIncomeUserExchangeConfirmed(account, app_id, cycle));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::app_income_redeem_compensate()]
         pub fn app_income_redeem_compensate(origin, app_id: u32, cycle: T::BlockNumber) -> dispatch::DispatchResult {
             let who = ensure_signed(origin)?;
             let fkey = T::Hashing::hash_of(&(app_id, cycle));
@@ -2286,11 +2907,11 @@ decl_module! {
                 record.status = status;
             });
 
-            Self::deposit_event(RawEvent::AppIncomeUserExchangeCompensated(who));
+            Self::deposit_event(RawEvent::AppIncomeUserExchangeCompensated(who, app_id, cycle));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::democracy_slash_commodity_power(T::AppLeaderBoardMaxPos::get())]
         pub fn democracy_slash_commodity_power(origin,
             app_id: u32,
             cart_id: Vec<u8>,
@@ -2324,16 +2945,16 @@ decl_module! {
             // perform slash
             let key_hash = T::Hashing::hash_of(&(app_id, &cart_id));
             let owner_account = Self::convert_account(&doc.owner);
-            Self::slash_power(&key_hash, &owner_account);
+            let power = Self::slash_power(&key_hash, &owner_account);
             Self::remove_leader_board_item(app_id, &model_id, &cart_id);
 
             Self::add_commodity_power_slash_record(app_id, &comment_id, &cart_id);
 
-            Self::deposit_event(RawEvent::PowerSlashed(owner_account));
+            Self::deposit_event(RawEvent::PowerSlashed(owner_account, app_id, PowerTarget::Commodity(cart_id), power));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::democracy_model_dispute()]
         pub fn democracy_model_dispute(origin,
             app_id: u32,
             model_id: Vec<u8>,
@@ -2354,26 +2975,31 @@ decl_module! {
             // according dispute type to decide slash
             let owner_account = Self::convert_account(&model.owner);
 
+            // a dispute may queue the model for a deposit check, make sure there is room
+            let due = <system::Module<T>>::block_number() + T::ModelDisputeDelayTime::get();
+            ensure!(Self::pre_black_list_slot(due).is_some(), Error::<T>::PreBlackListFull);
+
             Self::model_dispute(app_id, &model_id, dispute_type, &owner_account, &reporter_account);
 
             // update store
             Self::add_model_dispute_record(app_id, &model_id, &comment_id, dispute_type);
 
-            Self::deposit_event(RawEvent::ModelDisputed(owner_account));
+            Self::deposit_event(RawEvent::ModelDisputed(owner_account, app_id, model_id, comment_id));
             Ok(())
         }
 
         /// Register new app
-        #[weight = 0]
+        #[weight = T::WeightInfo::democracy_add_app()]
         pub fn democracy_add_app(origin, params: AddAppParams<T::AccountId>,
             app_user_account: AuthAccountId,
-            app_user_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            app_user_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
             print("democracy_add_app enter");
             ensure_root(origin)?;
 
             print("democracy_add_app pass root check");
             let buf = params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &buf), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::verify_payload(b"kp.democracy_add_app", &app_user_account, app_user_sign, &buf, expiry), Error::<T>::SignVerifyErrorUser);
 
             let AddAppParams {
               app_type,
@@ -2427,14 +3053,15 @@ decl_module! {
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::democracy_app_financed()]
         pub fn democracy_app_financed(origin,
             params: AppFinancedProposalParams<T::AccountId, BalanceOf<T>>,
             app_user_account: AuthAccountId,
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
             ensure_root(origin)?;
             print("pass root check");
 
@@ -2453,10 +3080,12 @@ decl_module! {
             print("pass tech member check");
 
             let buf = params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &buf), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.democracy_app_financed", &app_user_account, app_user_sign, &buf, expiry), Error::<T>::SignVerifyErrorUser);
             print("pass user sign check");
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &buf), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.democracy_app_financed", &auth_server, auth_sign, &buf, expiry), Error::<T>::SignVerifyErrorAuth);
             print("pass auth sign check");
+            T::PayloadVerify::use_payload(b"kp.democracy_app_financed", &app_user_account, &buf, expiry);
+            T::PayloadVerify::use_payload(b"kp.democracy_app_financed", &auth_server, &buf, expiry);
 
             let AppFinancedProposalParams {
                 account,
@@ -2496,7 +3125,7 @@ decl_module! {
 
             <AppFinancedRecord<T>>::insert(&key, AppFinancedData::<BalanceOf<T>, T::BlockNumber> {
                 app_id,
-                proposal_id,
+                proposal_id: proposal_id.clone(),
                 amount,
                 exchange,
                 block: current_block,
@@ -2511,24 +3140,27 @@ decl_module! {
             <AppFinancedCount>::put(<AppFinancedCount>::get() + 1);
 
             print("done");
-            Self::deposit_event(RawEvent::AppFinanced(app_id));
+            Self::deposit_event(RawEvent::AppFinanced(app_id, proposal_id));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::app_financed_user_exchange_request()]
         pub fn app_financed_user_exchange_request(origin,
             params: AppFinancedUserExchangeParams<T::AccountId, BalanceOf<T>>,
             app_user_account: AuthAccountId,
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> dispatch::DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> dispatch::DispatchResult {
 
             let _who = ensure_signed(origin)?;
 
             let buf = params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &buf), Error::<T>::SignVerifyErrorUser);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &buf), Error::<T>::SignVerifyErrorAuth);
+            ensure!(T::PayloadVerify::check_payload(b"kp.app_financed_user_exchange_request", &app_user_account, app_user_sign, &buf, expiry), Error::<T>::SignVerifyErrorUser);
+            ensure!(T::PayloadVerify::check_payload(b"kp.app_financed_user_exchange_request", &auth_server, auth_sign, &buf, expiry), Error::<T>::SignVerifyErrorAuth);
+            T::PayloadVerify::use_payload(b"kp.app_financed_user_exchange_request", &app_user_account, &buf, expiry);
+            T::PayloadVerify::use_payload(b"kp.app_financed_user_exchange_request", &auth_server, &buf, expiry);
 
             let AppFinancedUserExchangeParams {
                 account,
@@ -2559,15 +3191,10 @@ decl_module! {
             ensure!(financed_record.exchanged + exchange_amount <= financed_record.exchange,
                 Error::<T>::AppFinancedUserExchangeOverflow);
 
-            // get finance member AppFinanceFinanceMember
-            let finance_member: T::AccountId;
             // check if we have specified a finance member to do confirm
             if !<AppFinanceFinanceMember<T>>::contains_key(&fkey) {
-                // random choose one
-                finance_member = Self::choose_finance_member()?;
-                <AppFinanceFinanceMember<T>>::insert(&fkey, finance_member.clone());
-            } else {
-                finance_member = <AppFinanceFinanceMember<T>>::get(&fkey);
+                // random choose one with the randomness of a later epoch
+                Self::schedule_finance_member_draw(&fkey, FinanceMemberDrawKind::AppFinance)?;
             }
 
             // reserve finance fee
@@ -2587,15 +3214,13 @@ decl_module! {
                 ..Default::default()
             });
 
-            let mut accounts = <AppFinancedUserExchangeSet<T>>::get(&fkey);
-            accounts.push(account.clone());
-            <AppFinancedUserExchangeSet<T>>::insert(&fkey, accounts);
+            <AppFinancedUserExchangeSet<T>>::append(&fkey, &account);
 
-            Self::deposit_event(RawEvent::AppFinanceUserExchangeStart(account, finance_member));
+            Self::deposit_event(RawEvent::AppFinanceUserExchangeStart(account, app_id, proposal_id));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::app_financed_user_exchange_confirm()]
         pub fn app_financed_user_exchange_confirm(origin, params: AppFinancedUserExchangeConfirmParams<T::AccountId>) -> dispatch::DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -2608,6 +3233,7 @@ decl_module! {
 
             // get this cycle's finance member
             let member_key = T::Hashing::hash_of(&(app_id, &proposal_id));
+            ensure!(<AppFinanceFinanceMember<T>>::contains_key(&member_key), Error::<T>::FinanceMemberNotDrawn);
             let finance_member = <AppFinanceFinanceMember<T>>::get(&member_key);
             ensure!(finance_member == who, Error::<T>::AuthIdentityNotExpectedFinanceMember);
 
@@ -2664,11 +3290,11 @@ decl_module! {
 
             <AppFinancedBurnTotal<T>>::put(<AppFinancedBurnTotal<T>>::get() + record.exchange_amount);
 
-            Self::deposit_event(RawEvent::AppFinanceUserExchangeConfirmed(account));
+            Self::deposit_event(RawEvent::AppFinanceUserExchangeConfirmed(account, app_id, proposal_id));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::app_finance_redeem_compensate()]
         pub fn app_finance_redeem_compensate(origin, app_id: u32, proposal_id: Vec<u8>) -> dispatch::DispatchResult {
             let who = ensure_signed(origin)?;
             let fkey = T::Hashing::hash_of(&(app_id, &proposal_id));
@@ -2705,11 +3331,11 @@ decl_module! {
                 record.status = status;
             });
 
-            Self::deposit_event(RawEvent::AppFinanceUserExchangeCompensated(who));
+            Self::deposit_event(RawEvent::AppFinanceUserExchangeCompensated(who, app_id, proposal_id));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::create_power_leader_board(T::AppLeaderBoardMaxPos::get(), T::MaxDocumentComments::get())]
         pub fn create_power_leader_board(origin, app_id: u32, model_id: Vec<u8>) -> dispatch::DispatchResult {
             ensure_root(origin)?;
 
@@ -2724,14 +3350,24 @@ decl_module! {
                 let diff = current_block - last_block;
                 ensure!(diff > T::AppLeaderBoardInterval::get(), Error::<T>::LeaderBoardCreateNotPermit);
             }
+            ensure!(!<AppLeaderBoardPendingDraw<T>>::contains_key(&last_key), Error::<T>::LeaderBoardDrawPending);
+            ensure!(
+                (<AppLeaderBoardDrawQueue<T>>::decode_len().unwrap_or(0) as u32) < T::MaxPendingDraws::get(),
+                Error::<T>::DrawQueueFull
+            );
 
-            Self::leader_board_lottery(current_block, app_id, &model_id);
+            // entries close now, the lottery is drawn in on_initialize once the seed is fixed
+            if let Some(draw) = Self::leader_board_close(current_block, app_id, &model_id) {
+                <AppLeaderBoardPendingDraw<T>>::insert(&last_key, draw);
+                <AppLeaderBoardDrawQueue<T>>::append(&last_key);
+                <AppLeaderBoardLastTime<T>>::insert(&last_key, current_block);
+            }
 
             Self::deposit_event(RawEvent::LeaderBoardsCreated(current_block, app_id, model_id));
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::democracy_tech_fund_withdraw()]
         pub fn democracy_tech_fund_withdraw(origin, receiver: T::AccountId, reason: T::Hash, dev_type: TechFundWithdrawType, dev_level: TechFundWithdrawLevel) -> dispatch::DispatchResult {
             print("democracy_tech_fund_withdraw enter");
             T::TechMemberOrigin::ensure_origin(origin)?;
@@ -2752,53 +3388,60 @@ decl_module! {
             print("pass transfer");
 
             // Records it
-            let mut records = <TechFundWithdrawRecords<T>>::get();
-            records.push(TechFundWithdrawData {
+            <TechFundWithdrawRecords<T>>::append(TechFundWithdrawData {
                 account: receiver.clone(),
                 amount,
                 dev_level,
                 dev_type,
                 reason,
             });
-            <TechFundWithdrawRecords<T>>::put(records);
 
-            Self::deposit_event(RawEvent::TechFundWithdrawed(receiver));
+            Self::deposit_event(RawEvent::TechFundWithdrawed(receiver, reason, amount));
             Ok(())
         }
 
+        #[weight = T::WeightInfo::set_power_params()]
+        pub fn set_power_params(origin, params: PowerParams) -> dispatch::DispatchResult {
+            T::TechMemberOrigin::ensure_origin(origin)?;
+            ensure!(params.is_valid(), Error::<T>::PowerParamsInvalid);
+
+            <KPPowerParams>::put(&params);
+            Self::deposit_event(RawEvent::PowerParamsUpdated(params));
+            Ok(())
+        }
+
+        fn integrity_test() {
+            assert!(T::MaxPreBlackListPerBlock::get() > 0, "MaxPreBlackListPerBlock must not be zero");
+        }
+
         // regular timer based task here
         fn on_initialize(n: T::BlockNumber) -> Weight {
-            /*
-            print("kp pallet block on_initialize");
-            let mut pre_black_list = <ModelPreBlackList<T>>::get();
-            let mut is_changed = false;
-
-            pre_black_list.retain(|x| {
-                //let (app_id, model_id, owner, end_block) = x;
-                if x.3 < n {
-                    // check if account reserve balance recover
-                    let balance = T::Currency::reserved_balance(&x.2);
-                    if balance <  T::ModelCreateDeposit::get() / 2u32.into() {
-                        print("kill model");
-                        let key = T::Hashing::hash_of(&(x.0, &x.1));
-                        <KPModelDataByIdHash<T>>::mutate(&key, |model| {
-                            model.status = ModelStatus::DISABLED;
-                        });
+            let due = <ModelPreBlackList<T>>::take(n);
+            let count = due.len() as u32;
+
+            for (app_id, model_id) in due {
+                let key = T::Hashing::hash_of(&(app_id, &model_id));
+                <ModelPreBlackListDue<T>>::remove(&key);
+
+                // the owner may have topped the deposit up again in the meantime
+                if Self::is_valid_model(app_id, &model_id) {
+                    let owner = Self::convert_account(&<KPModelDataByIdHash<T>>::get(&key).owner);
+                    if !Self::is_model_deposit_enough(&key, &owner) {
+                        Self::disable_model(app_id, &model_id);
                     }
-
-                    // remove item from list
-                    is_changed = true;
-                    return false;
                 }
+            }
 
-                return true;
-            });
-
-            if is_changed {
-                <ModelPreBlackList<T>>::put(pre_black_list);
-            }*/
-
-            0
+            let mut weight = T::WeightInfo::on_initialize_pre_black_list(count)
+                .saturating_add(Self::leader_board_draw_due())
+                .saturating_add(Self::finance_member_draw_due())
+                .saturating_add(T::DbWeight::get().reads(1));
+            if <StorageVersion>::get() == Releases::V1_0_0 {
+                weight = weight.saturating_add(
+                    Self::migrate_leader_board_records(LEADER_BOARD_MIGRATION_BATCH),
+                );
+            }
+            weight
         }
     }
 }
@@ -2817,19 +3460,20 @@ impl<T: Trait> Module<T> {
         Self::kp_account_power(account_id)
     }
 
-    pub fn power_factor(p: u128) -> PowerRatioType {
-        match p {
-            0..=100000 => (
-                1u32,
-                Perbill::from_rational_approximation::<u128>(3, 20_0000),
-                p as u32,
-            ), //1 + (3 / 20) * x,
-            _ => (
-                0u32,
-                Perbill::from_rational_approximation::<u128>(p + 17_0000u128, p + 162_0000u128),
-                16u32,
-            ), // 16 * (x + 17) / (x + 162),
-        }
+    /// Vote factor for an account holding `p` power: the stake counts once, plus
+    /// `PowerVoteMaxBoost` times the curve value at `p / PowerVoteMaxPower`.
+    pub fn power_factor(p: PowerSize) -> PowerRatioType {
+        let max_power = max(T::PowerVoteMaxPower::get(), 1);
+        let parts = T::PowerVoteCurve::get().calculate_for_fraction_times_denominator(
+            Perbill::from_rational_approximation(min(p, max_power), max_power).deconstruct(),
+            Perbill::one().deconstruct(),
+        );
+
+        (
+            1u32,
+            Perbill::from_parts(parts),
+            T::PowerVoteMaxBoost::get(),
+        )
     }
 
     pub fn balance_apply_power(b: BalanceOf<T>, factor: PowerRatioType) -> BalanceOf<T> {
@@ -2847,7 +3491,7 @@ impl<T: Trait> Module<T> {
     }
 
     pub fn kp_account_power_ratio(account: &T::AccountId) -> PowerRatioType {
-        let p = <MinerPowerByAccount<T>>::get(account) as u128;
+        let p = <MinerPowerByAccount<T>>::get(account);
         Self::power_factor(p)
     }
 
@@ -2967,6 +3611,11 @@ impl<T: Trait> Module<T> {
             ..Default::default()
         };
 
+        if <KPDocumentStatus<T>>::get(&key) == ContentStatus::Hidden {
+            result.is_slashed = true;
+            return result;
+        }
+
         // check doc type to see if it's try or identity doc
         match &doc.document_data {
             DocumentSpecificData::ProductIdentify(data) => {
@@ -2982,6 +3631,52 @@ impl<T: Trait> Module<T> {
         result
     }
 
+    /// Power of the document recomputed with `params` instead of the stored weights, against
+    /// the current max records of its app, to preview a change of the weights.
+    pub fn kp_document_power_with_params(
+        app_id: u32,
+        document_id: Vec<u8>,
+        params: PowerParams,
+    ) -> DocumentPowerInfo {
+        let key = T::Hashing::hash_of(&(app_id, &document_id));
+        let mut result = Self::kp_document_power(app_id, document_id);
+        if !result.is_exist
+            || result.is_slashed
+            || <KPDocumentStatus<T>>::get(&key) != ContentStatus::Active
+        {
+            return result;
+        }
+
+        let doc = <KPDocumentDataByIdHash<T>>::get(&key);
+        let (_, comment_power_weight, top_weight, judge_weight) =
+            Self::document_weights(&params, &doc.document_type);
+
+        let content_power = Self::compute_document_content_power(&params, &doc);
+        let judge_power = Self::compute_judge_power(
+            Self::compute_doc_trend_power(&doc),
+            top_weight,
+            judge_weight,
+        );
+        let mut attend_power = 0;
+        if doc.comment_count > 0 {
+            attend_power = Self::compute_attend_power(
+                &params,
+                Self::compute_comment_action_rate(
+                    &<DocumentCommentPowerBase<T>>::get(&key),
+                    doc.comment_count,
+                    doc.comment_total_fee,
+                    doc.comment_positive_count,
+                    doc.comment_total_fee / doc.comment_count,
+                ),
+                comment_power_weight,
+                top_weight,
+            );
+        }
+
+        result.power = content_power + judge_power + attend_power;
+        result
+    }
+
     pub fn kp_account_attend_power(app_id: u32, account: T::AccountId) -> PowerSize {
         let key = T::Hashing::hash_of(&(&account, app_id));
         <AccountAttendPowerMap<T>>::get(&key)
@@ -3002,7 +3697,50 @@ impl<T: Trait> Module<T> {
         model_id: Vec<u8>,
     ) -> LeaderBoardResult<T::AccountId> {
         let lottery_record_key = Self::leader_record_key(app_id, block.into(), &model_id);
-        <AppLeaderBoardRcord<T>>::get(&lottery_record_key)
+        let board_key = T::Hashing::hash_of(&(app_id, &model_id));
+        <AppLeaderBoardBlockEpoch<T>>::get(&lottery_record_key)
+            .and_then(|epoch| <AppLeaderBoardHistory<T>>::get(&board_key, epoch))
+            .map(|item| item.result)
+            .unwrap_or_default()
+    }
+
+    /// Kept leader board records of (app_id, model_id) from epoch `from` on, at most `limit`
+    /// of them and never more than `LEADER_BOARD_HISTORY_PAGE`.
+    pub fn leader_board_history(
+        app_id: u32,
+        model_id: Vec<u8>,
+        from: u32,
+        limit: u32,
+    ) -> LeaderBoardHistoryPage<T::AccountId, T::BlockNumber, T::Hash> {
+        let board_key = T::Hashing::hash_of(&(app_id, &model_id));
+        let (first, next) = <AppLeaderBoardEpochs<T>>::get(&board_key);
+
+        let start = max(from, first);
+        let end = min(
+            next,
+            start.saturating_add(min(limit, LEADER_BOARD_HISTORY_PAGE)),
+        );
+        let records = (start..end)
+            .filter_map(|epoch| <AppLeaderBoardHistory<T>>::get(&board_key, epoch))
+            .collect();
+
+        LeaderBoardHistoryPage {
+            records,
+            next: if end < next { Some(end) } else { None },
+            first,
+            pruned_root: <AppLeaderBoardArchive<T>>::get(&board_key).root,
+        }
+    }
+
+    /// Bytes `signer` has to sign for the encoded `params` of `call`, e.g. `kp.create_comment`,
+    /// over the signer's current nonce; `expiry` must be within the payload lifetime.
+    pub fn signed_payload(
+        call: Vec<u8>,
+        signer: AuthAccountId,
+        params: Vec<u8>,
+        expiry: T::BlockNumber,
+    ) -> Vec<u8> {
+        T::PayloadVerify::payload_bytes(&call, &signer, &params, expiry)
     }
 
     pub fn is_tech_member_sign(account: AuthAccountId, msg: Vec<u8>, sign: Vec<u8>) -> bool {
@@ -3056,20 +3794,109 @@ impl<T: Trait> Module<T> {
     }
 
     // belows are internal using
-    fn choose_finance_member() -> Result<T::AccountId, sp_runtime::DispatchError> {
-        let seed = T::Randomness::random(b"ctt_finance");
+    /// Queue a finance member draw for `key`, it is drawn once the randomness of two epochs
+    /// later is in use, so the requester can not time the request to pick the member.
+    fn schedule_finance_member_draw(key: &T::Hash, kind: FinanceMemberDrawKind) -> dispatch::DispatchResult {
+        if <FinanceMemberPendingDraws<T>>::contains_key(key) {
+            return Ok(());
+        }
+        ensure!(!T::Membership::valid_finance_members().is_empty(), Error::<T>::NotFoundValidFinanceMember);
+        ensure!(
+            (<FinanceMemberDrawQueue<T>>::decode_len().unwrap_or(0) as u32) < T::MaxPendingDraws::get(),
+            Error::<T>::DrawQueueFull
+        );
+
+        <FinanceMemberPendingDraws<T>>::insert(key, FinanceMemberPendingDraw {
+            kind,
+            seed_epoch: T::EpochRandomness::current_epoch() + 2,
+        });
+        <FinanceMemberDrawQueue<T>>::append(key);
+        Ok(())
+    }
+
+    fn choose_finance_member(
+        key: &T::Hash,
+        kind: FinanceMemberDrawKind,
+        candidates: Vec<T::AccountId>,
+    ) -> Option<T::AccountId> {
+        let seed = T::EpochRandomness::random(&(&b"ctt_finance"[..], key).encode());
+        let member = Self::finance_member_draw_winner(&seed, &candidates)?;
+
+        match kind {
+            FinanceMemberDrawKind::AppCycleIncome => <AppCycleIncomeFinanceMember<T>>::insert(key, &member),
+            FinanceMemberDrawKind::AppFinance => <AppFinanceFinanceMember<T>>::insert(key, &member),
+        }
+        <FinanceMemberDraws<T>>::insert(
+            key,
+            FinanceMemberDrawRecord {
+                seed_epoch: T::EpochRandomness::current_epoch(),
+                seed,
+                candidates,
+                member: member.clone(),
+            },
+        );
+        Self::deposit_event(RawEvent::FinanceMemberDrawn(member.clone(), *key));
+
+        Some(member)
+    }
+
+    /// Draw the finance members at the head of `FinanceMemberDrawQueue` whose seed epoch is
+    /// reached, at most `MaxDrawsPerBlock` of them. A draw without candidates is dropped, the
+    /// next request queues it again.
+    fn finance_member_draw_due() -> Weight {
+        let mut queue = <FinanceMemberDrawQueue<T>>::get();
+        let mut weight = T::DbWeight::get().reads(1);
+        let mut taken = 0usize;
+        let mut drawn = 0u32;
+
+        while drawn < T::MaxDrawsPerBlock::get() {
+            let key = match queue.get(taken) {
+                Some(key) => *key,
+                None => break,
+            };
+            weight = weight.saturating_add(T::DbWeight::get().reads(1));
+            let draw = match <FinanceMemberPendingDraws<T>>::get(&key) {
+                Some(draw) => draw,
+                None => {
+                    taken += 1;
+                    continue;
+                }
+            };
+            if T::EpochRandomness::current_epoch() < draw.seed_epoch {
+                break;
+            }
+
+            taken += 1;
+            drawn += 1;
+            <FinanceMemberPendingDraws<T>>::remove(&key);
+            let candidates = T::Membership::valid_finance_members();
+            weight = weight.saturating_add(T::WeightInfo::on_initialize_finance_member_draw(
+                candidates.len() as u32,
+            ));
+            Self::choose_finance_member(&key, draw.kind, candidates);
+        }
+
+        if taken > 0 {
+            queue.drain(..taken);
+            <FinanceMemberDrawQueue<T>>::put(queue);
+            weight = weight.saturating_add(T::DbWeight::get().writes(1));
+        }
+        weight
+    }
+
+    pub fn finance_member_draw_winner(
+        seed: &T::Hash,
+        candidates: &Vec<T::AccountId>,
+    ) -> Option<T::AccountId> {
+        let mut rng = Self::seed_rng(seed);
+        pick_item(&mut rng, candidates).cloned()
+    }
+
+    fn seed_rng(seed: &T::Hash) -> ChaChaRng {
         // seed needs to be guaranteed to be 32 bytes.
         let seed = <[u8; 32]>::decode(&mut TrailingZeroInput::new(seed.as_ref()))
             .expect("input is padded with zeroes; qed");
-        let mut rng = ChaChaRng::from_seed(seed);
-
-        let members = T::Membership::valid_finance_members();
-
-        return if let Some(member) = pick_item(&mut rng, &members) {
-            Ok(member.clone())
-        } else {
-            Err(Error::<T>::NotFoundValidFinanceMember.into())
-        };
+        ChaChaRng::from_seed(seed)
     }
 
     fn leader_record_key(app_id: u32, block: T::BlockNumber, model_id: &Vec<u8>) -> T::Hash {
@@ -3077,11 +3904,6 @@ impl<T: Trait> Module<T> {
         T::Hashing::hash_of(&(buf, model_id))
     }
 
-    fn verify_sign(pub_key: &AuthAccountId, sign: sr25519::Signature, msg: &[u8]) -> bool {
-        let ms: MultiSignature = sign.into();
-        ms.verify(msg, &pub_key)
-    }
-
     fn convert_account(origin: &AuthAccountId) -> T::AccountId {
         let tmp: [u8; 32] = origin.clone().into();
         T::AccountId::decode(&mut &tmp[..]).unwrap_or_default()
@@ -3157,12 +3979,13 @@ impl<T: Trait> Module<T> {
         // read out total power
         let mut total_power = TotalPower::get();
         let mut account_power = <MinerPowerByAccount<T>>::get(owner);
+        let mut org_power = 0;
 
         // check if this has been added to total power before
         if <KPPurchasePowerByIdHash<T>>::contains_key(&key) {
             let org_power_set = <KPPurchasePowerByIdHash<T>>::get(&key);
             // only add a diff to total power
-            let org_power = Self::compute_commodity_power(&org_power_set);
+            org_power = Self::compute_commodity_power(&org_power_set);
 
             // for total power
             if total_power >= org_power {
@@ -3192,6 +4015,16 @@ impl<T: Trait> Module<T> {
         Self::update_realtime_power_leader_boards(app_id, model_id, cart_id, power, owner.clone());
         // uupdate app board
         Self::update_realtime_power_leader_boards(app_id, &vec![], cart_id, power, owner.clone());
+
+        if org_power != power {
+            Self::deposit_event(RawEvent::PowerChanged(
+                owner.clone(),
+                app_id,
+                PowerTarget::Commodity(cart_id.clone()),
+                org_power,
+                power,
+            ));
+        }
     }
 
     fn clear_purchase_power(key: &T::Hash) {
@@ -3285,104 +4118,111 @@ impl<T: Trait> Module<T> {
     }
 
     fn compute_publish_product_content_power(
+        params: &PowerParams,
         para_issue_rate: Permill,
         self_issue_rate: Permill,
         attend_rate: Permill,
     ) -> PowerSize {
-        let mut base = Permill::from_percent(T::TopWeightProductPublish::get() as u32)
+        let mut base = Permill::from_percent(params.top_weight_product_publish as u32)
             * FLOAT_COMPUTE_PRECISION;
 
-        base = Permill::from_percent(T::DocumentPowerWeightContent::get() as u32) * base;
+        base = Permill::from_percent(params.document_power_weight_content as u32) * base;
 
         let mut sub1 = para_issue_rate * base;
-        sub1 = Permill::from_percent(T::DocumentPublishWeightParamsRate::get() as u32) * sub1;
+        sub1 = Permill::from_percent(params.document_publish_weight_params_rate as u32) * sub1;
 
         let mut sub2 = self_issue_rate * base;
-        sub2 = Permill::from_percent(T::DocumentPublishWeightParamsSelfRate::get() as u32) * sub2;
+        sub2 = Permill::from_percent(params.document_publish_weight_params_self_rate as u32) * sub2;
 
         let mut sub3 = attend_rate * base;
-        sub3 = Permill::from_percent(T::DocumentPublishWeightParamsAttendRate::get() as u32) * sub3;
+        sub3 =
+            Permill::from_percent(params.document_publish_weight_params_attend_rate as u32) * sub3;
 
         sub1 + sub2 + sub3
     }
 
     fn compute_identify_content_power(
+        params: &PowerParams,
         ident_rate: Permill,
         ident_consistence: Permill,
         seller_consistence: Permill,
     ) -> PowerSize {
-        let mut base = Permill::from_percent(T::TopWeightDocumentIdentify::get() as u32)
+        let mut base = Permill::from_percent(params.top_weight_document_identify as u32)
             * FLOAT_COMPUTE_PRECISION;
 
-        base = Permill::from_percent(T::DocumentPowerWeightContent::get() as u32) * base;
+        base = Permill::from_percent(params.document_power_weight_content as u32) * base;
 
         let mut sub1 = ident_rate * base;
-        sub1 = Permill::from_percent(T::DocumentIdentifyWeightParamsRate::get() as u32) * sub1;
+        sub1 = Permill::from_percent(params.document_identify_weight_params_rate as u32) * sub1;
 
         let mut sub2 = ident_consistence * base;
-        sub2 = Permill::from_percent(T::DocumentIdentifyWeightCheckRate::get() as u32) * sub2;
+        sub2 = Permill::from_percent(params.document_identify_weight_check_rate as u32) * sub2;
 
         let mut sub3 = seller_consistence * base;
-        sub3 = Permill::from_percent(T::DocumentIdentifyWeightConsistentRate::get() as u32) * sub3;
+        sub3 = Permill::from_percent(params.document_identify_weight_consistent_rate as u32) * sub3;
 
         sub1 + sub2 + sub3
     }
 
     fn compute_try_content_power(
+        params: &PowerParams,
         offset_rate: Permill,
         true_rate: Permill,
         seller_consistence: Permill,
     ) -> PowerSize {
         let mut base =
-            Permill::from_percent(T::TopWeightDocumentTry::get() as u32) * FLOAT_COMPUTE_PRECISION;
+            Permill::from_percent(params.top_weight_document_try as u32) * FLOAT_COMPUTE_PRECISION;
 
-        base = Permill::from_percent(T::DocumentPowerWeightContent::get() as u32) * base;
+        base = Permill::from_percent(params.document_power_weight_content as u32) * base;
 
         let mut sub1 = offset_rate * base;
-        sub1 = Permill::from_percent(T::DocumentTryWeightBiasRate::get() as u32) * sub1;
+        sub1 = Permill::from_percent(params.document_try_weight_bias_rate as u32) * sub1;
 
         let mut sub2 = true_rate * base;
-        sub2 = Permill::from_percent(T::DocumentTryWeightTrueRate::get() as u32) * sub2;
+        sub2 = Permill::from_percent(params.document_try_weight_true_rate as u32) * sub2;
 
         let mut sub3 = seller_consistence * base;
-        sub3 = Permill::from_percent(T::DocumentTryWeightConsistentRate::get() as u32) * sub3;
+        sub3 = Permill::from_percent(params.document_try_weight_consistent_rate as u32) * sub3;
 
         sub1 + sub2 + sub3
     }
 
     fn compute_choose_content_power(
+        params: &PowerParams,
         sell_count_rate: Permill,
         try_count_rate: Permill,
     ) -> PowerSize {
-        let base = Permill::from_percent(T::DocumentCMPowerWeightContent::get() as u32)
+        let base = Permill::from_percent(params.document_cm_power_weight_content as u32)
             * FLOAT_COMPUTE_PRECISION;
 
         let mut sub1 = sell_count_rate * base;
-        sub1 = Permill::from_percent(T::DocumentChooseWeightSellCount::get() as u32) * sub1;
+        sub1 = Permill::from_percent(params.document_choose_weight_sell_count as u32) * sub1;
 
         let mut sub2 = try_count_rate * base;
-        sub2 = Permill::from_percent(T::DocumentChooseWeightTryCount::get() as u32) * sub2;
+        sub2 = Permill::from_percent(params.document_choose_weight_try_count as u32) * sub2;
 
         sub1 + sub2
     }
 
     fn compute_model_content_power(
+        params: &PowerParams,
         producer_count_rate: Permill,
         product_count_rate: Permill,
     ) -> PowerSize {
-        let base = Permill::from_percent(T::DocumentCMPowerWeightContent::get() as u32)
+        let base = Permill::from_percent(params.document_cm_power_weight_content as u32)
             * FLOAT_COMPUTE_PRECISION;
 
         let mut sub1 = producer_count_rate * base;
-        sub1 = Permill::from_percent(T::DocumentModelWeightProducerCount::get() as u32) * sub1;
+        sub1 = Permill::from_percent(params.document_model_weight_producer_count as u32) * sub1;
 
         let mut sub2 = product_count_rate * base;
-        sub2 = Permill::from_percent(T::DocumentModelWeightProductCount::get() as u32) * sub2;
+        sub2 = Permill::from_percent(params.document_model_weight_product_count as u32) * sub2;
 
         sub1 + sub2
     }
 
     fn compute_attend_power(
+        params: &PowerParams,
         rates: (Permill, Permill, Permill, Permill),
         second_weight: PowerSize,
         top_weight: PowerSize,
@@ -3392,16 +4232,16 @@ impl<T: Trait> Module<T> {
         base = Permill::from_percent(second_weight as u32) * base;
 
         let mut sub1 = rates.0 * base;
-        sub1 = Permill::from_percent(T::CommentPowerWeightCount::get() as u32) * sub1;
+        sub1 = Permill::from_percent(params.comment_power_weight_count as u32) * sub1;
 
         let mut sub2 = rates.1 * base;
-        sub2 = Permill::from_percent(T::CommentPowerWeightCost::get() as u32) * sub2;
+        sub2 = Permill::from_percent(params.comment_power_weight_cost as u32) * sub2;
 
         let mut sub3 = rates.2 * base;
-        sub3 = Permill::from_percent(T::CommentPowerWeightPerCost::get() as u32) * sub3;
+        sub3 = Permill::from_percent(params.comment_power_weight_per_cost as u32) * sub3;
 
         let mut sub4 = rates.3 * base;
-        sub4 = Permill::from_percent(T::CommentPowerWeightPositive::get() as u32) * sub4;
+        sub4 = Permill::from_percent(params.comment_power_weight_positive as u32) * sub4;
 
         sub1 + sub2 + sub3 + sub4
     }
@@ -3417,12 +4257,100 @@ impl<T: Trait> Module<T> {
         origin_power * base
     }
 
-    fn compute_price_power(commodity_price: PowerSize) -> PowerSize {
+    /// Weights of the document type: account attend, document attend, top and judge weight.
+    fn document_weights(
+        params: &PowerParams,
+        document_type: &DocumentType,
+    ) -> (PowerSize, PowerSize, PowerSize, u8) {
+        match document_type {
+            DocumentType::ProductPublish => (
+                params.top_weight_account_attend as PowerSize,
+                params.document_power_weight_attend as PowerSize,
+                params.top_weight_product_publish as PowerSize,
+                params.document_power_weight_judge,
+            ),
+            DocumentType::ProductIdentify => (
+                params.top_weight_account_attend as PowerSize,
+                params.document_power_weight_attend as PowerSize,
+                params.top_weight_document_identify as PowerSize,
+                params.document_power_weight_judge,
+            ),
+            DocumentType::ProductTry => (
+                params.top_weight_account_attend as PowerSize,
+                params.document_power_weight_attend as PowerSize,
+                params.top_weight_document_try as PowerSize,
+                params.document_power_weight_judge,
+            ),
+            DocumentType::ProductChoose | DocumentType::ModelCreate => (
+                params.cm_power_account_attend as PowerSize,
+                params.document_cm_power_weight_attend as PowerSize,
+                100 as PowerSize,
+                params.document_cm_power_weight_judge,
+            ),
+            _ => (0, 0, 0, 0),
+        }
+    }
+
+    // content power of the document against the current max records, without updating them
+    fn compute_document_content_power(
+        params: &PowerParams,
+        doc: &KPDocumentData<T::AccountId, T::Hash>,
+    ) -> PowerSize {
+        let rate = |value: PowerSize, max: PowerSize| Self::update_max(value, max, |_| {});
+
+        match &doc.document_data {
+            DocumentSpecificData::ProductPublish(data) => {
+                let max = <DocumentPublishMaxParams>::get(doc.app_id);
+                Self::compute_publish_product_content_power(
+                    params,
+                    rate(data.para_issue_rate, max.para_issue_rate),
+                    rate(data.self_issue_rate, max.self_issue_rate),
+                    rate(data.refer_count, max.refer_count),
+                )
+            }
+            DocumentSpecificData::ProductIdentify(data) => {
+                let max = <DocumentIdentifyMaxParams>::get(doc.app_id);
+                Self::compute_identify_content_power(
+                    params,
+                    rate(data.ident_rate, max.ident_rate),
+                    rate(data.ident_consistence, max.ident_consistence),
+                    rate(data.seller_consistence, max.seller_consistence),
+                )
+            }
+            DocumentSpecificData::ProductTry(data) => {
+                let max = <DocumentTryMaxParams>::get(doc.app_id);
+                Self::compute_try_content_power(
+                    params,
+                    rate(data.offset_rate, max.offset_rate),
+                    rate(data.true_rate, max.true_rate),
+                    rate(data.seller_consistence, max.seller_consistence),
+                )
+            }
+            DocumentSpecificData::ProductChoose(data) => {
+                let max = <DocumentChooseMaxParams>::get(doc.app_id);
+                Self::compute_choose_content_power(
+                    params,
+                    rate(data.sell_count, max.sell_count),
+                    rate(data.try_count, max.try_count),
+                )
+            }
+            DocumentSpecificData::ModelCreate(data) => {
+                let max = <DocumentModelCreateMaxParams>::get(doc.app_id);
+                Self::compute_model_content_power(
+                    params,
+                    rate(data.producer_count, max.producer_count),
+                    rate(data.product_count, max.product_count),
+                )
+            }
+        }
+    }
+
+    fn compute_price_power(params: &PowerParams, commodity_price: PowerSize) -> PowerSize {
         let max = <MaxGoodsPrice>::get();
         if max == 0 {
             0
         } else {
-            let base = Permill::from_percent(T::TopWeightAccountStake::get() as u32)
+            let base = Permill::from_percent(params.top_weight_account_stake as u32)
                 * FLOAT_COMPUTE_PRECISION;
             Permill::from_rational_approximation(commodity_price as u32, max as u32) * base
         }
@@ -3612,10 +4540,7 @@ impl<T: Trait> Module<T> {
             });
 
             // update account commodity store record
-            let mut owner_cart_ids = <AccountCommoditySet<T>>::get(&owner_id, app_id);
-            owner_cart_ids.push(cart_id.clone());
-
-            <AccountCommoditySet<T>>::insert(&owner_id, app_id, owner_cart_ids);
+            <AccountCommoditySet<T>>::append(&owner_id, app_id, cart_id);
         };
 
         match doc_type {
@@ -3638,16 +4563,16 @@ impl<T: Trait> Module<T> {
         }
     }
 
-    fn leader_board_lottery(block: T::BlockNumber, app_id: u32, model_id: &Vec<u8>) {
-        let seed = T::Randomness::random(b"ctt_power");
-        // seed needs to be guaranteed to be 32 bytes.
-        let seed = <[u8; 32]>::decode(&mut TrailingZeroInput::new(seed.as_ref()))
-            .expect("input is padded with zeroes; qed");
-        let mut rng = ChaChaRng::from_seed(seed);
+    /// Close the entries of a leader board, its lottery is drawn once the randomness of two
+    /// epochs later is in use. That randomness comes from VRF outputs of the closing epoch and
+    /// is fixed one epoch after the entries close.
+    fn leader_board_close(
+        block: T::BlockNumber,
+        app_id: u32,
+        model_id: &Vec<u8>,
+    ) -> Option<LeaderBoardPendingDraw<T::AccountId, T::BlockNumber>> {
         let mut pdc_map: BTreeMap<T::Hash, ()> = BTreeMap::new();
 
-        //pick_item(&mut rng, &votes)
-
         // get border items
         let leader_key = T::Hashing::hash_of(&(app_id, model_id));
         let board: Vec<CommodityLeaderBoardData<T>> =
@@ -3655,7 +4580,7 @@ impl<T: Trait> Module<T> {
 
         if board.len() == 0 {
             print("board empty");
-            return;
+            return None;
         }
 
         // get this board(appid, model_id) total items count
@@ -3669,7 +4594,7 @@ impl<T: Trait> Module<T> {
 
         if total == 0 {
             print("total commodity empty");
-            return;
+            return None;
         }
 
         // get board items count
@@ -3684,8 +4609,8 @@ impl<T: Trait> Module<T> {
         // load board leaders
         let leaders: Vec<CommodityLeaderBoardData<T>> = (&board[..count]).to_vec();
 
-        // hit records
-        let mut records: Vec<T::AccountId> = vec![];
+        // lottery entries of each document
+        let mut pools: Vec<LeaderBoardDrawPool<T::AccountId>> = vec![];
 
         // get max comment info
         let max = <CommentMaxInfoPerDocMap>::get(app_id);
@@ -3738,25 +4663,11 @@ impl<T: Trait> Module<T> {
                 }
             }
 
-            // now we got total reassigned position info
-            let total_positions = *weight_pool.last().unwrap_or(&0);
-            print("total_positions");
-            print(total_positions);
-            // start lottery
-            for _l in 0..hit_max {
-                let pos = pick_usize(&mut rng, total_positions as usize) as u32;
-                print("hit pos:");
-                print(pos);
-                // now check which chance hit, binary search weight_pool
-                let mut closet_index = Self::binary_search_closet(&weight_pool, &pos);
-                if weight_pool[closet_index] <= pos && closet_index < hit_max - 1 {
-                    // always take is right neighbor
-                    closet_index += 1;
-                }
-                print("hit index:");
-                print(closet_index);
-                records.push(comment_set[closet_index].account.clone());
-            }
+            pools.push(LeaderBoardDrawPool {
+                accounts: comment_set.into_iter().map(|c| c.account).collect(),
+                positions: weight_pool,
+                hits: hit_max as u32,
+            });
         };
 
         for index in 0..count {
@@ -3788,9 +4699,6 @@ impl<T: Trait> Module<T> {
             }
         }
 
-        print("lottery done");
-        print(records.len());
-
         // convert leader data to RPC query required
         let mut leader_rpc_data: Vec<LeaderBoardItem<T::AccountId>> = vec![];
 
@@ -3802,19 +4710,253 @@ impl<T: Trait> Module<T> {
             });
         }
 
-        // write this time record
-        let lottery_last_time_key = T::Hashing::hash_of(&(app_id, model_id));
-        let lottery_record_key = Self::leader_record_key(app_id, block, model_id);
-        let record = LeaderBoardResult {
+        Some(LeaderBoardPendingDraw {
+            app_id,
+            model_id: model_id.clone(),
+            block,
+            seed_epoch: T::EpochRandomness::current_epoch() + 2,
             board: leader_rpc_data,
-            accounts: records,
-        };
-        <AppLeaderBoardRcord<T>>::insert(&lottery_record_key, &record);
-        <AppLeaderBoardLastTime<T>>::insert(&lottery_last_time_key, block);
-        // update sequence keys
+            pools,
+        })
+    }
+
+    /// Draw the lotteries at the head of `AppLeaderBoardDrawQueue` whose seed epoch is
+    /// reached, at most `MaxDrawsPerBlock` of them.
+    fn leader_board_draw_due() -> Weight {
+        let mut queue = <AppLeaderBoardDrawQueue<T>>::get();
+        let mut weight = T::DbWeight::get().reads(1);
+        let mut taken = 0usize;
+        let mut drawn = 0u32;
+
+        while drawn < T::MaxDrawsPerBlock::get() {
+            let board_key = match queue.get(taken) {
+                Some(key) => *key,
+                None => break,
+            };
+            weight = weight.saturating_add(T::DbWeight::get().reads(1));
+            let draw = match <AppLeaderBoardPendingDraw<T>>::get(&board_key) {
+                Some(draw) => draw,
+                None => {
+                    taken += 1;
+                    continue;
+                }
+            };
+            if T::EpochRandomness::current_epoch() < draw.seed_epoch {
+                break;
+            }
+
+            taken += 1;
+            drawn += 1;
+            <AppLeaderBoardPendingDraw<T>>::remove(&board_key);
+            weight = weight.saturating_add(T::WeightInfo::on_initialize_leader_board_draw(
+                draw.pools.len() as u32,
+                draw.pools
+                    .iter()
+                    .map(|pool| pool.accounts.len() as u32)
+                    .max()
+                    .unwrap_or(0),
+            ));
+            Self::leader_board_draw(board_key, draw);
+        }
+
+        if taken > 0 {
+            queue.drain(..taken);
+            <AppLeaderBoardDrawQueue<T>>::put(queue);
+            weight = weight.saturating_add(T::DbWeight::get().writes(1));
+        }
+        weight
+    }
+
+    fn leader_board_draw(
+        board_key: T::Hash,
+        draw: LeaderBoardPendingDraw<T::AccountId, T::BlockNumber>,
+    ) {
+        let seed_epoch = T::EpochRandomness::current_epoch();
+        let seed =
+            T::EpochRandomness::random(&(&b"ctt_power"[..], &board_key, draw.block).encode());
+        let accounts = Self::leader_board_draw_winners(&seed, &draw.pools);
+
+        Self::add_leader_board_history(
+            draw.app_id,
+            &draw.model_id,
+            draw.block,
+            LeaderBoardResult {
+                board: draw.board,
+                accounts,
+            },
+            seed_epoch,
+            seed,
+            draw.pools,
+        );
+        Self::deposit_event(RawEvent::LeaderBoardDrawn(
+            draw.block,
+            draw.app_id,
+            draw.model_id,
+            seed_epoch,
+            seed,
+        ));
+    }
+
+    /// The lottery hits of the pools drawn with `seed`, in pool order.
+    pub fn leader_board_draw_winners(
+        seed: &T::Hash,
+        pools: &Vec<LeaderBoardDrawPool<T::AccountId>>,
+    ) -> Vec<T::AccountId> {
+        let mut rng = Self::seed_rng(seed);
+        let mut records: Vec<T::AccountId> = vec![];
+
+        for pool in pools {
+            let weight_pool = &pool.positions;
+            let hit_max = pool.hits as usize;
+            // now we got total reassigned position info
+            let total_positions = *weight_pool.last().unwrap_or(&0);
+            // start lottery
+            for _l in 0..hit_max {
+                let pos = pick_usize(&mut rng, total_positions as usize) as u32;
+                // now check which chance hit, binary search weight_pool
+                let mut closet_index = Self::binary_search_closet(weight_pool, &pos);
+                if weight_pool[closet_index] <= pos && closet_index < hit_max - 1 {
+                    // always take is right neighbor
+                    closet_index += 1;
+                }
+                records.push(pool.accounts[closet_index].clone());
+            }
+        }
+
+        records
+    }
+
+    fn add_leader_board_history(
+        app_id: u32,
+        model_id: &Vec<u8>,
+        block: T::BlockNumber,
+        result: LeaderBoardResult<T::AccountId>,
+        seed_epoch: u64,
+        seed: T::Hash,
+        pools: Vec<LeaderBoardDrawPool<T::AccountId>>,
+    ) {
+        let board_key = T::Hashing::hash_of(&(app_id, model_id));
+        let (mut first, mut next) = <AppLeaderBoardEpochs<T>>::get(&board_key);
+
+        <AppLeaderBoardHistory<T>>::insert(
+            &board_key,
+            next,
+            LeaderBoardHistoryItem {
+                epoch: next,
+                block,
+                result,
+                seed_epoch,
+                seed,
+                pools,
+            },
+        );
+        <AppLeaderBoardBlockEpoch<T>>::insert(
+            Self::leader_record_key(app_id, block, model_id),
+            next,
+        );
+        next += 1;
+
+        // prune the records out of retention window, a few at a time
+        let retention = T::AppLeaderBoardRetention::get();
+        let mut pruned = 0;
+        while first < next - 1 && pruned < LEADER_BOARD_PRUNE_LIMIT {
+            match <AppLeaderBoardHistory<T>>::get(&board_key, first) {
+                Some(expired) if expired.block.saturating_add(retention) <= block => {
+                    <AppLeaderBoardHistory<T>>::remove(&board_key, first);
+                    <AppLeaderBoardBlockEpoch<T>>::remove(Self::leader_record_key(
+                        app_id,
+                        expired.block,
+                        model_id,
+                    ));
+                    <AppLeaderBoardArchive<T>>::mutate(&board_key, |archive| {
+                        Self::archive_append(archive, T::Hashing::hash_of(&expired));
+                    });
+                }
+                Some(_) => break,
+                None => {}
+            }
+            first += 1;
+            pruned += 1;
+        }
+
+        <AppLeaderBoardEpochs<T>>::insert(&board_key, (first, next));
+    }
+
+    fn archive_append(archive: &mut LeaderBoardArchive<T::Hash>, leaf: T::Hash) {
+        // hash the new leaf up with its left siblings until it becomes a left child
+        let mut node = leaf;
+        let mut size = archive.count + 1;
+        let mut level = 0;
+        while size & 1 == 0 {
+            node = T::Hashing::hash_of(&(archive.branch[level], node));
+            size >>= 1;
+            level += 1;
+        }
+        if level < archive.branch.len() {
+            archive.branch[level] = node;
+        } else {
+            archive.branch.push(node);
+        }
+        archive.count += 1;
+
+        // the missing right subtrees are all empty
+        let mut root = T::Hash::default();
+        let mut empty = T::Hash::default();
+        let mut size = archive.count;
+        for level in 0..LEADER_BOARD_ARCHIVE_DEPTH {
+            root = if size & 1 == 1 {
+                T::Hashing::hash_of(&(archive.branch[level], root))
+            } else {
+                T::Hashing::hash_of(&(root, empty))
+            };
+            empty = T::Hashing::hash_of(&(empty, empty));
+            size >>= 1;
+        }
+        archive.root = root;
+    }
+
+    /// Move up to `limit` legacy leader board records into the paged history and remove
+    /// the orphaned ones, the storage version is bumped once none are left.
+    fn migrate_leader_board_records(limit: u32) -> Weight {
         let mut keys = <AppLeaderBoardSequenceKeys<T>>::get();
-        keys.push((app_id, block, model_id.clone()));
-        <AppLeaderBoardSequenceKeys<T>>::put(keys);
+        let moved = min(limit as usize, keys.len());
+        for (app_id, block, model_id) in keys.drain(..moved) {
+            let result =
+                <AppLeaderBoardRcord<T>>::take(Self::leader_record_key(app_id, block, &model_id));
+            Self::add_leader_board_history(
+                app_id,
+                &model_id,
+                block,
+                result,
+                0,
+                Default::default(),
+                vec![],
+            );
+        }
+        let moved = moved as Weight;
+        let weight = T::DbWeight::get().reads_writes(1 + moved * 4, 1 + moved * 6);
+        if !keys.is_empty() {
+            <AppLeaderBoardSequenceKeys<T>>::put(keys);
+            return weight;
+        }
+        <AppLeaderBoardSequenceKeys<T>>::kill();
+
+        // records whose key was dropped from the full sequence have no history entry to move to
+        let left = (limit as usize).saturating_sub(moved as usize);
+        let orphans: Vec<T::Hash> = <AppLeaderBoardRcord<T>>::iter()
+            .map(|(key, _)| key)
+            .take(left + 1)
+            .collect();
+        let done = orphans.len() <= left;
+        for key in orphans.iter().take(left) {
+            <AppLeaderBoardRcord<T>>::remove(key);
+        }
+        if done {
+            <StorageVersion>::put(Releases::V2_0_0);
+        }
+
+        let orphans = orphans.len() as Weight;
+        weight.saturating_add(T::DbWeight::get().reads_writes(orphans, orphans + 1))
     }
 
     fn update_document_comment_pool(
@@ -3822,16 +4964,13 @@ impl<T: Trait> Module<T> {
         doc: &KPDocumentData<T::AccountId, T::Hash>,
     ) {
         let key = T::Hashing::hash_of(&(doc.app_id, &doc.document_id));
-        let mut pool = <DocumentCommentsAccountPool<T>>::get(&key);
-
         let pool_item = CommentWeightData {
             account: new_comment.sender.clone(),
             position: doc.comment_count,
             cash_cost: new_comment.comment_fee,
         };
 
-        pool.push(pool_item);
-        <DocumentCommentsAccountPool<T>>::insert(&key, pool);
+        <DocumentCommentsAccountPool<T>>::append(&key, pool_item);
     }
 
     fn update_max_goods_price(price: PowerSize) {
@@ -3854,6 +4993,7 @@ impl<T: Trait> Module<T> {
         };
 
         <KPDocumentPowerByIdHash<T>>::insert(&key, &power);
+        Self::deposit_document_power_changed(doc, 0, power.total());
     }
 
     fn update_document_power(
@@ -3865,6 +5005,7 @@ impl<T: Trait> Module<T> {
         // read out original first
         let key = T::Hashing::hash_of(&(doc.app_id, &doc.document_id));
         let mut org_power = <KPDocumentPowerByIdHash<T>>::get(&key);
+        let before = org_power.total();
 
         if attend_power > 0 {
             org_power.attend = attend_power;
@@ -3878,12 +5019,42 @@ impl<T: Trait> Module<T> {
             org_power.content = content_power;
         }
 
+        let after = org_power.total();
         // update store
         <KPDocumentPowerByIdHash<T>>::insert(&key, org_power);
+        Self::deposit_document_power_changed(doc, before, after);
 
         Some(0)
     }
 
+    fn deposit_document_power_changed(
+        doc: &KPDocumentData<T::AccountId, T::Hash>,
+        before: PowerSize,
+        after: PowerSize,
+    ) {
+        if before != after {
+            Self::deposit_event(RawEvent::PowerChanged(
+                Self::convert_account(&doc.owner),
+                doc.app_id,
+                PowerTarget::Document(doc.document_id.clone()),
+                before,
+                after,
+            ));
+        }
+    }
+
+    fn deposit_knowledge_created(who: T::AccountId, doc: &KPDocumentData<T::AccountId, T::Hash>) {
+        Self::deposit_event(RawEvent::KnowledgeCreated(
+            who,
+            doc.app_id,
+            doc.document_id.clone(),
+            doc.document_type.clone(),
+            doc.model_id.clone(),
+            doc.product_id.clone(),
+            Self::document_cart_id(doc).cloned().unwrap_or_default(),
+        ));
+    }
+
     fn process_publish_doc_content_refer_power(
         app_id: u32,
         product_id: &Vec<u8>,
@@ -3924,6 +5095,7 @@ impl<T: Trait> Module<T> {
 
                 // compute power
                 let content_power = Self::compute_publish_product_content_power(
+                    &Self::power_params(),
                     para_issue_rate_p,
                     self_issue_rate_p,
                     attend_rate_p,
@@ -3940,6 +5112,7 @@ impl<T: Trait> Module<T> {
 
     // only invoked when creating document
     fn process_document_content_power(doc: &KPDocumentData<T::AccountId, T::Hash>) {
+        let params = Self::power_params();
         let content_power;
         let initial_judge_power;
 
@@ -3969,15 +5142,18 @@ impl<T: Trait> Module<T> {
 
                 // compute power
                 content_power = Self::compute_publish_product_content_power(
+                    &params,
                     para_issue_rate_p,
                     self_issue_rate_p,
                     attend_rate_p,
                 );
 
+                let (_, _, top_weight, judge_weight) =
+                    Self::document_weights(&params, &doc.document_type);
                 initial_judge_power = Self::compute_judge_power(
                     Self::compute_doc_trend_power(&doc),
-                    T::TopWeightProductPublish::get() as PowerSize,
-                    T::DocumentPowerWeightJudge::get(),
+                    top_weight,
+                    judge_weight,
                 );
                 Self::insert_document_power(&doc, content_power, initial_judge_power);
             }
@@ -4007,15 +5183,18 @@ impl<T: Trait> Module<T> {
                 );
 
                 content_power = Self::compute_identify_content_power(
+                    &params,
                     ident_rate_p,
                     ident_consistence_p,
                     seller_consistence_p,
                 );
 
+                let (_, _, top_weight, judge_weight) =
+                    Self::document_weights(&params, &doc.document_type);
                 initial_judge_power = Self::compute_judge_power(
                     Self::compute_doc_trend_power(&doc),
-                    T::TopWeightDocumentIdentify::get() as PowerSize,
-                    T::DocumentPowerWeightJudge::get(),
+                    top_weight,
+                    judge_weight,
                 );
 
                 Self::update_max_goods_price(data.goods_price);
@@ -4048,15 +5227,18 @@ impl<T: Trait> Module<T> {
                 );
 
                 content_power = Self::compute_try_content_power(
+                    &params,
                     offset_rate_p,
                     true_rate_p,
                     seller_consistence_p,
                 );
 
+                let (_, _, top_weight, judge_weight) =
+                    Self::document_weights(&params, &doc.document_type);
                 initial_judge_power = Self::compute_judge_power(
                     Self::compute_doc_trend_power(&doc),
-                    T::TopWeightDocumentTry::get() as PowerSize,
-                    T::DocumentPowerWeightJudge::get(),
+                    top_weight,
+                    judge_weight,
                 );
 
                 Self::update_max_goods_price(data.goods_price);
@@ -4077,12 +5259,15 @@ impl<T: Trait> Module<T> {
                     })
                 });
 
-                content_power = Self::compute_choose_content_power(sell_count_p, try_count_p);
+                content_power =
+                    Self::compute_choose_content_power(&params, sell_count_p, try_count_p);
 
+                let (_, _, top_weight, judge_weight) =
+                    Self::document_weights(&params, &doc.document_type);
                 initial_judge_power = Self::compute_judge_power(
                     Self::compute_doc_trend_power(&doc),
-                    100 as PowerSize,
-                    T::DocumentCMPowerWeightJudge::get(),
+                    top_weight,
+                    judge_weight,
                 );
                 Self::insert_document_power(&doc, content_power, initial_judge_power);
             }
@@ -4103,12 +5288,14 @@ impl<T: Trait> Module<T> {
                     });
 
                 content_power =
-                    Self::compute_model_content_power(producer_count_p, product_count_p);
+                    Self::compute_model_content_power(&params, producer_count_p, product_count_p);
 
+                let (_, _, top_weight, judge_weight) =
+                    Self::document_weights(&params, &doc.document_type);
                 initial_judge_power = Self::compute_judge_power(
                     Self::compute_doc_trend_power(&doc),
-                    100 as PowerSize,
-                    T::DocumentCMPowerWeightJudge::get(),
+                    top_weight,
+                    judge_weight,
                 );
                 Self::insert_document_power(&doc, content_power, initial_judge_power);
             }
@@ -4116,10 +5303,7 @@ impl<T: Trait> Module<T> {
 
         // update account document store record
         let owner_account = Self::convert_account(&doc.owner);
-        let mut owner_doc_ids = <AccountDocumentSet<T>>::get(&owner_account, doc.app_id);
-        owner_doc_ids.push(doc.document_id.clone());
-
-        <AccountDocumentSet<T>>::insert(&owner_account, doc.app_id, owner_doc_ids);
+        <AccountDocumentSet<T>>::append(&owner_account, doc.app_id, &doc.document_id);
     }
 
     fn give_comment_reward(is_normal: bool, owner: &T::AccountId, cost: u64) {
@@ -4171,47 +5355,13 @@ impl<T: Trait> Module<T> {
             account_comment_unit_fee,
         );
 
-        let mut account_attend_weight: PowerSize = 0;
-        let mut comment_power_weight: PowerSize = 0;
-        let mut doc_comment_top_weight: PowerSize = 0;
-        let mut doc_judge_weight: u8 = 0;
-
         // according doc type to decide weight
-        match doc.document_type {
-            DocumentType::ProductPublish => {
-                account_attend_weight = T::TopWeightAccountAttend::get() as PowerSize;
-                comment_power_weight = T::DocumentPowerWeightAttend::get() as PowerSize;
-                doc_comment_top_weight = T::TopWeightProductPublish::get() as PowerSize;
-                doc_judge_weight = T::DocumentPowerWeightJudge::get()
-            }
-            DocumentType::ProductIdentify => {
-                account_attend_weight = T::TopWeightAccountAttend::get() as PowerSize;
-                comment_power_weight = T::DocumentPowerWeightAttend::get() as PowerSize;
-                doc_comment_top_weight = T::TopWeightDocumentIdentify::get() as PowerSize;
-                doc_judge_weight = T::DocumentPowerWeightJudge::get()
-            }
-            DocumentType::ProductTry => {
-                account_attend_weight = T::TopWeightAccountAttend::get() as PowerSize;
-                comment_power_weight = T::DocumentPowerWeightAttend::get() as PowerSize;
-                doc_comment_top_weight = T::TopWeightDocumentTry::get() as PowerSize;
-                doc_judge_weight = T::DocumentPowerWeightJudge::get()
-            }
-            DocumentType::ProductChoose => {
-                account_attend_weight = T::CMPowerAccountAttend::get() as PowerSize;
-                comment_power_weight = T::DocumentCMPowerWeightAttend::get() as PowerSize;
-                doc_comment_top_weight = 100 as PowerSize;
-                doc_judge_weight = T::DocumentCMPowerWeightJudge::get();
-            }
-            DocumentType::ModelCreate => {
-                account_attend_weight = T::CMPowerAccountAttend::get() as PowerSize;
-                comment_power_weight = T::DocumentCMPowerWeightAttend::get() as PowerSize;
-                doc_comment_top_weight = 100 as PowerSize;
-                doc_judge_weight = T::DocumentCMPowerWeightJudge::get();
-            }
-            _ => {}
-        }
+        let params = Self::power_params();
+        let (account_attend_weight, comment_power_weight, doc_comment_top_weight, doc_judge_weight) =
+            Self::document_weights(&params, &doc.document_type);
 
         account_comment_power = Self::compute_attend_power(
+            &params,
             Self::compute_comment_action_rate(
                 &account_comment_max,
                 account.count,
@@ -4257,6 +5407,7 @@ impl<T: Trait> Module<T> {
             compare_base = doc_comment_max.clone();
         }
         doc_comment_power = Self::compute_attend_power(
+            &params,
             Self::compute_comment_action_rate(
                 &compare_base,
                 doc.comment_count,
@@ -4325,7 +5476,17 @@ impl<T: Trait> Module<T> {
 
         // update account attend power store
         let key = T::Hashing::hash_of(&(&comment.sender, comment.app_id));
+        let attend_power = <AccountAttendPowerMap<T>>::get(&key);
         <AccountAttendPowerMap<T>>::insert(&key, account_comment_power);
+        if attend_power != account_comment_power {
+            Self::deposit_event(RawEvent::PowerChanged(
+                comment.sender.clone(),
+                comment.app_id,
+                PowerTarget::Attend,
+                attend_power,
+                account_comment_power,
+            ));
+        }
 
         // update document attend power store
         Self::update_document_power(&doc, doc_comment_power, platform_comment_power, 0);
@@ -4382,7 +5543,8 @@ impl<T: Trait> Module<T> {
                     // update publish power
                     update_publish(&mut commodity_power);
                     // update price power
-                    commodity_power.4 = Self::compute_price_power(data.goods_price);
+                    commodity_power.4 =
+                        Self::compute_price_power(&Self::power_params(), data.goods_price);
 
                     let model_id = Self::get_model_id_from_product(doc.app_id, &doc.product_id)?;
 
@@ -4408,7 +5570,8 @@ impl<T: Trait> Module<T> {
                     // update publish power
                     update_publish(&mut commodity_power);
                     // update price power
-                    commodity_power.4 = Self::compute_price_power(data.goods_price);
+                    commodity_power.4 =
+                        Self::compute_price_power(&Self::power_params(), data.goods_price);
 
                     let model_id = Self::get_model_id_from_product(doc.app_id, &doc.product_id)?;
                     Self::update_purchase_power(
@@ -4427,17 +5590,277 @@ impl<T: Trait> Module<T> {
             }
             // left is product choose and model create doc, only update commodity power
             _ => {
-                <KPMiscDocumentPowerByIdHash<T>>::insert(
-                    &doc_key,
-                    owner_account_power + doc_power.total(),
-                );
+                let before = <KPMiscDocumentPowerByIdHash<T>>::get(&doc_key);
+                let after = owner_account_power + doc_power.total();
+                <KPMiscDocumentPowerByIdHash<T>>::insert(&doc_key, after);
+
+                if before != after {
+                    Self::deposit_event(RawEvent::PowerChanged(
+                        commodity_owner,
+                        doc.app_id,
+                        PowerTarget::MiscDocument(doc.document_id.clone()),
+                        before,
+                        after,
+                    ));
+                }
             }
         }
 
         Some(0)
     }
 
-    fn slash_power(cart_key: &T::Hash, power_owner: &T::AccountId) {
+    // The active document a moderation target belongs to, a comment's document decides
+    // which model experts handle it.
+    fn moderation_document(
+        app_id: u32,
+        target: &ModerationTarget,
+    ) -> Result<KPDocumentDataOf<T>, Error<T>> {
+        let document_id = match target {
+            ModerationTarget::Document(document_id) => document_id.clone(),
+            ModerationTarget::Comment(comment_id) => {
+                let comment_key = T::Hashing::hash_of(&(app_id, comment_id));
+                ensure!(
+                    <KPCommentDataByIdHash<T>>::contains_key(&comment_key),
+                    Error::<T>::CommentNotFound
+                );
+                ensure!(
+                    <KPCommentStatus<T>>::get(&comment_key) == ContentStatus::Active,
+                    Error::<T>::CommentNotActive
+                );
+                <KPCommentDataByIdHash<T>>::get(&comment_key).document_id
+            }
+        };
+
+        let doc_key = T::Hashing::hash_of(&(app_id, &document_id));
+        ensure!(
+            <KPDocumentDataByIdHash<T>>::contains_key(&doc_key),
+            Error::<T>::DocumentNotFound
+        );
+        ensure!(
+            <KPDocumentStatus<T>>::get(&doc_key) == ContentStatus::Active,
+            Error::<T>::DocumentNotActive
+        );
+        Ok(<KPDocumentDataByIdHash<T>>::get(&doc_key))
+    }
+
+    // Return the deposits of the open reports on a retracted or hidden document and on
+    // its comments, no expert can handle them any more.
+    fn drop_document_reports(doc: &KPDocumentDataOf<T>) {
+        let queue_key = T::Hashing::hash_of(&(doc.app_id, &doc.model_id));
+        let (dropped, kept): (Vec<_>, Vec<_>) = <ModerationQueue<T>>::get(&queue_key)
+            .into_iter()
+            .partition(|report| match &report.target {
+                ModerationTarget::Document(document_id) => document_id == &doc.document_id,
+                ModerationTarget::Comment(comment_id) => {
+                    let comment_key = T::Hashing::hash_of(&(doc.app_id, comment_id));
+                    <KPCommentDataByIdHash<T>>::get(&comment_key).document_id == doc.document_id
+                }
+            });
+        if dropped.is_empty() {
+            return;
+        }
+
+        <ModerationQueue<T>>::insert(&queue_key, kept);
+        for report in dropped {
+            T::Currency::unreserve(&report.reporter, report.deposit);
+            Self::deposit_event(RawEvent::ReportDropped(report.reporter, doc.app_id, report.target));
+        }
+    }
+
+    fn document_cart_id(doc: &KPDocumentData<T::AccountId, T::Hash>) -> Option<&Vec<u8>> {
+        match &doc.document_data {
+            DocumentSpecificData::ProductIdentify(data) => Some(&data.cart_id),
+            DocumentSpecificData::ProductTry(data) => Some(&data.cart_id),
+            _ => None,
+        }
+    }
+
+    // Take a retracted document's part out of its commodity power, the commodity is
+    // cleared once neither its identify nor its try document is left.
+    fn rollback_document_power(doc: &KPDocumentData<T::AccountId, T::Hash>) {
+        let doc_key = T::Hashing::hash_of(&(doc.app_id, &doc.document_id));
+        let doc_power = <KPDocumentPowerByIdHash<T>>::take(&doc_key).total();
+        Self::deposit_document_power_changed(doc, doc_power, 0);
+
+        let cart_id = match Self::document_cart_id(doc) {
+            Some(cart_id) => cart_id,
+            None => {
+                let power = <KPMiscDocumentPowerByIdHash<T>>::take(&doc_key);
+                if power > 0 {
+                    Self::deposit_event(RawEvent::PowerChanged(
+                        Self::convert_account(&doc.owner),
+                        doc.app_id,
+                        PowerTarget::MiscDocument(doc.document_id.clone()),
+                        power,
+                        0,
+                    ));
+                }
+                return;
+            }
+        };
+
+        let commodity_key = T::Hashing::hash_of(&(doc.app_id, cart_id));
+        if !<KPPurchasePowerByIdHash<T>>::contains_key(&commodity_key)
+            || <KPPurchaseBlackList<T>>::contains_key(&commodity_key)
+        {
+            return;
+        }
+
+        let mut power_set = <KPPurchasePowerByIdHash<T>>::get(&commodity_key);
+        if doc.document_type == DocumentType::ProductIdentify {
+            power_set.1 = Default::default();
+        } else {
+            power_set.2 = Default::default();
+        }
+
+        let owner = Self::convert_account(&doc.owner);
+        if power_set.1 == Default::default() && power_set.2 == Default::default() {
+            let power = Self::get_purchase_power(&commodity_key);
+            Self::clear_purchase_power(&commodity_key);
+            <MinerPowerByAccount<T>>::mutate(&owner, |pow| *pow = pow.saturating_sub(power));
+            Self::remove_leader_board_item(doc.app_id, &doc.model_id, cart_id);
+            Self::remove_leader_board_item(doc.app_id, &vec![], cart_id);
+            Self::deposit_event(RawEvent::PowerChanged(
+                owner,
+                doc.app_id,
+                PowerTarget::Commodity(cart_id.clone()),
+                power,
+                0,
+            ));
+        } else {
+            Self::update_purchase_power(
+                &commodity_key,
+                &power_set,
+                doc.app_id,
+                &doc.model_id,
+                cart_id,
+                &owner,
+            );
+        }
+    }
+
+    // Slash the power a hidden document brought its author, the whole commodity for
+    // identify and try documents.
+    fn slash_document_power(doc: &KPDocumentData<T::AccountId, T::Hash>) {
+        let owner = Self::convert_account(&doc.owner);
+        let doc_key = T::Hashing::hash_of(&(doc.app_id, &doc.document_id));
+
+        let (target, power) = match Self::document_cart_id(doc) {
+            Some(cart_id) => {
+                let commodity_key = T::Hashing::hash_of(&(doc.app_id, cart_id));
+                let power = Self::slash_power(&commodity_key, &owner);
+                Self::remove_leader_board_item(doc.app_id, &doc.model_id, cart_id);
+                Self::remove_leader_board_item(doc.app_id, &vec![], cart_id);
+                (PowerTarget::Commodity(cart_id.clone()), power)
+            }
+            None => {
+                let power = <KPDocumentPowerByIdHash<T>>::get(&doc_key)
+                    .total()
+                    .max(<KPMiscDocumentPowerByIdHash<T>>::get(&doc_key));
+                <KPMiscDocumentPowerByIdHash<T>>::remove(&doc_key);
+                <AccountStatisticsMap<T>>::mutate(&owner, |info| {
+                    info.slash_kp_total += power;
+                });
+                (PowerTarget::Document(doc.document_id.clone()), power)
+            }
+        };
+
+        <KPDocumentPowerByIdHash<T>>::remove(&doc_key);
+        Self::deposit_event(RawEvent::PowerSlashed(owner, doc.app_id, target, power));
+    }
+
+    // Take a hidden comment out of the comment records of its author and document, then
+    // recompute the attend power of both from what is left.
+    fn slash_comment_power(comment: &KPCommentData<T::AccountId, T::Hash>) {
+        let doc_key = T::Hashing::hash_of(&(comment.app_id, &comment.document_id));
+        let mut doc = Self::kp_document_data_by_idhash(&doc_key);
+        let comment_account_key = T::Hashing::hash_of(&(comment.app_id, &comment.sender));
+        let mut account = Self::kp_comment_account_record_map(&comment_account_key);
+
+        account.count = account.count.saturating_sub(1);
+        account.fees = account.fees.saturating_sub(comment.comment_fee);
+        doc.comment_count = doc.comment_count.saturating_sub(1);
+        doc.comment_total_fee = doc.comment_total_fee.saturating_sub(comment.comment_fee);
+        if comment.comment_trend == 0 {
+            account.positive_count = account.positive_count.saturating_sub(1);
+            doc.comment_positive_count = doc.comment_positive_count.saturating_sub(1);
+        }
+
+        let params = Self::power_params();
+        let (account_attend_weight, comment_power_weight, doc_comment_top_weight, _) =
+            Self::document_weights(&params, &doc.document_type);
+
+        let account_comment_power = if account.count == 0 {
+            0
+        } else {
+            Self::compute_attend_power(
+                &params,
+                Self::compute_comment_action_rate(
+                    &Self::comment_max_info_per_account_map(comment.app_id),
+                    account.count,
+                    account.fees,
+                    account.positive_count,
+                    account.fees / account.count,
+                ),
+                100,
+                account_attend_weight,
+            )
+        };
+        let doc_comment_power = if doc.comment_count == 0 {
+            0
+        } else {
+            Self::compute_attend_power(
+                &params,
+                Self::compute_comment_action_rate(
+                    &<DocumentCommentPowerBase<T>>::get(&doc_key),
+                    doc.comment_count,
+                    doc.comment_total_fee,
+                    doc.comment_positive_count,
+                    doc.comment_total_fee / doc.comment_count,
+                ),
+                comment_power_weight,
+                doc_comment_top_weight,
+            )
+        };
+
+        <KPDocumentDataByIdHash<T>>::insert(&doc_key, &doc);
+        <KPCommentAccountRecordMap<T>>::insert(&comment_account_key, &account);
+
+        // the comment leaves the document lottery
+        <DocumentCommentsAccountPool<T>>::mutate(&doc_key, |pool| {
+            if let Some(index) = pool.iter().position(|item| {
+                item.account == comment.sender && item.cash_cost == comment.comment_fee
+            }) {
+                pool.remove(index);
+            }
+        });
+
+        let key = T::Hashing::hash_of(&(&comment.sender, comment.app_id));
+        let attend_power = <AccountAttendPowerMap<T>>::get(&key);
+        let power = attend_power.saturating_sub(account_comment_power);
+        <AccountAttendPowerMap<T>>::insert(&key, account_comment_power);
+        <AccountStatisticsMap<T>>::mutate(&comment.sender, |info| {
+            info.slash_kp_total += power;
+        });
+        Self::deposit_event(RawEvent::PowerSlashed(
+            comment.sender.clone(),
+            comment.app_id,
+            PowerTarget::Attend,
+            power,
+        ));
+
+        // a document which is not active any more has no power to update
+        if <KPDocumentStatus<T>>::get(&doc_key) == ContentStatus::Active {
+            let mut doc_power = <KPDocumentPowerByIdHash<T>>::get(&doc_key);
+            let before = doc_power.total();
+            doc_power.attend = doc_comment_power;
+            <KPDocumentPowerByIdHash<T>>::insert(&doc_key, &doc_power);
+            Self::deposit_document_power_changed(&doc, before, doc_power.total());
+            Self::process_commodity_power(&doc);
+        }
+    }
+
+    fn slash_power(cart_key: &T::Hash, power_owner: &T::AccountId) -> PowerSize {
         let cart_power = Self::get_purchase_power(cart_key);
         print("slash_power");
         print(cart_power);
@@ -4459,6 +5882,8 @@ impl<T: Trait> Module<T> {
                 info.slash_kp_total += cart_power;
             });
         }
+
+        cart_power
     }
 
     fn model_income_stage(block: T::BlockNumber) -> (ModelIncomeStage, T::BlockNumber) {
@@ -4549,13 +5974,12 @@ impl<T: Trait> Module<T> {
             ModelDisputeType::Serious => {
                 if cycle_dispute_count >= T::ModelDisputeCycleCount::get() {
                     cancel_model_cycle_reward();
-                    <KPModelDataByIdHash<T>>::mutate(&key, |model| {
-                        model.status = ModelStatus::DISABLED;
-                    });
+                    Self::disable_model(app_id, model_id);
 
                     T::Slash::on_unbalanced(
                         T::Currency::slash_reserved(owner, <KPModelDepositMap<T>>::get(&key)).0,
                     );
+                    <KPModelDepositMap<T>>::remove(&key);
                 }
 
                 cycle_dispute_count += T::ModelDisputeCycleLv3IncreaseCount::get();
@@ -4565,11 +5989,63 @@ impl<T: Trait> Module<T> {
 
         <ModelCycleDisputeCount<T>>::insert(cycle, &key, cycle_dispute_count);
 
+        // give the owner `ModelDisputeDelayTime` to top an underfunded deposit up
+        if Self::is_valid_model(app_id, model_id) && !Self::is_model_deposit_enough(&key, owner) {
+            let when = current_block + T::ModelDisputeDelayTime::get();
+            Self::schedule_model_pre_black_list(app_id, model_id, when);
+        }
+
         // reward reporter
         let treasury_account: T::AccountId = T::TreasuryModuleId::get().into_account();
         T::Currency::transfer(&treasury_account, &reporter, reporter_reward, KeepAlive).ok();
     }
 
+    /// Whether the deposit of the model, capped by what `owner` still has reserved, is at
+    /// least half of `ModelCreateDeposit`.
+    fn is_model_deposit_enough(key: &T::Hash, owner: &T::AccountId) -> bool {
+        let deposit = min(<KPModelDepositMap<T>>::get(key), T::Currency::reserved_balance(owner));
+        deposit >= T::ModelCreateDeposit::get() / 2u32.into()
+    }
+
+    /// The first block from `when` on with room left in the pre black list, searching at
+    /// most `MAX_SCHEDULE_PROBE` blocks.
+    fn pre_black_list_slot(when: T::BlockNumber) -> Option<T::BlockNumber> {
+        let max = max(T::MaxPreBlackListPerBlock::get(), 1) as usize;
+        let mut when = when;
+        for _ in 0..MAX_SCHEDULE_PROBE {
+            if <ModelPreBlackList<T>>::decode_len(when).unwrap_or(0) < max {
+                return Some(when);
+            }
+            when += 1u32.into();
+        }
+        None
+    }
+
+    /// Queue the model for a deposit check at `when`, or the first later block with room left.
+    fn schedule_model_pre_black_list(app_id: u32, model_id: &Vec<u8>, when: T::BlockNumber) {
+        let key = T::Hashing::hash_of(&(app_id, model_id));
+        if <ModelPreBlackListDue<T>>::contains_key(&key) {
+            return;
+        }
+
+        if let Some(when) = Self::pre_black_list_slot(when) {
+            <ModelPreBlackList<T>>::mutate(when, |list| list.push((app_id, model_id.clone())));
+            <ModelPreBlackListDue<T>>::insert(&key, when);
+        }
+    }
+
+    fn disable_model(app_id: u32, model_id: &Vec<u8>) {
+        let key = T::Hashing::hash_of(&(app_id, model_id));
+        let model = <KPModelDataByIdHash<T>>::mutate(&key, |model| {
+            model.status = ModelStatus::DISABLED;
+            model.clone()
+        });
+
+        let owner = Self::convert_account(&model.owner);
+        T::Membership::model_disabled(&key, &owner);
+        Self::deposit_event(RawEvent::ModelDisabled(owner, app_id, model_id.clone()));
+    }
+
     fn add_model_dispute_record(
         app_id: u32,
         model_id: &Vec<u8>,
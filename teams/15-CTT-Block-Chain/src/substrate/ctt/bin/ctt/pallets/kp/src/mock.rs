@@ -1,18 +1,18 @@
 // Creating mock runtime here
 
 use frame_support::{
-    impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types,
-    traits::{Contains, TestRandomness},
+    impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types, traits::Contains,
     weights::Weight,
 };
 use frame_system::{EnsureRoot, EnsureSignedBy};
 use sp_core::H256;
 use sp_runtime::{
+    curve::PiecewiseLinear,
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
     ModuleId, Perbill,
 };
-use sp_std::prelude::*;
+use sp_std::{cell::RefCell, prelude::*};
 
 use frame_system as system;
 
@@ -41,6 +41,20 @@ impl_outer_origin! {
     pub enum Origin for Test {}
 }
 
+// Same curve as the runtime, so the tests check the tuned vote factor.
+const POWER_VOTE_CURVE: PiecewiseLinear<'static> = PiecewiseLinear {
+    points: &[
+        (Perbill::from_parts(0), Perbill::from_parts(0)),
+        (Perbill::from_parts(10_000_000), Perbill::from_parts(100_000_000)),
+        (Perbill::from_parts(50_000_000), Perbill::from_parts(270_440_251)),
+        (Perbill::from_parts(162_000_000), Perbill::from_parts(522_633_744)),
+        (Perbill::from_parts(250_000_000), Perbill::from_parts(624_595_469)),
+        (Perbill::from_parts(500_000_000), Perbill::from_parts(766_364_551)),
+        (Perbill::from_parts(1_000_000_000), Perbill::from_parts(866_896_156)),
+    ],
+    maximum: Perbill::from_parts(866_896_156),
+};
+
 // For testing the pallet, we construct most of a mock runtime. This means
 // first constructing a configuration type (`Test`) which `impl`s each of the
 // configuration traits of pallets we want to use.
@@ -52,48 +66,14 @@ parameter_types! {
     pub const MaximumBlockLength: u32 = 2 * 1024;
     pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
 
-    pub const DocumentPowerWeightAttend: u8 = 40;
-    pub const DocumentPowerWeightContent: u8 = 30;
-    pub const DocumentPowerWeightJudge: u8 = 30;
-    pub const CommentPowerWeightCount: u8 = 65;
-    pub const CommentPowerWeightCost: u8 = 20;
-    pub const CommentPowerWeightPerCost: u8 = 10;
-    pub const CommentPowerWeightPositive: u8 = 5;
-    pub const CommentPowerWeight: u8 = 40;
-    pub const DocumentPublishWeightParamsRate: u8 = 60;
-    pub const DocumentPublishWeightParamsSelfRate: u8 = 40;
-    pub const DocumentPublishWeightParamsAttendRate: u8 = 45;
-    pub const DocumentIdentifyWeightParamsRate: u8 = 50;
-    pub const DocumentIdentifyWeightCheckRate: u8 = 50;
-    pub const DocumentIdentifyWeightConsistentRate: u8 = 45;
-    pub const DocumentTryWeightBiasRate: u8 = 60;
-    pub const DocumentTryWeightTrueRate: u8 = 40;
-    pub const DocumentTryWeightConsistentRate: u8 = 45;
-    pub const TopWeightProductPublish: u8 = 15;
-    pub const TopWeightDocumentIdentify: u8 = 25;
-    pub const TopWeightDocumentTry: u8 = 35;
-    pub const TopWeightAccountAttend: u8 = 10;
-    pub const TopWeightAccountStake: u8 = 15;
-
-    // for CM
-    pub const DocumentChooseWeightSellCount: u8 = 60;
-    pub const DocumentChooseWeightTryCount: u8 = 40;
-
-    pub const DocumentModelWeightProducerCount: u8 = 40;
-    pub const DocumentModelWeightProductCount: u8 = 60;
-
-    pub const DocumentCMPowerWeightAttend: u8 = 35;
-    pub const DocumentCMPowerWeightContent: u8 = 30;
-    pub const DocumentCMPowerWeightJudge: u8 = 30;
-    pub const CMPowerAccountAttend: u8 = 5;
-
-    pub const CommentCMPowerWeightCount: u8 = 65;
-    pub const CommentCMPowerWeightCost: u8 = 20;
-    pub const CommentCMPowerWeightPerCost: u8 = 10;
-    pub const CommentCMPowerWeightPositive: u8 = 5;
-
     pub const AppLeaderBoardInterval: u32 = 5;
     pub const AppLeaderBoardMaxPos: u32 = 5;
+    pub const AppLeaderBoardRetention: u64 = 10;
+    pub const MaxDocumentComments: u32 = 10;
+    pub const MaxCommodityTypes: u32 = 10;
+    pub const MaxModelIncomeItems: u32 = 10;
+    pub const MaxPendingDraws: u32 = 2;
+    pub const MaxDrawsPerBlock: u32 = 1;
     pub const AppFinanceExchangePeriod: u32 = 1;
 
     pub const ModelIncomeCyclePeriod: u32 = 1;
@@ -112,12 +92,29 @@ parameter_types! {
     pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
 
     pub const MaxFinanceMembers: u32 = 0;
+    pub const MaxInvestorMembers: u32 = 10;
+    pub const MaxPlatformExpertMembers: u32 = 10;
+    pub const MaxModelExpertMembers: u32 = 10;
     pub const MinFinanceMemberDeposit: u64 = 0;
+    pub const StableEscrowModuleId: ModuleId = ModuleId(*b"py/stesc");
+    pub const StableDisputeWindow: u64 = 10;
+    pub const StablePendingTimeout: u64 = 20;
+    pub const MaxStableExpiriesPerBlock: u32 = 2;
+    pub const MaxPayloadLifetime: u64 = 100;
 
     pub const RedeemFeeRate: u32 = 0;
 
     pub const CommentRewardNormalRate: u32 = 0;
     pub const CommentRewardExpertRate: u32 = 0;
+
+    pub const PowerVoteCurve: &'static PiecewiseLinear<'static> = &POWER_VOTE_CURVE;
+    pub const PowerVoteMaxPower: primitives::PowerSize = 10_000_000;
+    pub const PowerVoteMaxBoost: u32 = 15;
+    pub const ModelCreateDeposit: u64 = 100;
+    pub const MaxPreBlackListPerBlock: u32 = 2;
+    pub const MaxDocumentRevisions: u32 = 2;
+    pub const MaxModerationQueue: u32 = 2;
+    pub const ModerationReportDeposit: u64 = 10;
 }
 
 impl system::Trait for Test {
@@ -155,6 +152,25 @@ impl Contains<u64> for MockContains {
     }
 }
 
+thread_local! {
+    static EPOCH: RefCell<u64> = RefCell::new(0);
+}
+
+pub struct MockEpochRandomness;
+impl MockEpochRandomness {
+    pub fn set_epoch(epoch: u64) {
+        EPOCH.with(|v| *v.borrow_mut() = epoch);
+    }
+}
+impl EpochRandomness<H256> for MockEpochRandomness {
+    fn current_epoch() -> u64 {
+        EPOCH.with(|v| *v.borrow())
+    }
+    fn random(subject: &[u8]) -> H256 {
+        BlakeTwo256::hash_of(&(subject, Self::current_epoch()))
+    }
+}
+
 ord_parameter_types! {
     pub const One: u64 = 1;
     pub const Two: u64 = 2;
@@ -168,47 +184,20 @@ impl Trait for Test {
     type Event = TestEvent;
     type Currency = Balances;
     type Membership = members::Module<Test>;
-    type TopWeightProductPublish = TopWeightProductPublish;
-    type TopWeightDocumentIdentify = TopWeightDocumentIdentify;
-    type TopWeightDocumentTry = TopWeightDocumentTry;
-    type TopWeightAccountAttend = TopWeightAccountAttend;
-    type TopWeightAccountStake = TopWeightAccountStake;
-    type DocumentPowerWeightAttend = DocumentPowerWeightAttend;
-    type DocumentPowerWeightContent = DocumentPowerWeightContent;
-    type DocumentPowerWeightJudge = DocumentPowerWeightJudge;
-    type CommentPowerWeightCount = CommentPowerWeightCount;
-    type CommentPowerWeightCost = CommentPowerWeightCost;
-    type CommentPowerWeightPerCost = CommentPowerWeightPerCost;
-    type CommentPowerWeightPositive = CommentPowerWeightPositive;
-    type CommentPowerWeight = CommentPowerWeight;
-    type DocumentPublishWeightParamsRate = DocumentPublishWeightParamsRate;
-    type DocumentPublishWeightParamsSelfRate = DocumentPublishWeightParamsSelfRate;
-    type DocumentPublishWeightParamsAttendRate = DocumentPublishWeightParamsAttendRate;
-    type DocumentIdentifyWeightParamsRate = DocumentIdentifyWeightParamsRate;
-    type DocumentIdentifyWeightCheckRate = DocumentIdentifyWeightCheckRate;
-    type DocumentIdentifyWeightConsistentRate = DocumentIdentifyWeightConsistentRate;
-    type DocumentTryWeightBiasRate = DocumentTryWeightBiasRate;
-    type DocumentTryWeightTrueRate = DocumentTryWeightTrueRate;
-    type DocumentTryWeightConsistentRate = DocumentTryWeightConsistentRate;
-    type DocumentChooseWeightSellCount = ();
-    type DocumentChooseWeightTryCount = ();
-    type DocumentModelWeightProducerCount = ();
-    type DocumentModelWeightProductCount = ();
-    type DocumentCMPowerWeightAttend = ();
-    type DocumentCMPowerWeightContent = ();
-    type DocumentCMPowerWeightJudge = ();
-    type CommentCMPowerWeightCount = ();
-    type CommentCMPowerWeightCost = ();
-    type CommentCMPowerWeightPerCost = ();
-    type CommentCMPowerWeightPositive = ();
-    type CMPowerAccountAttend = ();
-    type ModelCreateDeposit = ();
+    type PayloadVerify = members::Module<Test>;
+    type ModelCreateDeposit = ModelCreateDeposit;
     type ModelCycleIncomeRewardTotal = ();
     type KptExchangeMinRate = ();
     type Slash = ();
     type AppLeaderBoardInterval = AppLeaderBoardInterval;
     type AppLeaderBoardMaxPos = AppLeaderBoardMaxPos;
-    type Randomness = TestRandomness;
+    type AppLeaderBoardRetention = AppLeaderBoardRetention;
+    type MaxDocumentComments = MaxDocumentComments;
+    type MaxCommodityTypes = MaxCommodityTypes;
+    type MaxModelIncomeItems = MaxModelIncomeItems;
+    type MaxPendingDraws = MaxPendingDraws;
+    type MaxDrawsPerBlock = MaxDrawsPerBlock;
+    type EpochRandomness = MockEpochRandomness;
     type FinTreasuryModuleId = TreasuryModuleId;
     type ModTreasuryModuleId = TreasuryModuleId;
     type TechTreasuryModuleId = TreasuryModuleId;
@@ -234,6 +223,15 @@ impl Trait for Test {
 
     type CommentRewardNormalRate = CommentRewardNormalRate;
     type CommentRewardExpertRate = CommentRewardExpertRate;
+
+    type PowerVoteCurve = PowerVoteCurve;
+    type PowerVoteMaxPower = PowerVoteMaxPower;
+    type PowerVoteMaxBoost = PowerVoteMaxBoost;
+    type MaxPreBlackListPerBlock = MaxPreBlackListPerBlock;
+    type MaxDocumentRevisions = MaxDocumentRevisions;
+    type MaxModerationQueue = MaxModerationQueue;
+    type ModerationReportDeposit = ModerationReportDeposit;
+    type WeightInfo = ();
 }
 
 impl pallet_balances::Trait for Test {
@@ -253,6 +251,16 @@ impl members::Trait for Test {
     type ModTreasuryModuleId = TreasuryModuleId;
     type MaxFinanceMembers = MaxFinanceMembers;
     type MinFinanceMemberDeposit = MinFinanceMemberDeposit;
+    type MaxInvestorMembers = MaxInvestorMembers;
+    type MaxPlatformExpertMembers = MaxPlatformExpertMembers;
+    type MaxModelExpertMembers = MaxModelExpertMembers;
+    type StableEscrowModuleId = StableEscrowModuleId;
+    type StableDisputeWindow = StableDisputeWindow;
+    type StablePendingTimeout = StablePendingTimeout;
+    type MaxStableExpiriesPerBlock = MaxStableExpiriesPerBlock;
+    type MaxPayloadLifetime = MaxPayloadLifetime;
+    type TechMemberOrigin = EnsureRoot<u64>;
+    type WeightInfo = ();
 }
 pub type System = system::Module<Test>;
 pub type Balances = pallet_balances::Module<Test>;
@@ -1,4 +1,4 @@
-use frame_support::{assert_err, assert_ok, dispatch};
+use frame_support::{assert_err, assert_ok, dispatch, traits::OnInitialize};
 use frame_system::RawOrigin;
 use sp_core::H256;
 use sp_io::hashing::blake2_256;
@@ -15,7 +15,15 @@ fn kp_account_power() {
         let balance: u64 = 1000_000_000;
         let factor = KpModule::power_factor(200000);
         let converted = KpModule::balance_apply_power(balance, factor);
-        assert!(converted == 3252747248u64);
+        assert!(converted == 3139150930u64);
+        let converted = KpModule::balance_apply_power(balance, KpModule::power_factor(100000));
+        assert!(converted == 2500_000_000u64);
+
+        // no power votes with the bare stake, the boost saturates at the curve end
+        let converted = KpModule::balance_apply_power(balance, KpModule::power_factor(0));
+        assert!(converted == balance);
+        let converted = KpModule::balance_apply_power(balance, KpModule::power_factor(50_000_000));
+        assert!(converted == 14003442340u64);
 
         let test_list = vec![1, 23, 34, 67, 88, 200, 300];
         assert!(KpModule::binary_search_closet(&test_list, &32) == 2);
@@ -27,3 +35,699 @@ fn kp_account_power() {
         assert!(KpModule::binary_search_closet(&test_list, &300) == 6);
     });
 }
+
+#[test]
+fn model_pre_black_list_disables_underfunded_models() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        // models are owned by the default auth account, which is account 0
+        let owner = 0u64;
+        Balances::make_free_balance_be(&owner, 1000);
+        assert_ok!(<Balances as ReservableCurrency<u64>>::reserve(&owner, 200));
+
+        let models: Vec<Vec<u8>> = vec![b"m1".to_vec(), b"m2".to_vec(), b"m3".to_vec(), b"m4".to_vec()];
+        for model_id in models.iter() {
+            let key = <Test as system::Trait>::Hashing::hash_of(&(app_id, model_id));
+            <KPModelDataByIdHash<Test>>::insert(
+                &key,
+                KPModelData {
+                    app_id,
+                    model_id: model_id.clone(),
+                    ..Default::default()
+                },
+            );
+            // m4 keeps its whole deposit, the others are below half of ModelCreateDeposit
+            let deposit = if model_id == &models[3] { 100 } else { 40 };
+            <KPModelDepositMap<Test>>::insert(&key, deposit);
+
+            KpModule::model_dispute(app_id, model_id, ModelDisputeType::NoneIntendNormal, &owner, &9);
+            // disputes do not slash the deposit
+            assert!(KpModule::kp_model_deposit_map(&key) == deposit);
+        }
+
+        // only two checks fit in a block, the third one moves to the next block,
+        // m4 is not queued at all
+        assert!(KpModule::model_black_list_pre(1).len() == 2);
+        assert!(KpModule::model_black_list_pre(2).len() == 1);
+
+        // m2 gets its deposit back before the check
+        let key = <Test as system::Trait>::Hashing::hash_of(&(app_id, &models[1]));
+        <KPModelDepositMap<Test>>::insert(&key, 60);
+
+        KpModule::on_initialize(1);
+        assert!(!KpModule::is_valid_model(app_id, &models[0]));
+        assert!(KpModule::is_valid_model(app_id, &models[1]));
+        assert!(KpModule::is_valid_model(app_id, &models[2]));
+        assert!(KpModule::model_black_list_pre(1).is_empty());
+        assert!(KpModule::model_black_list_pre_due(&key).is_none());
+
+        // m3 is topped up in the map, but the owner no longer has it reserved
+        let key = <Test as system::Trait>::Hashing::hash_of(&(app_id, &models[2]));
+        <KPModelDepositMap<Test>>::insert(&key, 100);
+        <Balances as ReservableCurrency<u64>>::unreserve(&owner, 170);
+
+        KpModule::on_initialize(2);
+        assert!(!KpModule::is_valid_model(app_id, &models[2]));
+        assert!(KpModule::is_valid_model(app_id, &models[3]));
+    });
+}
+
+#[test]
+fn model_pre_black_list_gives_up_when_full() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        let model_id = b"m1".to_vec();
+        let key = <Test as system::Trait>::Hashing::hash_of(&(app_id, &model_id));
+        <KPModelDataByIdHash<Test>>::insert(
+            &key,
+            KPModelData {
+                app_id,
+                model_id: model_id.clone(),
+                ..Default::default()
+            },
+        );
+
+        // every block the probe looks at is already full
+        let full = vec![(app_id, b"other".to_vec()); MaxPreBlackListPerBlock::get() as usize];
+        for n in 1..=MAX_SCHEDULE_PROBE as u64 {
+            <ModelPreBlackList<Test>>::insert(n, full.clone());
+        }
+        assert!(KpModule::pre_black_list_slot(1).is_none());
+
+        KpModule::model_dispute(app_id, &model_id, ModelDisputeType::NoneIntendNormal, &0, &9);
+        assert!(KpModule::model_black_list_pre_due(&key).is_none());
+
+        <ModelPreBlackList<Test>>::remove(MAX_SCHEDULE_PROBE as u64);
+        assert!(KpModule::pre_black_list_slot(1) == Some(MAX_SCHEDULE_PROBE as u64));
+    });
+}
+
+#[test]
+fn serious_model_dispute_clears_deposit() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        let model_id = b"m1".to_vec();
+        let key = <Test as system::Trait>::Hashing::hash_of(&(app_id, &model_id));
+        <KPModelDataByIdHash<Test>>::insert(
+            &key,
+            KPModelData {
+                app_id,
+                model_id: model_id.clone(),
+                ..Default::default()
+            },
+        );
+        Balances::make_free_balance_be(&0, 1000);
+        assert_ok!(<Balances as ReservableCurrency<u64>>::reserve(&0, 100));
+        <KPModelDepositMap<Test>>::insert(&key, 100);
+        <ModelCycleDisputeCount<Test>>::insert(
+            KpModule::model_income_cycle_index(0),
+            &key,
+            ModelDisputeCycleCount::get(),
+        );
+
+        KpModule::model_dispute(app_id, &model_id, ModelDisputeType::Serious, &0, &9);
+        assert!(!KpModule::is_valid_model(app_id, &model_id));
+        assert!(Balances::reserved_balance(&0) == 0);
+        assert!(KpModule::kp_model_deposit_map(&key) == 0);
+    });
+}
+
+#[test]
+fn leader_board_history_pages_and_prunes() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        let model_id = b"m1".to_vec();
+        let board_key = <Test as system::Trait>::Hashing::hash_of(&(app_id, &model_id));
+        for block in 0..13u64 {
+            KpModule::add_leader_board_history(
+                app_id,
+                &model_id,
+                block,
+                LeaderBoardResult {
+                    accounts: vec![block],
+                    board: vec![],
+                },
+                0,
+                H256::default(),
+                vec![],
+            );
+        }
+
+        // three records are out of the window of 10
+        assert!(KpModule::app_leader_board_epochs(&board_key) == (3, 13));
+        assert!(KpModule::app_leader_board_history(&board_key, 2).is_none());
+        assert!(KpModule::leader_board_result(2, app_id, model_id.clone())
+            .accounts
+            .is_empty());
+        assert!(KpModule::leader_board_result(3, app_id, model_id.clone()).accounts == vec![3]);
+
+        let archive = KpModule::app_leader_board_archive(&board_key);
+        assert!(archive.count == 3);
+        assert!(archive.root != H256::default());
+
+        let page = KpModule::leader_board_history(app_id, model_id.clone(), 0, 4);
+        assert!(page.first == 3);
+        assert!(page.next == Some(7));
+        assert!(page.pruned_root == archive.root);
+        assert!(page.records.iter().map(|r| r.epoch).collect::<Vec<_>>() == vec![3, 4, 5, 6]);
+
+        let page = KpModule::leader_board_history(app_id, model_id.clone(), 7, 100);
+        assert!(page.next.is_none());
+        assert!(page.records.len() == 6);
+
+        // pruning one more record moves the root
+        KpModule::add_leader_board_history(
+            app_id,
+            &model_id,
+            13,
+            LeaderBoardResult {
+                accounts: vec![13],
+                board: vec![],
+            },
+            0,
+            H256::default(),
+            vec![],
+        );
+        assert!(KpModule::app_leader_board_archive(&board_key).count == 4);
+        assert!(KpModule::app_leader_board_archive(&board_key).root != archive.root);
+    });
+}
+
+#[test]
+fn legacy_leader_board_records_migrate_in_batches() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        let model_id = b"m1".to_vec();
+        let board_key = <Test as system::Trait>::Hashing::hash_of(&(app_id, &model_id));
+        let count = LEADER_BOARD_MIGRATION_BATCH as u64 + 1;
+        let mut keys = vec![];
+        for block in 0..count {
+            <AppLeaderBoardRcord<Test>>::insert(
+                KpModule::leader_record_key(app_id, block, &model_id),
+                LeaderBoardResult {
+                    accounts: vec![block],
+                    board: vec![],
+                },
+            );
+            keys.push((app_id, block, model_id.clone()));
+        }
+        <AppLeaderBoardSequenceKeys<Test>>::put(keys);
+        // an orphan without a sequence key
+        <AppLeaderBoardRcord<Test>>::insert(H256::repeat_byte(9), LeaderBoardResult::default());
+
+        KpModule::on_initialize(1);
+        assert!(KpModule::app_leader_board_sequence_keys().len() == 1);
+        assert!(<StorageVersion>::get() == Releases::V1_0_0);
+
+        KpModule::on_initialize(2);
+        assert!(KpModule::app_leader_board_sequence_keys().is_empty());
+        assert!(<AppLeaderBoardRcord<Test>>::iter().next().is_none());
+        assert!(<StorageVersion>::get() == Releases::V2_0_0);
+        assert!(KpModule::app_leader_board_epochs(&board_key).1 == count as u32);
+
+        // nothing is migrated any more
+        <AppLeaderBoardRcord<Test>>::insert(H256::repeat_byte(9), LeaderBoardResult::default());
+        KpModule::on_initialize(3);
+        assert!(<AppLeaderBoardRcord<Test>>::contains_key(H256::repeat_byte(9)));
+    });
+}
+
+#[test]
+fn leader_board_lottery_waits_for_seed_epoch() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        let model_id = b"m1".to_vec();
+        let board_key = <Test as system::Trait>::Hashing::hash_of(&(app_id, &model_id));
+        let pools = vec![
+            LeaderBoardDrawPool {
+                accounts: vec![10, 11, 12],
+                positions: vec![20, 70, 100],
+                hits: 2,
+            },
+            LeaderBoardDrawPool {
+                accounts: vec![13],
+                positions: vec![100],
+                hits: 1,
+            },
+        ];
+
+        MockEpochRandomness::set_epoch(3);
+        <AppLeaderBoardPendingDraw<Test>>::insert(
+            &board_key,
+            LeaderBoardPendingDraw {
+                app_id,
+                model_id: model_id.clone(),
+                block: 5,
+                seed_epoch: 5,
+                board: vec![],
+                pools: pools.clone(),
+            },
+        );
+        <AppLeaderBoardDrawQueue<Test>>::put(vec![board_key]);
+
+        // the seed of epoch 5 is not in use yet
+        KpModule::on_initialize(6);
+        assert!(KpModule::app_leader_board_pending_draw(&board_key).is_some());
+
+        MockEpochRandomness::set_epoch(5);
+        KpModule::on_initialize(7);
+        assert!(KpModule::app_leader_board_pending_draw(&board_key).is_none());
+        assert!(KpModule::app_leader_board_draw_queue().is_empty());
+
+        // anyone can re-run the draw from the recorded seed
+        let item = KpModule::app_leader_board_history(&board_key, 0).unwrap();
+        assert!(item.block == 5);
+        assert!(item.seed_epoch == 5);
+        assert!(item.seed != H256::default());
+        assert!(item.result.accounts.len() == 3);
+        assert!(item.pools == pools);
+        assert!(
+            item.result.accounts == KpModule::leader_board_draw_winners(&item.seed, &item.pools)
+        );
+        assert!(
+            KpModule::leader_board_result(5, app_id, model_id).accounts == item.result.accounts
+        );
+    });
+}
+
+#[test]
+fn finance_member_draw_waits_for_seed_epoch() {
+    new_test_ext().execute_with(|| {
+        let key = |id: u32| <Test as system::Trait>::Hashing::hash_of(&(1u32, id));
+        // members pallet storage `FinanceMembers`
+        let finance_members = [
+            sp_io::hashing::twox_128(b"Members"),
+            sp_io::hashing::twox_128(b"FinanceMembers"),
+        ]
+        .concat();
+
+        assert_err!(
+            KpModule::schedule_finance_member_draw(&key(1), FinanceMemberDrawKind::AppCycleIncome),
+            Error::<Test>::NotFoundValidFinanceMember
+        );
+        frame_support::storage::unhashed::put(&finance_members, &vec![20u64, 21, 22]);
+
+        MockEpochRandomness::set_epoch(3);
+        for id in 1..=2 {
+            assert_ok!(KpModule::schedule_finance_member_draw(
+                &key(id),
+                FinanceMemberDrawKind::AppCycleIncome
+            ));
+        }
+        // queued once, and no more than MaxPendingDraws
+        assert_ok!(KpModule::schedule_finance_member_draw(&key(1), FinanceMemberDrawKind::AppCycleIncome));
+        assert_err!(
+            KpModule::schedule_finance_member_draw(&key(3), FinanceMemberDrawKind::AppCycleIncome),
+            Error::<Test>::DrawQueueFull
+        );
+
+        // the seed of epoch 5 is not in use yet
+        MockEpochRandomness::set_epoch(4);
+        KpModule::on_initialize(2);
+        assert!(!<AppCycleIncomeFinanceMember<Test>>::contains_key(&key(1)));
+
+        // one draw a block
+        MockEpochRandomness::set_epoch(5);
+        KpModule::on_initialize(3);
+        assert!(<AppCycleIncomeFinanceMember<Test>>::contains_key(&key(1)));
+        assert!(!<AppCycleIncomeFinanceMember<Test>>::contains_key(&key(2)));
+        KpModule::on_initialize(4);
+        assert!(KpModule::finance_member_draw_queue().is_empty());
+
+        let draw = KpModule::finance_member_draws(&key(2)).unwrap();
+        assert!(draw.seed_epoch == 5);
+        assert!(draw.candidates == vec![20, 21, 22]);
+        assert!(KpModule::app_cycle_income_finance_member(&key(2)) == draw.member);
+        assert!(KpModule::finance_member_draw_winner(&draw.seed, &draw.candidates) == Some(draw.member));
+    });
+}
+
+#[test]
+fn document_retract_and_moderation_roll_back_power() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        let model_id = b"m1".to_vec();
+        let owner_id = AuthAccountId::from([7u8; 32]);
+        let owner = KpModule::convert_account(&owner_id);
+        let hash = |id: &[u8]| <Test as system::Trait>::Hashing::hash_of(&(app_id, &id.to_vec()));
+        let doc_power = |content| DocumentPower {
+            content,
+            ..Default::default()
+        };
+        let has_event = |event| {
+            System::events()
+                .iter()
+                .any(|record| record.event == TestEvent::kp(event))
+        };
+        System::set_block_number(1);
+
+        for (document_id, document_type, data) in vec![
+            (
+                &b"identify"[..],
+                DocumentType::ProductIdentify,
+                DocumentSpecificData::ProductIdentify(KPProductIdentifyData {
+                    cart_id: b"cart".to_vec(),
+                    ..Default::default()
+                }),
+            ),
+            (
+                &b"try"[..],
+                DocumentType::ProductTry,
+                DocumentSpecificData::ProductTry(KPProductTryData {
+                    cart_id: b"cart".to_vec(),
+                    ..Default::default()
+                }),
+            ),
+        ] {
+            <KPDocumentDataByIdHash<Test>>::insert(
+                hash(document_id),
+                KPDocumentData {
+                    app_id,
+                    document_id: document_id.to_vec(),
+                    model_id: model_id.clone(),
+                    owner: owner_id.clone(),
+                    document_type,
+                    document_data: data,
+                    ..Default::default()
+                },
+            );
+        }
+        let cart_key = hash(b"cart");
+        <KPPurchasePowerByIdHash<Test>>::insert(
+            &cart_key,
+            (doc_power(10), doc_power(20), doc_power(30), 5, 5),
+        );
+        TotalPower::put(70);
+        <MinerPowerByAccount<Test>>::insert(&owner, 70);
+
+        // only the author revises, the replaced version is kept
+        assert_err!(
+            KpModule::revise_document(
+                Origin::signed(1),
+                app_id,
+                b"identify".to_vec(),
+                H256::repeat_byte(1)
+            ),
+            Error::<Test>::NotDocumentOwner
+        );
+        assert_ok!(KpModule::revise_document(
+            Origin::signed(owner),
+            app_id,
+            b"identify".to_vec(),
+            H256::repeat_byte(1)
+        ));
+        assert!(KpModule::kp_document_revisions(hash(b"identify")).len() == 1);
+        assert!(
+            KpModule::kp_document_data_by_idhash(hash(b"identify")).content_hash
+                == H256::repeat_byte(1)
+        );
+
+        // retracting the identify document takes its part out of the commodity
+        assert_ok!(KpModule::retract_document(
+            Origin::signed(owner),
+            app_id,
+            b"identify".to_vec()
+        ));
+        assert!(KpModule::total_power() == 50);
+        assert!(KpModule::miner_power_by_account(&owner) == 50);
+        assert!(has_event(RawEvent::PowerChanged(
+            owner,
+            app_id,
+            PowerTarget::Commodity(b"cart".to_vec()),
+            70,
+            50
+        )));
+        assert_err!(
+            KpModule::revise_document(
+                Origin::signed(owner),
+                app_id,
+                b"identify".to_vec(),
+                H256::default()
+            ),
+            Error::<Test>::DocumentNotActive
+        );
+
+        // a model expert hides the reported try document and slashes the commodity
+        let target = ModerationTarget::Document(b"try".to_vec());
+        assert!(
+            KpModule::report_content(Origin::signed(2), app_id, target.clone(), H256::default())
+                .is_err()
+        );
+        Balances::make_free_balance_be(&2, 100);
+        assert_ok!(KpModule::report_content(
+            Origin::signed(2),
+            app_id,
+            target.clone(),
+            H256::default()
+        ));
+        assert_err!(
+            KpModule::report_content(Origin::signed(3), app_id, target.clone(), H256::default()),
+            Error::<Test>::ModerationAlreadyReported
+        );
+        assert_err!(
+            KpModule::moderate_content(Origin::signed(9), app_id, target.clone(), true),
+            Error::<Test>::NotModelExpert
+        );
+        <Test as Trait>::Membership::set_model_creator(&hash(&model_id), &9, false);
+        assert_ok!(KpModule::moderate_content(
+            Origin::signed(9),
+            app_id,
+            target,
+            true
+        ));
+        assert!(KpModule::total_power() == 0);
+        assert!(KpModule::miner_power_by_account(&owner) == 0);
+        assert!(KpModule::kp_document_status(hash(b"try")) == ContentStatus::Hidden);
+        assert!(KpModule::moderation_queue(hash(&model_id)).is_empty());
+        assert!(KpModule::kp_document_power(app_id, b"try".to_vec()).is_slashed);
+        assert!(has_event(RawEvent::PowerSlashed(
+            owner,
+            app_id,
+            PowerTarget::Commodity(b"cart".to_vec()),
+            50
+        )));
+        // the upheld report's deposit is returned
+        assert!(Balances::free_balance(&2) == 100);
+        assert!(Balances::reserved_balance(&2) == 0);
+    });
+}
+
+#[test]
+fn dismissed_report_slashes_deposit_and_hidden_comment_keeps_its_share_out() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        let model_id = b"m1".to_vec();
+        let hash = |id: &[u8]| <Test as system::Trait>::Hashing::hash_of(&(app_id, &id.to_vec()));
+        let comment = |comment_id: &[u8], comment_fee| KPCommentData {
+            app_id,
+            document_id: b"doc".to_vec(),
+            comment_id: comment_id.to_vec(),
+            comment_hash: H256::default(),
+            comment_fee,
+            comment_trend: 0,
+            sender: 3,
+            owner: AuthAccountId::from([3u8; 32]),
+        };
+        System::set_block_number(1);
+        Balances::make_free_balance_be(&2, 100);
+        <Test as Trait>::Membership::set_model_creator(&hash(&model_id), &9, false);
+
+        <KPDocumentDataByIdHash<Test>>::insert(
+            hash(b"doc"),
+            KPDocumentData {
+                app_id,
+                document_id: b"doc".to_vec(),
+                model_id: model_id.clone(),
+                document_type: DocumentType::ProductChoose,
+                ..Default::default()
+            },
+        );
+        for (comment_id, fee) in vec![(&b"c1"[..], 10), (&b"c2"[..], 30)] {
+            <KPCommentDataByIdHash<Test>>::insert(hash(comment_id), comment(comment_id, fee));
+            KpModule::process_comment_power(&comment(comment_id, fee));
+        }
+        let attend_key = <Test as system::Trait>::Hashing::hash_of(&(3u64, app_id));
+        let account_key = <Test as system::Trait>::Hashing::hash_of(&(app_id, 3u64));
+        let attend_power = KpModule::account_attend_power_map(&attend_key);
+
+        // an expert keeps the content, the reporter loses the deposit
+        let target = ModerationTarget::Comment(b"c2".to_vec());
+        assert_ok!(KpModule::report_content(
+            Origin::signed(2),
+            app_id,
+            target.clone(),
+            H256::default()
+        ));
+        assert!(Balances::reserved_balance(&2) == ModerationReportDeposit::get());
+        assert_ok!(KpModule::moderate_content(
+            Origin::signed(9),
+            app_id,
+            target.clone(),
+            false
+        ));
+        assert!(Balances::free_balance(&2) == 100 - ModerationReportDeposit::get());
+        assert!(Balances::reserved_balance(&2) == 0);
+        assert!(KpModule::account_attend_power_map(&attend_key) == attend_power);
+
+        // hiding the comment takes only its share out of the records
+        Balances::make_free_balance_be(&2, 100);
+        assert_ok!(KpModule::report_content(
+            Origin::signed(2),
+            app_id,
+            target.clone(),
+            H256::default()
+        ));
+        assert_ok!(KpModule::moderate_content(Origin::signed(9), app_id, target, true));
+        let account = KpModule::kp_comment_account_record_map(&account_key);
+        assert!(account.count == 1 && account.fees == 10 && account.positive_count == 1);
+        let doc = KpModule::kp_document_data_by_idhash(&hash(b"doc"));
+        assert!(doc.comment_count == 1 && doc.comment_total_fee == 10);
+        assert!(KpModule::document_comments_account_pool(&hash(b"doc")).len() == 1);
+        let slashed = KpModule::account_attend_power_map(&attend_key);
+        assert!(0 < slashed && slashed < attend_power);
+
+        // a later comment builds on what is left
+        KpModule::process_comment_power(&comment(b"c3", 30));
+        let account = KpModule::kp_comment_account_record_map(&account_key);
+        assert!(account.count == 2 && account.fees == 40);
+    });
+}
+
+#[test]
+fn retracted_or_hidden_document_returns_open_report_deposits() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        let model_id = b"m1".to_vec();
+        let owner_id = AuthAccountId::from([7u8; 32]);
+        let owner = KpModule::convert_account(&owner_id);
+        let hash = |id: &[u8]| <Test as system::Trait>::Hashing::hash_of(&(app_id, &id.to_vec()));
+        System::set_block_number(1);
+        <Test as Trait>::Membership::set_model_creator(&hash(&model_id), &9, false);
+
+        for document_id in vec![&b"d1"[..], &b"d2"[..]] {
+            <KPDocumentDataByIdHash<Test>>::insert(
+                hash(document_id),
+                KPDocumentData {
+                    app_id,
+                    document_id: document_id.to_vec(),
+                    model_id: model_id.clone(),
+                    owner: owner_id.clone(),
+                    document_type: DocumentType::ProductChoose,
+                    ..Default::default()
+                },
+            );
+            let comment_id = [document_id, &b"c"[..]].concat();
+            <KPCommentDataByIdHash<Test>>::insert(
+                hash(&comment_id),
+                KPCommentData {
+                    app_id,
+                    document_id: document_id.to_vec(),
+                    comment_id: comment_id.clone(),
+                    ..Default::default()
+                },
+            );
+        }
+        let report = |reporter, target: ModerationTarget| {
+            Balances::make_free_balance_be(&reporter, 100);
+            assert_ok!(KpModule::report_content(
+                Origin::signed(reporter),
+                app_id,
+                target,
+                H256::default()
+            ));
+            assert!(Balances::reserved_balance(&reporter) == ModerationReportDeposit::get());
+        };
+        let returned = |reporter| {
+            Balances::free_balance(&reporter) == 100 && Balances::reserved_balance(&reporter) == 0
+        };
+
+        // retracting a document drops the report on it, others stay queued
+        report(2, ModerationTarget::Document(b"d1".to_vec()));
+        report(4, ModerationTarget::Comment(b"d2c".to_vec()));
+        assert_ok!(KpModule::retract_document(Origin::signed(owner), app_id, b"d1".to_vec()));
+        assert!(returned(2));
+        assert!(System::events().iter().any(|record| record.event
+            == TestEvent::kp(RawEvent::ReportDropped(
+                2,
+                app_id,
+                ModerationTarget::Document(b"d1".to_vec())
+            ))));
+        assert!(KpModule::moderation_queue(hash(&model_id)).len() == 1);
+
+        // hiding a document drops the reports on its comments
+        report(5, ModerationTarget::Document(b"d2".to_vec()));
+        assert_ok!(KpModule::moderate_content(
+            Origin::signed(9),
+            app_id,
+            ModerationTarget::Document(b"d2".to_vec()),
+            true
+        ));
+        assert!(returned(4) && returned(5));
+        assert!(KpModule::moderation_queue(hash(&model_id)).is_empty());
+    });
+}
+
+#[test]
+fn power_params_set_by_tech_members_and_previewed() {
+    new_test_ext().execute_with(|| {
+        let app_id = 1u32;
+        let document_id = b"identify".to_vec();
+        let doc = KPDocumentData {
+            app_id,
+            document_id: document_id.clone(),
+            owner: AuthAccountId::from([7u8; 32]),
+            document_type: DocumentType::ProductIdentify,
+            document_data: DocumentSpecificData::ProductIdentify(KPProductIdentifyData {
+                goods_price: 100,
+                ident_rate: 50,
+                ident_consistence: 50,
+                seller_consistence: 50,
+                cart_id: b"cart".to_vec(),
+            }),
+            ..Default::default()
+        };
+        let key = <Test as system::Trait>::Hashing::hash_of(&(app_id, &document_id));
+        <KPDocumentDataByIdHash<Test>>::insert(&key, &doc);
+        KpModule::process_document_content_power(&doc);
+
+        // the dry run with the current weights gives the stored power
+        let stored = KpModule::kp_document_power(app_id, document_id.clone()).power;
+        assert!(stored > 0);
+        assert!(
+            KpModule::kp_document_power_with_params(
+                app_id,
+                document_id.clone(),
+                KpModule::power_params()
+            )
+            .power
+                == stored
+        );
+
+        let params = PowerParams {
+            top_weight_document_identify: 20,
+            top_weight_document_try: 50,
+            ..Default::default()
+        };
+        let preview =
+            KpModule::kp_document_power_with_params(app_id, document_id.clone(), params.clone());
+        assert!(preview.is_exist && preview.power < stored);
+
+        // each group of weights sums to 100 and only the tech members set them
+        assert_err!(
+            KpModule::set_power_params(
+                Origin::root(),
+                PowerParams {
+                    top_weight_account_stake: 6,
+                    ..Default::default()
+                }
+            ),
+            Error::<Test>::PowerParamsInvalid
+        );
+        assert!(KpModule::set_power_params(Origin::signed(1), params.clone()).is_err());
+        assert_ok!(KpModule::set_power_params(Origin::root(), params.clone()));
+        assert!(KpModule::power_params() == params);
+
+        // stored powers are only recomputed by new actions
+        assert!(KpModule::kp_document_power(app_id, document_id).power == stored);
+    });
+}
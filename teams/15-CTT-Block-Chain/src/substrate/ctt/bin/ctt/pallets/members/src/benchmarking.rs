@@ -0,0 +1,435 @@
+//! Members pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::traits::Bounded;
+
+use crate::Module as Members;
+
+const SEED: u32 = 0;
+const APP_ID: u32 = 1;
+const KEY_TYPE: KeyTypeId = KeyTypeId(*b"memb");
+
+fn funded_account<T: Trait>(name: &'static str, index: u32) -> T::AccountId {
+    let who: T::AccountId = account(name, index, SEED);
+    T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 2u32.into());
+    who
+}
+
+// Generate a key in the benchmark keystore, returns it with its chain account.
+fn auth_key<T: Trait>() -> (sr25519::Public, T::AccountId) {
+    let key = sp_io::crypto::sr25519_generate(KEY_TYPE, None);
+    let who = Members::<T>::convert_account(&AuthAccountId::from(key.clone()));
+    (key, who)
+}
+
+// Sign `params` of `call`, valid for as long as a payload may be.
+fn sign<T: Trait, P: Encode>(call: &[u8], key: &sr25519::Public, params: &P) -> sr25519::Signature {
+    let payload = Members::<T>::payload_bytes(
+        call,
+        &AuthAccountId::from(key.clone()),
+        &params.encode(),
+        Members::<T>::max_expiry(),
+    );
+    sp_io::crypto::sr25519_sign(KEY_TYPE, key, &payload)
+        .expect("key was generated in the keystore; qed")
+}
+
+// Sorted list of `n` accounts which always contains `with`.
+fn members_with<T: Trait>(name: &'static str, n: u32, with: &[T::AccountId]) -> Vec<T::AccountId> {
+    let mut members: Vec<T::AccountId> = with.to_vec();
+    for i in with.len() as u32..n {
+        members.push(account(name, i, SEED));
+    }
+    members.sort();
+    members
+}
+
+fn register_app<T: Trait>(admins: Vec<T::AccountId>, keys: Vec<T::AccountId>) {
+    <AppDataMap<T>>::insert(APP_ID, AppData::default());
+    <AppAdmins<T>>::insert(APP_ID, admins);
+    <AppKeys<T>>::insert(APP_ID, keys);
+}
+
+// An escrowed exchange of `receipt` from "payer" to "payee", the amount is held by
+// the escrow account on top of its existential deposit while it is pending.
+fn escrow_of<T: Trait>(
+    receipt: &[u8],
+    status: StableEscrowStatus,
+    confirmer: Option<T::AccountId>,
+) -> T::Hash {
+    let escrow_account = Members::<T>::stable_escrow_account();
+    let amount = T::Currency::minimum_balance() * 10u32.into();
+    if T::Currency::free_balance(&escrow_account).is_zero() {
+        T::Currency::make_free_balance_be(&escrow_account, T::Currency::minimum_balance());
+    }
+    let _ = T::Currency::deposit_creating(&escrow_account, amount);
+    let receipt = receipt.to_vec();
+    let key = Members::<T>::stable_escrow_key(APP_ID, &receipt, StableEscrowKind::Exchange);
+    <StableEscrows<T>>::insert(
+        &key,
+        StableEscrowData {
+            payer: account("payer", 0, SEED),
+            payee: account("payee", 0, SEED),
+            amount,
+            confirmer,
+            confirmed_at: <system::Module<T>>::block_number(),
+            status,
+            record: T::Hashing::hash_of(&(APP_ID, &receipt)),
+            kind: StableEscrowKind::Exchange,
+        },
+    );
+    key
+}
+
+fn escrow<T: Trait>(status: StableEscrowStatus, confirmer: Option<T::AccountId>) -> T::Hash {
+    escrow_of::<T>(b"receipt", status, confirmer)
+}
+
+benchmarks! {
+    _ { }
+
+    add_investor_member {
+        let m in 1 .. T::MaxInvestorMembers::get();
+        let admin: T::AccountId = account("admin", 0, SEED);
+        register_app::<T>(vec![admin.clone()], vec![]);
+        InvestorMembers::<T>::put(members_with::<T>("investor", m - 1, &[]));
+        let new_member: T::AccountId = account("new", 0, SEED);
+    }: _(RawOrigin::Signed(admin), APP_ID, new_member.clone())
+    verify {
+        ensure!(Members::<T>::is_investor(&new_member), "investor not added");
+    }
+
+    remove_investor_member {
+        let m in 1 .. T::MaxInvestorMembers::get();
+        let admin: T::AccountId = account("admin", 0, SEED);
+        register_app::<T>(vec![admin.clone()], vec![]);
+        let old_member: T::AccountId = account("old", 0, SEED);
+        InvestorMembers::<T>::put(members_with::<T>("investor", m, &[old_member.clone()]));
+    }: _(RawOrigin::Signed(admin), APP_ID, old_member.clone())
+    verify {
+        ensure!(!Members::<T>::is_investor(&old_member), "investor not removed");
+    }
+
+    add_finance_member {
+        let m in 1 .. T::MaxFinanceMembers::get();
+        let root: T::AccountId = account("root", 0, SEED);
+        FinanceRoot::<T>::put(&root);
+        FinanceMembers::<T>::put(members_with::<T>("finance", m - 1, &[]));
+        let member = funded_account::<T>("new", 0);
+        let params = FinanceMemberParams {
+            deposit: T::MinFinanceMemberDeposit::get(),
+            member: member.clone(),
+        };
+        let (key, _) = auth_key::<T>();
+        let sign = sign::<T, _>(b"members.add_finance_member", &key, &params);
+    }: _(RawOrigin::Signed(root), params, AuthAccountId::from(key), sign, Members::<T>::max_expiry())
+    verify {
+        ensure!(Members::<T>::is_finance_member(&member), "finance member not added");
+    }
+
+    remove_finance_member {
+        let m in 1 .. T::MaxFinanceMembers::get();
+        let root: T::AccountId = account("root", 0, SEED);
+        FinanceRoot::<T>::put(&root);
+        let old_member = funded_account::<T>("old", 0);
+        let deposit = T::MinFinanceMemberDeposit::get();
+        T::Currency::reserve(&old_member, deposit)?;
+        <FinanceMemberDeposit<T>>::insert(&old_member, deposit);
+        FinanceMembers::<T>::put(members_with::<T>("finance", m, &[old_member.clone()]));
+    }: _(RawOrigin::Signed(root), old_member.clone())
+    verify {
+        ensure!(!Members::<T>::is_finance_member(&old_member), "finance member not removed");
+    }
+
+    finance_member_add_deposit {
+        let caller = funded_account::<T>("caller", 0);
+        let deposit = T::Currency::minimum_balance() * 10u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), deposit)
+    verify {
+        ensure!(<FinanceMemberDeposit<T>>::get(&caller) == deposit, "deposit not added");
+    }
+
+    add_app_admin {
+        let (key, admin) = auth_key::<T>();
+        let caller: T::AccountId = account("app_key", 0, SEED);
+        let max = MAX_APP_KEYS as u32;
+        register_app::<T>(members_with::<T>("admin", max - 1, &[admin]), vec![caller.clone()]);
+        let member: T::AccountId = account("new", 0, SEED);
+        let params = AppKeyManageParams {
+            admin: AuthAccountId::from(key.clone()),
+            app_id: APP_ID,
+            member: member.clone(),
+        };
+        let sign = sign::<T, _>(b"members.add_app_admin", &key, &params);
+    }: _(RawOrigin::Signed(caller), params, sign, Members::<T>::max_expiry())
+    verify {
+        ensure!(Members::<T>::is_app_admin(&member, APP_ID), "app admin not added");
+    }
+
+    remove_app_admin {
+        let (key, admin) = auth_key::<T>();
+        let caller: T::AccountId = account("app_key", 0, SEED);
+        let member: T::AccountId = account("old", 0, SEED);
+        let max = MAX_APP_KEYS as u32;
+        register_app::<T>(members_with::<T>("admin", max, &[admin, member.clone()]), vec![caller.clone()]);
+        let params = AppKeyManageParams {
+            admin: AuthAccountId::from(key.clone()),
+            app_id: APP_ID,
+            member: member.clone(),
+        };
+        let sign = sign::<T, _>(b"members.remove_app_admin", &key, &params);
+    }: _(RawOrigin::Signed(caller), params, sign, Members::<T>::max_expiry())
+    verify {
+        ensure!(!Members::<T>::is_app_admin(&member, APP_ID), "app admin not removed");
+    }
+
+    add_app_key {
+        let (key, admin) = auth_key::<T>();
+        let caller: T::AccountId = account("app_key", 0, SEED);
+        let max = MAX_APP_KEYS as u32;
+        register_app::<T>(vec![admin], members_with::<T>("app_key", max - 1, &[caller.clone()]));
+        let member: T::AccountId = account("new", 0, SEED);
+        let params = AppKeyManageParams {
+            admin: AuthAccountId::from(key.clone()),
+            app_id: APP_ID,
+            member: member.clone(),
+        };
+        let sign = sign::<T, _>(b"members.add_app_key", &key, &params);
+    }: _(RawOrigin::Signed(caller), params, sign, Members::<T>::max_expiry())
+    verify {
+        ensure!(Members::<T>::is_app_identity(&member, APP_ID), "app key not added");
+    }
+
+    remove_app_key {
+        let (key, admin) = auth_key::<T>();
+        let caller: T::AccountId = account("app_key", 0, SEED);
+        let member: T::AccountId = account("old", 0, SEED);
+        let max = MAX_APP_KEYS as u32;
+        register_app::<T>(vec![admin], members_with::<T>("app_key", max, &[caller.clone(), member.clone()]));
+        let params = AppKeyManageParams {
+            admin: AuthAccountId::from(key.clone()),
+            app_id: APP_ID,
+            member: member.clone(),
+        };
+        let sign = sign::<T, _>(b"members.remove_app_key", &key, &params);
+    }: _(RawOrigin::Signed(caller), params, sign, Members::<T>::max_expiry())
+    verify {
+        ensure!(!Members::<T>::is_app_identity(&member, APP_ID), "app key not removed");
+    }
+
+    add_app_platform_expert_member {
+        let m in 1 .. T::MaxPlatformExpertMembers::get();
+        let admin: T::AccountId = account("admin", 0, SEED);
+        register_app::<T>(vec![admin.clone()], vec![]);
+        <AppPlatformExpertMembers<T>>::insert(APP_ID, members_with::<T>("expert", m - 1, &[]));
+        let new_member: T::AccountId = account("new", 0, SEED);
+    }: _(RawOrigin::Signed(admin), APP_ID, new_member.clone())
+    verify {
+        ensure!(Members::<T>::is_platform_expert(&new_member, APP_ID), "platform expert not added");
+    }
+
+    remove_app_platform_expert_member {
+        let m in 1 .. T::MaxPlatformExpertMembers::get();
+        let admin: T::AccountId = account("admin", 0, SEED);
+        register_app::<T>(vec![admin.clone()], vec![]);
+        let old_member: T::AccountId = account("old", 0, SEED);
+        <AppPlatformExpertMembers<T>>::insert(APP_ID, members_with::<T>("expert", m, &[old_member.clone()]));
+    }: _(RawOrigin::Signed(admin), APP_ID, old_member.clone())
+    verify {
+        ensure!(!Members::<T>::is_platform_expert(&old_member, APP_ID), "platform expert not removed");
+    }
+
+    add_expert_member {
+        let m in 1 .. T::MaxModelExpertMembers::get();
+        register_app::<T>(vec![], vec![]);
+        let model_id = b"model".to_vec();
+        let model_key = T::Hashing::hash_of(&(APP_ID, &model_id));
+        let (key, creator) = auth_key::<T>();
+        <ModelCreators<T>>::insert(&model_key, &creator);
+        <ExpertMembers<T>>::insert(&model_key, members_with::<T>("expert", m - 1, &[]));
+        let params = ModelExpertAddMemberParams {
+            app_id: APP_ID,
+            model_id: model_id.clone(),
+            kpt_profit_rate: 10,
+        };
+        let sign = sign::<T, _>(b"members.add_expert_member", &key, &params);
+        let caller: T::AccountId = account("new", 0, SEED);
+    }: _(RawOrigin::Signed(caller.clone()), params, AuthAccountId::from(key), sign, Members::<T>::max_expiry())
+    verify {
+        ensure!(Members::<T>::is_model_expert(&caller, APP_ID, &model_id), "expert not added");
+    }
+
+    remove_expert_member {
+        let m in 1 .. T::MaxModelExpertMembers::get();
+        let model_id = b"model".to_vec();
+        let model_key = T::Hashing::hash_of(&(APP_ID, &model_id));
+        let (creator_key, creator) = auth_key::<T>();
+        let (admin_key, admin) = auth_key::<T>();
+        register_app::<T>(vec![admin], vec![]);
+        <ModelCreators<T>>::insert(&model_key, &creator);
+        let member: T::AccountId = account("old", 0, SEED);
+        <ExpertMembers<T>>::insert(&model_key, members_with::<T>("expert", m, &[member.clone()]));
+        let params = ModelExpertDelMemberParams {
+            app_id: APP_ID,
+            model_id: model_id.clone(),
+            member: member.clone(),
+        };
+        let creator_sign = sign::<T, _>(b"members.remove_expert_member", &creator_key, &params);
+        let admin_sign = sign::<T, _>(b"members.remove_expert_member", &admin_key, &params);
+        let caller: T::AccountId = account("caller", 0, SEED);
+    }: _(
+        RawOrigin::Signed(caller),
+        params,
+        AuthAccountId::from(creator_key),
+        creator_sign,
+        AuthAccountId::from(admin_key),
+        admin_sign,
+        Members::<T>::max_expiry()
+    )
+    verify {
+        ensure!(!Members::<T>::is_model_expert(&member, APP_ID, &model_id), "expert not removed");
+    }
+
+    air_drop_new_user_benefit {
+        register_app::<T>(vec![], vec![]);
+        let caller = funded_account::<T>("caller", 0);
+        let receiver: T::AccountId = account("receiver", 0, SEED);
+        let amount = T::Currency::minimum_balance() * 10u32.into();
+        let user_id = b"user".to_vec();
+    }: _(RawOrigin::Signed(caller), APP_ID, user_id.clone(), receiver, amount)
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &user_id));
+        ensure!(<NewAccountBenefitRecords<T>>::contains_key(&key), "benefit not dropped");
+    }
+
+    stable_exchange {
+        register_app::<T>(vec![], vec![]);
+        AppStableEscrow::insert(APP_ID, true);
+        let caller = funded_account::<T>("caller", 0);
+        let receiver: T::AccountId = account("receiver", 0, SEED);
+        let amount = T::Currency::minimum_balance() * 10u32.into();
+        let cash_receipt = b"receipt".to_vec();
+    }: _(RawOrigin::Signed(caller), amount, receiver, APP_ID, cash_receipt.clone())
+    verify {
+        let key = T::Hashing::hash_of(&(APP_ID, &cash_receipt));
+        ensure!(<StableExchangeRecords<T>>::contains_key(&key), "exchange not recorded");
+        let escrow_key = Members::<T>::stable_escrow_key(APP_ID, &cash_receipt, StableEscrowKind::Exchange);
+        ensure!(<StableEscrows<T>>::contains_key(&escrow_key), "exchange not escrowed");
+    }
+
+    stable_redeem {
+        register_app::<T>(vec![], vec![]);
+        AppStableEscrow::insert(APP_ID, true);
+        let caller = funded_account::<T>("caller", 0);
+        let redeem_account: T::AccountId = account("redeem", 0, SEED);
+        <AppRedeemAccount<T>>::insert(APP_ID, &redeem_account);
+        let cash_receipt = b"receipt".to_vec();
+        let key = T::Hashing::hash_of(&(APP_ID, &cash_receipt));
+        <StableExchangeRecords<T>>::insert(&key, StableExchangeData {
+            receiver: caller.clone(),
+            amount: T::Currency::minimum_balance() * 10u32.into(),
+            redeemed: false,
+        });
+    }: _(RawOrigin::Signed(caller), APP_ID, cash_receipt)
+    verify {
+        ensure!(<StableExchangeRecords<T>>::get(&key).redeemed, "receipt not redeemed");
+    }
+
+    set_app_redeem_account {
+        let admin: T::AccountId = account("admin", 0, SEED);
+        register_app::<T>(vec![admin.clone()], vec![]);
+        let redeem_account: T::AccountId = account("redeem", 0, SEED);
+    }: _(RawOrigin::Signed(admin), APP_ID, redeem_account.clone())
+    verify {
+        ensure!(<AppRedeemAccount<T>>::get(APP_ID) == redeem_account, "redeem account not set");
+    }
+
+    set_app_stable_escrow {
+        let admin: T::AccountId = account("admin", 0, SEED);
+        register_app::<T>(vec![admin.clone()], vec![]);
+    }: _(RawOrigin::Signed(admin), APP_ID, true)
+    verify {
+        ensure!(AppStableEscrow::get(APP_ID), "escrow not enabled");
+    }
+
+    confirm_stable_receipt {
+        let member = funded_account::<T>("finance", 0);
+        <Members<T> as Membership<_, _, _>>::add_finance_member(&member);
+        let key = escrow::<T>(StableEscrowStatus::Pending, None);
+    }: _(RawOrigin::Signed(member), APP_ID, b"receipt".to_vec(), StableEscrowKind::Exchange)
+    verify {
+        ensure!(
+            <StableEscrows<T>>::get(&key).map(|e| e.status) == Some(StableEscrowStatus::Confirmed),
+            "receipt not confirmed"
+        );
+    }
+
+    open_stable_dispute {
+        let member: T::AccountId = account("finance", 0, SEED);
+        let key = escrow::<T>(StableEscrowStatus::Confirmed, Some(member));
+        let payer: T::AccountId = account("payer", 0, SEED);
+    }: _(RawOrigin::Signed(payer), APP_ID, b"receipt".to_vec(), StableEscrowKind::Exchange)
+    verify {
+        ensure!(
+            <StableEscrows<T>>::get(&key).map(|e| e.status) == Some(StableEscrowStatus::Disputed),
+            "dispute not opened"
+        );
+    }
+
+    resolve_stable_dispute {
+        // the confirmer is slashed
+        let member = funded_account::<T>("finance", 0);
+        <Members<T> as Membership<_, _, _>>::add_finance_member(&member);
+        let key = escrow::<T>(StableEscrowStatus::Disputed, Some(member));
+        let origin = T::TechMemberOrigin::successful_origin();
+    }: _(origin, APP_ID, b"receipt".to_vec(), StableEscrowKind::Exchange, true)
+    verify {
+        ensure!(
+            <StableEscrows<T>>::get(&key).map(|e| e.status) == Some(StableEscrowStatus::Resolved),
+            "dispute not resolved"
+        );
+    }
+
+    vote_stable_dispute {
+        let m in 4 .. T::MaxFinanceMembers::get();
+        let member = funded_account::<T>("finance", 0);
+        <Members<T> as Membership<_, _, _>>::add_finance_member(&member);
+        let voter: T::AccountId = account("voter", 0, SEED);
+        let members = members_with::<T>("finance", m, &[member.clone(), voter.clone()]);
+        FinanceMembers::<T>::put(&members);
+        let key = escrow::<T>(StableEscrowStatus::Disputed, Some(member.clone()));
+
+        // the last vote settles the dispute
+        let ayes: Vec<T::AccountId> = members
+            .into_iter()
+            .filter(|a| *a != member && *a != voter)
+            .take(m as usize / 2)
+            .collect();
+        <StableDisputeVotes<T>>::insert(&key, (ayes, Vec::<T::AccountId>::new()));
+    }: _(RawOrigin::Signed(voter), APP_ID, b"receipt".to_vec(), StableEscrowKind::Exchange, true)
+    verify {
+        ensure!(
+            <StableEscrows<T>>::get(&key).map(|e| e.status) == Some(StableEscrowStatus::Resolved),
+            "dispute not settled"
+        );
+    }
+
+    on_initialize_stable_expiry {
+        let n in 1 .. T::MaxStableExpiriesPerBlock::get();
+        let when: T::BlockNumber = 1u32.into();
+        for i in 0 .. n {
+            let key = escrow_of::<T>(&i.encode(), StableEscrowStatus::Pending, None);
+            <StableEscrowExpiry<T>>::append(when, key);
+        }
+    }: {
+        Members::<T>::expire_stable_escrows(when);
+    }
+    verify {
+        ensure!(!<StableEscrowExpiry<T>>::contains_key(when), "expiries not taken");
+    }
+}
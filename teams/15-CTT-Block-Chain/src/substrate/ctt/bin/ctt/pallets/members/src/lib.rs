@@ -8,14 +8,19 @@ use frame_support::{
     decl_error, decl_event, decl_module, decl_storage,
     dispatch::DispatchResult,
     ensure,
-    traits::{Currency, ExistenceRequirement::KeepAlive, Get, ReservableCurrency},
+    traits::{
+        Currency, EnsureOrigin,
+        ExistenceRequirement::KeepAlive,
+        Get, ReservableCurrency,
+    },
+    weights::Weight,
 };
 use frame_system::{self as system, ensure_signed};
-use primitives::{AuthAccountId, Membership};
+use primitives::{AuthAccountId, Membership, SignedPayload, SignedPayloadVerify};
 use sp_core::sr25519;
 use sp_runtime::{
     print,
-    traits::{AccountIdConversion, Hash, Verify},
+    traits::{AccountIdConversion, Hash, Saturating, Verify, Zero},
     ModuleId, MultiSignature, RuntimeDebug,
 };
 use sp_std::cmp::*;
@@ -24,6 +29,35 @@ use sp_std::prelude::*;
 #[cfg(test)]
 mod tests;
 
+mod benchmarking;
+mod default_weights;
+
+pub trait WeightInfo {
+    fn add_investor_member(m: u32) -> Weight;
+    fn remove_investor_member(m: u32) -> Weight;
+    fn add_finance_member(m: u32) -> Weight;
+    fn remove_finance_member(m: u32) -> Weight;
+    fn finance_member_add_deposit() -> Weight;
+    fn add_app_admin() -> Weight;
+    fn remove_app_admin() -> Weight;
+    fn add_app_key() -> Weight;
+    fn remove_app_key() -> Weight;
+    fn add_app_platform_expert_member(m: u32) -> Weight;
+    fn remove_app_platform_expert_member(m: u32) -> Weight;
+    fn add_expert_member(m: u32) -> Weight;
+    fn remove_expert_member(m: u32) -> Weight;
+    fn air_drop_new_user_benefit() -> Weight;
+    fn stable_exchange() -> Weight;
+    fn stable_redeem() -> Weight;
+    fn set_app_redeem_account() -> Weight;
+    fn set_app_stable_escrow() -> Weight;
+    fn confirm_stable_receipt() -> Weight;
+    fn open_stable_dispute() -> Weight;
+    fn resolve_stable_dispute() -> Weight;
+    fn vote_stable_dispute(m: u32) -> Weight;
+    fn on_initialize_stable_expiry(n: u32) -> Weight;
+}
+
 type BalanceOf<T> =
     <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 
@@ -51,6 +85,49 @@ impl<T: Trait> Default for StableExchangeData<T> {
     }
 }
 
+/// Storage layout versions of the pallet.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+    V1_0_0,
+    /// The stable escrow account holds the existential deposit.
+    V2_0_0,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1_0_0
+    }
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum StableEscrowKind {
+    Exchange,
+    Redeem,
+}
+
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum StableEscrowStatus {
+    /// Amount is held by the escrow account, waiting for a finance member
+    Pending,
+    /// Amount is released to payee, disputable until the window closes
+    Confirmed,
+    Disputed,
+    Resolved,
+}
+
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub struct StableEscrowData<AccountId, Balance, BlockNumber, Hash> {
+    payer: AccountId,
+    payee: AccountId,
+    amount: Balance,
+    confirmer: Option<AccountId>,
+    confirmed_at: BlockNumber,
+    status: StableEscrowStatus,
+    /// Key of the `StableExchangeRecords` entry the escrow belongs to
+    record: Hash,
+    kind: StableEscrowKind,
+}
+
 #[derive(Encode, Decode, Clone, Default, PartialEq, RuntimeDebug)]
 pub struct ModelExpertAddMemberParams {
     app_id: u32,
@@ -85,15 +162,42 @@ pub trait Trait: system::Trait {
     type ModTreasuryModuleId: Get<ModuleId>;
     type MaxFinanceMembers: Get<u32>;
     type MinFinanceMemberDeposit: Get<BalanceOf<Self>>;
+
+    /// The maximum number of investor members.
+    type MaxInvestorMembers: Get<u32>;
+    /// The maximum number of platform expert members of an app.
+    type MaxPlatformExpertMembers: Get<u32>;
+    /// The maximum number of expert members of a model.
+    type MaxModelExpertMembers: Get<u32>;
+
+    /// The account holding escrowed stable exchange and redeem amounts.
+    type StableEscrowModuleId: Get<ModuleId>;
+    /// How long a confirmed escrow receipt can be disputed.
+    type StableDisputeWindow: Get<Self::BlockNumber>;
+    /// How long an escrow waits for a finance member before it is refunded.
+    type StablePendingTimeout: Get<Self::BlockNumber>;
+    /// The maximum number of escrows refunded in one block.
+    type MaxStableExpiriesPerBlock: Get<u32>;
+    /// How far ahead of the current block a signed payload may expire.
+    type MaxPayloadLifetime: Get<Self::BlockNumber>;
+    /// The origin which resolves stable exchange disputes besides a finance member vote.
+    type TechMemberOrigin: EnsureOrigin<Self::Origin>;
+
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
 }
 
 const MAX_APP_KEYS: usize = 16;
 
+/// Blocks from the due one searched for room in a per-block queue before giving up.
+const MAX_SCHEDULE_PROBE: u32 = 64;
+
 decl_event!(
     pub enum Event<T>
     where
         Balance = BalanceOf<T>,
         <T as frame_system::Trait>::AccountId,
+        <T as frame_system::Trait>::Hash,
     {
         /// Added a member2222
         MemberAdded(AccountId),
@@ -107,6 +211,18 @@ decl_event!(
         AppRedeemAccountSet(AccountId),
         AppRedeemed(AccountId, AccountId, Balance),
         FinanceMemberDeposit(AccountId),
+        ModelDisabled(AccountId, Hash),
+        AppStableEscrowSet(u32, bool),
+        /// Payer moved the amount into escrow, key of the escrow
+        StableEscrowed(AccountId, Hash),
+        /// Finance member confirmed the receipt and released the amount
+        StableReceiptConfirmed(AccountId, Hash),
+        StableDisputeOpened(AccountId, Hash),
+        StableDisputeVoted(AccountId, Hash, bool),
+        /// Dispute closed, true if the receipt is judged false
+        StableDisputeResolved(Hash, bool),
+        /// No finance member confirmed the receipt in time, the payer is refunded
+        StableEscrowExpired(Hash),
     }
 );
 
@@ -164,6 +280,35 @@ decl_storage! {
         // app_id stash account(for redeem receiver)
         AppRedeemAccount get(fn app_redeem_account):
             map hasher(twox_64_concat) u32 => T::AccountId;
+
+        // app_id -> whether stable exchange and redeem go through escrow
+        AppStableEscrow get(fn app_stable_escrow):
+            map hasher(twox_64_concat) u32 => bool;
+
+        // app_id cash_receipt kind -> escrowed stable exchange or redeem
+        StableEscrows get(fn stable_escrows):
+            map hasher(twox_64_concat) T::Hash =>
+                Option<StableEscrowData<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>>;
+
+        // block -> escrows refunded at that block if they are still pending
+        StableEscrowExpiry get(fn stable_escrow_expiry):
+            map hasher(twox_64_concat) T::BlockNumber => Vec<T::Hash>;
+
+        // escrow key -> finance member votes of the open dispute (uphold, reject)
+        StableDisputeVotes get(fn stable_dispute_votes):
+            map hasher(twox_64_concat) T::Hash => (Vec<T::AccountId>, Vec<T::AccountId>);
+
+        // auth account -> next nonce of its signed payloads, shared by all pallets
+        PayloadNonces get(fn payload_nonce):
+            map hasher(twox_64_concat) AuthAccountId => u64;
+
+        // storage layout version, the stable escrow account is funded on the upgrade to V2_0_0
+        StorageVersion: Releases;
+    }
+    add_extra_genesis {
+        build(|_config| {
+            Module::<T>::fund_stable_escrow();
+        });
     }
 }
 
@@ -194,6 +339,15 @@ decl_error! {
         FinanceMemberSizeOver,
         FinanceMemberDepositTooLow,
         DepositTooSmall,
+        MembershipLimitReached,
+        StableEscrowNotFound,
+        StableEscrowStatusInvalid,
+        StableEscrowNotParty,
+        StableEscrowPartyCannotConfirm,
+        StableDisputeWindowClosed,
+        StableDisputeVoteRepeat,
+        /// No block within `MAX_SCHEDULE_PROBE` of the timeout has room for another expiry.
+        StableExpiryFull,
     }
 }
 
@@ -203,11 +357,6 @@ impl<T: Trait> Module<T> {
         T::AccountId::decode(&mut &tmp[..]).unwrap_or_default()
     }
 
-    fn verify_sign(pub_key: &AuthAccountId, sign: sr25519::Signature, msg: &[u8]) -> bool {
-        let ms: MultiSignature = sign.into();
-        ms.verify(msg, &pub_key)
-    }
-
     pub fn is_platform_expert(who: &T::AccountId, app_id: u32) -> bool {
         let members = <AppPlatformExpertMembers<T>>::get(app_id);
         match members.binary_search(who) {
@@ -362,6 +511,193 @@ impl<T: Trait> Module<T> {
             .map(|deposit| deposit.0.clone())
             .collect::<Vec<T::AccountId>>()
     }
+
+    pub fn stable_escrow_account() -> T::AccountId {
+        T::StableEscrowModuleId::get().into_account()
+    }
+
+    pub fn stable_escrow_key(
+        app_id: u32,
+        cash_receipt: &Vec<u8>,
+        kind: StableEscrowKind,
+    ) -> T::Hash {
+        T::Hashing::hash_of(&(app_id, cash_receipt, kind))
+    }
+
+    /// Keep the escrow account above the existential deposit, so paying out the last
+    /// escrowed amount never reaps it. Runs once, at genesis or on the upgrade to `V2_0_0`.
+    fn fund_stable_escrow() -> Weight {
+        if <StorageVersion>::get() == Releases::V2_0_0 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let escrow_account = Self::stable_escrow_account();
+        let minimum = T::Currency::minimum_balance();
+        let missing = minimum.saturating_sub(T::Currency::free_balance(&escrow_account));
+        if !missing.is_zero() {
+            // dropping the imbalance raises the total issuance by what was deposited
+            drop(T::Currency::deposit_creating(&escrow_account, missing));
+        }
+        <StorageVersion>::put(Releases::V2_0_0);
+        T::DbWeight::get().reads_writes(3, 3)
+    }
+
+    fn stable_escrow_hold(
+        key: &T::Hash,
+        record: &T::Hash,
+        kind: StableEscrowKind,
+        payer: &T::AccountId,
+        payee: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        // refund at the first block after the timeout with room left
+        let timeout = <system::Module<T>>::block_number() + T::StablePendingTimeout::get();
+        let when = Self::stable_expiry_slot(timeout).ok_or(Error::<T>::StableExpiryFull)?;
+
+        T::Currency::transfer(payer, &Self::stable_escrow_account(), amount, KeepAlive)?;
+
+        <StableEscrows<T>>::insert(
+            key,
+            StableEscrowData {
+                payer: payer.clone(),
+                payee: payee.clone(),
+                amount,
+                confirmer: None,
+                confirmed_at: 0u32.into(),
+                status: StableEscrowStatus::Pending,
+                record: *record,
+                kind,
+            },
+        );
+
+        <StableEscrowExpiry<T>>::append(when, *key);
+
+        Self::deposit_event(RawEvent::StableEscrowed(payer.clone(), *key));
+        Ok(())
+    }
+
+    /// The first block from `when` on with room left for an escrow expiry, searching at most
+    /// `MAX_SCHEDULE_PROBE` blocks.
+    fn stable_expiry_slot(when: T::BlockNumber) -> Option<T::BlockNumber> {
+        let max = max(T::MaxStableExpiriesPerBlock::get(), 1) as usize;
+        let mut when = when;
+        for _ in 0..MAX_SCHEDULE_PROBE {
+            if <StableEscrowExpiry<T>>::decode_len(when).unwrap_or(0) < max {
+                return Some(when);
+            }
+            when += 1u32.into();
+        }
+        None
+    }
+
+    /// Undo the exchange or redeem of a receipt judged false, so the receipt can be used again.
+    fn rollback_stable_record(
+        escrow: &StableEscrowData<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>,
+    ) {
+        match escrow.kind {
+            StableEscrowKind::Exchange => <StableExchangeRecords<T>>::remove(&escrow.record),
+            StableEscrowKind::Redeem => <StableExchangeRecords<T>>::mutate(&escrow.record, |record| {
+                record.redeemed = false;
+            }),
+        }
+    }
+
+    /// Give a pending escrow back to the payer.
+    fn refund_stable_escrow(
+        escrow: &StableEscrowData<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>,
+    ) -> DispatchResult {
+        T::Currency::transfer(&Self::stable_escrow_account(), &escrow.payer, escrow.amount, KeepAlive)?;
+        Self::rollback_stable_record(escrow);
+        Ok(())
+    }
+
+    /// Refund the escrows due at `n` that no finance member confirmed.
+    fn expire_stable_escrows(n: T::BlockNumber) -> Weight {
+        let due = <StableEscrowExpiry<T>>::take(n);
+        let count = due.len() as u32;
+
+        for key in due {
+            if let Some(mut escrow) = <StableEscrows<T>>::get(&key) {
+                if escrow.status == StableEscrowStatus::Pending && Self::refund_stable_escrow(&escrow).is_ok() {
+                    escrow.status = StableEscrowStatus::Resolved;
+                    <StableEscrows<T>>::insert(&key, escrow);
+                    Self::deposit_event(RawEvent::StableEscrowExpired(key));
+                }
+            }
+        }
+
+        T::WeightInfo::on_initialize_stable_expiry(count)
+    }
+
+    fn settle_stable_dispute(
+        key: &T::Hash,
+        mut escrow: StableEscrowData<T::AccountId, BalanceOf<T>, T::BlockNumber, T::Hash>,
+        upheld: bool,
+    ) -> DispatchResult {
+        let escrow_account = Self::stable_escrow_account();
+        match (&escrow.confirmer, upheld) {
+            // amount is still in escrow, give it back or pay it out
+            (None, true) => Self::refund_stable_escrow(&escrow)?,
+            (None, false) => {
+                T::Currency::transfer(&escrow_account, &escrow.payee, escrow.amount, KeepAlive)?
+            }
+            // the member confirmed a false receipt, payer is compensated from its deposit
+            (Some(member), true) => {
+                Self::slash_finance_member(member, &escrow.payer, escrow.amount)?;
+                Self::rollback_stable_record(&escrow);
+            }
+            (Some(_), false) => {}
+        }
+
+        escrow.status = StableEscrowStatus::Resolved;
+        <StableEscrows<T>>::insert(key, escrow);
+        <StableDisputeVotes<T>>::remove(key);
+
+        Self::deposit_event(RawEvent::StableDisputeResolved(*key, upheld));
+        Ok(())
+    }
+}
+
+impl<T: Trait> SignedPayloadVerify<T::BlockNumber> for Module<T> {
+    fn max_expiry() -> T::BlockNumber {
+        <system::Module<T>>::block_number().saturating_add(T::MaxPayloadLifetime::get())
+    }
+
+    fn payload_bytes(
+        call: &[u8],
+        signer: &AuthAccountId,
+        params: &[u8],
+        expiry: T::BlockNumber,
+    ) -> Vec<u8> {
+        SignedPayload {
+            call: call.to_vec(),
+            genesis_hash: <system::Module<T>>::block_hash(T::BlockNumber::zero()),
+            nonce: PayloadNonces::get(signer),
+            expiry,
+            params: params.to_vec(),
+        }
+        .signing_bytes()
+    }
+
+    fn check_payload(
+        call: &[u8],
+        signer: &AuthAccountId,
+        sign: sr25519::Signature,
+        params: &[u8],
+        expiry: T::BlockNumber,
+    ) -> bool {
+        if <system::Module<T>>::block_number() > expiry || expiry > Self::max_expiry() {
+            return false;
+        }
+
+        let payload = Self::payload_bytes(call, signer, params, expiry);
+        let ms: MultiSignature = sign.into();
+        ms.verify(&payload[..], signer)
+    }
+
+    fn use_payload(_call: &[u8], signer: &AuthAccountId, _params: &[u8], _expiry: T::BlockNumber) {
+        PayloadNonces::mutate(signer, |nonce| *nonce += 1);
+    }
 }
 
 impl<T: Trait> Membership<T::AccountId, T::Hash, BalanceOf<T>> for Module<T> {
@@ -421,6 +757,10 @@ impl<T: Trait> Membership<T::AccountId, T::Hash, BalanceOf<T>> for Module<T> {
         Self::model_add_expert(key, new_owner);
     }
 
+    fn model_disabled(key: &T::Hash, creator: &T::AccountId) {
+        Self::deposit_event(RawEvent::ModelDisabled(creator.clone(), *key));
+    }
+
     fn is_model_creator(who: &T::AccountId, app_id: u32, model_id: &Vec<u8>) -> bool {
         Self::is_model_creator(who, app_id, model_id)
     }
@@ -474,6 +814,38 @@ impl<T: Trait> Membership<T::AccountId, T::Hash, BalanceOf<T>> for Module<T> {
     ) -> DispatchResult {
         Self::slash_finance_member(member, receiver, amount)
     }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn add_finance_member(who: &T::AccountId) {
+        let deposit = T::MinFinanceMemberDeposit::get();
+        let _ = T::Currency::reserve(who, deposit);
+        <FinanceMemberDeposit<T>>::insert(who, deposit);
+
+        let mut members = <FinanceMembers<T>>::get();
+        if let Err(index) = members.binary_search(who) {
+            members.insert(index, who.clone());
+            <FinanceMembers<T>>::put(members);
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn add_investor(who: &T::AccountId) {
+        let mut members = <InvestorMembers<T>>::get();
+        if let Err(index) = members.binary_search(who) {
+            members.insert(index, who.clone());
+            <InvestorMembers<T>>::put(members);
+        }
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn add_model_expert(who: &T::AccountId, app_id: u32, model_id: &Vec<u8>) {
+        let key = T::Hashing::hash_of(&(app_id, model_id));
+        let mut members = <ExpertMembers<T>>::get(&key);
+        if let Err(index) = members.binary_search(who) {
+            members.insert(index, who.clone());
+            <ExpertMembers<T>>::insert(&key, members);
+        }
+    }
 }
 
 decl_module! {
@@ -482,7 +854,27 @@ decl_module! {
 
         type Error = Error<T>;
 
-        #[weight = 0]
+        const MaxFinanceMembers: u32 = T::MaxFinanceMembers::get();
+        const MaxInvestorMembers: u32 = T::MaxInvestorMembers::get();
+        const MaxPlatformExpertMembers: u32 = T::MaxPlatformExpertMembers::get();
+        const MaxModelExpertMembers: u32 = T::MaxModelExpertMembers::get();
+        const StableDisputeWindow: T::BlockNumber = T::StableDisputeWindow::get();
+        const StablePendingTimeout: T::BlockNumber = T::StablePendingTimeout::get();
+        const MaxPayloadLifetime: T::BlockNumber = T::MaxPayloadLifetime::get();
+
+        fn on_runtime_upgrade() -> Weight {
+            Self::fund_stable_escrow()
+        }
+
+        fn integrity_test() {
+            assert!(T::MaxStableExpiriesPerBlock::get() > 0, "MaxStableExpiriesPerBlock must not be zero");
+        }
+
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            Self::expire_stable_escrows(n)
+        }
+
+        #[weight = T::WeightInfo::add_investor_member(T::MaxInvestorMembers::get())]
         pub fn add_investor_member(origin, app_id: u32, new_member: T::AccountId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -492,7 +884,7 @@ decl_module! {
             ensure!(Self::is_app_admin(&who, app_id), Error::<T>::NotAppAdmin);
 
             let mut members = InvestorMembers::<T>::get();
-            //ensure!(members.len() < MAX_MEMBERS, Error::<T>::MembershipLimitReached);
+            ensure!((members.len() as u32) < T::MaxInvestorMembers::get(), Error::<T>::MembershipLimitReached);
 
             // We don't want to add duplicate members, so we check whether the potential new
             // member is already present in the list. Because the list is always ordered, we can
@@ -512,7 +904,7 @@ decl_module! {
         }
 
         /// Removes a member.
-        #[weight = 0]
+        #[weight = T::WeightInfo::remove_investor_member(T::MaxInvestorMembers::get())]
         pub fn remove_investor_member(origin, app_id: u32, old_member: T::AccountId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -536,14 +928,14 @@ decl_module! {
             }
         }
 
-        #[weight = 0]
-        pub fn add_finance_member(origin, params: FinanceMemberParams<T::AccountId, BalanceOf<T>>, app_user_account: AuthAccountId, app_user_sign: sr25519::Signature) -> DispatchResult {
+        #[weight = T::WeightInfo::add_finance_member(T::MaxFinanceMembers::get())]
+        pub fn add_finance_member(origin, params: FinanceMemberParams<T::AccountId, BalanceOf<T>>, app_user_account: AuthAccountId, app_user_sign: sr25519::Signature, expiry: T::BlockNumber) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             ensure!(Self::is_finance_root(&who), Error::<T>::CallerNotFinanceRoot);
 
             let buf = params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &buf), Error::<T>::SignVerifyError);
+            ensure!(Self::verify_payload(b"members.add_finance_member", &app_user_account, app_user_sign, &buf, expiry), Error::<T>::SignVerifyError);
 
             let FinanceMemberParams {
                 deposit,
@@ -575,7 +967,7 @@ decl_module! {
         }
 
         /// Removes a member.
-        #[weight = 0]
+        #[weight = T::WeightInfo::remove_finance_member(T::MaxFinanceMembers::get())]
         pub fn remove_finance_member(origin, old_member: T::AccountId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -601,7 +993,7 @@ decl_module! {
             }
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::finance_member_add_deposit()]
         pub fn finance_member_add_deposit(origin, deposit: BalanceOf<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -617,8 +1009,8 @@ decl_module! {
             Ok(())
         }
 
-        #[weight = 0]
-        pub fn add_app_admin(origin, params: AppKeyManageParams<T::AccountId>, sign: sr25519::Signature) -> DispatchResult {
+        #[weight = T::WeightInfo::add_app_admin()]
+        pub fn add_app_admin(origin, params: AppKeyManageParams<T::AccountId>, sign: sr25519::Signature, expiry: T::BlockNumber) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             let sign_buf = params.encode();
@@ -639,7 +1031,7 @@ decl_module! {
             // check max length
             ensure!(members.len() < MAX_APP_KEYS, Error::<T>::AppKeysLimitReached);
             // check sign
-            ensure!(Self::verify_sign(&admin, sign, &sign_buf), Error::<T>::SignVerifyError);
+            ensure!(Self::verify_payload(b"members.add_app_admin", &admin, sign, &sign_buf, expiry), Error::<T>::SignVerifyError);
             // all pass now add
             match members.binary_search(&member) {
                 // If the search succeeds, the caller is already a member, so just return
@@ -655,8 +1047,8 @@ decl_module! {
             }
         }
 
-         #[weight = 0]
-        pub fn remove_app_admin(origin, params: AppKeyManageParams<T::AccountId>, sign: sr25519::Signature) -> DispatchResult {
+        #[weight = T::WeightInfo::remove_app_admin()]
+        pub fn remove_app_admin(origin, params: AppKeyManageParams<T::AccountId>, sign: sr25519::Signature, expiry: T::BlockNumber) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             let sign_buf = params.encode();
@@ -677,7 +1069,7 @@ decl_module! {
             // check max length
             ensure!(members.len() > 1, Error::<T>::AppKeysOnlyOne);
             // check sign
-            ensure!(Self::verify_sign(&admin, sign, &sign_buf), Error::<T>::SignVerifyError);
+            ensure!(Self::verify_payload(b"members.remove_app_admin", &admin, sign, &sign_buf, expiry), Error::<T>::SignVerifyError);
             // all pass now add
             match members.binary_search(&member) {
                 // If the search succeeds, the caller is already a member, so just return
@@ -692,8 +1084,8 @@ decl_module! {
             }
         }
 
-        #[weight = 0]
-        pub fn add_app_key(origin, params: AppKeyManageParams<T::AccountId>, sign: sr25519::Signature) -> DispatchResult {
+        #[weight = T::WeightInfo::add_app_key()]
+        pub fn add_app_key(origin, params: AppKeyManageParams<T::AccountId>, sign: sr25519::Signature, expiry: T::BlockNumber) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             let sign_buf = params.encode();
@@ -714,7 +1106,7 @@ decl_module! {
             // check max length
             ensure!(members.len() < MAX_APP_KEYS, Error::<T>::AppKeysLimitReached);
             // check sign
-            ensure!(Self::verify_sign(&admin, sign, &sign_buf), Error::<T>::SignVerifyError);
+            ensure!(Self::verify_payload(b"members.add_app_key", &admin, sign, &sign_buf, expiry), Error::<T>::SignVerifyError);
             // all pass now add
             match members.binary_search(&member) {
                 // If the search succeeds, the caller is already a member, so just return
@@ -730,8 +1122,8 @@ decl_module! {
             }
         }
 
-         #[weight = 0]
-        pub fn remove_app_key(origin, params: AppKeyManageParams<T::AccountId>, sign: sr25519::Signature) -> DispatchResult {
+        #[weight = T::WeightInfo::remove_app_key()]
+        pub fn remove_app_key(origin, params: AppKeyManageParams<T::AccountId>, sign: sr25519::Signature, expiry: T::BlockNumber) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             let sign_buf = params.encode();
@@ -752,7 +1144,7 @@ decl_module! {
             // check max length
             ensure!(members.len() > 1, Error::<T>::AppKeysOnlyOne);
             // check sign
-            ensure!(Self::verify_sign(&admin, sign, &sign_buf), Error::<T>::SignVerifyError);
+            ensure!(Self::verify_payload(b"members.remove_app_key", &admin, sign, &sign_buf, expiry), Error::<T>::SignVerifyError);
             // all pass now add
             match members.binary_search(&member) {
                 // If the search succeeds, the caller is already a member, so just return
@@ -767,7 +1159,7 @@ decl_module! {
             }
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::add_app_platform_expert_member(T::MaxPlatformExpertMembers::get())]
         pub fn add_app_platform_expert_member(origin, app_id: u32, new_member: T::AccountId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -777,6 +1169,7 @@ decl_module! {
             ensure!(Self::is_app_admin(&who, app_id),  Error::<T>::NotAppAdmin);
 
             let mut members = <AppPlatformExpertMembers<T>>::get(app_id);
+            ensure!((members.len() as u32) < T::MaxPlatformExpertMembers::get(), Error::<T>::MembershipLimitReached);
 
             match members.binary_search(&new_member) {
                 // If the search succeeds, the caller is already a member, so just return
@@ -792,7 +1185,7 @@ decl_module! {
             }
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::remove_app_platform_expert_member(T::MaxPlatformExpertMembers::get())]
         pub fn remove_app_platform_expert_member(origin, app_id: u32, old_member: T::AccountId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -816,11 +1209,11 @@ decl_module! {
             }
         }
 
-        #[weight = 0]
-        pub fn add_expert_member(origin, params: ModelExpertAddMemberParams, model_creator: AuthAccountId, model_creator_sign: sr25519::Signature) -> DispatchResult {
+        #[weight = T::WeightInfo::add_expert_member(T::MaxModelExpertMembers::get())]
+        pub fn add_expert_member(origin, params: ModelExpertAddMemberParams, model_creator: AuthAccountId, model_creator_sign: sr25519::Signature, expiry: T::BlockNumber) -> DispatchResult {
             let new_member = ensure_signed(origin)?;
 
-            ensure!(Self::verify_sign(&model_creator, model_creator_sign, &params.encode()), Error::<T>::SignVerifyError);
+            ensure!(Self::verify_payload(b"members.add_expert_member", &model_creator, model_creator_sign, &params.encode(), expiry), Error::<T>::SignVerifyError);
 
             let ModelExpertAddMemberParams {
                 app_id,
@@ -836,6 +1229,7 @@ decl_module! {
             let key = T::Hashing::hash_of(&(app_id, &model_id));
 
             let mut members = <ExpertMembers<T>>::get(&key);
+            ensure!((members.len() as u32) < T::MaxModelExpertMembers::get(), Error::<T>::MembershipLimitReached);
 
             match members.binary_search(&new_member) {
                 // If the search succeeds, the caller is already a member, so just return
@@ -856,21 +1250,24 @@ decl_module! {
             }
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::remove_expert_member(T::MaxModelExpertMembers::get())]
         pub fn remove_expert_member(origin,
             params: ModelExpertDelMemberParams<T::AccountId>,
             app_user_account: AuthAccountId,
             app_user_sign: sr25519::Signature,
 
             auth_server: AuthAccountId,
-            auth_sign: sr25519::Signature) -> DispatchResult {
+            auth_sign: sr25519::Signature,
+            expiry: T::BlockNumber) -> DispatchResult {
 
             // this is app server account
             let _who = ensure_signed(origin)?;
 
             let buf = params.encode();
-            ensure!(Self::verify_sign(&app_user_account, app_user_sign, &buf), Error::<T>::SignVerifyError);
-            ensure!(Self::verify_sign(&auth_server, auth_sign, &buf), Error::<T>::SignVerifyError);
+            ensure!(Self::check_payload(b"members.remove_expert_member", &app_user_account, app_user_sign, &buf, expiry), Error::<T>::SignVerifyError);
+            ensure!(Self::check_payload(b"members.remove_expert_member", &auth_server, auth_sign, &buf, expiry), Error::<T>::SignVerifyError);
+            Self::use_payload(b"members.remove_expert_member", &app_user_account, &buf, expiry);
+            Self::use_payload(b"members.remove_expert_member", &auth_server, &buf, expiry);
 
             let ModelExpertDelMemberParams {
                 app_id,
@@ -902,7 +1299,7 @@ decl_module! {
             }
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::air_drop_new_user_benefit()]
         pub fn air_drop_new_user_benefit(origin, app_id: u32, user_id: Vec<u8>,
             receiver: <T as frame_system::Trait>::AccountId, amount: BalanceOf<T>) -> DispatchResult {
 
@@ -928,7 +1325,7 @@ decl_module! {
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::stable_exchange()]
         pub fn stable_exchange(origin, amount: BalanceOf<T>, receiver: T::AccountId, app_id: u32, cash_receipt: Vec<u8>) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -940,7 +1337,12 @@ decl_module! {
             let key = T::Hashing::hash_of(&(app_id, &cash_receipt));
             ensure!(!<StableExchangeRecords<T>>::contains_key(&key), Error::<T>::StableExchangeReceiptExist);
 
-            T::Currency::transfer(&who, &receiver, amount, KeepAlive)?;
+            if AppStableEscrow::get(app_id) {
+                let escrow_key = Self::stable_escrow_key(app_id, &cash_receipt, StableEscrowKind::Exchange);
+                Self::stable_escrow_hold(&escrow_key, &key, StableEscrowKind::Exchange, &who, &receiver, amount)?;
+            } else {
+                T::Currency::transfer(&who, &receiver, amount, KeepAlive)?;
+            }
 
             <StableExchangeRecords<T>>::insert(&key, StableExchangeData {
                 receiver: receiver.clone(),
@@ -952,7 +1354,7 @@ decl_module! {
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::stable_redeem()]
         pub fn stable_redeem(origin, app_id: u32, cash_receipt: Vec<u8>) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -970,7 +1372,12 @@ decl_module! {
             // read out application store account
             let receiver = <AppRedeemAccount<T>>::get(app_id);
 
-            T::Currency::transfer(&who, &receiver, record.amount, KeepAlive)?;
+            if AppStableEscrow::get(app_id) {
+                let escrow_key = Self::stable_escrow_key(app_id, &cash_receipt, StableEscrowKind::Redeem);
+                Self::stable_escrow_hold(&escrow_key, &key, StableEscrowKind::Redeem, &who, &receiver, record.amount)?;
+            } else {
+                T::Currency::transfer(&who, &receiver, record.amount, KeepAlive)?;
+            }
 
             // update record
             record.redeemed = true;
@@ -980,7 +1387,7 @@ decl_module! {
             Ok(())
         }
 
-        #[weight = 0]
+        #[weight = T::WeightInfo::set_app_redeem_account()]
         pub fn set_app_redeem_account(origin, app_id: u32, account: T::AccountId) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -994,5 +1401,113 @@ decl_module! {
             Self::deposit_event(RawEvent::AppRedeemAccountSet(account));
             Ok(())
         }
+
+        #[weight = T::WeightInfo::set_app_stable_escrow()]
+        pub fn set_app_stable_escrow(origin, app_id: u32, enabled: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(Self::is_valid_app(app_id), Error::<T>::AppIdInvalid);
+            ensure!(Self::is_app_admin(&who, app_id), Error::<T>::NotAppAdmin);
+
+            AppStableEscrow::insert(app_id, enabled);
+
+            Self::deposit_event(RawEvent::AppStableEscrowSet(app_id, enabled));
+            Ok(())
+        }
+
+        /// A finance member confirms the off-chain side of an escrowed receipt, the amount goes to payee.
+        #[weight = T::WeightInfo::confirm_stable_receipt()]
+        pub fn confirm_stable_receipt(origin, app_id: u32, cash_receipt: Vec<u8>, kind: StableEscrowKind) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(Self::is_finance_member(&who), Error::<T>::CallerNotFinanceMemeber);
+
+            let key = Self::stable_escrow_key(app_id, &cash_receipt, kind);
+            let mut escrow = <StableEscrows<T>>::get(&key).ok_or(Error::<T>::StableEscrowNotFound)?;
+            ensure!(escrow.status == StableEscrowStatus::Pending, Error::<T>::StableEscrowStatusInvalid);
+            ensure!(who != escrow.payer && who != escrow.payee, Error::<T>::StableEscrowPartyCannotConfirm);
+
+            T::Currency::transfer(&Self::stable_escrow_account(), &escrow.payee, escrow.amount, KeepAlive)?;
+
+            escrow.confirmer = Some(who.clone());
+            escrow.confirmed_at = <system::Module<T>>::block_number();
+            escrow.status = StableEscrowStatus::Confirmed;
+            <StableEscrows<T>>::insert(&key, escrow);
+
+            Self::deposit_event(RawEvent::StableReceiptConfirmed(who, key));
+            Ok(())
+        }
+
+        /// Payer or payee contests a receipt, pending ones any time, confirmed ones within `StableDisputeWindow`.
+        #[weight = T::WeightInfo::open_stable_dispute()]
+        pub fn open_stable_dispute(origin, app_id: u32, cash_receipt: Vec<u8>, kind: StableEscrowKind) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let key = Self::stable_escrow_key(app_id, &cash_receipt, kind);
+            let mut escrow = <StableEscrows<T>>::get(&key).ok_or(Error::<T>::StableEscrowNotFound)?;
+            ensure!(who == escrow.payer || who == escrow.payee, Error::<T>::StableEscrowNotParty);
+
+            match escrow.status {
+                StableEscrowStatus::Pending => {},
+                StableEscrowStatus::Confirmed => {
+                    let deadline = escrow.confirmed_at + T::StableDisputeWindow::get();
+                    ensure!(<system::Module<T>>::block_number() <= deadline, Error::<T>::StableDisputeWindowClosed);
+                },
+                _ => return Err(Error::<T>::StableEscrowStatusInvalid.into()),
+            }
+
+            escrow.status = StableEscrowStatus::Disputed;
+            <StableEscrows<T>>::insert(&key, escrow);
+
+            Self::deposit_event(RawEvent::StableDisputeOpened(who, key));
+            Ok(())
+        }
+
+        /// Close a dispute, `upheld` means the receipt is false.
+        #[weight = T::WeightInfo::resolve_stable_dispute()]
+        pub fn resolve_stable_dispute(origin, app_id: u32, cash_receipt: Vec<u8>, kind: StableEscrowKind, upheld: bool) -> DispatchResult {
+            T::TechMemberOrigin::ensure_origin(origin)?;
+
+            let key = Self::stable_escrow_key(app_id, &cash_receipt, kind);
+            let escrow = <StableEscrows<T>>::get(&key).ok_or(Error::<T>::StableEscrowNotFound)?;
+            ensure!(escrow.status == StableEscrowStatus::Disputed, Error::<T>::StableEscrowStatusInvalid);
+
+            Self::settle_stable_dispute(&key, escrow, upheld)
+        }
+
+        /// Finance members other than the parties and the confirmer vote on a dispute,
+        /// a side wins once more than half of all finance members join it.
+        #[weight = T::WeightInfo::vote_stable_dispute(T::MaxFinanceMembers::get())]
+        pub fn vote_stable_dispute(origin, app_id: u32, cash_receipt: Vec<u8>, kind: StableEscrowKind, upheld: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(Self::is_finance_member(&who), Error::<T>::CallerNotFinanceMemeber);
+
+            let key = Self::stable_escrow_key(app_id, &cash_receipt, kind);
+            let escrow = <StableEscrows<T>>::get(&key).ok_or(Error::<T>::StableEscrowNotFound)?;
+            ensure!(escrow.status == StableEscrowStatus::Disputed, Error::<T>::StableEscrowStatusInvalid);
+            ensure!(who != escrow.payer && who != escrow.payee, Error::<T>::StableEscrowPartyCannotConfirm);
+            ensure!(escrow.confirmer.as_ref() != Some(&who), Error::<T>::StableEscrowPartyCannotConfirm);
+
+            let (mut ayes, mut nays) = <StableDisputeVotes<T>>::get(&key);
+            ensure!(!ayes.contains(&who) && !nays.contains(&who), Error::<T>::StableDisputeVoteRepeat);
+
+            let votes = if upheld {
+                ayes.push(who.clone());
+                ayes.len()
+            } else {
+                nays.push(who.clone());
+                nays.len()
+            };
+
+            Self::deposit_event(RawEvent::StableDisputeVoted(who, key, upheld));
+
+            if votes > <FinanceMembers<T>>::decode_len().unwrap_or(0) / 2 {
+                return Self::settle_stable_dispute(&key, escrow, upheld);
+            }
+
+            <StableDisputeVotes<T>>::insert(&key, (ayes, nays));
+            Ok(())
+        }
     }
 }
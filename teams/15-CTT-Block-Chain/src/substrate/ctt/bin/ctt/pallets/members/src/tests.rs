@@ -1,5 +1,8 @@
 use crate::*;
-use frame_support::{assert_ok, impl_outer_event, impl_outer_origin, parameter_types};
+use frame_support::{
+    assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types,
+    traits::{OnInitialize, OnRuntimeUpgrade},
+};
 use frame_system::{self as system, RawOrigin};
 use sp_core::H256;
 use sp_io::TestExternalities;
@@ -23,6 +26,14 @@ parameter_types! {
     pub const AvailableBlockRatio: Perbill = Perbill::one();
     pub const MaxFinanceMembers: u32 = 0;
     pub const MinFinanceMemberDeposit: u64 = 0;
+    pub const MaxInvestorMembers: u32 = 10;
+    pub const MaxPlatformExpertMembers: u32 = 10;
+    pub const MaxModelExpertMembers: u32 = 10;
+    pub const StableEscrowModuleId: ModuleId = ModuleId(*b"py/stesc");
+    pub const StableDisputeWindow: u64 = 10;
+    pub const StablePendingTimeout: u64 = 20;
+    pub const MaxStableExpiriesPerBlock: u32 = 2;
+    pub const MaxPayloadLifetime: u64 = 10;
 }
 impl system::Trait for TestRuntime {
     type Origin = Origin;
@@ -81,6 +92,16 @@ impl Trait for TestRuntime {
     type ModTreasuryModuleId = ();
     type MaxFinanceMembers = MaxFinanceMembers;
     type MinFinanceMemberDeposit = MinFinanceMemberDeposit;
+    type MaxInvestorMembers = MaxInvestorMembers;
+    type MaxPlatformExpertMembers = MaxPlatformExpertMembers;
+    type MaxModelExpertMembers = MaxModelExpertMembers;
+    type StableEscrowModuleId = StableEscrowModuleId;
+    type StableDisputeWindow = StableDisputeWindow;
+    type StablePendingTimeout = StablePendingTimeout;
+    type MaxStableExpiriesPerBlock = MaxStableExpiriesPerBlock;
+    type MaxPayloadLifetime = MaxPayloadLifetime;
+    type TechMemberOrigin = frame_system::EnsureRoot<u64>;
+    type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
@@ -111,3 +132,312 @@ fn add_member_works() {
         assert_eq!(Members::council_members(), vec![1]);
     })
 }
+
+#[test]
+fn stable_exchange_escrow_dispute_slashes_confirmer() {
+    ExtBuilder::build().execute_with(|| {
+        <Members as Membership<_, _, _>>::config_app_setting(1, 0, b"app".to_vec(), 0);
+        <Members as Membership<_, _, _>>::config_app_admin(&1, 1);
+        let _ = Balances::make_free_balance_be(&10, 1000);
+        let _ = Balances::make_free_balance_be(&20, 1000);
+        assert_ok!(Balances::reserve(&20, 500));
+        FinanceMembers::<TestRuntime>::put(vec![20, 21, 22]);
+        FinanceMemberDeposit::<TestRuntime>::insert(20, 500);
+
+        assert_ok!(Members::set_app_stable_escrow(Origin::signed(1), 1, true));
+        assert_ok!(Members::stable_exchange(
+            Origin::signed(10),
+            100,
+            11,
+            1,
+            b"r1".to_vec()
+        ));
+        assert_eq!(
+            Balances::free_balance(&Members::stable_escrow_account()),
+            100
+        );
+        assert_eq!(Balances::free_balance(&11), 0);
+
+        // only finance members confirm a receipt
+        assert_noop!(
+            Members::confirm_stable_receipt(
+                Origin::signed(10),
+                1,
+                b"r1".to_vec(),
+                StableEscrowKind::Exchange
+            ),
+            Error::<TestRuntime>::CallerNotFinanceMemeber
+        );
+        assert_ok!(Members::confirm_stable_receipt(
+            Origin::signed(20),
+            1,
+            b"r1".to_vec(),
+            StableEscrowKind::Exchange
+        ));
+        assert_eq!(Balances::free_balance(&11), 100);
+
+        System::set_block_number(5);
+        assert_ok!(Members::open_stable_dispute(
+            Origin::signed(10),
+            1,
+            b"r1".to_vec(),
+            StableEscrowKind::Exchange
+        ));
+
+        // the confirmer can not vote, two of three members settle it
+        assert_noop!(
+            Members::vote_stable_dispute(
+                Origin::signed(20),
+                1,
+                b"r1".to_vec(),
+                StableEscrowKind::Exchange,
+                false
+            ),
+            Error::<TestRuntime>::StableEscrowPartyCannotConfirm
+        );
+        assert_ok!(Members::vote_stable_dispute(
+            Origin::signed(21),
+            1,
+            b"r1".to_vec(),
+            StableEscrowKind::Exchange,
+            true
+        ));
+        assert_ok!(Members::vote_stable_dispute(
+            Origin::signed(22),
+            1,
+            b"r1".to_vec(),
+            StableEscrowKind::Exchange,
+            true
+        ));
+
+        let key = Members::stable_escrow_key(1, &b"r1".to_vec(), StableEscrowKind::Exchange);
+        assert_eq!(
+            Members::stable_escrows(&key).map(|e| e.status),
+            Some(StableEscrowStatus::Resolved)
+        );
+        assert_eq!(Members::finance_member_deposit(&20), 400);
+        assert_eq!(Balances::free_balance(&10), 1000);
+    })
+}
+
+#[test]
+fn stable_escrow_dispute_window_and_tech_resolve() {
+    ExtBuilder::build().execute_with(|| {
+        <Members as Membership<_, _, _>>::config_app_setting(1, 0, b"app".to_vec(), 0);
+        <Members as Membership<_, _, _>>::config_app_admin(&1, 1);
+        let _ = Balances::make_free_balance_be(&10, 1000);
+        FinanceMembers::<TestRuntime>::put(vec![20]);
+        assert_ok!(Members::set_app_stable_escrow(Origin::signed(1), 1, true));
+
+        assert_ok!(Members::stable_exchange(
+            Origin::signed(10),
+            100,
+            11,
+            1,
+            b"r1".to_vec()
+        ));
+        assert_ok!(Members::confirm_stable_receipt(
+            Origin::signed(20),
+            1,
+            b"r1".to_vec(),
+            StableEscrowKind::Exchange
+        ));
+        System::set_block_number(1 + StableDisputeWindow::get() + 1);
+        assert_noop!(
+            Members::open_stable_dispute(
+                Origin::signed(11),
+                1,
+                b"r1".to_vec(),
+                StableEscrowKind::Exchange
+            ),
+            Error::<TestRuntime>::StableDisputeWindowClosed
+        );
+
+        // a pending receipt is refunded when the dispute is upheld
+        assert_ok!(Members::stable_exchange(
+            Origin::signed(10),
+            100,
+            11,
+            1,
+            b"r2".to_vec()
+        ));
+        assert_ok!(Members::open_stable_dispute(
+            Origin::signed(11),
+            1,
+            b"r2".to_vec(),
+            StableEscrowKind::Exchange
+        ));
+        assert_noop!(
+            Members::resolve_stable_dispute(
+                Origin::signed(20),
+                1,
+                b"r2".to_vec(),
+                StableEscrowKind::Exchange,
+                true
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Members::resolve_stable_dispute(
+            RawOrigin::Root.into(),
+            1,
+            b"r2".to_vec(),
+            StableEscrowKind::Exchange,
+            true
+        ));
+        assert_eq!(Balances::free_balance(&10), 900);
+        assert_eq!(Balances::free_balance(&Members::stable_escrow_account()), 0);
+        // the false receipt is forgotten
+        let record = <TestRuntime as system::Trait>::Hashing::hash_of(&(1u32, &b"r2".to_vec()));
+        assert!(!StableExchangeRecords::<TestRuntime>::contains_key(&record));
+    })
+}
+
+#[test]
+fn unconfirmed_stable_escrow_expires() {
+    ExtBuilder::build().execute_with(|| {
+        <Members as Membership<_, _, _>>::config_app_setting(1, 0, b"app".to_vec(), 0);
+        <Members as Membership<_, _, _>>::config_app_admin(&1, 1);
+        let _ = Balances::make_free_balance_be(&10, 1000);
+        FinanceMembers::<TestRuntime>::put(vec![20]);
+        assert_ok!(Members::set_app_stable_escrow(Origin::signed(1), 1, true));
+
+        for receipt in &[b"r1", b"r2", b"r3"] {
+            assert_ok!(Members::stable_exchange(
+                Origin::signed(10),
+                100,
+                11,
+                1,
+                receipt.to_vec()
+            ));
+        }
+        assert_ok!(Members::confirm_stable_receipt(
+            Origin::signed(20),
+            1,
+            b"r1".to_vec(),
+            StableEscrowKind::Exchange
+        ));
+
+        // two expiries a block, the third one moves to the next block
+        let due = 1 + StablePendingTimeout::get();
+        assert_eq!(Members::stable_escrow_expiry(due).len(), 2);
+        assert_eq!(Members::stable_escrow_expiry(due + 1).len(), 1);
+
+        Members::on_initialize(due);
+        Members::on_initialize(due + 1);
+        assert_eq!(Balances::free_balance(&10), 900);
+        assert_eq!(Balances::free_balance(&11), 100);
+        for receipt in &[b"r2", b"r3"] {
+            let key = Members::stable_escrow_key(1, &receipt.to_vec(), StableEscrowKind::Exchange);
+            assert_eq!(
+                Members::stable_escrows(&key).map(|e| e.status),
+                Some(StableEscrowStatus::Resolved)
+            );
+            let expected_event = TestEvent::members(RawEvent::StableEscrowExpired(key));
+            assert!(System::events().iter().any(|a| a.event == expected_event));
+        }
+        let key = Members::stable_escrow_key(1, &b"r1".to_vec(), StableEscrowKind::Exchange);
+        assert_eq!(
+            Members::stable_escrows(&key).map(|e| e.status),
+            Some(StableEscrowStatus::Confirmed)
+        );
+    })
+}
+
+#[test]
+fn stable_escrow_fails_when_expiries_are_full() {
+    ExtBuilder::build().execute_with(|| {
+        <Members as Membership<_, _, _>>::config_app_setting(1, 0, b"app".to_vec(), 0);
+        <Members as Membership<_, _, _>>::config_app_admin(&1, 1);
+        let _ = Balances::make_free_balance_be(&10, 1000);
+        assert_ok!(Members::set_app_stable_escrow(Origin::signed(1), 1, true));
+
+        let due = 1 + StablePendingTimeout::get();
+        let full = vec![H256::default(); MaxStableExpiriesPerBlock::get() as usize];
+        for n in 0..MAX_SCHEDULE_PROBE as u64 {
+            StableEscrowExpiry::<TestRuntime>::insert(due + n, full.clone());
+        }
+        assert_noop!(
+            Members::stable_exchange(Origin::signed(10), 100, 11, 1, b"r1".to_vec()),
+            Error::<TestRuntime>::StableExpiryFull
+        );
+        assert_eq!(Balances::free_balance(&10), 1000);
+
+        StableEscrowExpiry::<TestRuntime>::remove(due + MAX_SCHEDULE_PROBE as u64 - 1);
+        assert_ok!(Members::stable_exchange(Origin::signed(10), 100, 11, 1, b"r1".to_vec()));
+        assert_eq!(
+            Members::stable_escrow_expiry(due + MAX_SCHEDULE_PROBE as u64 - 1).len(),
+            1
+        );
+    })
+}
+
+#[test]
+fn stable_escrow_is_funded_once() {
+    ExtBuilder::build().execute_with(|| {
+        assert!(<StorageVersion>::get() == Releases::V1_0_0);
+        Members::on_runtime_upgrade();
+        assert!(<StorageVersion>::get() == Releases::V2_0_0);
+
+        // later upgrades leave the escrow account alone
+        let issuance = Balances::total_issuance();
+        Members::on_runtime_upgrade();
+        assert_eq!(Balances::total_issuance(), issuance);
+    })
+}
+
+#[test]
+fn signed_payload_is_single_use_and_expires() {
+    use sp_core::Pair;
+
+    ExtBuilder::build().execute_with(|| {
+        let pair = sr25519::Pair::from_seed(&[1u8; 32]);
+        let signer = AuthAccountId::from(pair.public());
+        let other = sr25519::Pair::from_seed(&[2u8; 32]);
+        let params = (1u32, b"model".to_vec()).encode();
+        let call = &b"members.add_expert_member"[..];
+
+        let payload = Members::payload_bytes(call, &signer, &params, 5);
+        let sign = pair.sign(&payload);
+        // bound to the call
+        assert!(!Members::check_payload(
+            &b"members.remove_expert_member"[..],
+            &signer,
+            sign.clone(),
+            &params,
+            5
+        ));
+        // checking uses nothing up, a failed co-signature leaves it usable
+        assert!(Members::check_payload(call, &signer, sign.clone(), &params, 5));
+        assert!(!Members::check_payload(
+            call,
+            &AuthAccountId::from(other.public()),
+            sign.clone(),
+            &params,
+            5
+        ));
+        assert!(Members::verify_payload(call, &signer, sign.clone(), &params, 5));
+        assert_eq!(Members::payload_nonce(&signer), 1);
+        // replay
+        assert!(!Members::verify_payload(call, &signer, sign, &params, 5));
+        // signed over the used nonce
+        let stale = pair.sign(&payload);
+        assert!(!Members::check_payload(call, &signer, stale, &params, 5));
+
+        // the nonce of another signer is its own
+        let other_sign = other.sign(&payload);
+        let other = AuthAccountId::from(other.public());
+        assert!(Members::verify_payload(call, &other, other_sign, &params, 5));
+
+        // no further ahead than the lifetime
+        let max_expiry = 1 + MaxPayloadLifetime::get();
+        assert_eq!(Members::max_expiry(), max_expiry);
+        let late = pair.sign(&Members::payload_bytes(call, &signer, &params, max_expiry + 1));
+        assert!(!Members::check_payload(call, &signer, late, &params, max_expiry + 1));
+        let next = pair.sign(&Members::payload_bytes(call, &signer, &params, 6));
+
+        // expired
+        System::set_block_number(7);
+        assert!(!Members::verify_payload(call, &signer, next, &params, 6));
+        assert_eq!(Members::payload_nonce(&signer), 1);
+    })
+}
@@ -22,10 +22,12 @@
 use sp_std::collections::btree_set::BTreeSet;
 use sp_std::prelude::*;
 
+use codec::{Decode, Encode};
+use sp_core::sr25519;
 use sp_runtime::{
     generic,
     traits::{BlakeTwo256, IdentifyAccount, Verify},
-    MultiSignature, OpaqueExtrinsic,
+    MultiSignature, OpaqueExtrinsic, RuntimeDebug,
 };
 
 use frame_support::dispatch::DispatchResult;
@@ -121,6 +123,7 @@ pub trait Membership<AccountId, Hash, Balance> {
     fn is_finance_member(who: &AccountId) -> bool;
     fn set_model_creator(key: &Hash, creator: &AccountId, is_give_benefit: bool) -> Balance;
     fn transfer_model_owner(key: &Hash, new_owner: &AccountId);
+    fn model_disabled(key: &Hash, creator: &AccountId);
     fn is_model_creator(who: &AccountId, app_id: u32, model_id: &Vec<u8>) -> bool;
     fn config_app_admin(who: &AccountId, app_id: u32);
     fn config_app_key(who: &AccountId, app_id: u32);
@@ -134,6 +137,112 @@ pub trait Membership<AccountId, Hash, Balance> {
         receiver: &AccountId,
         amount: Balance,
     ) -> DispatchResult;
+
+    /// Add `who` to the finance members with enough deposit to be a valid one.
+    ///
+    /// **Should be used for benchmarking only!!!**
+    #[cfg(feature = "runtime-benchmarks")]
+    fn add_finance_member(_who: &AccountId) {
+        unimplemented!()
+    }
+
+    /// Add `who` to the investor members.
+    ///
+    /// **Should be used for benchmarking only!!!**
+    #[cfg(feature = "runtime-benchmarks")]
+    fn add_investor(_who: &AccountId) {
+        unimplemented!()
+    }
+
+    /// Add `who` to the experts of the model.
+    ///
+    /// **Should be used for benchmarking only!!!**
+    #[cfg(feature = "runtime-benchmarks")]
+    fn add_model_expert(_who: &AccountId, _app_id: u32, _model_id: &Vec<u8>) {
+        unimplemented!()
+    }
+}
+
+/// Envelope of the params an auth account signs off-chain, binding the signature to one
+/// call on one chain, to a single use and to a deadline.
+///
+/// `params` goes last, so the bytes to sign for already encoded params are the encoded
+/// envelope fields followed by them, see `signing_bytes`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct SignedPayload<T, H = Hash, N = BlockNumber> {
+    /// The call the params are for, as `<pallet>.<call>`, e.g. `kp.create_model`.
+    pub call: Vec<u8>,
+    pub genesis_hash: H,
+    /// The signer's next nonce, every used payload moves it on.
+    pub nonce: u64,
+    /// Last block at which the signature is accepted.
+    pub expiry: N,
+    pub params: T,
+}
+
+impl<H: Encode, N: Encode> SignedPayload<Vec<u8>, H, N> {
+    /// Bytes to sign when `params` holds the encoded params, the same as encoding the
+    /// payload of the decoded params.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = (&self.call, &self.genesis_hash, self.nonce, &self.expiry).encode();
+        bytes.extend_from_slice(&self.params);
+        bytes
+    }
+}
+
+/// Checks signatures over `SignedPayload`s and keeps the nonce of each auth account.
+pub trait SignedPayloadVerify<BlockNumber> {
+    /// The latest `expiry` accepted for a payload signed now.
+    fn max_expiry() -> BlockNumber;
+
+    /// Bytes `signer` has to sign for the encoded `params` of `call`.
+    fn payload_bytes(
+        call: &[u8],
+        signer: &AuthAccountId,
+        params: &[u8],
+        expiry: BlockNumber,
+    ) -> Vec<u8>;
+
+    /// Check `sign` over the payload, without using it up. The payload must carry the
+    /// signer's current nonce, must not have expired and must not expire after `max_expiry`.
+    fn check_payload(
+        call: &[u8],
+        signer: &AuthAccountId,
+        sign: sr25519::Signature,
+        params: &[u8],
+        expiry: BlockNumber,
+    ) -> bool;
+
+    /// Use up the signer's nonce, call it once all signatures of the call are checked. It
+    /// stays used even if the call fails afterwards.
+    fn use_payload(call: &[u8], signer: &AuthAccountId, params: &[u8], expiry: BlockNumber);
+
+    /// Check `sign` and use up the payload when it matches, for calls with a single signer.
+    fn verify_payload(
+        call: &[u8],
+        signer: &AuthAccountId,
+        sign: sr25519::Signature,
+        params: &[u8],
+        expiry: BlockNumber,
+    ) -> bool
+    where
+        BlockNumber: Clone,
+    {
+        if !Self::check_payload(call, signer, sign, params, expiry.clone()) {
+            return false;
+        }
+        Self::use_payload(call, signer, params, expiry);
+        true
+    }
+}
+
+/// Randomness of an epoch, fixed from VRF outputs before the epoch starts so the
+/// block author can neither predict it early nor bias it.
+pub trait EpochRandomness<Hash> {
+    /// Index of the current epoch.
+    fn current_epoch() -> u64;
+    /// Randomness of the current epoch mixed with `subject`.
+    fn random(subject: &[u8]) -> Hash;
 }
 
 // For RPC return data types
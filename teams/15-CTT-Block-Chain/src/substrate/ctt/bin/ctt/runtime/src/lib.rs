@@ -38,7 +38,8 @@ use frame_support::{
 use frame_system::{EnsureOneOf, EnsureRoot};
 use kp::{
     AppFinancedData, AppFinancedUserExchangeData, AppIncomeCycleRecord, CommoditySlashRecord,
-    DocumentPowerInfo, LeaderBoardResult, ModelDisputeRecord, ModelIncomeCurrentStage,
+    DocumentPowerInfo, ElectionRank, LeaderBoardHistoryPage, LeaderBoardResult, ModelDisputeRecord,
+    ModelIncomeCurrentStage, PowerParams,
 };
 pub use node_primitives::{AccountId, AuthAccountId, PowerSize, Signature};
 use node_primitives::{AccountIndex, Balance, BlockNumber, Hash, Index, Moment};
@@ -340,6 +341,19 @@ impl pallet_babe::Trait for Runtime {
     type QueryWeight = Staking;
 }
 
+/// BABE VRF randomness of the current epoch, which is fixed one epoch before it starts.
+pub struct BabeEpochRandomness;
+
+impl node_primitives::EpochRandomness<Hash> for BabeEpochRandomness {
+    fn current_epoch() -> u64 {
+        Babe::epoch_index()
+    }
+
+    fn random(subject: &[u8]) -> Hash {
+        Babe::random(subject)
+    }
+}
+
 parameter_types! {
     pub const IndexDeposit: Balance = 1 * DOLLARS;
 }
@@ -1035,6 +1049,14 @@ parameter_types! {
     pub const ModelCreatorCreateBenefit: Balance = 2000 * DOLLARS;
     pub const MaxFinanceMembers: u32 = 64;
     pub const MinFinanceMemberDeposit: Balance = 10000 * DOLLARS;
+    pub const MaxInvestorMembers: u32 = 256;
+    pub const MaxPlatformExpertMembers: u32 = 64;
+    pub const MaxModelExpertMembers: u32 = 64;
+    pub const StableEscrowModuleId: ModuleId = ModuleId(*b"py/stesc");
+    pub const StableDisputeWindow: BlockNumber = 3 * DAYS;
+    pub const StablePendingTimeout: BlockNumber = 1 * DAYS;
+    pub const MaxStableExpiriesPerBlock: u32 = 16;
+    pub const MaxPayloadLifetime: BlockNumber = 1 * HOURS;
 }
 
 impl members::Trait for Runtime {
@@ -1044,6 +1066,16 @@ impl members::Trait for Runtime {
     type ModTreasuryModuleId = AccountModModuleId;
     type MaxFinanceMembers = MaxFinanceMembers;
     type MinFinanceMemberDeposit = MinFinanceMemberDeposit;
+    type MaxInvestorMembers = MaxInvestorMembers;
+    type MaxPlatformExpertMembers = MaxPlatformExpertMembers;
+    type MaxModelExpertMembers = MaxModelExpertMembers;
+    type StableEscrowModuleId = StableEscrowModuleId;
+    type StableDisputeWindow = StableDisputeWindow;
+    type StablePendingTimeout = StablePendingTimeout;
+    type MaxStableExpiriesPerBlock = MaxStableExpiriesPerBlock;
+    type MaxPayloadLifetime = MaxPayloadLifetime;
+    type TechMemberOrigin = EnsureRootOrHalfTech;
+    type WeightInfo = weights::members::WeightInfo;
 }
 
 type EnsureRootOrHalfTech = EnsureOneOf<
@@ -1053,52 +1085,6 @@ type EnsureRootOrHalfTech = EnsureOneOf<
 >;
 
 parameter_types! {
-    pub const DocumentPowerWeightAttend: u8 = 40;
-    pub const DocumentPowerWeightContent: u8 = 30;
-    pub const DocumentPowerWeightJudge: u8 = 30;
-
-    pub const CommentPowerWeightCount: u8 = 65;
-    pub const CommentPowerWeightCost: u8 = 20;
-    pub const CommentPowerWeightPerCost: u8 = 10;
-    pub const CommentPowerWeightPositive: u8 = 5;
-    pub const CommentPowerWeight: u8 = 40;
-
-    pub const DocumentPublishWeightParamsRate: u8 = 20;
-    pub const DocumentPublishWeightParamsSelfRate: u8 = 35;
-    pub const DocumentPublishWeightParamsAttendRate: u8 = 45;
-
-    pub const DocumentIdentifyWeightParamsRate: u8 = 20;
-    pub const DocumentIdentifyWeightCheckRate: u8 = 35;
-    pub const DocumentIdentifyWeightConsistentRate: u8 = 45;
-
-    pub const DocumentTryWeightBiasRate: u8 = 20;
-    pub const DocumentTryWeightTrueRate: u8 = 35;
-    pub const DocumentTryWeightConsistentRate: u8 = 45;
-
-
-    pub const TopWeightProductPublish: u8 = 15;
-    pub const TopWeightDocumentIdentify: u8 = 30;
-    pub const TopWeightDocumentTry: u8 = 40;
-    pub const TopWeightAccountAttend: u8 = 10;
-    pub const TopWeightAccountStake: u8 = 5;
-
-    // for CM
-    pub const DocumentChooseWeightSellCount: u8 = 60;
-    pub const DocumentChooseWeightTryCount: u8 = 40;
-
-    pub const DocumentModelWeightProducerCount: u8 = 40;
-    pub const DocumentModelWeightProductCount: u8 = 60;
-
-    pub const DocumentCMPowerWeightAttend: u8 = 40;
-    pub const DocumentCMPowerWeightContent: u8 = 20;
-    pub const DocumentCMPowerWeightJudge: u8 = 30;
-    pub const CMPowerAccountAttend: u8 = 10;
-
-    pub const CommentCMPowerWeightCount: u8 = 65;
-    pub const CommentCMPowerWeightCost: u8 = 20;
-    pub const CommentCMPowerWeightPerCost: u8 = 10;
-    pub const CommentCMPowerWeightPositive: u8 = 5;
-
     pub const ModelCreateDeposit: Balance = 100 * DOLLARS;
     //pub const ModelCycleIncomeRewardTotal: Balance = 10_000_000 * DOLLARS;
     // TODO: test
@@ -1109,6 +1095,12 @@ parameter_types! {
     //pub const AppLeaderBoardInterval: BlockNumber = 1 * DAYS;
     pub const AppLeaderBoardInterval: BlockNumber = 5 * MINUTES;
     pub const AppLeaderBoardMaxPos: u32 = 96;
+    pub const AppLeaderBoardRetention: BlockNumber = 7 * DAYS;
+    pub const MaxDocumentComments: u32 = 1000;
+    pub const MaxCommodityTypes: u32 = 256;
+    pub const MaxModelIncomeItems: u32 = 100;
+    pub const MaxPendingDraws: u32 = 256;
+    pub const MaxDrawsPerBlock: u32 = 4;
     //pub const AppFinanceExchangePeriod: BlockNumber = 28 * DAYS;
     pub const AppFinanceExchangePeriod: BlockNumber = 30 * MINUTES; // TODO: Test only
 
@@ -1140,7 +1132,30 @@ parameter_types! {
 
     pub const CommentRewardNormalRate: u32 = 2;
     pub const CommentRewardExpertRate: u32 = 10;
-}
+
+    pub const PowerVoteCurve: &'static PiecewiseLinear<'static> = &POWER_VOTE_CURVE;
+    pub const PowerVoteMaxPower: PowerSize = 10_000_000;
+    pub const PowerVoteMaxBoost: u32 = 15;
+    pub const MaxPreBlackListPerBlock: u32 = 50;
+    pub const MaxDocumentRevisions: u32 = 20;
+    pub const MaxModerationQueue: u32 = 200;
+    pub const ModerationReportDeposit: Balance = 1 * DOLLARS;
+}
+
+// Share of `PowerVoteMaxBoost` granted at `power / PowerVoteMaxPower`. Sampled from the former
+// fixed vote factor: 1 + 3 * x / 20 up to x = 10, then 16 * (x + 17) / (x + 162), x = power / 10000.
+const POWER_VOTE_CURVE: PiecewiseLinear<'static> = PiecewiseLinear {
+    points: &[
+        (Perbill::from_parts(0), Perbill::from_parts(0)),
+        (Perbill::from_parts(10_000_000), Perbill::from_parts(100_000_000)),
+        (Perbill::from_parts(50_000_000), Perbill::from_parts(270_440_251)),
+        (Perbill::from_parts(162_000_000), Perbill::from_parts(522_633_744)),
+        (Perbill::from_parts(250_000_000), Perbill::from_parts(624_595_469)),
+        (Perbill::from_parts(500_000_000), Perbill::from_parts(766_364_551)),
+        (Perbill::from_parts(1_000_000_000), Perbill::from_parts(866_896_156)),
+    ],
+    maximum: Perbill::from_parts(866_896_156),
+};
 
 impl kp::Trait for Runtime {
     type Event = Event;
@@ -1148,46 +1163,18 @@ impl kp::Trait for Runtime {
     type Slash = Treasury;
     type TechMembers = TechnicalMembership;
     type Membership = members::Module<Runtime>;
-    type TopWeightProductPublish = TopWeightProductPublish;
-    type TopWeightDocumentIdentify = TopWeightDocumentIdentify;
-    type TopWeightDocumentTry = TopWeightDocumentTry;
-    type TopWeightAccountAttend = TopWeightAccountAttend;
-    type TopWeightAccountStake = TopWeightAccountStake;
-    type DocumentPowerWeightAttend = DocumentPowerWeightAttend;
-    type DocumentPowerWeightContent = DocumentPowerWeightContent;
-    type DocumentPowerWeightJudge = DocumentPowerWeightJudge;
-    type CommentPowerWeightCount = CommentPowerWeightCount;
-    type CommentPowerWeightCost = CommentPowerWeightCost;
-    type CommentPowerWeightPerCost = CommentPowerWeightPerCost;
-    type CommentPowerWeightPositive = CommentPowerWeightPositive;
-    type CommentPowerWeight = CommentPowerWeight;
-    type DocumentPublishWeightParamsRate = DocumentPublishWeightParamsRate;
-    type DocumentPublishWeightParamsSelfRate = DocumentPublishWeightParamsSelfRate;
-    type DocumentPublishWeightParamsAttendRate = DocumentPublishWeightParamsAttendRate;
-    type DocumentIdentifyWeightParamsRate = DocumentIdentifyWeightParamsRate;
-    type DocumentIdentifyWeightCheckRate = DocumentIdentifyWeightCheckRate;
-    type DocumentIdentifyWeightConsistentRate = DocumentIdentifyWeightConsistentRate;
-    type DocumentTryWeightBiasRate = DocumentTryWeightBiasRate;
-    type DocumentTryWeightTrueRate = DocumentTryWeightTrueRate;
-    type DocumentTryWeightConsistentRate = DocumentTryWeightConsistentRate;
-
-    type DocumentChooseWeightSellCount = DocumentChooseWeightSellCount;
-    type DocumentChooseWeightTryCount = DocumentChooseWeightTryCount;
-    type DocumentModelWeightProducerCount = DocumentModelWeightProducerCount;
-    type DocumentModelWeightProductCount = DocumentModelWeightProductCount;
-    type DocumentCMPowerWeightAttend = DocumentCMPowerWeightAttend;
-    type DocumentCMPowerWeightContent = DocumentCMPowerWeightContent;
-    type DocumentCMPowerWeightJudge = DocumentCMPowerWeightJudge;
-    type CommentCMPowerWeightCount = CommentCMPowerWeightCount;
-    type CommentCMPowerWeightCost = CommentCMPowerWeightCost;
-    type CommentCMPowerWeightPerCost = CommentCMPowerWeightPerCost;
-    type CommentCMPowerWeightPositive = CommentCMPowerWeightPositive;
-    type CMPowerAccountAttend = CMPowerAccountAttend;
+    type PayloadVerify = members::Module<Runtime>;
     type ModelCreateDeposit = ModelCreateDeposit;
     type KptExchangeMinRate = KptExchangeMinRate;
     type AppLeaderBoardInterval = AppLeaderBoardInterval;
     type AppLeaderBoardMaxPos = AppLeaderBoardMaxPos;
-    type Randomness = RandomnessCollectiveFlip;
+    type AppLeaderBoardRetention = AppLeaderBoardRetention;
+    type MaxDocumentComments = MaxDocumentComments;
+    type MaxCommodityTypes = MaxCommodityTypes;
+    type MaxModelIncomeItems = MaxModelIncomeItems;
+    type MaxPendingDraws = MaxPendingDraws;
+    type MaxDrawsPerBlock = MaxDrawsPerBlock;
+    type EpochRandomness = BabeEpochRandomness;
     type TreasuryModuleId = TreasuryModuleId;
     type FinTreasuryModuleId = TreasuryFinModuleId;
     type ModTreasuryModuleId = TreasuryModModuleId;
@@ -1215,6 +1202,15 @@ impl kp::Trait for Runtime {
 
     type CommentRewardNormalRate = CommentRewardNormalRate;
     type CommentRewardExpertRate = CommentRewardExpertRate;
+
+    type PowerVoteCurve = PowerVoteCurve;
+    type PowerVoteMaxPower = PowerVoteMaxPower;
+    type PowerVoteMaxBoost = PowerVoteMaxBoost;
+    type MaxPreBlackListPerBlock = MaxPreBlackListPerBlock;
+    type MaxDocumentRevisions = MaxDocumentRevisions;
+    type MaxModerationQueue = MaxModerationQueue;
+    type ModerationReportDeposit = ModerationReportDeposit;
+    type WeightInfo = weights::kp::WeightInfo;
 }
 
 construct_runtime!(
@@ -1321,6 +1317,10 @@ impl_runtime_apis! {
             Kp::kp_document_power(app_id, doc_id)
         }
 
+        fn document_power_with_params(app_id: u32, doc_id: Vec<u8>, params: PowerParams) -> DocumentPowerInfo {
+            Kp::kp_document_power_with_params(app_id, doc_id, params)
+        }
+
         fn is_commodity_power_exist(app_id: u32, cart_id: Vec<u8>) -> bool {
             Kp::kp_is_commodity_power_exist(app_id, cart_id)
         }
@@ -1329,6 +1329,10 @@ impl_runtime_apis! {
             Kp::leader_board_result(block, app_id, model_id)
         }
 
+        fn leader_board_history(app_id: u32, model_id: Vec<u8>, from: u32, limit: u32) -> LeaderBoardHistoryPage<AccountId, BlockNumber, Hash> {
+            Kp::leader_board_history(app_id, model_id, from, limit)
+        }
+
         fn stake_to_vote(account: AuthAccountId, stake: Balance) -> Balance {
             Kp::kp_staking_to_vote(&account, stake)
         }
@@ -1388,6 +1392,14 @@ impl_runtime_apis! {
         fn power_ratio(account: AccountId) -> u64 {
             Kp::kp_account_power_ratio_by_mini(&account)
         }
+
+        fn election_rank(account: AccountId) -> ElectionRank<Balance> {
+            Staking::election_rank_of(&account)
+        }
+
+        fn signed_payload(call: Vec<u8>, signer: AccountId, params: Vec<u8>, expiry: BlockNumber) -> Vec<u8> {
+            Kp::signed_payload(call, signer, params, expiry)
+        }
     }
 
     impl members_runtime_api::MembersApi<Block, AccountId> for Runtime {
@@ -1660,6 +1672,8 @@ impl_runtime_apis! {
             add_benchmark!(params, batches, pallet_identity, Identity);
             add_benchmark!(params, batches, pallet_im_online, ImOnline);
             add_benchmark!(params, batches, pallet_indices, Indices);
+            add_benchmark!(params, batches, kp, Kp);
+            add_benchmark!(params, batches, members, Members);
             add_benchmark!(params, batches, pallet_multisig, Multisig);
             add_benchmark!(params, batches, pallet_offences, OffencesBench::<Runtime>);
             add_benchmark!(params, batches, pallet_proxy, Proxy);
@@ -0,0 +1,140 @@
+//! Weights for members in the ctt runtime. These are estimates, not benchmark output:
+//! each weight is a base cost plus the database reads and writes the call performs, sized
+//! by hand against comparable substrate pallets. Replace them with the output of the
+//! `members` benchmarks once they have been run on the reference machine.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+
+pub struct WeightInfo;
+impl members::WeightInfo for WeightInfo {
+	fn add_investor_member(m: u32, ) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn remove_investor_member(m: u32, ) -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn add_finance_member(m: u32, ) -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add((500_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+	fn remove_finance_member(m: u32, ) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((500_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn finance_member_add_deposit() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn add_app_admin() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn remove_app_admin() -> Weight {
+		(92_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn add_app_key() -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn remove_app_key() -> Weight {
+		(92_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn add_app_platform_expert_member(m: u32, ) -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn remove_app_platform_expert_member(m: u32, ) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn add_expert_member(m: u32, ) -> Weight {
+		(78_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn remove_expert_member(m: u32, ) -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add((400_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(6 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn air_drop_new_user_benefit() -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn stable_exchange() -> Weight {
+		(66_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(5 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+	fn stable_redeem() -> Weight {
+		(69_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(6 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+	fn set_app_redeem_account() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn set_app_stable_escrow() -> Weight {
+		(19_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn confirm_stable_receipt() -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn open_stable_dispute() -> Weight {
+		(24_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn resolve_stable_dispute() -> Weight {
+		(72_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn vote_stable_dispute(m: u32, ) -> Weight {
+		(80_000_000 as Weight)
+			.saturating_add((450_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(DbWeight::get().reads(6 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn on_initialize_stable_expiry(n: u32, ) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((60_000_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+			.saturating_add(DbWeight::get().writes((4 as Weight).saturating_mul(n as Weight)))
+	}
+}
@@ -2191,19 +2191,72 @@ impl<T: Trait> Module<T> {
 
 	/// internal impl of [`slashable_balance_of`] that returns [`VoteWeight`].
 	fn slashable_balance_of_vote_weight(stash: &T::AccountId) -> VoteWeight {
-	    // CTT: read out account kp power ratio, multiply with slashable account balance vote
-	    let stash_balance = Self::slashable_balance_of(stash);
-	    let mut converted: BalanceOf<T>;
-
-	    match T::PowerVote::account_power_ratio(stash) {
-			(num, frac, frac_cond) => {
-				converted = stash_balance * num.into();
-				let divided = frac * stash_balance;
-				converted += divided * frac_cond.into();
+		<T::CurrencyToVote as Convert<BalanceOf<T>, VoteWeight>>::convert(
+			Self::slashable_balance_of_power_weighted(stash)
+		)
+	}
+
+	/// CTT: slashable balance of `stash` with its kp power ratio applied.
+	fn slashable_balance_of_power_weighted(stash: &T::AccountId) -> BalanceOf<T> {
+		let stash_balance = Self::slashable_balance_of(stash);
+		let (num, frac, frac_cond) = T::PowerVote::account_power_ratio(stash);
+
+		stash_balance * num.into() + (frac * stash_balance) * frac_cond.into()
+	}
+
+	/// CTT: estimate where `who` would stand in the next election if it were a candidate.
+	///
+	/// Every candidate is backed by its own power weighted stake plus an equal share of the
+	/// power weighted stake of each nominator voting for it, which is what a single round of
+	/// phragmen hands out before balancing. A nominating `who` is counted as a candidate only.
+	pub fn election_rank_of(who: &T::AccountId) -> kp::ElectionRank<BalanceOf<T>> {
+		let mut backing: BTreeMap<T::AccountId, BalanceOf<T>> = <Validators<T>>::iter()
+			.map(|(validator, _)| {
+				let weight = Self::slashable_balance_of_power_weighted(&validator);
+				(validator, weight)
+			})
+			.collect();
+
+		let vote_weight = Self::slashable_balance_of_power_weighted(who);
+		if !vote_weight.is_zero() {
+			backing.insert(who.clone(), vote_weight);
+		}
+
+		for (nominator, nominations) in <Nominators<T>>::iter() {
+			if &nominator == who {
+				continue;
+			}
+
+			let Nominations { submitted_in, targets, suppressed: _ } = nominations;
+			let targets = targets.into_iter()
+				.filter(|stash| backing.contains_key(stash))
+				.filter(|stash| {
+					<Self as Store>::SlashingSpans::get(stash).map_or(
+						true,
+						|spans| submitted_in >= spans.last_nonzero_slash(),
+					)
+				})
+				.collect::<Vec<_>>();
+			if targets.is_empty() {
+				continue;
 			}
-	    }
 
-	    <T::CurrencyToVote as Convert<BalanceOf<T>, VoteWeight>>::convert(converted)
+			let share = Self::slashable_balance_of_power_weighted(&nominator) / (targets.len() as u32).into();
+			for target in targets {
+				if let Some(total) = backing.get_mut(&target) {
+					*total = total.saturating_add(share);
+				}
+			}
+		}
+
+		let own = backing.get(who).copied();
+		kp::ElectionRank {
+			vote_weight,
+			backing: own.unwrap_or_default(),
+			rank: own.map_or(0, |own| backing.values().filter(|b| **b > own).count() as u32 + 1),
+			candidates: backing.len() as u32,
+			seats: Self::validator_count(),
+		}
 	}
 
 	/// Dump the list of validators and nominators into vectors and keep them on-chain.
@@ -0,0 +1,14 @@
+//! Runtime API definition of the PNS pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+
+pub use pallet_pns::{DnsRecordType, Resolution};
+
+sp_api::decl_runtime_apis! {
+	pub trait PnsApi {
+		/// Resolve the `record_type` record of the dot separated `name`.
+		fn resolve(name: Vec<u8>, record_type: DnsRecordType) -> Resolution;
+	}
+}
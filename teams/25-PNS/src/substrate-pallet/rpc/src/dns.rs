@@ -0,0 +1,527 @@
+//! A DNS server answering A, AAAA, TXT and CNAME queries from the resolver records, and a
+//! stub client to query it.
+//!
+//! The records are the `dns.A`, `dns.AAAA`, `dns.TXT` and `dns.CNAME` text records of the
+//! names, served with the TTL of the name. A name without the queried record answers with its
+//! CNAME, if any, and unowned or expired names answer NXDOMAIN.
+//!
+//! The server listens on UDP and on TCP at the same port. Answers too large for UDP are
+//! truncated there, and the clients retry over TCP.
+
+use std::{
+	io::{self, Read, Write},
+	net::{SocketAddr, TcpListener, TcpStream, UdpSocket},
+	sync::Arc,
+	thread,
+	time::Duration,
+};
+use crate::{DnsRecordType, Resolution};
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_CNAME: u16 = 5;
+pub const TYPE_TXT: u16 = 16;
+pub const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+
+pub const RCODE_NOERROR: u8 = 0;
+pub const RCODE_FORMERR: u8 = 1;
+pub const RCODE_SERVFAIL: u8 = 2;
+pub const RCODE_NXDOMAIN: u8 = 3;
+pub const RCODE_NOTIMP: u8 = 4;
+
+/// The maximum size of the messages over UDP.
+pub const MAX_MESSAGE_SIZE: usize = 512;
+/// The maximum size of the messages over TCP, which are prefixed by their length.
+pub const MAX_TCP_MESSAGE_SIZE: usize = u16::MAX as usize;
+/// How long a TCP connection may stay silent before it is dropped.
+const TCP_TIMEOUT: Duration = Duration::from_secs(5);
+/// The maximum length of a name in its wire format.
+const MAX_NAME_LENGTH: usize = 255;
+/// The maximum TTL, as the DNS treats the larger ones as zero.
+const MAX_TTL: u64 = i32::MAX as u64;
+
+/// Resolves the queries of the DNS server.
+pub trait Resolver {
+	/// Resolve the `record_type` record of `name`, `None` if it cannot be resolved right now.
+	fn resolve(&self, name: &str, record_type: DnsRecordType) -> Option<Resolution>;
+}
+
+/// Serve DNS over UDP and TCP on `addr` from `resolver`, in a thread for each, returning the
+/// bound address.
+pub fn start<R: Resolver + Send + Sync + 'static>(
+	addr: SocketAddr,
+	resolver: R,
+) -> io::Result<(SocketAddr, Vec<thread::JoinHandle<()>>)> {
+	let socket = UdpSocket::bind(addr)?;
+	let addr = socket.local_addr()?;
+	// On the port picked for UDP, when `addr` leaves it to the system.
+	let listener = TcpListener::bind(addr)?;
+	let resolver = Arc::new(resolver);
+
+	let udp_resolver = resolver.clone();
+	let udp = thread::Builder::new()
+		.name("pns-dns-udp".into())
+		.spawn(move || serve_udp(socket, &*udp_resolver))?;
+	let tcp = thread::Builder::new()
+		.name("pns-dns-tcp".into())
+		.spawn(move || serve_tcp(listener, &*resolver))?;
+	log::info!(target: "pns-dns", "DNS server listening on {}", addr);
+	Ok((addr, vec![udp, tcp]))
+}
+
+fn serve_udp<R: Resolver>(socket: UdpSocket, resolver: &R) {
+	let mut buf = [0u8; MAX_MESSAGE_SIZE];
+	loop {
+		let (len, peer) = match socket.recv_from(&mut buf) {
+			Ok(received) => received,
+			Err(e) => {
+				log::warn!(target: "pns-dns", "Unable to receive a query: {}", e);
+				continue;
+			}
+		};
+		if let Some(response) = answer(&buf[..len], resolver, MAX_MESSAGE_SIZE) {
+			if let Err(e) = socket.send_to(&response, peer) {
+				log::warn!(target: "pns-dns", "Unable to answer {}: {}", peer, e);
+			}
+		}
+	}
+}
+
+/// Serve the TCP connections one at a time, each until its client closes it or stays silent
+/// for `TCP_TIMEOUT`.
+fn serve_tcp<R: Resolver>(listener: TcpListener, resolver: &R) {
+	for stream in listener.incoming() {
+		let result = stream.and_then(|mut stream| {
+			let peer = stream.peer_addr()?;
+			serve_connection(&mut stream, resolver)
+				.map_err(|e| io::Error::new(e.kind(), format!("{}: {}", peer, e)))
+		});
+		if let Err(e) = result {
+			log::debug!(target: "pns-dns", "Dropped a TCP connection: {}", e);
+		}
+	}
+}
+
+fn serve_connection<R: Resolver>(stream: &mut TcpStream, resolver: &R) -> io::Result<()> {
+	stream.set_read_timeout(Some(TCP_TIMEOUT))?;
+	stream.set_write_timeout(Some(TCP_TIMEOUT))?;
+	loop {
+		let mut len = [0u8; 2];
+		match stream.read_exact(&mut len) {
+			Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+			result => result?,
+		}
+		let mut query = vec![0u8; u16::from_be_bytes(len) as usize];
+		stream.read_exact(&mut query)?;
+		match answer(&query, resolver, MAX_TCP_MESSAGE_SIZE) {
+			Some(response) => write_tcp_message(stream, &response)?,
+			None => return Ok(()),
+		}
+	}
+}
+
+/// Write `message` prefixed by its length, as DNS over TCP does.
+fn write_tcp_message(stream: &mut TcpStream, message: &[u8]) -> io::Result<()> {
+	let mut framed = Vec::with_capacity(message.len() + 2);
+	framed.extend_from_slice(&(message.len() as u16).to_be_bytes());
+	framed.extend_from_slice(message);
+	stream.write_all(&framed)
+}
+
+/// The response to the DNS message `query`, `None` if it deserves none. Responses larger than
+/// `max_size` are truncated to their question, with the TC bit set.
+pub fn answer<R: Resolver>(query: &[u8], resolver: &R, max_size: usize) -> Option<Vec<u8>> {
+	if query.len() < 12 {
+		return None;
+	}
+	let flags = read_u16(query, 2)?;
+	// Never answer a response.
+	if flags & 0x8000 != 0 {
+		return None;
+	}
+	let response = |rcode: u8, question: &[u8], answers: &[Vec<u8>]| {
+		let mut message = Vec::with_capacity(MAX_MESSAGE_SIZE.min(max_size));
+		message.extend_from_slice(&query[..2]);
+		// QR, the opcode, AA and RD.
+		let flags = 0x8000 | (flags & 0x7800) | 0x0400 | (flags & 0x0100) | rcode as u16;
+		message.extend_from_slice(&flags.to_be_bytes());
+		message.extend_from_slice(&(if question.is_empty() { 0u16 } else { 1 }).to_be_bytes());
+		message.extend_from_slice(&(answers.len() as u16).to_be_bytes());
+		message.extend_from_slice(&[0, 0, 0, 0]);
+		message.extend_from_slice(question);
+		let header_and_question = message.len();
+		for record in answers {
+			message.extend_from_slice(record);
+		}
+		// Truncate what does not fit, the client retries over TCP.
+		if message.len() > max_size {
+			message.truncate(header_and_question);
+			message[2] |= 0x02;
+			message[6..8].copy_from_slice(&[0, 0]);
+		}
+		message
+	};
+
+	if (flags >> 11) & 0xf != 0 {
+		return Some(response(RCODE_NOTIMP, &[], &[]));
+	}
+	if read_u16(query, 4)? != 1 {
+		return Some(response(RCODE_FORMERR, &[], &[]));
+	}
+	let (name, end) = match read_name(query, 12) {
+		Some(name) => name,
+		None => return Some(response(RCODE_FORMERR, &[], &[])),
+	};
+	let (qtype, qclass) = match (read_u16(query, end), read_u16(query, end + 2)) {
+		(Some(qtype), Some(qclass)) => (qtype, qclass),
+		_ => return Some(response(RCODE_FORMERR, &[], &[])),
+	};
+	let question = &query[12..end + 4];
+
+	if qclass != CLASS_IN {
+		return Some(response(RCODE_NOTIMP, question, &[]));
+	}
+	let record_type = match qtype {
+		TYPE_A => DnsRecordType::A,
+		TYPE_AAAA => DnsRecordType::AAAA,
+		TYPE_CNAME => DnsRecordType::CNAME,
+		TYPE_TXT => DnsRecordType::TXT,
+		// The name may exist, but has no record of other types.
+		_ => return Some(response(RCODE_NOERROR, question, &[])),
+	};
+
+	match resolver.resolve(&name.to_ascii_lowercase(), record_type) {
+		None => Some(response(RCODE_SERVFAIL, question, &[])),
+		Some(Resolution::NxDomain) => Some(response(RCODE_NXDOMAIN, question, &[])),
+		Some(Resolution::Found { ttl, record }) => {
+			let answers: Vec<_> = record
+				.and_then(|(record_type, data)| encode_record(record_type, ttl, &data))
+				.into_iter()
+				.collect();
+			Some(response(RCODE_NOERROR, question, &answers))
+		}
+	}
+}
+
+/// The answer resource record of `data`, pointing to the name of the question. `None` when
+/// the data is not valid for its type.
+fn encode_record(record_type: DnsRecordType, ttl: u64, data: &[u8]) -> Option<Vec<u8>> {
+	let (qtype, rdata) = match record_type {
+		DnsRecordType::A if data.len() == 4 => (TYPE_A, data.to_vec()),
+		DnsRecordType::AAAA if data.len() == 16 => (TYPE_AAAA, data.to_vec()),
+		DnsRecordType::CNAME => (TYPE_CNAME, encode_name(std::str::from_utf8(data).ok()?)?),
+		DnsRecordType::TXT => {
+			let mut rdata = Vec::with_capacity(data.len() + data.len() / 255 + 1);
+			for chunk in data.chunks(255) {
+				rdata.push(chunk.len() as u8);
+				rdata.extend_from_slice(chunk);
+			}
+			(TYPE_TXT, rdata)
+		}
+		_ => return None,
+	};
+
+	let mut record = vec![0xc0, 12];
+	record.extend_from_slice(&qtype.to_be_bytes());
+	record.extend_from_slice(&CLASS_IN.to_be_bytes());
+	record.extend_from_slice(&(ttl.min(MAX_TTL) as u32).to_be_bytes());
+	record.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+	record.extend_from_slice(&rdata);
+	Some(record)
+}
+
+/// The wire format of the dot separated `name`.
+pub fn encode_name(name: &str) -> Option<Vec<u8>> {
+	let mut encoded = Vec::with_capacity(name.len() + 2);
+	for label in name.trim_end_matches('.').split('.') {
+		if label.is_empty() || label.len() > 63 {
+			return None;
+		}
+		encoded.push(label.len() as u8);
+		encoded.extend_from_slice(label.as_bytes());
+	}
+	encoded.push(0);
+	if encoded.len() > MAX_NAME_LENGTH {
+		return None;
+	}
+	Some(encoded)
+}
+
+/// Read the uncompressed name at `offset` of `message`, with the offset following it.
+fn read_name(message: &[u8], mut offset: usize) -> Option<(String, usize)> {
+	let start = offset;
+	let mut labels = Vec::new();
+	loop {
+		let len = *message.get(offset)? as usize;
+		offset += 1;
+		if len == 0 {
+			break;
+		}
+		// Questions have no compression pointers.
+		if len > 63 {
+			return None;
+		}
+		labels.push(std::str::from_utf8(message.get(offset..offset + len)?).ok()?);
+		offset += len;
+		if offset - start > MAX_NAME_LENGTH {
+			return None;
+		}
+	}
+	Some((labels.join("."), offset))
+}
+
+fn read_u16(message: &[u8], offset: usize) -> Option<u16> {
+	Some(u16::from_be_bytes([*message.get(offset)?, *message.get(offset + 1)?]))
+}
+
+/// A stub DNS client, to query the server of a local dev chain.
+pub mod client {
+	use super::*;
+
+	/// A resource record of an answer.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	pub struct Record {
+		pub record_type: u16,
+		pub ttl: u32,
+		pub data: Vec<u8>,
+	}
+
+	/// The response of a DNS server.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	pub struct Response {
+		pub rcode: u8,
+		/// Whether the answers did not fit in the message.
+		pub truncated: bool,
+		pub answers: Vec<Record>,
+	}
+
+	fn invalid(message: &str) -> io::Error {
+		io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+	}
+
+	/// A query of the `record_type` record of `name`, with its id.
+	pub(super) fn build_query(name: &str, record_type: u16) -> io::Result<(u16, Vec<u8>)> {
+		let id = std::process::id() as u16;
+		let mut message = Vec::new();
+		message.extend_from_slice(&id.to_be_bytes());
+		// A recursion desired query with one question.
+		message.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]);
+		message.extend_from_slice(&encode_name(name).ok_or_else(|| invalid("invalid name"))?);
+		message.extend_from_slice(&record_type.to_be_bytes());
+		message.extend_from_slice(&CLASS_IN.to_be_bytes());
+		Ok((id, message))
+	}
+
+	/// Query the `record_type` record of `name` from the DNS server at `server`, over UDP and
+	/// again over TCP if the answer was truncated.
+	pub fn query(server: SocketAddr, name: &str, record_type: u16) -> io::Result<Response> {
+		let (id, message) = build_query(name, record_type)?;
+
+		let local: SocketAddr = if server.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }
+			.parse()
+			.expect("valid socket address; qed");
+		let socket = UdpSocket::bind(local)?;
+		socket.set_read_timeout(Some(TCP_TIMEOUT))?;
+		socket.send_to(&message, server)?;
+
+		let mut buf = [0u8; MAX_MESSAGE_SIZE];
+		let len = socket.recv(&mut buf)?;
+		let response = parse_response(&buf[..len], id).ok_or_else(|| invalid("malformed response"))?;
+		if response.truncated {
+			return query_tcp(server, name, record_type);
+		}
+		Ok(response)
+	}
+
+	/// Query the `record_type` record of `name` from the DNS server at `server` over TCP.
+	pub fn query_tcp(server: SocketAddr, name: &str, record_type: u16) -> io::Result<Response> {
+		let (id, message) = build_query(name, record_type)?;
+
+		let mut stream = TcpStream::connect_timeout(&server, TCP_TIMEOUT)?;
+		stream.set_read_timeout(Some(TCP_TIMEOUT))?;
+		write_tcp_message(&mut stream, &message)?;
+
+		let mut len = [0u8; 2];
+		stream.read_exact(&mut len)?;
+		let mut buf = vec![0u8; u16::from_be_bytes(len) as usize];
+		stream.read_exact(&mut buf)?;
+		parse_response(&buf, id).ok_or_else(|| invalid("malformed response"))
+	}
+
+	fn parse_response(message: &[u8], id: u16) -> Option<Response> {
+		if read_u16(message, 0)? != id {
+			return None;
+		}
+		let flags = read_u16(message, 2)?;
+		let rcode = (flags & 0xf) as u8;
+		let truncated = flags & 0x0200 != 0;
+		let questions = read_u16(message, 4)?;
+		let ancount = read_u16(message, 6)?;
+
+		let mut offset = 12;
+		for _ in 0..questions {
+			offset = read_name(message, offset)?.1 + 4;
+		}
+		let mut answers = Vec::new();
+		for _ in 0..ancount {
+			offset = skip_name(message, offset)?;
+			let record_type = read_u16(message, offset)?;
+			let ttl = u32::from_be_bytes([
+				*message.get(offset + 4)?,
+				*message.get(offset + 5)?,
+				*message.get(offset + 6)?,
+				*message.get(offset + 7)?,
+			]);
+			let len = read_u16(message, offset + 8)? as usize;
+			offset += 10;
+			let data = message.get(offset..offset + len)?.to_vec();
+			offset += len;
+			answers.push(Record { record_type, ttl, data });
+		}
+		Some(Response { rcode, truncated, answers })
+	}
+
+	fn skip_name(message: &[u8], mut offset: usize) -> Option<usize> {
+		loop {
+			let len = *message.get(offset)? as usize;
+			if len == 0 {
+				return Some(offset + 1);
+			}
+			if len & 0xc0 == 0xc0 {
+				return Some(offset + 2);
+			}
+			offset += len + 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	struct StubResolver(HashMap<String, (u64, Vec<(DnsRecordType, Vec<u8>)>)>);
+
+	impl Resolver for StubResolver {
+		fn resolve(&self, name: &str, record_type: DnsRecordType) -> Option<Resolution> {
+			if name == "broken.dot" {
+				return None;
+			}
+			Some(match self.0.get(name) {
+				None => Resolution::NxDomain,
+				Some((ttl, records)) => {
+					let find = |wanted| records.iter().find(|(t, _)| *t == wanted).cloned();
+					Resolution::Found {
+						ttl: *ttl,
+						record: find(record_type).or_else(|| find(DnsRecordType::CNAME)),
+					}
+				}
+			})
+		}
+	}
+
+	fn start_stub() -> SocketAddr {
+		let mut names = HashMap::new();
+		names.insert("gav.dot".to_string(), (300, vec![
+			(DnsRecordType::A, vec![127, 0, 0, 1]),
+			(DnsRecordType::AAAA, vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+			(DnsRecordType::TXT, b"hello".to_vec()),
+		]));
+		names.insert("www.gav.dot".to_string(), (60, vec![
+			(DnsRecordType::CNAME, b"gav.dot".to_vec()),
+		]));
+		names.insert("bad.dot".to_string(), (60, vec![(DnsRecordType::A, vec![1, 2, 3])]));
+		names.insert("long.dot".to_string(), (60, vec![(DnsRecordType::TXT, vec![b'a'; 600])]));
+		start("127.0.0.1:0".parse().unwrap(), StubResolver(names)).unwrap().0
+	}
+
+	#[test]
+	fn answers_records_with_their_ttl() {
+		let server = start_stub();
+
+		let response = client::query(server, "gav.dot", TYPE_A).unwrap();
+		assert_eq!(response.rcode, RCODE_NOERROR);
+		assert_eq!(response.answers, vec![client::Record {
+			record_type: TYPE_A,
+			ttl: 300,
+			data: vec![127, 0, 0, 1],
+		}]);
+
+		let response = client::query(server, "GAV.dot.", TYPE_AAAA).unwrap();
+		assert_eq!(response.answers[0].record_type, TYPE_AAAA);
+		assert_eq!(response.answers[0].data.len(), 16);
+
+		let response = client::query(server, "gav.dot", TYPE_TXT).unwrap();
+		assert_eq!(response.answers[0].data, b"\x05hello".to_vec());
+	}
+
+	#[test]
+	fn answers_cnames_for_missing_records() {
+		let server = start_stub();
+
+		let response = client::query(server, "www.gav.dot", TYPE_A).unwrap();
+		assert_eq!(response.rcode, RCODE_NOERROR);
+		assert_eq!(response.answers, vec![client::Record {
+			record_type: TYPE_CNAME,
+			ttl: 60,
+			data: encode_name("gav.dot").unwrap(),
+		}]);
+	}
+
+	#[test]
+	fn answers_errors() {
+		let server = start_stub();
+
+		let response = client::query(server, "nobody.dot", TYPE_A).unwrap();
+		assert_eq!(response.rcode, RCODE_NXDOMAIN);
+		assert!(response.answers.is_empty());
+
+		assert_eq!(client::query(server, "broken.dot", TYPE_A).unwrap().rcode, RCODE_SERVFAIL);
+
+		// Invalid records and other types have no answer.
+		let response = client::query(server, "bad.dot", TYPE_A).unwrap();
+		assert_eq!(response.rcode, RCODE_NOERROR);
+		assert!(response.answers.is_empty());
+		let response = client::query(server, "gav.dot", 15).unwrap();
+		assert_eq!(response.rcode, RCODE_NOERROR);
+		assert!(response.answers.is_empty());
+	}
+
+	#[test]
+	fn malformed_queries_are_rejected() {
+		let resolver = StubResolver(HashMap::new());
+
+		assert_eq!(answer(&[0; 4], &resolver, MAX_MESSAGE_SIZE), None);
+		let mut query = vec![0, 1, 0x01, 0, 0, 2, 0, 0, 0, 0, 0, 0];
+		assert_eq!(answer(&query, &resolver, MAX_MESSAGE_SIZE).unwrap()[3] & 0xf, RCODE_FORMERR);
+		query[5] = 1;
+		query.extend_from_slice(&[3, b'd', b'o']);
+		assert_eq!(answer(&query, &resolver, MAX_MESSAGE_SIZE).unwrap()[3] & 0xf, RCODE_FORMERR);
+		// A response is never answered.
+		query[2] |= 0x80;
+		assert_eq!(answer(&query, &resolver, MAX_MESSAGE_SIZE), None);
+	}
+
+	#[test]
+	fn large_answers_are_served_over_tcp() {
+		let server = start_stub();
+
+		let udp_only = |name| {
+			let (_, message) = client::build_query(name, TYPE_TXT).unwrap();
+			let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+			socket.send_to(&message, server).unwrap();
+			let mut buf = [0u8; MAX_MESSAGE_SIZE];
+			let len = socket.recv(&mut buf).unwrap();
+			buf[..len].to_vec()
+		};
+		let truncated = udp_only("long.dot");
+		assert_ne!(truncated[2] & 0x02, 0);
+		assert_eq!(read_u16(&truncated, 6), Some(0));
+
+		// The stub client retries over TCP, which carries the whole answer.
+		let response = client::query(server, "long.dot", TYPE_TXT).unwrap();
+		assert!(!response.truncated);
+		assert_eq!(response.answers[0].data.len(), 600 + 3);
+		assert_eq!(client::query_tcp(server, "gav.dot", TYPE_A).unwrap().answers.len(), 1);
+	}
+}
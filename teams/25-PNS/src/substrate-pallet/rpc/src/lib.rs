@@ -0,0 +1,140 @@
+//! RPC interface of the PNS pallet, and a DNS server answering from the same resolver records.
+
+use std::{io, marker::PhantomData, net::SocketAddr, sync::Arc, thread};
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+pub use pallet_pns_rpc_runtime_api::{DnsRecordType, PnsApi as PnsRuntimeApi, Resolution};
+pub use self::gen_client::Client as PnsClient;
+
+pub mod dns;
+
+#[rpc]
+pub trait PnsApi<BlockHash> {
+	/// Resolve the `record_type` record of `name` at the block `at`, or the best one.
+	#[rpc(name = "pns_resolve")]
+	fn resolve(
+		&self,
+		name: String,
+		record_type: DnsRecordType,
+		at: Option<BlockHash>,
+	) -> Result<Resolution>;
+}
+
+/// A struct that implements the [`PnsApi`].
+pub struct Pns<C, B> {
+	client: Arc<C>,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> Pns<C, B> {
+	/// Create new `Pns` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block> PnsApi<<Block as BlockT>::Hash> for Pns<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: PnsRuntimeApi<Block>,
+{
+	fn resolve(
+		&self,
+		name: String,
+		record_type: DnsRecordType,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Resolution> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.resolve(&at, normalize(&name), record_type).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to resolve the name.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
+
+/// The block at which the DNS server resolves the names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveAt {
+	/// The best block, up to date but which may be reverted.
+	Best,
+	/// The last finalized block.
+	Finalized,
+}
+
+/// Resolves the DNS queries with the runtime of a client.
+pub struct ClientResolver<C, B> {
+	client: Arc<C>,
+	at: ResolveAt,
+	_marker: PhantomData<B>,
+}
+
+impl<C, B> ClientResolver<C, B> {
+	/// Create a resolver answering at the `at` block of `client`.
+	pub fn new(client: Arc<C>, at: ResolveAt) -> Self {
+		Self { client, at, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> dns::Resolver for ClientResolver<C, Block>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: PnsRuntimeApi<Block>,
+{
+	fn resolve(&self, name: &str, record_type: DnsRecordType) -> Option<Resolution> {
+		let info = self.client.info();
+		let at = BlockId::hash(match self.at {
+			ResolveAt::Best => info.best_hash,
+			ResolveAt::Finalized => info.finalized_hash,
+		});
+
+		self.client.runtime_api()
+			.resolve(&at, normalize(name), record_type)
+			.map_err(|e| log::warn!(target: "pns-dns", "Unable to resolve {}: {:?}", name, e))
+			.ok()
+	}
+}
+
+/// Start the DNS server of the node on `addr`, over UDP and TCP, answering at the `at` block of
+/// `client`.
+pub fn start_dns_server<C, Block>(
+	client: Arc<C>,
+	addr: SocketAddr,
+	at: ResolveAt,
+) -> io::Result<(SocketAddr, Vec<thread::JoinHandle<()>>)>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: PnsRuntimeApi<Block>,
+{
+	dns::start(addr, ClientResolver::new(client, at))
+}
+
+/// The name as hashed on chain: DNS names are case insensitive and may be fully qualified.
+pub fn normalize(name: &str) -> Vec<u8> {
+	name.trim_end_matches('.').to_ascii_lowercase().into_bytes()
+}
@@ -1,18 +1,111 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use sp_std::prelude::*;
+use sp_std::{cmp::max, prelude::*};
+use sp_core::H256;
 use sp_runtime::{
-	traits::{StaticLookup, Zero}
+	traits::{Saturating, StaticLookup, Zero}
 };
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
 use frame_support::{
-	decl_module, decl_event, decl_storage, ensure, decl_error,
-	traits::{Currency, EnsureOrigin, ReservableCurrency, OnUnbalanced, Get},
+	decl_module, decl_event, decl_storage, ensure, decl_error, transactional,
+	dispatch::DispatchResult,
+	traits::{
+		BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement, ReservableCurrency, OnUnbalanced, Get,
+		WithdrawReasons,
+	},
+	weights::Weight,
 };
 use frame_system::ensure_signed;
 
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
 
+/// A node of the name tree, the namehash of its name.
+pub type Node = H256;
+
+/// The node of the empty name, parent of the top level domains.
+pub const ROOT_NODE: Node = H256([0u8; 32]);
+
+/// Blocks from the end of a grace period searched for room in `Releases` before giving up.
+const MAX_SCHEDULE_PROBE: u32 = 64;
+
+/// The hash of a label, as used to derive the nodes.
+pub fn label_hash(label: &[u8]) -> H256 {
+	H256(sp_io::hashing::keccak_256(label))
+}
+
+/// The node of the label under `parent`, `keccak256(parent ++ keccak256(label))`.
+pub fn subnode(parent: &Node, label: &[u8]) -> Node {
+	let mut input = [0u8; 64];
+	input[..32].copy_from_slice(parent.as_bytes());
+	input[32..].copy_from_slice(label_hash(label).as_bytes());
+	H256(sp_io::hashing::keccak_256(&input))
+}
+
+/// The namehash of a dot separated name, the same as the one of ENS.
+pub fn namehash(name: &[u8]) -> Node {
+	if name.is_empty() {
+		return ROOT_NODE;
+	}
+	name.split(|c| *c == b'.')
+		.rev()
+		.fold(ROOT_NODE, |node, label| subnode(&node, label))
+}
+
+/// The DNS record types served from the text records of the nodes.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum DnsRecordType {
+	/// An IPv4 address, 4 bytes.
+	A,
+	/// An IPv6 address, 16 bytes.
+	AAAA,
+	/// The dot separated name this one is an alias of.
+	CNAME,
+	/// Free form text.
+	TXT,
+}
+
+impl DnsRecordType {
+	/// The key of the text record holding records of this type.
+	pub fn text_key(&self) -> &'static [u8] {
+		match self {
+			DnsRecordType::A => b"dns.A",
+			DnsRecordType::AAAA => b"dns.AAAA",
+			DnsRecordType::CNAME => b"dns.CNAME",
+			DnsRecordType::TXT => b"dns.TXT",
+		}
+	}
+}
+
+/// The answer to a DNS query.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Resolution {
+	/// The name has no owner, or it or one of its parents has expired.
+	NxDomain,
+	/// The name exists, with the record of the queried type or else its CNAME, if any.
+	Found {
+		ttl: u64,
+		record: Option<(DnsRecordType, Vec<u8>)>,
+	},
+}
+
+/// The price of the names of the registrar controller.
+pub trait PriceOracle<Balance> {
+	/// The price of one year of a label of `length` bytes.
+	fn annual_price(length: usize) -> Balance;
+}
+
 pub trait Config: frame_system::Config {
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
 
@@ -27,26 +120,129 @@ pub trait Config: frame_system::Config {
 	type MinLength: Get<usize>;
 
 	type MaxLength: Get<usize>;
+
+	/// The maximum length of the text keys and of the resolver records.
+	type MaxRecordLength: Get<usize>;
+
+	/// The maximum number of address and text records of a node.
+	type MaxRecords: Get<u32>;
+
+	/// The node under which the registrar controller hands out names, e.g. `namehash("dot")`.
+	///
+	/// It should have no owner, who could otherwise take the registered names back.
+	type BaseNode: Get<Node>;
+
+	/// The annual price of the registered names.
+	type PriceOracle: PriceOracle<BalanceOf<Self>>;
+
+	/// What to do with the registration and renewal fees.
+	type Proceeds: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The number of blocks of a year of registration.
+	type BlocksPerYear: Get<Self::BlockNumber>;
+
+	/// The number of blocks after its expiry during which a name can only be renewed.
+	type GracePeriod: Get<Self::BlockNumber>;
+
+	/// The maximum number of names released in a block, the others wait for the next blocks.
+	type MaxReleasesPerBlock: Get<u32>;
+
+	/// The maximum number of open offers for a name, all returned when it is released.
+	type MaxOffers: Get<u32>;
+
+	/// The number of blocks a commitment must wait before its name can be registered.
+	type MinCommitmentAge: Get<Self::BlockNumber>;
+
+	/// The number of blocks after which a commitment can no longer be used.
+	type MaxCommitmentAge: Get<Self::BlockNumber>;
 }
 
 decl_storage! {
-	trait Store for Module<T: Config> as Nicks {
+	trait Store for Module<T: Config> as Pns {
 		NameOf: map hasher(twox_64_concat) T::AccountId => Option<(Vec<u8>, BalanceOf<T>)>;
 
-		Records: map hasher(twox_64_concat) Vec<u8> => Option<(T::AccountId)>;
-		Ttls: map hasher(twox_64_concat) Vec<u8> => Option<(u64)>;
-		ExpiryTimes: map hasher(twox_64_concat) Vec<u8> => Option<(u64)>;
-
-		Hashs: map hasher(twox_64_concat) Vec<u8> => Option<(Vec<u8>)>;
-		Addresses: map hasher(twox_64_concat) Vec<u8> => Option<(Vec<u8>)>;
-		Texts: map hasher(twox_64_concat) Vec<u8> => Option<(Vec<u8>)>;
+		/// The owner of each node, see `owner`.
+		Records: map hasher(twox_64_concat) Node => Option<T::AccountId>;
+		/// The name of the registrar controller each subnode was created under, with the
+		/// generation of the name at the time.
+		Anchors get(fn anchor): map hasher(twox_64_concat) Node => Option<(Node, u32)>;
+		/// The number of times each name of the registrar controller was released.
+		Generations get(fn generation): map hasher(twox_64_concat) Node => u32;
+		/// The time in seconds the records of each node may be cached.
+		Ttls get(fn ttl): map hasher(twox_64_concat) Node => u64;
+		/// The block at which each name of the registrar controller expires.
+		ExpiryTimes get(fn expiry_time): map hasher(twox_64_concat) Node => Option<T::BlockNumber>;
+		/// The account which registered each name and the deposit it reserved.
+		Deposits get(fn deposit): map hasher(twox_64_concat) Node => Option<(T::AccountId, BalanceOf<T>)>;
+		/// The names to release at each block, from the end of their grace period, at most
+		/// `MaxReleasesPerBlock` a block.
+		Releases: map hasher(twox_64_concat) T::BlockNumber => Vec<Node>;
+		/// The account approved to transfer each name.
+		Approvals get(fn approval): map hasher(twox_64_concat) Node => Option<T::AccountId>;
+		/// The price of each name for sale.
+		Prices get(fn price): map hasher(twox_64_concat) Node => Option<BalanceOf<T>>;
+		/// The offers for each name, reserved from the accounts which made them.
+		Offers get(fn offer): double_map hasher(twox_64_concat) Node, hasher(twox_64_concat) T::AccountId => Option<BalanceOf<T>>;
+		/// The number of open offers for each name.
+		OfferCounts get(fn offer_count): map hasher(twox_64_concat) Node => u32;
+		/// The block of each registration commitment.
+		Commitments get(fn commitment): map hasher(twox_64_concat) H256 => Option<T::BlockNumber>;
+		/// The operators allowed to manage all the nodes of an owner.
+		Operators get(fn is_approved_for_all): double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) T::AccountId => bool;
+
+		/// The contenthash record of each node.
+		ContentHashes get(fn contenthash): map hasher(twox_64_concat) Node => Option<Vec<u8>>;
+		/// The address records of each node, by SLIP-44 coin type.
+		Addresses get(fn addr): double_map hasher(twox_64_concat) Node, hasher(twox_64_concat) u64 => Option<Vec<u8>>;
+		/// The text records of each node, by key.
+		Texts get(fn text): double_map hasher(twox_64_concat) Node, hasher(blake2_128_concat) Vec<u8> => Option<Vec<u8>>;
+		/// The number of address and text records of each node.
+		RecordCounts get(fn record_count): map hasher(twox_64_concat) Node => u32;
 	}
 }
 
 decl_event!(
-	pub enum Event<T> where AccountId = <T as frame_system::Config>::AccountId, Balance = BalanceOf<T> {
+	pub enum Event<T> where
+		AccountId = <T as frame_system::Config>::AccountId,
+		Balance = BalanceOf<T>,
+		BlockNumber = <T as frame_system::Config>::BlockNumber,
+	{
 		NameChanged(AccountId),
 		NameCleared(AccountId, Balance),
+		/// The owner of a node changed. \[node, owner\]
+		Transfer(Node, AccountId),
+		/// The owner of a subnode changed. \[parent, label hash, owner\]
+		NewOwner(Node, H256, AccountId),
+		/// The TTL of a node changed. \[node, ttl\]
+		NewTtl(Node, u64),
+		/// An operator was approved or disapproved. \[owner, operator, approved\]
+		ApprovalForAll(AccountId, AccountId, bool),
+		/// An address record changed, empty when removed. \[node, coin type, address\]
+		AddrChanged(Node, u64, Vec<u8>),
+		/// A text record changed, empty when removed. \[node, key, value\]
+		TextChanged(Node, Vec<u8>, Vec<u8>),
+		/// The contenthash record changed, empty when removed. \[node, hash\]
+		ContenthashChanged(Node, Vec<u8>),
+		/// A registration commitment was made. \[commitment\]
+		Committed(H256),
+		/// A name was registered. \[node, owner, expiry\]
+		NameRegistered(Node, AccountId, BlockNumber),
+		/// A name was renewed. \[node, expiry\]
+		NameRenewed(Node, BlockNumber),
+		/// A name was released at the end of its grace period. \[node\]
+		NameReleased(Node),
+		/// An account was approved to transfer a name. \[node, owner, delegate\]
+		ApprovedTransfer(Node, AccountId, AccountId),
+		/// The approval to transfer a name was cancelled. \[node, owner, delegate\]
+		ApprovalCancelled(Node, AccountId, AccountId),
+		/// A name was put on sale, or taken off it. \[node, price\]
+		PriceSet(Node, Option<Balance>),
+		/// An offer was made for a name. \[node, who, amount\]
+		OfferMade(Node, AccountId, Balance),
+		/// An offer for a name was withdrawn. \[node, who\]
+		OfferWithdrawn(Node, AccountId),
+		/// A name was sold. \[node, seller, buyer, price\]
+		NameSold(Node, AccountId, AccountId, Balance),
 	}
 );
 
@@ -55,6 +251,43 @@ decl_error! {
 		TooShort,
 		TooLong,
 		Unnamed,
+		/// The sender is neither the owner of the node nor an operator of the owner.
+		NotAuthorised,
+		/// The label is empty or contains a dot or an uppercase letter.
+		InvalidLabel,
+		/// The record or text key is longer than `MaxRecordLength`.
+		RecordTooLong,
+		/// The node already has `MaxRecords` address and text records.
+		TooManyRecords,
+		/// The name has expired and can only be renewed.
+		Expired,
+		/// An unexpired commitment with the same hash exists.
+		CommitmentExists,
+		/// There is no commitment for the registration.
+		NoCommitment,
+		/// The commitment is younger than `MinCommitmentAge`.
+		CommitmentTooNew,
+		/// The commitment is older than `MaxCommitmentAge`.
+		CommitmentTooOld,
+		/// The name is registered or otherwise owned.
+		NotAvailable,
+		/// The name is not registered by the registrar controller.
+		NotRegistered,
+		/// The registration is for zero years.
+		InvalidDuration,
+		/// The name has no price.
+		NotForSale,
+		/// The bid is lower than the price of the name.
+		BidTooLow,
+		/// The account already owns the name.
+		AlreadyOwner,
+		/// The account has no offer for the name.
+		NoOffer,
+		/// The name already has `MaxOffers` open offers.
+		TooManyOffers,
+		/// No block within `MAX_SCHEDULE_PROBE` of the end of the grace period has room for
+		/// another release.
+		ReleasesFull,
 	}
 }
 
@@ -70,6 +303,41 @@ decl_module! {
 
 		const MaxLength: u32 = T::MaxLength::get() as u32;
 
+		const MaxRecordLength: u32 = T::MaxRecordLength::get() as u32;
+
+		const MaxRecords: u32 = T::MaxRecords::get();
+
+		const BaseNode: Node = T::BaseNode::get();
+
+		const BlocksPerYear: T::BlockNumber = T::BlocksPerYear::get();
+
+		const GracePeriod: T::BlockNumber = T::GracePeriod::get();
+
+		const MaxReleasesPerBlock: u32 = T::MaxReleasesPerBlock::get();
+
+		const MaxOffers: u32 = T::MaxOffers::get();
+
+		const MinCommitmentAge: T::BlockNumber = T::MinCommitmentAge::get();
+
+		const MaxCommitmentAge: T::BlockNumber = T::MaxCommitmentAge::get();
+
+		fn integrity_test() {
+			assert!(T::MaxReleasesPerBlock::get() > 0, "MaxReleasesPerBlock must not be zero");
+		}
+
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let nodes = <Releases<T>>::take(now);
+			let mut weight = T::DbWeight::get().reads_writes(1, 1)
+				.saturating_add(T::DbWeight::get().reads(2).saturating_mul(nodes.len() as Weight));
+			// Renewed and re-registered names are left in the buckets, they are not available.
+			for node in &nodes {
+				if Self::available(node) {
+					weight = weight.saturating_add(Self::release(node));
+				}
+			}
+			weight
+		}
+
 		#[weight = 50_000_000]
 		fn set_name(origin, name: Vec<u8>) {
 			let sender = ensure_signed(origin)?;
@@ -101,43 +369,595 @@ decl_module! {
 			Self::deposit_event(RawEvent::NameCleared(sender, deposit));
 		}
 
+		/// Transfer the ownership of `node` to `owner`.
+		///
+		/// The sender must own `node` or be an operator of its owner.
 		#[weight = 50_000_000]
-		fn set_owner(origin, name: Vec<u8>) {
+		fn set_owner(origin, node: Node, owner: <T::Lookup as StaticLookup>::Source) {
 			let sender = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
 
-			<Records<T>>::insert(name, &sender);
+			Self::ensure_authorised(&node, &sender)?;
+
+			Self::do_set_owner(node, owner);
+		}
+
+		/// Give the ownership of the `label` subnode of `node` to `owner`.
+		///
+		/// The sender must own `node` or be an operator of its owner, so the owner of a name
+		/// delegates its subdomains and can always take them back. The records left by a
+		/// previous registration of the name are removed.
+		#[weight = 50_000_000 + Module::<T>::clear_records_weight(T::MaxRecords::get())]
+		fn set_subnode_owner(origin, node: Node, label: Vec<u8>, owner: <T::Lookup as StaticLookup>::Source) {
+			let sender = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Self::ensure_authorised(&node, &sender)?;
+			Self::ensure_valid_label(&label)?;
+
+			Self::do_set_subnode_owner(&node, &label, owner);
+		}
+
+		/// Set the time in seconds the records of `node` may be cached.
+		#[weight = 50_000_000]
+		fn set_ttl(origin, node: Node, ttl: u64) {
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_authorised(&node, &sender)?;
+
+			<Ttls>::insert(node, ttl);
+			Self::deposit_event(RawEvent::NewTtl(node, ttl));
 		}
 
+		/// Approve or disapprove `operator` to manage all the nodes of the sender.
 		#[weight = 50_000_000]
-		fn set_subnode_owner(origin, label: Vec<u8>, name: Vec<u8>) {
+		fn set_approval_for_all(origin, operator: <T::Lookup as StaticLookup>::Source, approved: bool) {
+			let sender = ensure_signed(origin)?;
+			let operator = T::Lookup::lookup(operator)?;
+
+			if approved {
+				<Operators<T>>::insert(&sender, &operator, true);
+			} else {
+				<Operators<T>>::remove(&sender, &operator);
+			}
+			Self::deposit_event(RawEvent::ApprovalForAll(sender, operator, approved));
+		}
+
+		/// Set the address of `node` for `coin_type`, an empty address removes it.
+		#[weight = 50_000_000]
+		fn set_addr(origin, node: Node, coin_type: u64, addr: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_authorised(&node, &sender)?;
+			ensure!(addr.len() <= T::MaxRecordLength::get(), Error::<T>::RecordTooLong);
+
+			if addr.is_empty() {
+				if <Addresses>::take(node, coin_type).is_some() {
+					<RecordCounts>::mutate(node, |count| *count = count.saturating_sub(1));
+				}
+			} else {
+				if !<Addresses>::contains_key(node, coin_type) {
+					Self::add_record(&node)?;
+				}
+				<Addresses>::insert(node, coin_type, &addr);
+			}
+			Self::deposit_event(RawEvent::AddrChanged(node, coin_type, addr));
+		}
+
+		/// Set the text record `key` of `node`, an empty value removes it.
+		#[weight = 50_000_000]
+		fn set_text(origin, node: Node, key: Vec<u8>, value: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_authorised(&node, &sender)?;
+			ensure!(key.len() <= T::MaxRecordLength::get(), Error::<T>::RecordTooLong);
+			ensure!(value.len() <= T::MaxRecordLength::get(), Error::<T>::RecordTooLong);
+
+			if value.is_empty() {
+				if <Texts>::take(node, &key).is_some() {
+					<RecordCounts>::mutate(node, |count| *count = count.saturating_sub(1));
+				}
+			} else {
+				if !<Texts>::contains_key(node, &key) {
+					Self::add_record(&node)?;
+				}
+				<Texts>::insert(node, &key, &value);
+			}
+			Self::deposit_event(RawEvent::TextChanged(node, key, value));
+		}
+
+		/// Set the contenthash of `node`, an empty hash removes it.
+		#[weight = 50_000_000]
+		fn set_contenthash(origin, node: Node, hash: Vec<u8>) {
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_authorised(&node, &sender)?;
+			ensure!(hash.len() <= T::MaxRecordLength::get(), Error::<T>::RecordTooLong);
+
+			if hash.is_empty() {
+				<ContentHashes>::remove(node);
+			} else {
+				<ContentHashes>::insert(node, &hash);
+			}
+			Self::deposit_event(RawEvent::ContenthashChanged(node, hash));
+		}
+
+		/// Commit to the registration of a name, see `make_commitment`.
+		///
+		/// The name can be registered once the commitment is `MinCommitmentAge` blocks old,
+		/// so the registration cannot be front-run by someone watching the pool.
+		#[weight = 50_000_000]
+		fn commit(origin, commitment: H256) {
+			ensure_signed(origin)?;
+
+			let now = frame_system::Module::<T>::block_number();
+			if let Some(at) = <Commitments<T>>::get(&commitment) {
+				ensure!(at.saturating_add(T::MaxCommitmentAge::get()) < now, Error::<T>::CommitmentExists);
+			}
+
+			<Commitments<T>>::insert(&commitment, now);
+			Self::deposit_event(RawEvent::Committed(commitment));
+		}
+
+		/// Register `label` under the base node for `owner` and `years` years.
+		///
+		/// The sender pays the price of the name and reserves `ReservationFee`, which is
+		/// returned when the name is released.
+		#[weight = 100_000_000
+			+ Module::<T>::release_weight(T::MaxRecords::get(), T::MaxOffers::get())
+			+ Module::<T>::schedule_weight()]
+		#[transactional]
+		fn register(origin, label: Vec<u8>, owner: <T::Lookup as StaticLookup>::Source, secret: H256, years: u32) {
+			let sender = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Self::ensure_valid_label(&label)?;
+			ensure!(label.len() >= T::MinLength::get(), Error::<T>::TooShort);
+			ensure!(years > 0, Error::<T>::InvalidDuration);
+
+			let now = frame_system::Module::<T>::block_number();
+			let commitment = Self::make_commitment(&label, &owner, &secret);
+			let at = <Commitments<T>>::get(&commitment).ok_or(Error::<T>::NoCommitment)?;
+			ensure!(at.saturating_add(T::MinCommitmentAge::get()) <= now, Error::<T>::CommitmentTooNew);
+			ensure!(now <= at.saturating_add(T::MaxCommitmentAge::get()), Error::<T>::CommitmentTooOld);
+
+			let node = subnode(&T::BaseNode::get(), &label);
+			ensure!(Self::available(&node), Error::<T>::NotAvailable);
+			let expiry = now.saturating_add(Self::duration(years));
+			let release = Self::release_slot(expiry.saturating_add(T::GracePeriod::get()))
+				.ok_or(Error::<T>::ReleasesFull)?;
+			// A name past its grace period may not have been released yet.
+			Self::release(&node);
+
+			let fee = T::Currency::withdraw(
+				&sender,
+				Self::rent_price(&label, years),
+				WithdrawReasons::FEE,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			T::Proceeds::on_unbalanced(fee);
+			let deposit = T::ReservationFee::get();
+			T::Currency::reserve(&sender, deposit)?;
+
+			<Commitments<T>>::remove(&commitment);
+			<ExpiryTimes<T>>::insert(node, expiry);
+			<Deposits<T>>::insert(node, (sender, deposit));
+			<Releases<T>>::append(release, node);
+
+			Self::do_set_subnode_owner(&T::BaseNode::get(), &label, owner.clone());
+			Self::deposit_event(RawEvent::NameRegistered(node, owner, expiry));
+		}
+
+		/// Extend the registration of `label` by `years` years, paid by the sender.
+		///
+		/// Anyone can renew a name, until the end of its grace period.
+		#[weight = 70_000_000 + Module::<T>::schedule_weight()]
+		fn renew(origin, label: Vec<u8>, years: u32) {
+			let sender = ensure_signed(origin)?;
+
+			ensure!(years > 0, Error::<T>::InvalidDuration);
+
+			let node = subnode(&T::BaseNode::get(), &label);
+			let expiry = <ExpiryTimes<T>>::get(node).ok_or(Error::<T>::NotRegistered)?;
+			let now = frame_system::Module::<T>::block_number();
+			ensure!(now < expiry.saturating_add(T::GracePeriod::get()), Error::<T>::NotRegistered);
+			let expiry = expiry.saturating_add(Self::duration(years));
+			let release = Self::release_slot(expiry.saturating_add(T::GracePeriod::get()))
+				.ok_or(Error::<T>::ReleasesFull)?;
+
+			let fee = T::Currency::withdraw(
+				&sender,
+				Self::rent_price(&label, years),
+				WithdrawReasons::FEE,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			T::Proceeds::on_unbalanced(fee);
+
+			<ExpiryTimes<T>>::insert(node, expiry);
+			<Releases<T>>::append(release, node);
+
+			Self::deposit_event(RawEvent::NameRenewed(node, expiry));
+		}
+
+		/// Transfer the name `node` to `dest`, removing its TTL and records if `clear_records`.
+		///
+		/// The sender must own the name, be an operator of its owner or its approved delegate.
+		#[weight = 50_000_000 + Module::<T>::transfer_weight(*clear_records)]
+		fn transfer(origin, node: Node, dest: <T::Lookup as StaticLookup>::Source, clear_records: bool) {
 			let sender = ensure_signed(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+
+			Self::ensure_name(&node)?;
+			ensure!(
+				Self::is_authorised(&node, &sender) || <Approvals<T>>::get(node).as_ref() == Some(&sender),
+				Error::<T>::NotAuthorised,
+			);
 
-			<Records<T>>::insert(name, &sender);
+			Self::do_transfer(node, dest, clear_records);
 		}
 
+		/// Approve `delegate` to transfer the name `node`, until it changes hands.
 		#[weight = 50_000_000]
-		fn set_ttl(origin, node: Vec<u8>, ttl: u64) {
+		fn approve_transfer(origin, node: Node, delegate: <T::Lookup as StaticLookup>::Source) {
 			let sender = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+
+			Self::ensure_name(&node)?;
+			Self::ensure_authorised(&node, &sender)?;
 
+			let owner = <Records<T>>::get(node).ok_or(Error::<T>::NotRegistered)?;
+			<Approvals<T>>::insert(node, &delegate);
+			Self::deposit_event(RawEvent::ApprovedTransfer(node, owner, delegate));
 		}
 
+		/// Cancel the approval to transfer the name `node`.
 		#[weight = 50_000_000]
-		fn set_addr(origin, node: Vec<u8>, cointype: Vec<u8>, value: Vec<u8>) {
+		fn cancel_approval(origin, node: Node) {
 			let sender = ensure_signed(origin)?;
 
+			Self::ensure_authorised(&node, &sender)?;
+
+			let owner = <Records<T>>::get(node).ok_or(Error::<T>::NotRegistered)?;
+			if let Some(delegate) = <Approvals<T>>::take(node) {
+				Self::deposit_event(RawEvent::ApprovalCancelled(node, owner, delegate));
+			}
 		}
 
+		/// Put the name `node` on sale at `price`, or take it off sale with `None`.
 		#[weight = 50_000_000]
-		fn set_text(origin, node: Vec<u8>, key: Vec<u8>, value: Vec<u8>) {
+		fn set_price(origin, node: Node, price: Option<BalanceOf<T>>) {
+			let sender = ensure_signed(origin)?;
+
+			Self::ensure_name(&node)?;
+			Self::ensure_authorised(&node, &sender)?;
+
+			match price {
+				Some(price) => <Prices<T>>::insert(node, price),
+				None => <Prices<T>>::remove(node),
+			}
+			Self::deposit_event(RawEvent::PriceSet(node, price));
+		}
+
+		/// Buy the name `node` at its price, paying at most `bid_price` to its owner.
+		#[weight = 100_000_000 + Module::<T>::transfer_weight(*clear_records)]
+		#[transactional]
+		fn buy_item(origin, node: Node, bid_price: BalanceOf<T>, clear_records: bool) {
+			let buyer = ensure_signed(origin)?;
+
+			Self::ensure_name(&node)?;
+			let owner = <Records<T>>::get(node).ok_or(Error::<T>::NotRegistered)?;
+			ensure!(owner != buyer, Error::<T>::AlreadyOwner);
+			let price = <Prices<T>>::get(node).ok_or(Error::<T>::NotForSale)?;
+			ensure!(bid_price >= price, Error::<T>::BidTooLow);
+
+			T::Currency::transfer(&buyer, &owner, price, ExistenceRequirement::KeepAlive)?;
+			Self::do_transfer(node, buyer.clone(), clear_records);
+			Self::deposit_event(RawEvent::NameSold(node, owner, buyer, price));
+		}
+
+		/// Offer `amount` for the name `node`, replacing the previous offer of the sender.
+		///
+		/// The amount is reserved until the offer is withdrawn or accepted, or the name is
+		/// released. A name has at most `MaxOffers` open offers.
+		#[weight = 70_000_000]
+		#[transactional]
+		fn make_offer(origin, node: Node, amount: BalanceOf<T>) {
 			let sender = ensure_signed(origin)?;
 
+			Self::ensure_name(&node)?;
+			ensure!(<Records<T>>::get(node).as_ref() != Some(&sender), Error::<T>::AlreadyOwner);
+
+			match <Offers<T>>::get(node, &sender) {
+				Some(previous) => {
+					let _ = T::Currency::unreserve(&sender, previous);
+				}
+				None => Self::add_offer(&node)?,
+			}
+			T::Currency::reserve(&sender, amount)?;
+			<Offers<T>>::insert(node, &sender, amount);
+			Self::deposit_event(RawEvent::OfferMade(node, sender, amount));
 		}
 
+		/// Withdraw the offer of the sender for the name `node`.
 		#[weight = 50_000_000]
-		fn set_contenthash(origin, node: Vec<u8>, hash: Vec<u8>) {
+		fn withdraw_offer(origin, node: Node) {
 			let sender = ensure_signed(origin)?;
 
+			let amount = <Offers<T>>::take(node, &sender).ok_or(Error::<T>::NoOffer)?;
+			<OfferCounts>::mutate(node, |count| *count = count.saturating_sub(1));
+			let _ = T::Currency::unreserve(&sender, amount);
+			Self::deposit_event(RawEvent::OfferWithdrawn(node, sender));
+		}
+
+		/// Accept the offer of `buyer` for the name `node`, which is transferred to it.
+		#[weight = 100_000_000 + Module::<T>::transfer_weight(*clear_records)]
+		#[transactional]
+		fn accept_offer(origin, node: Node, buyer: <T::Lookup as StaticLookup>::Source, clear_records: bool) {
+			let sender = ensure_signed(origin)?;
+			let buyer = T::Lookup::lookup(buyer)?;
+
+			Self::ensure_name(&node)?;
+			Self::ensure_authorised(&node, &sender)?;
+
+			let owner = <Records<T>>::get(node).ok_or(Error::<T>::NotRegistered)?;
+			let amount = <Offers<T>>::take(node, &buyer).ok_or(Error::<T>::NoOffer)?;
+			<OfferCounts>::mutate(node, |count| *count = count.saturating_sub(1));
+			let missing = T::Currency::repatriate_reserved(&buyer, &owner, amount, BalanceStatus::Free)?;
+			ensure!(missing.is_zero(), Error::<T>::NoOffer);
+
+			Self::do_transfer(node, buyer.clone(), clear_records);
+			Self::deposit_event(RawEvent::NameSold(node, owner, buyer, amount));
+		}
+
+		/// Set the owner of any node, e.g. the root node or the top level domains.
+		#[weight = 50_000_000]
+		fn force_set_owner(origin, node: Node, owner: <T::Lookup as StaticLookup>::Source) {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Self::do_set_owner(node, owner);
+		}
+	}
+}
+
+impl<T: Config> Module<T> {
+	/// The owner of `node`, none for the subnodes of a released name.
+	pub fn owner(node: Node) -> Option<T::AccountId> {
+		<Records<T>>::get(node).filter(|_| Self::is_current(&node))
+	}
+
+	/// Whether `who` owns `node` or is an operator of its owner, and neither `node` nor the
+	/// name it was created under has expired.
+	pub fn is_authorised(node: &Node, who: &T::AccountId) -> bool {
+		if Self::is_lapsed(node) || !Self::is_current(node) {
+			return false;
+		}
+		match <Records<T>>::get(node) {
+			Some(owner) => &owner == who || <Operators<T>>::get(&owner, who),
+			None => false,
+		}
+	}
+
+	/// Whether `node` is a name of the registrar controller past its expiry.
+	pub fn is_expired(node: &Node) -> bool {
+		match <ExpiryTimes<T>>::get(node) {
+			Some(expiry) => expiry <= frame_system::Module::<T>::block_number(),
+			None => false,
+		}
+	}
+
+	/// Whether `node` or the name of the registrar controller it was created under is past
+	/// its expiry, an expired name takes its subdomains with it like in `resolve`.
+	pub fn is_lapsed(node: &Node) -> bool {
+		Self::is_expired(node) || <Anchors>::get(node).map_or(false, |(name, _)| Self::is_expired(&name))
+	}
+
+	/// Whether `node` was created under the current registration of the name it is a subnode
+	/// of, if any.
+	pub fn is_current(node: &Node) -> bool {
+		match <Anchors>::get(node) {
+			Some((name, generation)) => <Generations>::get(name) == generation,
+			None => true,
+		}
+	}
+
+	/// Whether `node` can be registered: it is unowned or past its grace period.
+	pub fn available(node: &Node) -> bool {
+		match <ExpiryTimes<T>>::get(node) {
+			Some(expiry) => {
+				expiry.saturating_add(T::GracePeriod::get()) <= frame_system::Module::<T>::block_number()
+			}
+			None => !<Records<T>>::contains_key(node),
+		}
+	}
+
+	/// Resolve the `record_type` record of the dot separated `name`, for the DNS.
+	pub fn resolve(name: &[u8], record_type: DnsRecordType) -> Resolution {
+		let node = namehash(name);
+		if !<Records<T>>::contains_key(node) || !Self::is_current(&node) {
+			return Resolution::NxDomain;
+		}
+		// An expired name takes its subdomains with it.
+		let mut parent = ROOT_NODE;
+		for label in name.split(|c| *c == b'.').rev() {
+			parent = subnode(&parent, label);
+			if Self::is_expired(&parent) {
+				return Resolution::NxDomain;
+			}
+		}
+
+		let record = <Texts>::get(node, record_type.text_key().to_vec())
+			.map(|data| (record_type, data))
+			.or_else(|| {
+				<Texts>::get(node, DnsRecordType::CNAME.text_key().to_vec())
+					.map(|data| (DnsRecordType::CNAME, data))
+			});
+		Resolution::Found { ttl: <Ttls>::get(node), record }
+	}
+
+	/// The price of the registration or renewal of `label` for `years` years.
+	pub fn rent_price(label: &[u8], years: u32) -> BalanceOf<T> {
+		T::PriceOracle::annual_price(label.len()).saturating_mul(years.into())
+	}
+
+	/// The commitment to the registration of `label` for `owner`, hiding the label behind
+	/// the secret until the registration.
+	pub fn make_commitment(label: &[u8], owner: &T::AccountId, secret: &H256) -> H256 {
+		H256(sp_io::hashing::blake2_256(&(label, owner, secret).encode()))
+	}
+
+	fn duration(years: u32) -> T::BlockNumber {
+		T::BlocksPerYear::get().saturating_mul(years.into())
+	}
+
+	fn ensure_authorised(node: &Node, who: &T::AccountId) -> DispatchResult {
+		ensure!(!Self::is_lapsed(node), Error::<T>::Expired);
+		ensure!(Self::is_authorised(node, who), Error::<T>::NotAuthorised);
+		Ok(())
+	}
+
+	/// Ensure `node` is a name of the registrar controller which has not expired.
+	fn ensure_name(node: &Node) -> DispatchResult {
+		ensure!(<ExpiryTimes<T>>::contains_key(node), Error::<T>::NotRegistered);
+		ensure!(!Self::is_expired(node), Error::<T>::Expired);
+		Ok(())
+	}
+
+	fn ensure_valid_label(label: &[u8]) -> DispatchResult {
+		ensure!(!label.is_empty() && !label.contains(&b'.'), Error::<T>::InvalidLabel);
+		// Names resolve as they are hashed, `Gav` would be another name than `gav`.
+		ensure!(!label.iter().any(u8::is_ascii_uppercase), Error::<T>::InvalidLabel);
+		ensure!(label.len() <= T::MaxLength::get(), Error::<T>::TooLong);
+		Ok(())
+	}
+
+	fn do_transfer(node: Node, dest: T::AccountId, clear_records: bool) {
+		if clear_records {
+			Self::clear_records(&node);
 		}
+		Self::do_set_owner(node, dest);
+	}
+
+	/// Set the owner of `node`, which drops its transfer approval and price.
+	fn do_set_owner(node: Node, owner: T::AccountId) {
+		<Records<T>>::insert(node, &owner);
+		<Approvals<T>>::remove(node);
+		<Prices<T>>::remove(node);
+		Self::deposit_event(RawEvent::Transfer(node, owner));
+	}
 
+	/// The first block from `at` on with room left in its bucket of `Releases`, searching at
+	/// most `MAX_SCHEDULE_PROBE` blocks.
+	fn release_slot(mut at: T::BlockNumber) -> Option<T::BlockNumber> {
+		let max = max(T::MaxReleasesPerBlock::get(), 1) as usize;
+		for _ in 0..MAX_SCHEDULE_PROBE {
+			if <Releases<T>>::decode_len(at).unwrap_or(0) < max {
+				return Some(at);
+			}
+			at = at.saturating_add(1u32.into());
+		}
+		None
+	}
+
+	/// The weight of the search of a release slot and of the scheduling.
+	fn schedule_weight() -> Weight {
+		T::DbWeight::get().reads_writes(MAX_SCHEDULE_PROBE as Weight, 1)
+	}
+
+	/// Release a registered name: return its deposit and the open offers for it, drop its
+	/// owner and its records.
+	///
+	/// Returns the weight used.
+	fn release(node: &Node) -> Weight {
+		if <ExpiryTimes<T>>::take(node).is_none() {
+			return T::DbWeight::get().reads(1);
+		}
+		if let Some((who, deposit)) = <Deposits<T>>::take(node) {
+			let _ = T::Currency::unreserve(&who, deposit);
+		}
+		<Records<T>>::remove(node);
+		<Approvals<T>>::remove(node);
+		<Prices<T>>::remove(node);
+		// The subnodes of the name go with it.
+		<Generations>::mutate(node, |generation| *generation = generation.wrapping_add(1));
+		let mut offers = 0;
+		for (who, amount) in <Offers<T>>::drain_prefix(node) {
+			let _ = T::Currency::unreserve(&who, amount);
+			offers += 1;
+		}
+		<OfferCounts>::remove(node);
+		let records = <RecordCounts>::get(node);
+		Self::clear_records(node);
+		Self::deposit_event(RawEvent::NameReleased(*node));
+		Self::release_weight(records, offers)
+	}
+
+	/// The weight of the release of a name with `records` address and text records and
+	/// `offers` open offers.
+	fn release_weight(records: u32, offers: u32) -> Weight {
+		T::DbWeight::get().reads_writes(5, 8)
+			.saturating_add(T::DbWeight::get().reads_writes(2, 2).saturating_mul(offers as Weight))
+			.saturating_add(Self::clear_records_weight(records))
+	}
+
+	/// The weight of `clear_records` for `records` address and text records.
+	fn clear_records_weight(records: u32) -> Weight {
+		T::DbWeight::get().reads_writes(1, 5).saturating_add(T::DbWeight::get().writes(records as Weight))
+	}
+
+	/// The weight of a transfer of a name which clears its records or not.
+	fn transfer_weight(clear_records: bool) -> Weight {
+		if clear_records {
+			Self::clear_records_weight(T::MaxRecords::get())
+		} else {
+			0
+		}
+	}
+
+	/// Count a new offer for `node`, up to `MaxOffers`.
+	fn add_offer(node: &Node) -> DispatchResult {
+		<OfferCounts>::try_mutate(node, |count| {
+			ensure!(*count < T::MaxOffers::get(), Error::<T>::TooManyOffers);
+			*count += 1;
+			Ok(())
+		})
+	}
+
+	/// Count a new address or text record of `node`, up to `MaxRecords`.
+	fn add_record(node: &Node) -> DispatchResult {
+		<RecordCounts>::try_mutate(node, |count| {
+			ensure!(*count < T::MaxRecords::get(), Error::<T>::TooManyRecords);
+			*count += 1;
+			Ok(())
+		})
+	}
+
+	/// Remove the TTL and all the resolver records of `node`, at most `MaxRecords` address
+	/// and text records.
+	fn clear_records(node: &Node) {
+		<Ttls>::remove(node);
+		<ContentHashes>::remove(node);
+		<RecordCounts>::remove(node);
+		<Addresses>::remove_prefix(node);
+		<Texts>::remove_prefix(node);
+	}
+
+	fn do_set_subnode_owner(node: &Node, label: &[u8], owner: T::AccountId) -> Node {
+		let subnode = subnode(node, label);
+		if !Self::is_current(&subnode) {
+			Self::clear_records(&subnode);
+		}
+		let anchor = if <ExpiryTimes<T>>::contains_key(node) {
+			Some((*node, <Generations>::get(node)))
+		} else {
+			<Anchors>::get(node)
+		};
+		match anchor {
+			Some(anchor) => <Anchors>::insert(subnode, anchor),
+			None => <Anchors>::remove(subnode),
+		}
+		<Records<T>>::insert(subnode, &owner);
+		Self::deposit_event(RawEvent::NewOwner(*node, label_hash(label), owner));
+		subnode
 	}
 }
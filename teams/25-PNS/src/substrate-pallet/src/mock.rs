@@ -0,0 +1,135 @@
+// Creating mock runtime here
+
+use crate as pallet_pns;
+use crate::{namehash, Node, PriceOracle};
+use frame_support::{ord_parameter_types, parameter_types, traits::OnInitialize};
+use frame_system::EnsureSignedBy;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		Pns: pallet_pns::{Module, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Call = Call;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ReservationFee: u64 = 2;
+	pub const MinLength: usize = 3;
+	pub const MaxLength: usize = 16;
+	pub const MaxRecordLength: usize = 64;
+	pub const MaxRecords: u32 = 3;
+	pub BaseNode: Node = namehash(b"dot");
+	pub const BlocksPerYear: u64 = 100;
+	pub const GracePeriod: u64 = 10;
+	pub const MaxReleasesPerBlock: u32 = 2;
+	pub const MaxOffers: u32 = 2;
+	pub const MinCommitmentAge: u64 = 2;
+	pub const MaxCommitmentAge: u64 = 20;
+}
+
+// Three bytes labels cost 20 a year, longer ones 5.
+pub struct LengthPrice;
+impl PriceOracle<u64> for LengthPrice {
+	fn annual_price(length: usize) -> u64 {
+		if length <= 3 { 20 } else { 5 }
+	}
+}
+
+ord_parameter_types! {
+	pub const One: u64 = 1;
+}
+
+impl pallet_pns::Config for Test {
+	type Event = Event;
+	type Currency = Balances;
+	type ReservationFee = ReservationFee;
+	type Slashed = ();
+	type ForceOrigin = EnsureSignedBy<One, u64>;
+	type MinLength = MinLength;
+	type MaxLength = MaxLength;
+	type MaxRecordLength = MaxRecordLength;
+	type MaxRecords = MaxRecords;
+	type BaseNode = BaseNode;
+	type PriceOracle = LengthPrice;
+	type Proceeds = ();
+	type BlocksPerYear = BlocksPerYear;
+	type GracePeriod = GracePeriod;
+	type MaxReleasesPerBlock = MaxReleasesPerBlock;
+	type MaxOffers = MaxOffers;
+	type MinCommitmentAge = MinCommitmentAge;
+	type MaxCommitmentAge = MaxCommitmentAge;
+}
+
+// Accounts 1 to 3 start with some balance, 1 is the force origin.
+pub fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Pns::on_initialize(System::block_number());
+	}
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100), (2, 100), (3, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,589 @@
+// Tests to be written here
+
+use crate::{
+	mock::*, namehash, subnode, DnsRecordType, Error, Event as PnsEvent, RawEvent, Releases, Resolution,
+	MAX_SCHEDULE_PROBE, ROOT_NODE,
+};
+use frame_support::{assert_noop, assert_ok};
+use sp_core::H256;
+
+const SECRET: H256 = H256([7; 32]);
+
+fn eth() -> H256 {
+	namehash(b"eth")
+}
+
+// Give `eth` to account 2.
+fn setup_eth() {
+	assert_ok!(Pns::force_set_owner(Origin::signed(1), ROOT_NODE, 1));
+	assert_ok!(Pns::set_subnode_owner(Origin::signed(1), ROOT_NODE, b"eth".to_vec(), 2));
+}
+
+fn dot(label: &[u8]) -> H256 {
+	subnode(&namehash(b"dot"), label)
+}
+
+// Account 2 commits at block 1 and registers `label` for `owner` at block 3.
+fn register(label: &[u8], owner: u64, years: u32) {
+	let commitment = Pns::make_commitment(label, &owner, &SECRET);
+	assert_ok!(Pns::commit(Origin::signed(2), commitment));
+	run_to_block(System::block_number() + 2);
+	assert_ok!(Pns::register(Origin::signed(2), label.to_vec(), owner, SECRET, years));
+}
+
+fn last_event() -> PnsEvent<Test> {
+	System::events()
+		.into_iter()
+		.filter_map(|r| if let Event::pallet_pns(e) = r.event { Some(e) } else { None })
+		.last()
+		.unwrap()
+}
+
+#[test]
+fn namehash_matches_ens() {
+	assert_eq!(namehash(b""), ROOT_NODE);
+	assert_eq!(
+		namehash(b"eth"),
+		"93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae".parse().unwrap(),
+	);
+	assert_eq!(
+		namehash(b"foo.eth"),
+		"de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f".parse().unwrap(),
+	);
+	assert_eq!(subnode(&eth(), b"foo"), namehash(b"foo.eth"));
+}
+
+#[test]
+fn force_set_owner_works() {
+	new_test_ext().execute_with(|| {
+		assert!(Pns::force_set_owner(Origin::signed(2), ROOT_NODE, 2).is_err());
+		assert_ok!(Pns::force_set_owner(Origin::signed(1), ROOT_NODE, 1));
+		assert_eq!(Pns::owner(ROOT_NODE), Some(1));
+	});
+}
+
+#[test]
+fn subnode_owner_is_set_by_parent_owner() {
+	new_test_ext().execute_with(|| {
+		setup_eth();
+		assert_eq!(Pns::owner(eth()), Some(2));
+
+		assert_noop!(
+			Pns::set_subnode_owner(Origin::signed(3), eth(), b"foo".to_vec(), 3),
+			Error::<Test>::NotAuthorised,
+		);
+		assert_ok!(Pns::set_subnode_owner(Origin::signed(2), eth(), b"foo".to_vec(), 3));
+		assert_eq!(Pns::owner(namehash(b"foo.eth")), Some(3));
+
+		// The owner of the parent can always take the subdomain back.
+		assert_ok!(Pns::set_subnode_owner(Origin::signed(2), eth(), b"foo".to_vec(), 2));
+		assert_eq!(Pns::owner(namehash(b"foo.eth")), Some(2));
+	});
+}
+
+#[test]
+fn invalid_labels_are_rejected() {
+	new_test_ext().execute_with(|| {
+		setup_eth();
+
+		assert_noop!(
+			Pns::set_subnode_owner(Origin::signed(2), eth(), vec![], 3),
+			Error::<Test>::InvalidLabel,
+		);
+		assert_noop!(
+			Pns::set_subnode_owner(Origin::signed(2), eth(), b"foo.bar".to_vec(), 3),
+			Error::<Test>::InvalidLabel,
+		);
+		assert_noop!(
+			Pns::set_subnode_owner(Origin::signed(2), eth(), b"Foo".to_vec(), 3),
+			Error::<Test>::InvalidLabel,
+		);
+		assert_noop!(
+			Pns::register(Origin::signed(2), b"Gav".to_vec(), 2, SECRET, 1),
+			Error::<Test>::InvalidLabel,
+		);
+		assert_noop!(
+			Pns::set_subnode_owner(Origin::signed(2), eth(), b"abcdefghijklmnopq".to_vec(), 3),
+			Error::<Test>::TooLong,
+		);
+	});
+}
+
+#[test]
+fn set_owner_works() {
+	new_test_ext().execute_with(|| {
+		setup_eth();
+
+		assert_noop!(Pns::set_owner(Origin::signed(3), eth(), 3), Error::<Test>::NotAuthorised);
+		assert_ok!(Pns::set_owner(Origin::signed(2), eth(), 3));
+		assert_eq!(Pns::owner(eth()), Some(3));
+		assert_eq!(last_event(), RawEvent::Transfer(eth(), 3));
+
+		assert_noop!(Pns::set_owner(Origin::signed(2), eth(), 2), Error::<Test>::NotAuthorised);
+	});
+}
+
+#[test]
+fn operators_manage_all_nodes() {
+	new_test_ext().execute_with(|| {
+		setup_eth();
+
+		assert_ok!(Pns::set_approval_for_all(Origin::signed(2), 3, true));
+		assert!(Pns::is_approved_for_all(2, 3));
+		assert!(Pns::is_authorised(&eth(), &3));
+		assert_ok!(Pns::set_subnode_owner(Origin::signed(3), eth(), b"foo".to_vec(), 3));
+		assert_ok!(Pns::set_ttl(Origin::signed(3), eth(), 60));
+
+		assert_ok!(Pns::set_approval_for_all(Origin::signed(2), 3, false));
+		assert!(!Pns::is_approved_for_all(2, 3));
+		assert_noop!(Pns::set_ttl(Origin::signed(3), eth(), 30), Error::<Test>::NotAuthorised);
+	});
+}
+
+#[test]
+fn ttl_works() {
+	new_test_ext().execute_with(|| {
+		setup_eth();
+
+		assert_eq!(Pns::ttl(eth()), 0);
+		assert_noop!(Pns::set_ttl(Origin::signed(3), eth(), 60), Error::<Test>::NotAuthorised);
+		assert_ok!(Pns::set_ttl(Origin::signed(2), eth(), 60));
+		assert_eq!(Pns::ttl(eth()), 60);
+		assert_eq!(last_event(), RawEvent::NewTtl(eth(), 60));
+	});
+}
+
+#[test]
+fn resolver_records_work() {
+	new_test_ext().execute_with(|| {
+		setup_eth();
+
+		assert_noop!(
+			Pns::set_addr(Origin::signed(3), eth(), 60, vec![1; 20]),
+			Error::<Test>::NotAuthorised,
+		);
+		assert_ok!(Pns::set_addr(Origin::signed(2), eth(), 60, vec![1; 20]));
+		assert_eq!(Pns::addr(eth(), 60), Some(vec![1; 20]));
+		assert_eq!(Pns::addr(eth(), 354), None);
+
+		assert_ok!(Pns::set_text(Origin::signed(2), eth(), b"url".to_vec(), b"https://eth".to_vec()));
+		assert_eq!(Pns::text(eth(), b"url".to_vec()), Some(b"https://eth".to_vec()));
+
+		assert_ok!(Pns::set_contenthash(Origin::signed(2), eth(), vec![0xe3, 1]));
+		assert_eq!(Pns::contenthash(eth()), Some(vec![0xe3, 1]));
+
+		// Empty values remove the records.
+		assert_ok!(Pns::set_addr(Origin::signed(2), eth(), 60, vec![]));
+		assert_eq!(Pns::addr(eth(), 60), None);
+		assert_ok!(Pns::set_text(Origin::signed(2), eth(), b"url".to_vec(), vec![]));
+		assert_eq!(Pns::text(eth(), b"url".to_vec()), None);
+		assert_ok!(Pns::set_contenthash(Origin::signed(2), eth(), vec![]));
+		assert_eq!(Pns::contenthash(eth()), None);
+		assert_eq!(last_event(), RawEvent::ContenthashChanged(eth(), vec![]));
+	});
+}
+
+#[test]
+fn records_are_bounded() {
+	new_test_ext().execute_with(|| {
+		setup_eth();
+
+		assert_noop!(
+			Pns::set_addr(Origin::signed(2), eth(), 60, vec![1; 65]),
+			Error::<Test>::RecordTooLong,
+		);
+		assert_noop!(
+			Pns::set_text(Origin::signed(2), eth(), vec![1; 65], vec![1]),
+			Error::<Test>::RecordTooLong,
+		);
+		assert_noop!(
+			Pns::set_text(Origin::signed(2), eth(), vec![1], vec![1; 65]),
+			Error::<Test>::RecordTooLong,
+		);
+		assert_noop!(
+			Pns::set_contenthash(Origin::signed(2), eth(), vec![1; 65]),
+			Error::<Test>::RecordTooLong,
+		);
+
+		// At most 3 address and text records, replacing one does not count.
+		assert_ok!(Pns::set_addr(Origin::signed(2), eth(), 60, vec![1; 20]));
+		assert_ok!(Pns::set_text(Origin::signed(2), eth(), b"url".to_vec(), b"https://eth".to_vec()));
+		assert_ok!(Pns::set_text(Origin::signed(2), eth(), b"email".to_vec(), b"gav@eth".to_vec()));
+		assert_ok!(Pns::set_addr(Origin::signed(2), eth(), 60, vec![2; 20]));
+		assert_eq!(Pns::record_count(eth()), 3);
+		assert_noop!(
+			Pns::set_addr(Origin::signed(2), eth(), 354, vec![1; 32]),
+			Error::<Test>::TooManyRecords,
+		);
+		assert_noop!(
+			Pns::set_text(Origin::signed(2), eth(), b"avatar".to_vec(), b"gav.png".to_vec()),
+			Error::<Test>::TooManyRecords,
+		);
+		assert_ok!(Pns::set_text(Origin::signed(2), eth(), b"email".to_vec(), vec![]));
+		assert_ok!(Pns::set_text(Origin::signed(2), eth(), b"email".to_vec(), vec![]));
+		assert_eq!(Pns::record_count(eth()), 2);
+		assert_ok!(Pns::set_addr(Origin::signed(2), eth(), 354, vec![1; 32]));
+	});
+}
+
+#[test]
+fn nicks_still_work() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Pns::set_name(Origin::signed(2), b"ga".to_vec()), Error::<Test>::TooShort);
+		assert_ok!(Pns::set_name(Origin::signed(2), b"gav".to_vec()));
+		assert_eq!(Balances::reserved_balance(2), 2);
+		assert_ok!(Pns::clear_name(Origin::signed(2)));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_noop!(Pns::clear_name(Origin::signed(2)), Error::<Test>::Unnamed);
+	});
+}
+
+#[test]
+fn registration_needs_a_mature_commitment() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Pns::register(Origin::signed(2), b"gav".to_vec(), 2, SECRET, 1),
+			Error::<Test>::NoCommitment,
+		);
+
+		let commitment = Pns::make_commitment(b"gav", &2, &SECRET);
+		assert_ok!(Pns::commit(Origin::signed(2), commitment));
+		assert_eq!(Pns::commitment(commitment), Some(1));
+		// Someone watching the pool cannot reset the age of the commitment.
+		assert_noop!(Pns::commit(Origin::signed(3), commitment), Error::<Test>::CommitmentExists);
+
+		run_to_block(2);
+		assert_noop!(
+			Pns::register(Origin::signed(2), b"gav".to_vec(), 2, SECRET, 1),
+			Error::<Test>::CommitmentTooNew,
+		);
+		// The commitment does not reveal the label, nor hold for another owner.
+		run_to_block(3);
+		assert_noop!(
+			Pns::register(Origin::signed(3), b"gav".to_vec(), 3, SECRET, 1),
+			Error::<Test>::NoCommitment,
+		);
+		assert_noop!(
+			Pns::register(Origin::signed(2), b"gav".to_vec(), 2, SECRET, 0),
+			Error::<Test>::InvalidDuration,
+		);
+		run_to_block(22);
+		assert_noop!(
+			Pns::register(Origin::signed(2), b"gav".to_vec(), 2, SECRET, 1),
+			Error::<Test>::CommitmentTooOld,
+		);
+	});
+}
+
+#[test]
+fn register_works() {
+	new_test_ext().execute_with(|| {
+		register(b"gav", 2, 2);
+
+		assert_eq!(Pns::owner(dot(b"gav")), Some(2));
+		assert_eq!(Pns::expiry_time(dot(b"gav")), Some(203));
+		assert_eq!(Pns::deposit(dot(b"gav")), Some((2, 2)));
+		assert_eq!(last_event(), RawEvent::NameRegistered(dot(b"gav"), 2, 203));
+		// Two years of a three bytes name and the deposit.
+		assert_eq!(Balances::free_balance(2), 58);
+		assert_eq!(Balances::reserved_balance(2), 2);
+		assert_eq!(Pns::rent_price(b"gavin", 2), 10);
+
+		assert!(!Pns::available(&dot(b"gav")));
+		let commitment = Pns::make_commitment(b"gav", &3, &SECRET);
+		assert_ok!(Pns::commit(Origin::signed(3), commitment));
+		run_to_block(5);
+		assert_noop!(
+			Pns::register(Origin::signed(3), b"gav".to_vec(), 3, SECRET, 1),
+			Error::<Test>::NotAvailable,
+		);
+	});
+}
+
+#[test]
+fn names_expire_and_are_released() {
+	new_test_ext().execute_with(|| {
+		register(b"gav", 2, 1);
+		assert_ok!(Pns::set_addr(Origin::signed(2), dot(b"gav"), 60, vec![1; 20]));
+		let www = subnode(&dot(b"gav"), b"www");
+		assert_ok!(Pns::set_subnode_owner(Origin::signed(2), dot(b"gav"), b"www".to_vec(), 3));
+
+		// In the grace period the name can only be renewed, its subdomains are frozen too.
+		run_to_block(103);
+		assert!(Pns::is_expired(&dot(b"gav")));
+		assert_noop!(
+			Pns::set_addr(Origin::signed(2), dot(b"gav"), 60, vec![2; 20]),
+			Error::<Test>::Expired,
+		);
+		assert!(Pns::is_lapsed(&www));
+		assert!(!Pns::is_authorised(&www, &3));
+		assert_noop!(Pns::set_addr(Origin::signed(3), www, 60, vec![2; 20]), Error::<Test>::Expired);
+		assert!(!Pns::available(&dot(b"gav")));
+
+		run_to_block(113);
+		assert_eq!(last_event(), RawEvent::NameReleased(dot(b"gav")));
+		assert_eq!(Pns::owner(dot(b"gav")), None);
+		assert_eq!(Pns::expiry_time(dot(b"gav")), None);
+		assert_eq!(Pns::addr(dot(b"gav"), 60), None);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert!(Pns::available(&dot(b"gav")));
+
+		let commitment = Pns::make_commitment(b"gav", &3, &SECRET);
+		assert_ok!(Pns::commit(Origin::signed(3), commitment));
+		run_to_block(115);
+		assert_ok!(Pns::register(Origin::signed(3), b"gav".to_vec(), 3, SECRET, 1));
+		assert_eq!(Pns::owner(dot(b"gav")), Some(3));
+	});
+}
+
+#[test]
+fn releases_are_bounded_per_block() {
+	new_test_ext().execute_with(|| {
+		let labels: [&[u8]; 3] = [b"gav", b"bob", b"eve"];
+		for label in labels.iter() {
+			assert_ok!(Pns::commit(Origin::signed(2), Pns::make_commitment(label, &2, &SECRET)));
+		}
+		run_to_block(3);
+		for label in labels.iter() {
+			assert_ok!(Pns::register(Origin::signed(2), label.to_vec(), 2, SECRET, 1));
+		}
+
+		// Two names are released at the end of their grace period, the third one a block later.
+		run_to_block(113);
+		assert_eq!(Pns::owner(dot(b"gav")), None);
+		assert_eq!(Pns::owner(dot(b"bob")), None);
+		assert_eq!(Pns::owner(dot(b"eve")), Some(2));
+		assert!(Pns::available(&dot(b"eve")));
+		run_to_block(114);
+		assert_eq!(Pns::owner(dot(b"eve")), None);
+		assert_eq!(Balances::reserved_balance(2), 0);
+	});
+}
+
+#[test]
+fn registration_fails_when_no_release_slot_is_free() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pns::commit(Origin::signed(2), Pns::make_commitment(b"gav", &2, &SECRET)));
+		run_to_block(3);
+		// A one year name registered now is released at block 113 or in the next blocks.
+		for at in 113..113 + MAX_SCHEDULE_PROBE as u64 {
+			<Releases<Test>>::insert(at, vec![ROOT_NODE; 2]);
+		}
+		assert_noop!(
+			Pns::register(Origin::signed(2), b"gav".to_vec(), 2, SECRET, 1),
+			Error::<Test>::ReleasesFull,
+		);
+
+		<Releases<Test>>::remove(113 + MAX_SCHEDULE_PROBE as u64 - 1);
+		assert_ok!(Pns::register(Origin::signed(2), b"gav".to_vec(), 2, SECRET, 1));
+		assert_eq!(<Releases<Test>>::get(113 + MAX_SCHEDULE_PROBE as u64 - 1), vec![dot(b"gav")]);
+	});
+}
+
+#[test]
+fn subnodes_go_with_their_released_name() {
+	new_test_ext().execute_with(|| {
+		register(b"gav", 2, 1);
+		let www = subnode(&dot(b"gav"), b"www");
+		assert_ok!(Pns::set_subnode_owner(Origin::signed(2), dot(b"gav"), b"www".to_vec(), 3));
+		assert_ok!(Pns::set_addr(Origin::signed(3), www, 60, vec![1; 20]));
+		assert_ok!(Pns::set_subnode_owner(Origin::signed(3), www, b"api".to_vec(), 3));
+		assert_eq!(Pns::anchor(subnode(&www, b"api")), Some((dot(b"gav"), 0)));
+
+		run_to_block(113);
+		assert_eq!(Pns::generation(dot(b"gav")), 1);
+		assert_eq!(Pns::owner(www), None);
+		assert_eq!(Pns::owner(subnode(&www, b"api")), None);
+		assert!(!Pns::is_authorised(&www, &3));
+		assert_eq!(Pns::resolve(b"www.gav.dot", DnsRecordType::A), Resolution::NxDomain);
+
+		// The new registrant does not inherit the subdomains nor their records.
+		register(b"gav", 1, 1);
+		assert_eq!(Pns::owner(www), None);
+		assert_noop!(
+			Pns::set_addr(Origin::signed(3), www, 60, vec![2; 20]),
+			Error::<Test>::NotAuthorised,
+		);
+		assert_ok!(Pns::set_subnode_owner(Origin::signed(1), dot(b"gav"), b"www".to_vec(), 1));
+		assert_eq!(Pns::owner(www), Some(1));
+		assert_eq!(Pns::addr(www, 60), None);
+		assert_eq!(Pns::owner(subnode(&www, b"api")), None);
+	});
+}
+
+#[test]
+fn anyone_can_renew() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Pns::renew(Origin::signed(3), b"gav".to_vec(), 1), Error::<Test>::NotRegistered);
+		register(b"gav", 2, 1);
+
+		run_to_block(105);
+		assert_ok!(Pns::renew(Origin::signed(3), b"gav".to_vec(), 1));
+		assert_eq!(Balances::free_balance(3), 80);
+		assert_eq!(Pns::expiry_time(dot(b"gav")), Some(203));
+		assert_eq!(last_event(), RawEvent::NameRenewed(dot(b"gav"), 203));
+		assert!(!Pns::is_expired(&dot(b"gav")));
+
+		// The release moved to the end of the new grace period.
+		run_to_block(113);
+		assert_eq!(Pns::owner(dot(b"gav")), Some(2));
+		run_to_block(213);
+		assert_eq!(Pns::owner(dot(b"gav")), None);
+		assert_noop!(Pns::renew(Origin::signed(3), b"gav".to_vec(), 1), Error::<Test>::NotRegistered);
+	});
+}
+
+#[test]
+fn resolve_works() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Pns::resolve(b"gav.dot", DnsRecordType::A), Resolution::NxDomain);
+		register(b"gav", 2, 1);
+		assert_eq!(
+			Pns::resolve(b"gav.dot", DnsRecordType::A),
+			Resolution::Found { ttl: 0, record: None },
+		);
+
+		assert_ok!(Pns::set_ttl(Origin::signed(2), dot(b"gav"), 300));
+		assert_ok!(Pns::set_text(Origin::signed(2), dot(b"gav"), b"dns.A".to_vec(), vec![127, 0, 0, 1]));
+		assert_ok!(Pns::set_text(Origin::signed(2), dot(b"gav"), b"dns.CNAME".to_vec(), b"gav.io".to_vec()));
+		assert_eq!(
+			Pns::resolve(b"gav.dot", DnsRecordType::A),
+			Resolution::Found { ttl: 300, record: Some((DnsRecordType::A, vec![127, 0, 0, 1])) },
+		);
+		// Missing records fall back to the CNAME.
+		assert_eq!(
+			Pns::resolve(b"gav.dot", DnsRecordType::TXT),
+			Resolution::Found { ttl: 300, record: Some((DnsRecordType::CNAME, b"gav.io".to_vec())) },
+		);
+
+		// Expired names and their subdomains do not resolve.
+		assert_ok!(Pns::set_subnode_owner(Origin::signed(2), dot(b"gav"), b"www".to_vec(), 2));
+		assert!(matches!(Pns::resolve(b"www.gav.dot", DnsRecordType::A), Resolution::Found { .. }));
+		run_to_block(103);
+		assert_eq!(Pns::resolve(b"gav.dot", DnsRecordType::A), Resolution::NxDomain);
+		assert_eq!(Pns::resolve(b"www.gav.dot", DnsRecordType::A), Resolution::NxDomain);
+	});
+}
+
+#[test]
+fn names_transfer_with_approvals() {
+	new_test_ext().execute_with(|| {
+		setup_eth();
+		assert_noop!(Pns::transfer(Origin::signed(2), eth(), 3, false), Error::<Test>::NotRegistered);
+
+		register(b"gav", 2, 1);
+		assert_ok!(Pns::set_addr(Origin::signed(2), dot(b"gav"), 60, vec![1; 20]));
+		assert_noop!(
+			Pns::transfer(Origin::signed(3), dot(b"gav"), 3, false),
+			Error::<Test>::NotAuthorised,
+		);
+
+		assert_ok!(Pns::approve_transfer(Origin::signed(2), dot(b"gav"), 3));
+		assert_eq!(Pns::approval(dot(b"gav")), Some(3));
+		assert_ok!(Pns::transfer(Origin::signed(3), dot(b"gav"), 3, false));
+		assert_eq!(Pns::owner(dot(b"gav")), Some(3));
+		assert_eq!(Pns::approval(dot(b"gav")), None);
+		assert_eq!(Pns::addr(dot(b"gav"), 60), Some(vec![1; 20]));
+		assert_noop!(
+			Pns::transfer(Origin::signed(2), dot(b"gav"), 2, false),
+			Error::<Test>::NotAuthorised,
+		);
+
+		// The records of the previous owner may be cleared.
+		assert_ok!(Pns::transfer(Origin::signed(3), dot(b"gav"), 1, true));
+		assert_eq!(Pns::owner(dot(b"gav")), Some(1));
+		assert_eq!(Pns::addr(dot(b"gav"), 60), None);
+
+		assert_ok!(Pns::approve_transfer(Origin::signed(1), dot(b"gav"), 2));
+		assert_ok!(Pns::cancel_approval(Origin::signed(1), dot(b"gav")));
+		assert_eq!(last_event(), RawEvent::ApprovalCancelled(dot(b"gav"), 1, 2));
+		assert_noop!(
+			Pns::transfer(Origin::signed(2), dot(b"gav"), 2, false),
+			Error::<Test>::NotAuthorised,
+		);
+	});
+}
+
+#[test]
+fn names_sell_at_their_price() {
+	new_test_ext().execute_with(|| {
+		register(b"gav", 2, 1);
+
+		assert_noop!(Pns::buy_item(Origin::signed(3), dot(b"gav"), 50, false), Error::<Test>::NotForSale);
+		assert_noop!(Pns::set_price(Origin::signed(3), dot(b"gav"), Some(50)), Error::<Test>::NotAuthorised);
+		assert_ok!(Pns::set_price(Origin::signed(2), dot(b"gav"), Some(50)));
+		assert_eq!(Pns::price(dot(b"gav")), Some(50));
+
+		assert_noop!(Pns::buy_item(Origin::signed(2), dot(b"gav"), 50, false), Error::<Test>::AlreadyOwner);
+		assert_noop!(Pns::buy_item(Origin::signed(3), dot(b"gav"), 40, false), Error::<Test>::BidTooLow);
+		assert_ok!(Pns::buy_item(Origin::signed(3), dot(b"gav"), 60, false));
+		assert_eq!(last_event(), RawEvent::NameSold(dot(b"gav"), 2, 3, 50));
+		assert_eq!(Pns::owner(dot(b"gav")), Some(3));
+		assert_eq!(Pns::price(dot(b"gav")), None);
+		assert_eq!(Balances::free_balance(3), 50);
+		assert_eq!(Balances::free_balance(2), 128);
+
+		// Expired names cannot be traded.
+		assert_ok!(Pns::set_price(Origin::signed(3), dot(b"gav"), Some(10)));
+		run_to_block(103);
+		assert_noop!(Pns::buy_item(Origin::signed(1), dot(b"gav"), 10, false), Error::<Test>::Expired);
+	});
+}
+
+#[test]
+fn offers_work() {
+	new_test_ext().execute_with(|| {
+		register(b"gav", 2, 1);
+
+		assert_noop!(Pns::make_offer(Origin::signed(2), dot(b"gav"), 30), Error::<Test>::AlreadyOwner);
+		assert_ok!(Pns::make_offer(Origin::signed(3), dot(b"gav"), 30));
+		assert_ok!(Pns::make_offer(Origin::signed(3), dot(b"gav"), 40));
+		assert_eq!(Pns::offer(dot(b"gav"), 3), Some(40));
+		assert_eq!(Balances::reserved_balance(3), 40);
+		assert_ok!(Pns::make_offer(Origin::signed(1), dot(b"gav"), 10));
+
+		assert_noop!(
+			Pns::accept_offer(Origin::signed(3), dot(b"gav"), 3, false),
+			Error::<Test>::NotAuthorised,
+		);
+		assert_noop!(
+			Pns::accept_offer(Origin::signed(2), dot(b"gav"), 2, false),
+			Error::<Test>::NoOffer,
+		);
+		assert_ok!(Pns::accept_offer(Origin::signed(2), dot(b"gav"), 3, true));
+		assert_eq!(Pns::owner(dot(b"gav")), Some(3));
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::free_balance(3), 60);
+		assert_eq!(Balances::free_balance(2), 118);
+
+		// The other offers stay until withdrawn.
+		assert_noop!(Pns::withdraw_offer(Origin::signed(3), dot(b"gav")), Error::<Test>::NoOffer);
+		assert_ok!(Pns::withdraw_offer(Origin::signed(1), dot(b"gav")));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(last_event(), RawEvent::OfferWithdrawn(dot(b"gav"), 1));
+		assert_eq!(Pns::offer_count(dot(b"gav")), 0);
+	});
+}
+
+#[test]
+fn offers_are_bounded_and_returned_on_release() {
+	new_test_ext().execute_with(|| {
+		register(b"gav", 2, 1);
+
+		assert_ok!(Pns::make_offer(Origin::signed(1), dot(b"gav"), 10));
+		assert_ok!(Pns::make_offer(Origin::signed(3), dot(b"gav"), 30));
+		// Raising an offer does not take another place.
+		assert_ok!(Pns::make_offer(Origin::signed(3), dot(b"gav"), 40));
+		assert_eq!(Pns::offer_count(dot(b"gav")), 2);
+		assert_noop!(Pns::make_offer(Origin::signed(4), dot(b"gav"), 0), Error::<Test>::TooManyOffers);
+
+		run_to_block(113);
+		assert_eq!(last_event(), RawEvent::NameReleased(dot(b"gav")));
+		assert_eq!(Pns::offer(dot(b"gav"), 1), None);
+		assert_eq!(Pns::offer(dot(b"gav"), 3), None);
+		assert_eq!(Pns::offer_count(dot(b"gav")), 0);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::free_balance(3), 100);
+	});
+}